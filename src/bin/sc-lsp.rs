@@ -0,0 +1,19 @@
+//! Servidor de linguagem para arquivos `.sc`: o editor conversa com ele pela
+//! entrada e saída padrão.
+
+use std::io::{self, BufWriter};
+use std::process::ExitCode;
+use sc_dsl::dsl::lsp::Server;
+
+fn main() -> ExitCode {
+    let mut reader = io::stdin().lock();
+    let mut writer = BufWriter::new(io::stdout().lock());
+    match Server::new().serve(&mut reader, &mut writer) {
+        Ok(0) => ExitCode::SUCCESS,
+        Ok(_) => ExitCode::FAILURE,
+        Err(error) => {
+            eprintln!("error: {}", error);
+            ExitCode::FAILURE
+        }
+    }
+}
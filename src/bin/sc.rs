@@ -0,0 +1,361 @@
+use std::fs;
+use std::io::{self, BufRead, IsTerminal, Read, Write};
+use std::process::ExitCode;
+use sc_dsl::dsl::format;
+use sc_dsl::dsl::interp::{Builtins, Interpreter, Value, STACK_MAX_DEPTH, STACK_SIZE};
+use sc_dsl::dsl::parser::diagnostic::{ColorMode, Diagnostic, Severity};
+use sc_dsl::dsl::parser::tree::Tree;
+use sc_dsl::dsl::repl::{self, Repl, Reply};
+use sc_dsl::dsl::vm::{self, Vm};
+use sc_dsl::dsl::module::{self, Program};
+use sc_dsl::dsl::{json, resolve, sexpr, typeck};
+
+const USAGE: &str = "\
+usage: sc <command> [options] [file.sc | -]...
+
+commands:
+  parse                              report every syntax error
+  check                              syntax, names and types
+  fmt [--check]                      rewrite files in canonical form
+  run [--vm]                         execute each program (--vm: compile to bytecode first)
+  repl                               read and run statements and bugs interactively
+  ast [--format json|debug|sexpr]    print the syntax tree (default: json)
+  disasm                             print the compiled bytecode
+  schema                             print the JSON Schema of `ast --format json`
+
+with no files, or with `-`, the program is read from standard input";
+
+/// Programa lido de um arquivo ou da entrada padrão
+struct Input {
+    name: String,
+    source: String,
+    stdin: bool,
+}
+
+#[derive(Clone, Copy)]
+enum AstFormat {
+    Json,
+    Debug,
+    Sexpr,
+}
+
+enum Command {
+    Parse,
+    Check,
+    Fmt { check: bool },
+    Run { vm: bool },
+    Repl,
+    Ast(AstFormat),
+    Disasm,
+    Schema,
+}
+
+fn main() -> ExitCode {
+    // O interpretador desce pela pilha nativa a cada chamada de ethics
+    std::thread::Builder::new()
+        .stack_size(STACK_SIZE)
+        .spawn(sc)
+        .expect("failed to start the main thread")
+        .join()
+        .unwrap_or(ExitCode::from(101))
+}
+
+fn sc() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (command, files) = match parse_args(&args) {
+        Ok(parsed) => parsed,
+        Err(message) => {
+            if !message.is_empty() {
+                eprintln!("error: {}", message);
+            }
+            eprintln!("{}", USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    // O schema não depende de nenhum programa
+    if let Command::Schema = command {
+        println!("{}", json::schema::to_string_pretty());
+        return ExitCode::SUCCESS;
+    }
+    if let Command::Repl = command {
+        return if repl() { ExitCode::SUCCESS } else { ExitCode::FAILURE };
+    }
+
+    let mut failed = false;
+    for input in read_inputs(&files) {
+        let ok = match input {
+            Ok(input) => match command {
+                Command::Parse => parse(&input),
+                Command::Check => check(&input),
+                Command::Fmt { check } => fmt(&input, check),
+                Command::Run { vm } => run(&input, vm),
+                Command::Ast(format) => ast(&input, format),
+                Command::Disasm => disasm(&input),
+                Command::Schema | Command::Repl => true,
+            },
+            Err(message) => {
+                eprintln!("error: {}", message);
+                false
+            }
+        };
+        failed |= !ok;
+    }
+
+    if failed { ExitCode::FAILURE } else { ExitCode::SUCCESS }
+}
+
+fn parse_args(args: &[String]) -> Result<(Command, Vec<String>), String> {
+    let (name, rest) = args.split_first().ok_or_else(String::new)?;
+    let mut command = match name.as_str() {
+        "parse" => Command::Parse,
+        "check" => Command::Check,
+        "fmt" => Command::Fmt { check: false },
+        "run" => Command::Run { vm: false },
+        "repl" => Command::Repl,
+        "ast" => Command::Ast(AstFormat::Json),
+        "disasm" => Command::Disasm,
+        "schema" => Command::Schema,
+        "help" | "--help" | "-h" => return Err(String::new()),
+        other => return Err(format!("unknown command `{}`", other)),
+    };
+
+    let mut files = Vec::new();
+    let mut rest = rest.iter();
+    while let Some(arg) = rest.next() {
+        let (flag, value) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag, Some(value.to_string())),
+            _ => (arg.as_str(), None),
+        };
+        match (&mut command, flag) {
+            (Command::Fmt { check }, "--check") => *check = true,
+            (Command::Run { vm }, "--vm") => *vm = true,
+            (Command::Ast(format), "--format") => {
+                let value = value.or_else(|| rest.next().cloned()).ok_or("`--format` needs a value")?;
+                *format = match value.as_str() {
+                    "json" => AstFormat::Json,
+                    "debug" => AstFormat::Debug,
+                    "sexpr" => AstFormat::Sexpr,
+                    other => return Err(format!("unknown format `{}`, expected json, debug or sexpr", other)),
+                };
+            }
+            (_, flag) if flag.starts_with("--") => return Err(format!("unknown option `{}` for `{}`", flag, name)),
+            _ => files.push(arg.clone()),
+        }
+    }
+    if let Command::Repl = command
+        && !files.is_empty()
+    {
+        return Err("`repl` does not take files".to_string());
+    }
+    Ok((command, files))
+}
+
+fn read_inputs(files: &[String]) -> Vec<Result<Input, String>> {
+    if files.is_empty() {
+        return vec![read_stdin()];
+    }
+    files
+        .iter()
+        .map(|path| match path.as_str() {
+            "-" => read_stdin(),
+            _ => fs::read_to_string(path)
+                .map(|source| Input { name: path.clone(), source, stdin: false })
+                .map_err(|error| format!("cannot read {}: {}", path, error)),
+        })
+        .collect()
+}
+
+fn read_stdin() -> Result<Input, String> {
+    let mut source = String::new();
+    io::stdin().read_to_string(&mut source).map_err(|error| format!("cannot read standard input: {}", error))?;
+    Ok(Input { name: "<stdin>".to_string(), source, stdin: true })
+}
+
+fn color() -> ColorMode {
+    if io::stderr().is_terminal() { ColorMode::Ansi } else { ColorMode::Plain }
+}
+
+fn report(diagnostic: &Diagnostic, input: &Input) {
+    eprint!("{}", diagnostic.render(&input.source, &input.name, color()));
+}
+
+/// Analisa o programa ou reporta o erro de sintaxe
+fn tree(input: &Input) -> Option<Tree> {
+    match Tree::parse_input(input.source.clone()) {
+        Ok(tree) => Some(tree),
+        Err(error) => {
+            report(&error.diagnostic(&input.source), input);
+            None
+        }
+    }
+}
+
+/// O programa com todos os arquivos que ele importa, ou o primeiro erro
+fn program(input: &Input) -> Option<Program> {
+    match module::load_source(&input.name, input.source.clone()) {
+        Ok(program) => Some(program),
+        Err(error) => {
+            eprint!("{}", error.render(color()));
+            None
+        }
+    }
+}
+
+/// `sc parse`: todos os erros de sintaxe, não só o primeiro
+fn parse(input: &Input) -> bool {
+    let recovery = Tree::parse_recovering(input.source.clone());
+    for diagnostic in &recovery.diagnostics {
+        report(diagnostic, input);
+    }
+    recovery.is_ok()
+}
+
+/// `sc check`: nomes e tipos; avisos não fazem o comando falhar
+fn check(input: &Input) -> bool {
+    if !parse(input) {
+        return false;
+    }
+    let Some(program) = program(input) else {
+        return false;
+    };
+    let fly = program.link();
+
+    let mut diagnostics: Vec<Diagnostic> = resolve::resolve(&fly).errors.iter().map(|error| error.diagnostic()).collect();
+    for diagnostic in typeck::check(&fly).iter().map(|error| error.diagnostic()) {
+        // Nomes e espécies desconhecidos são vistos pelos dois passos
+        if !diagnostics.iter().any(|seen| seen.message == diagnostic.message && seen.span == diagnostic.span) {
+            diagnostics.push(diagnostic);
+        }
+    }
+    diagnostics.sort_by_key(|diagnostic| diagnostic.span.start);
+
+    for diagnostic in &diagnostics {
+        eprint!("{}", program.render(diagnostic, color()));
+    }
+    !diagnostics.iter().any(|diagnostic| diagnostic.severity == Severity::Error)
+}
+
+/// `sc fmt`: reescreve o arquivo (ou imprime, se veio da entrada padrão);
+/// com `--check` só aponta o que mudaria.
+fn fmt(input: &Input, check: bool) -> bool {
+    if check {
+        return match format::check(&input.source) {
+            Ok(None) => true,
+            Ok(Some(span)) => {
+                let diagnostic = Diagnostic::error("file is not formatted", span)
+                    .with_note(format!("run `sc fmt {}` to fix it", input.name));
+                report(&diagnostic, input);
+                false
+            }
+            Err(error) => {
+                report(&error.diagnostic(&input.source), input);
+                false
+            }
+        };
+    }
+
+    match format::format_source(&input.source) {
+        Ok(formatted) if input.stdin => {
+            print!("{}", formatted);
+            true
+        }
+        Ok(formatted) if formatted != input.source => match fs::write(&input.name, formatted) {
+            Ok(()) => true,
+            Err(error) => {
+                eprintln!("error: cannot write {}: {}", input.name, error);
+                false
+            }
+        },
+        Ok(_) => true,
+        Err(error) => {
+            report(&error.diagnostic(&input.source), input);
+            false
+        }
+    }
+}
+
+/// `sc run`: cada programa roda em um interpretador (ou uma VM) novo
+fn run(input: &Input, vm: bool) -> bool {
+    let Some(program) = program(input) else {
+        return false;
+    };
+    let fly = program.link();
+    // As espécies importadas aparecem com os nomes do arquivo de entrada
+    let host = Builtins { names: program.names(), ..Builtins::new() };
+    let result = if vm {
+        vm::compile(&fly).and_then(|compiled| Vm::with_host(host).run(&compiled))
+    } else {
+        Interpreter::with_host(host).with_max_depth(STACK_MAX_DEPTH).run(&fly)
+    };
+    match result {
+        Ok(_) => true,
+        Err(error) => {
+            eprint!("{}", program.render(&error.diagnostic(), color()));
+            false
+        }
+    }
+}
+
+/// `sc repl`: uma entrada por vez, com o estado mantido entre elas. Os
+/// prompts só aparecem no terminal, para que `sc repl < arquivo` fique limpo.
+fn repl() -> bool {
+    let interactive = io::stdin().is_terminal();
+    let mut session = Repl::new();
+    session.interpreter = Interpreter::new().with_max_depth(STACK_MAX_DEPTH);
+    let mut lines = io::stdin().lock().lines();
+    loop {
+        if interactive {
+            print!("{}", session.prompt());
+            let _ = io::stdout().flush();
+        }
+        let Some(Ok(line)) = lines.next() else {
+            break;
+        };
+        match session.feed(&line) {
+            Reply::Empty | Reply::More => {}
+            Reply::Defined(name) => println!("=> {}", name),
+            Reply::Value(Value::Nil) => {}
+            Reply::Value(value) => println!("=> {}", repl::show(&value)),
+            Reply::Error { diagnostic, source } => eprint!("{}", diagnostic.render(&source, "<repl>", color())),
+        }
+    }
+    if interactive {
+        println!();
+    }
+    if session.prompt() == repl::CONTINUE {
+        eprintln!("error: the input ended inside an open block");
+        return false;
+    }
+    true
+}
+
+fn ast(input: &Input, format: AstFormat) -> bool {
+    let Some(tree) = tree(input) else {
+        return false;
+    };
+    let text = match format {
+        AstFormat::Json => json::to_string_pretty(&tree),
+        AstFormat::Debug => format!("{:#?}", tree),
+        AstFormat::Sexpr => sexpr::tree(&tree).to_string(),
+    };
+    // Saída fechada antes do fim (`sc ast | head`) não é erro do programa
+    let _ = writeln!(io::stdout().lock(), "{}", text);
+    true
+}
+
+fn disasm(input: &Input) -> bool {
+    let Some(program) = program(input) else {
+        return false;
+    };
+    match vm::compile(&program.link()) {
+        Ok(compiled) => {
+            let _ = write!(io::stdout().lock(), "{}", vm::disassemble(&compiled));
+            true
+        }
+        Err(error) => {
+            eprint!("{}", program.render(&error.diagnostic(), color()));
+            false
+        }
+    }
+}
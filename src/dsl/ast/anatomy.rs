@@ -2,24 +2,54 @@ use pest::iterators::Pair;
 use pest::Parser;
 use serde::{Deserialize, Serialize};
 use crate::dsl::ast::bug::Bug;
+use crate::dsl::ast::contract::Contract;
+use crate::dsl::ast::emitter::Specie;
+use crate::dsl::ast::kind::Kind;
+use crate::dsl::ast::error::{expect_rule, AstBuildError, Children};
+use crate::dsl::ast::span::Span;
 use crate::dsl::parser::parser::{Rule, SCP};
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Anatomy {
     Bug(Bug),
+    Kind(Kind),
+    Contract(Contract),
 }
 
 impl Anatomy {
-    pub fn from_pair(pair: Pair<Rule>) -> Self {
-        let inner = pair.into_inner();
-        let bug = Bug::from_pair(inner.clone().next().unwrap());
-        Anatomy::Bug(bug)
+    pub fn from_pair(pair: Pair<Rule>) -> Result<Self, AstBuildError> {
+        expect_rule(&pair, Rule::anatomy)?;
+
+        let inner = Children::of(pair).require(Rule::bug)?;
+        match inner.as_rule() {
+            Rule::bug => Ok(Anatomy::Bug(Bug::from_pair(inner)?)),
+            Rule::kind => Ok(Anatomy::Kind(Kind::from_pair(inner)?)),
+            Rule::contract => Ok(Anatomy::Contract(Contract::from_pair(inner)?)),
+            _ => Err(AstBuildError::unexpected(&inner, &[Rule::bug, Rule::kind, Rule::contract])),
+        }
     }
 
     pub fn from_string(input: String) -> Result<Self, Box<dyn std::error::Error>> {
         let mut pairs = SCP::parse(Rule::anatomy, &input)?;
         let pair = pairs.next().ok_or("No pair found")?;
-        Ok(Anatomy::from_pair(pair))
+        Ok(Anatomy::from_pair(pair)?)
+    }
+
+    pub fn span(&self) -> Span {
+        match self {
+            Anatomy::Bug(bug) => bug.span,
+            Anatomy::Kind(kind) => kind.span,
+            Anatomy::Contract(contract) => contract.span,
+        }
+    }
+
+    /// Nome da espécie definida
+    pub fn specie(&self) -> &Specie {
+        match self {
+            Anatomy::Bug(bug) => &bug.specie,
+            Anatomy::Kind(kind) => &kind.specie,
+            Anatomy::Contract(contract) => &contract.specie,
+        }
     }
 }
 
@@ -50,6 +80,7 @@ mod tests {
                 assert!(!bug.genes.is_empty(), "Bug should have genes");
                 assert!(!bug.ethics.is_empty(), "Bug should have ethics");
             }
+            other => panic!("Expected a bug, got {:?}", other),
         }
     }
 
@@ -71,6 +102,7 @@ mod tests {
                 assert_eq!(bug.genes.len(), 1, "Bug should have exactly 1 gene");
                 assert_eq!(bug.ethics.len(), 4, "Bug should have exactly 4 ethics");
             }
+            other => panic!("Expected a bug, got {:?}", other),
         }
     }
 
@@ -90,6 +122,7 @@ mod tests {
                 assert_eq!(bug.genes.len(), cloned_bug.genes.len(), "Gene count should be equal");
                 assert_eq!(bug.ethics.len(), cloned_bug.ethics.len(), "Ethics count should be equal");
             }
+            other => panic!("Expected two bugs, got {:?}", other),
         }
     }
 
@@ -108,6 +141,7 @@ mod tests {
                 assert_eq!(bug.genes.len(), 2);
                 assert_eq!(bug.ethics.len(), 0);
             }
+            other => panic!("Expected a bug, got {:?}", other),
         }
     }
 }
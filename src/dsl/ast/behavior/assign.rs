@@ -3,32 +3,34 @@ use serde::{Deserialize, Serialize};
 use crate::dsl::parser::parser::Rule;
 use crate::dsl::ast::behavior::oop::Oop;
 use crate::dsl::ast::emitter::tag::Tag;
+use crate::dsl::ast::error::{expect_rule, AstBuildError, Children};
+use crate::dsl::ast::span::Span;
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Assign {
     pub raw: String,
     pub tag: Tag,
     pub oop: Oop,
+    pub span: Span,
 }
 
 impl Assign {
-    pub fn from_pair(pair: Pair<Rule>) -> Self {
-        assert_eq!(pair.as_rule(), Rule::assign);
+    pub fn from_pair(pair: Pair<Rule>) -> Result<Self, AstBuildError> {
+        expect_rule(&pair, Rule::assign)?;
+        let span = Span::from_pair(&pair);
         let raw = pair.as_str().to_string();
 
-        let mut inner = pair.into_inner();
+        let mut inner = Children::of(pair);
 
         // Parse tag
-        let tag_pair = inner.next().expect("Assign deve ter uma tag");
-        let tag = Tag::from_pair(tag_pair);
+        let tag = Tag::from_pair(inner.expect(Rule::tag)?)?;
 
         // Skip the "=" symbol (it's not captured as a rule)
 
         // Parse oop
-        let oop_pair = inner.next().expect("Assign deve ter um objeto oop");
-        let oop = Oop::from_pair(oop_pair);
+        let oop = Oop::from_pair(inner.expect(Rule::oop)?)?;
 
-        Assign { raw, tag, oop }
+        Ok(Assign { raw, tag, oop, span })
     }
 
     pub fn from_string(input: String) -> Result<Self, Box<dyn std::error::Error>> {
@@ -37,7 +39,7 @@ impl Assign {
 
         let mut pairs = SCP::parse(Rule::assign, &input)?;
         let pair = pairs.next().ok_or("No pair found")?;
-        Ok(Assign::from_pair(pair))
+        Ok(Assign::from_pair(pair)?)
     }
 }
 
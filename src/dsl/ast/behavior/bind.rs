@@ -1,30 +1,32 @@
 use pest::iterators::Pair;
 use serde::{Deserialize, Serialize};
 use crate::dsl::parser::parser::Rule;
+use crate::dsl::ast::error::{expect_rule, AstBuildError, Children};
+use crate::dsl::ast::span::Span;
 use super::oop::Oop;
-use crate::dsl::ast::emitter::{tag::Tag, specie::Specie};
+use crate::dsl::ast::emitter::{tag::Tag, specie_ref::SpecieRef};
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Bind {
     pub tag: Tag,
     pub oop: Oop,
+    pub span: Span,
 }
 
 impl Bind {
-    pub fn from_pair(pair: Pair<Rule>) -> Self {
-        assert_eq!(pair.as_rule(), Rule::bind);
+    pub fn from_pair(pair: Pair<Rule>) -> Result<Self, AstBuildError> {
+        expect_rule(&pair, Rule::bind)?;
+        let span = Span::from_pair(&pair);
 
-        let mut inner = pair.into_inner();
+        let mut inner = Children::of(pair);
 
         // Parse tag
-        let tag_pair = inner.next().expect("Bind deve ter uma tag");
-        let tag = Tag::from_pair(tag_pair);
+        let tag = Tag::from_pair(inner.expect(Rule::tag)?)?;
 
         // Parse oop
-        let oop_pair = inner.next().expect("Bind deve ter um oop");
-        let oop = Oop::from_pair(oop_pair);
+        let oop = Oop::from_pair(inner.expect(Rule::oop)?)?;
 
-        Bind { tag, oop }
+        Ok(Bind { tag, oop, span })
     }
 
     pub fn from_string(input: String) -> Result<Self, Box<dyn std::error::Error>> {
@@ -33,7 +35,7 @@ impl Bind {
 
         let mut pairs = SCP::parse(Rule::bind, &input)?;
         let pair = pairs.next().ok_or("No pair found")?;
-        Ok(Bind::from_pair(pair))
+        Ok(Bind::from_pair(pair)?)
     }
 }
 
@@ -41,23 +43,23 @@ impl Bind {
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct EthicsBind {
     pub tag: Tag,
-    pub specie: Specie,
+    pub specie: SpecieRef,
+    pub span: Span,
 }
 
 impl EthicsBind {
-    pub fn from_pair(pair: Pair<Rule>) -> Self {
-        assert_eq!(pair.as_rule(), Rule::ethics_bind);
+    pub fn from_pair(pair: Pair<Rule>) -> Result<Self, AstBuildError> {
+        expect_rule(&pair, Rule::ethics_bind)?;
+        let span = Span::from_pair(&pair);
 
-        let mut inner = pair.into_inner();
+        let mut inner = Children::of(pair);
 
         // Parse tag
-        let tag_pair = inner.next().expect("EthicsBind deve ter uma tag");
-        let tag = Tag::from_pair(tag_pair);
+        let tag = Tag::from_pair(inner.expect(Rule::tag)?)?;
 
         // Parse specie
-        let specie_pair = inner.next().expect("EthicsBind deve ter uma specie");
-        let specie = Specie::new(specie_pair.as_str().to_string());
+        let specie = SpecieRef::from_pair(inner.expect(Rule::specie_ref)?)?;
 
-        EthicsBind { tag, specie }
+        Ok(EthicsBind { tag, specie, span })
     }
 }
@@ -1,11 +1,14 @@
 use pest::iterators::Pair;
 use serde::{Deserialize, Serialize};
 use crate::dsl::parser::parser::Rule;
+use crate::dsl::ast::error::{expect_rule, AstBuildError};
+use crate::dsl::ast::span::Span;
 use super::bind::{Bind, EthicsBind};
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Binds {
     pub binds: Vec<Bind>,
+    pub span: Span,
 }
 
 /// EthicsBinds represents function parameter bindings for ethics functions
@@ -15,17 +18,18 @@ pub struct EthicsBinds {
 }
 
 impl Binds {
-    pub fn from_pair(pair: Pair<Rule>) -> Self {
-        assert_eq!(pair.as_rule(), Rule::binds);
+    pub fn from_pair(pair: Pair<Rule>) -> Result<Self, AstBuildError> {
+        expect_rule(&pair, Rule::binds)?;
+        let span = Span::from_pair(&pair);
 
         let mut binds = Vec::new();
         for bind_pair in pair.into_inner() {
             if bind_pair.as_rule() == Rule::bind {
-                binds.push(Bind::from_pair(bind_pair));
+                binds.push(Bind::from_pair(bind_pair)?);
             }
         }
 
-        Binds { binds }
+        Ok(Binds { binds, span })
     }
 
     pub fn from_string(input: String) -> Result<Self, Box<dyn std::error::Error>> {
@@ -34,6 +38,6 @@ impl Binds {
 
         let mut pairs = SCP::parse(Rule::binds, &input)?;
         let pair = pairs.next().ok_or("No pair found")?;
-        Ok(Binds::from_pair(pair))
+        Ok(Binds::from_pair(pair)?)
     }
 }
@@ -12,6 +12,8 @@ use crate::dsl::parser::parser::{Rule, SCP};
 use self::assign::Assign;
 use self::oop::Oop;
 use crate::dsl::ast::statement::Statement;
+use crate::dsl::ast::error::{expect_rule, AstBuildError, Children};
+use crate::dsl::ast::span::Span;
 
 // Re-export Trail types for easier access
 pub use trail::{Trail, Catalysis, Carrier};
@@ -26,22 +28,30 @@ pub enum Behavior {
 }
 
 impl Behavior {
-    pub fn from_pair(pair: Pair<Rule>) -> Self {
-        assert_eq!(pair.as_rule(), Rule::behavior);
+    pub fn from_pair(pair: Pair<Rule>) -> Result<Self, AstBuildError> {
+        expect_rule(&pair, Rule::behavior)?;
 
-        let inner_pair = pair.into_inner().next().unwrap();
+        let inner_pair = Children::of(pair).require(Rule::oop)?;
         match inner_pair.as_rule() {
-            Rule::statement => Behavior::Statement(Statement::from_pair(inner_pair)),
-            Rule::assign => Behavior::Assign(Assign::from_pair(inner_pair)),
-            Rule::oop => Behavior::Oop(Oop::from_pair(inner_pair)),
-            _ => panic!("Regra inesperada dentro de behavior: {:?}", inner_pair.as_rule()),
+            Rule::statement => Ok(Behavior::Statement(Statement::from_pair(inner_pair)?)),
+            Rule::assign => Ok(Behavior::Assign(Assign::from_pair(inner_pair)?)),
+            Rule::oop => Ok(Behavior::Oop(Oop::from_pair(inner_pair)?)),
+            _ => Err(AstBuildError::unexpected(&inner_pair, &[Rule::statement, Rule::assign, Rule::oop])),
         }
     }
 
     pub fn from_string(input: String) -> Result<Self, Box<dyn std::error::Error>> {
         let mut pairs = SCP::parse(Rule::behavior, &input)?;
         let pair = pairs.next().ok_or("No pair found")?;
-        Ok(Behavior::from_pair(pair))
+        Ok(Behavior::from_pair(pair)?)
+    }
+
+    pub fn span(&self) -> Span {
+        match self {
+            Behavior::Statement(statement) => statement.span(),
+            Behavior::Assign(assign) => assign.span,
+            Behavior::Oop(oop) => oop.span,
+        }
     }
 }
 
@@ -1,45 +1,56 @@
 use pest::iterators::Pair;
 use serde::{Deserialize, Serialize};
 use crate::dsl::parser::parser::Rule;
-use crate::dsl::ast::emitter::{Emitter, Literal, Specie, Tag};
+use crate::dsl::ast::emitter::{Emitter, List, Literal, Map, SelfRef, Specie, Tag, Template};
+use crate::dsl::ast::error::{expect_rule, AstBuildError, Children};
+use crate::dsl::ast::span::Span;
 use super::trail::Trail;
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Oop {
     pub emitter: Emitter,
     pub trails: Vec<Trail>,
+    pub span: Span,
 }
 
 impl Oop {
-    pub fn from_pair(pair: Pair<Rule>) -> Self {
-        assert_eq!(pair.as_rule(), Rule::oop);
+    pub fn from_pair(pair: Pair<Rule>) -> Result<Self, AstBuildError> {
+        expect_rule(&pair, Rule::oop)?;
+        let span = Span::from_pair(&pair);
 
-        let mut inner = pair.into_inner();
+        let mut inner = Children::of(pair);
 
-        let emitter_pair = inner.next().expect("Oop must have an emitter");
+        let emitter_pair = inner.require(Rule::emitter)?;
         let emitter = match emitter_pair.as_rule() {
             Rule::emitter => {
-                let mut emitter_inner = emitter_pair.into_inner();
-                let inner_pair = emitter_inner.next().expect("Emitter must have inner content");
-                match inner_pair.as_rule() {
-                    Rule::specie => Emitter::Specie(Specie { raw: inner_pair.as_str().to_string() }),
-                    Rule::tag => Emitter::Tag(Tag { raw: inner_pair.as_str().to_string() }),
-                    Rule::literal => Emitter::Literal(Literal::from_pair(inner_pair)),
-                    _ => panic!("Unexpected inner emitter type: {:?}", inner_pair.as_rule()),
-                }
+                let inner_pair = Children::of(emitter_pair).require(Rule::emitter)?;
+                Oop::emitter_from_pair(inner_pair)?
             },
-            Rule::specie => Emitter::Specie(Specie { raw: emitter_pair.as_str().to_string() }),
-            Rule::tag => Emitter::Tag(Tag { raw: emitter_pair.as_str().to_string() }),
-            Rule::literal => Emitter::Literal(Literal::from_pair(emitter_pair)),
-            _ => panic!("Unexpected emitter type: {:?}", emitter_pair.as_rule()),
+            _ => Oop::emitter_from_pair(emitter_pair)?,
         };
 
         let mut trails = Vec::new();
         for trail_pair in inner {
-            trails.push(Trail::from_pair(trail_pair));
+            trails.push(Trail::from_pair(trail_pair)?);
         }
 
-        Oop { emitter, trails }
+        Ok(Oop { emitter, trails, span })
+    }
+
+    fn emitter_from_pair(pair: Pair<Rule>) -> Result<Emitter, AstBuildError> {
+        match pair.as_rule() {
+            Rule::specie => Ok(Emitter::Specie(Specie::from_pair(pair)?)),
+            Rule::tag => Ok(Emitter::Tag(Tag::from_pair(pair)?)),
+            Rule::literal => Ok(Emitter::Literal(Literal::from_pair(pair)?)),
+            Rule::template => Ok(Emitter::Template(Template::from_pair(pair)?)),
+            Rule::self_ref => Ok(Emitter::SelfRef(SelfRef::from_pair(pair)?)),
+            Rule::list => Ok(Emitter::List(List::from_pair(pair)?)),
+            Rule::map => Ok(Emitter::Map(Map::from_pair(pair)?)),
+            _ => Err(AstBuildError::unexpected(
+                &pair,
+                &[Rule::specie, Rule::tag, Rule::literal, Rule::template, Rule::self_ref, Rule::list, Rule::map],
+            )),
+        }
     }
 
     pub fn from_string(input: String) -> Result<Self, Box<dyn std::error::Error>> {
@@ -48,6 +59,6 @@ impl Oop {
 
         let mut pairs = SCP::parse(Rule::oop, &input)?;
         let pair = pairs.next().ok_or("No pair found")?;
-        Ok(Oop::from_pair(pair))
+        Ok(Oop::from_pair(pair)?)
     }
 }
@@ -1,25 +1,29 @@
 use pest::iterators::Pair;
 use serde::{Deserialize, Serialize};
 use crate::dsl::parser::parser::Rule;
+use crate::dsl::ast::error::{expect_rule, AstBuildError};
+use crate::dsl::ast::span::Span;
 use super::oop::Oop;
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Sequence {
     pub oops: Vec<Oop>,
+    pub span: Span,
 }
 
 impl Sequence {
-    pub fn from_pair(pair: Pair<Rule>) -> Self {
-        assert_eq!(pair.as_rule(), Rule::sequence);
+    pub fn from_pair(pair: Pair<Rule>) -> Result<Self, AstBuildError> {
+        expect_rule(&pair, Rule::sequence)?;
+        let span = Span::from_pair(&pair);
 
         let mut oops = Vec::new();
         for oop_pair in pair.into_inner() {
             if oop_pair.as_rule() == Rule::oop {
-                oops.push(Oop::from_pair(oop_pair));
+                oops.push(Oop::from_pair(oop_pair)?);
             }
         }
 
-        Sequence { oops }
+        Ok(Sequence { oops, span })
     }
 
     pub fn from_string(input: String) -> Result<Self, Box<dyn std::error::Error>> {
@@ -28,7 +32,7 @@ impl Sequence {
 
         let mut pairs = SCP::parse(Rule::sequence, &input)?;
         let pair = pairs.next().ok_or("No pair found")?;
-        Ok(Sequence::from_pair(pair))
+        Ok(Sequence::from_pair(pair)?)
     }
 
     pub fn get_oops(&self) -> &[Oop] {
@@ -2,44 +2,49 @@ use pest::iterators::Pair;
 use serde::{Deserialize, Serialize};
 use crate::dsl::parser::parser::Rule;
 use crate::dsl::ast::emitter::Tag;
+use crate::dsl::ast::error::{expect_rule, AstBuildError, Children};
+use crate::dsl::ast::span::Span;
 use super::transport::Transport;
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Catalysis {
     pub tag: Tag,
     pub carrier: Option<Carrier>,
+    pub span: Span,
 }
 
 impl Catalysis {
-    pub fn from_pair(pair: Pair<Rule>) -> Self {
-        assert_eq!(pair.as_rule(), Rule::catalysis);
+    pub fn from_pair(pair: Pair<Rule>) -> Result<Self, AstBuildError> {
+        expect_rule(&pair, Rule::catalysis)?;
+        let span = Span::from_pair(&pair);
 
-        let mut inner = pair.into_inner();
+        let mut inner = Children::of(pair);
 
         // Parse tag (skip the "." token)
-        let tag_pair = inner.next().expect("Catalysis deve ter uma tag");
-        let tag = Tag::from_pair(tag_pair);
+        let tag = Tag::from_pair(inner.expect(Rule::tag)?)?;
 
         // Parse optional carrier
-        let carrier = inner.next().map(|carrier_pair| Carrier::from_pair(carrier_pair));
+        let carrier = inner.next().map(Carrier::from_pair).transpose()?;
 
-        Catalysis { tag, carrier }
+        Ok(Catalysis { tag, carrier, span })
     }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Carrier {
     pub transport: Option<Transport>,
+    pub span: Span,
 }
 
 impl Carrier {
-    pub fn from_pair(pair: Pair<Rule>) -> Self {
-        assert_eq!(pair.as_rule(), Rule::carrier);
+    pub fn from_pair(pair: Pair<Rule>) -> Result<Self, AstBuildError> {
+        expect_rule(&pair, Rule::carrier)?;
+        let span = Span::from_pair(&pair);
 
         // Parse optional transport
-        let transport = pair.into_inner().next().map(|transport_pair| Transport::from_pair(transport_pair));
+        let transport = pair.into_inner().next().map(Transport::from_pair).transpose()?;
 
-        Carrier { transport }
+        Ok(Carrier { transport, span })
     }
 }
 
@@ -50,14 +55,21 @@ pub enum Trail {
 }
 
 impl Trail {
-    pub fn from_pair(pair: Pair<Rule>) -> Self {
-        assert_eq!(pair.as_rule(), Rule::trail);
+    pub fn from_pair(pair: Pair<Rule>) -> Result<Self, AstBuildError> {
+        expect_rule(&pair, Rule::trail)?;
 
-        let inner_pair = pair.into_inner().next().expect("Trail must have an inner rule");
+        let inner_pair = Children::of(pair).require(Rule::catalysis)?;
         match inner_pair.as_rule() {
-            Rule::catalysis => Trail::Catalysis(Catalysis::from_pair(inner_pair)),
-            Rule::carrier => Trail::Carrier(Carrier::from_pair(inner_pair)),
-            _ => panic!("Unexpected rule inside trail: {:?}", inner_pair.as_rule()),
+            Rule::catalysis => Ok(Trail::Catalysis(Catalysis::from_pair(inner_pair)?)),
+            Rule::carrier => Ok(Trail::Carrier(Carrier::from_pair(inner_pair)?)),
+            _ => Err(AstBuildError::unexpected(&inner_pair, &[Rule::catalysis, Rule::carrier])),
+        }
+    }
+
+    pub fn span(&self) -> Span {
+        match self {
+            Trail::Catalysis(catalysis) => catalysis.span,
+            Trail::Carrier(carrier) => carrier.span,
         }
     }
 }
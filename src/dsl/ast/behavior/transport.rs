@@ -3,6 +3,8 @@ use serde::{Deserialize, Serialize};
 use crate::dsl::ast::behavior::binds::Binds;
 use crate::dsl::ast::behavior::sequence::Sequence;
 use crate::dsl::parser::parser::Rule;
+use crate::dsl::ast::error::{expect_rule, AstBuildError, Children};
+use crate::dsl::ast::span::Span;
 
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -12,14 +14,14 @@ pub enum Transport {
 }
 
 impl Transport {
-    pub fn from_pair(pair: Pair<Rule>) -> Self {
-        assert_eq!(pair.as_rule(), Rule::transport);
+    pub fn from_pair(pair: Pair<Rule>) -> Result<Self, AstBuildError> {
+        expect_rule(&pair, Rule::transport)?;
 
-        let inner_pair = pair.into_inner().next().expect("Transport deve ter uma regra interna");
+        let inner_pair = Children::of(pair).require(Rule::binds)?;
         match inner_pair.as_rule() {
-            Rule::binds => Transport::Binds(Binds::from_pair(inner_pair)),
-            Rule::sequence => Transport::Sequence(Sequence::from_pair(inner_pair)),
-            _ => panic!("Regra inesperada dentro de transport: {:?}", inner_pair.as_rule()),
+            Rule::binds => Ok(Transport::Binds(Binds::from_pair(inner_pair)?)),
+            Rule::sequence => Ok(Transport::Sequence(Sequence::from_pair(inner_pair)?)),
+            _ => Err(AstBuildError::unexpected(&inner_pair, &[Rule::binds, Rule::sequence])),
         }
     }
 
@@ -29,7 +31,14 @@ impl Transport {
 
         let mut pairs = SCP::parse(Rule::transport, &input)?;
         let pair = pairs.next().ok_or("No pair found")?;
-        Ok(Transport::from_pair(pair))
+        Ok(Transport::from_pair(pair)?)
+    }
+
+    pub fn span(&self) -> Span {
+        match self {
+            Transport::Binds(binds) => binds.span,
+            Transport::Sequence(sequence) => sequence.span,
+        }
     }
 
     pub fn is_binds(&self) -> bool {
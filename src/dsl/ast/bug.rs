@@ -3,46 +3,60 @@ use serde::{Deserialize, Serialize};
 use crate::dsl::ast::emitter::Specie;
 use crate::dsl::parser::parser::{Rule, SCP};
 use crate::dsl::ast::gene::Gene;
+use crate::dsl::ast::error::{expect_rule, AstBuildError};
+use crate::dsl::ast::span::Span;
 use super::ethics::Ethics;
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Bug {
     pub specie: Specie,
+    /// Parâmetros de tipo de um bug genérico (`T` em `bug Caixa<T>`)
+    pub params: Vec<Specie>,
+    /// Espécies depois de `<`: no máximo um bug, de quem herda genes e
+    /// ethics, e os contratos que promete cumprir
+    pub parents: Vec<Specie>,
     pub genes: Vec<Gene>,
     pub ethics: Vec<Ethics>,
+    pub span: Span,
 }
 
 impl Bug {
-    pub fn from_pair(pair: Pair<Rule>) -> Self {
-        assert_eq!(pair.as_rule(), Rule::bug);
+    pub fn from_pair(pair: Pair<Rule>) -> Result<Self, AstBuildError> {
+        expect_rule(&pair, Rule::bug)?;
+        let span = Span::from_pair(&pair);
 
         let inner = pair.into_inner();
 
         let mut specie = None;
+        let mut params = Vec::new();
+        let mut parents = Vec::new();
         let mut genes = Vec::new();
         let mut ethics = Vec::new();
         for inner_pair in inner {
             match inner_pair.as_rule() {
                 Rule::specie => {
-                    specie = Some(Specie::from_pair(inner_pair));
+                    specie = Some(Specie::from_pair(inner_pair)?);
                 }
-                Rule::gene => genes.push(Gene::from_pair(inner_pair)),
-                Rule::ethics => ethics.push(Ethics::from_pair(inner_pair)),
+                Rule::type_params => {
+                    params = inner_pair.into_inner().map(Specie::from_pair).collect::<Result<_, _>>()?;
+                }
+                Rule::parents => {
+                    parents = inner_pair.into_inner().map(Specie::from_pair).collect::<Result<_, _>>()?;
+                }
+                Rule::gene => genes.push(Gene::from_pair(inner_pair)?),
+                Rule::ethics => ethics.push(Ethics::from_pair(inner_pair)?),
                 _ => {}
             }
         }
 
-        // Make sure specie is initialized, otherwise panic with a helpful message
-        let specie = specie.expect("Bug deve ter uma espécie");
-        Bug { specie, genes, ethics }
+        let specie = specie.ok_or(AstBuildError::MissingRule { expected: Rule::specie, parent: Rule::bug, span })?;
+        Ok(Bug { specie, params, parents, genes, ethics, span })
     }
 
-    pub fn from_string(input: String) -> Self {
-        let pair = SCP::parse(Rule::bug, &input)
-            .expect("Failed to parse input")
-            .next()
-            .expect("No pair found");
-        Bug::from_pair(pair)
+    pub fn from_string(input: String) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut pairs = SCP::parse(Rule::bug, &input)?;
+        let pair = pairs.next().ok_or("No pair found")?;
+        Ok(Bug::from_pair(pair)?)
     }
 }
 
@@ -64,7 +78,7 @@ mod tests {
         assert!(!input.is_empty(), "Fixture file should not be empty");
 
         // Testa o parse usando from_string
-        let bug = Bug::from_string(input);
+        let bug = Bug::from_string(input).expect("Bug should parse");
 
         // Verifica o nome da espécie
         assert_eq!(bug.specie.raw, "TestBug", "Bug species should be TestBug");
@@ -84,7 +98,7 @@ mod tests {
             .expect("Failed to read complete.sc file");
 
         // Testa o parse
-        let bug = Bug::from_string(input);
+        let bug = Bug::from_string(input).expect("Bug should parse");
 
         // Verifica genes usando atributos diretos
         assert!(!bug.genes.is_empty(), "Bug should have genes");
@@ -100,7 +114,7 @@ mod tests {
         // Este teste usa from_string que internamente usa from_pair
         let input = "bug SimpleBug\n  gene y String\nend".to_string();
 
-        let bug = Bug::from_string(input);
+        let bug = Bug::from_string(input).expect("Bug should parse");
 
         assert_eq!(bug.specie.raw, "SimpleBug", "Bug species should be SimpleBug");
         assert!(!bug.genes.is_empty(), "Bug should have genes");
@@ -116,7 +130,7 @@ mod tests {
         let input = fs::read_to_string(path)
             .expect("Failed to read complete.sc file");
 
-        let bug = Bug::from_string(input);
+        let bug = Bug::from_string(input).expect("Bug should parse");
 
         // Verifica os metadados do bug
         assert_eq!(bug.specie.raw, "TestBug", "Bug species should be TestBug");
@@ -124,7 +138,7 @@ mod tests {
         // Verifica os genes
         assert_eq!(bug.genes.len(), 1, "Bug should have 1 gene");
         assert_eq!(bug.genes[0].tag.raw, "x", "Gene should be named 'x'");
-        assert_eq!(bug.genes[0].specie.raw, "Int", "Gene 'x' should have type Int");
+        assert_eq!(bug.genes[0].specie.name.raw, "Int", "Gene 'x' should have type Int");
 
         // Verifica os métodos de ethics
         assert_eq!(bug.ethics.len(), 4, "Bug should have 4 ethics methods");
@@ -165,7 +179,7 @@ mod tests {
         assert!(test_method3.feedback.is_some(), "test_method3 should have a feedback type");
 
         if let Some(feedback) = &test_method3.feedback {
-            assert_eq!(feedback.raw, "Int", "test_method3 should return Int");
+            assert_eq!(feedback.name.raw, "Int", "test_method3 should return Int");
         }
 
         if let Some(body) = &test_method3.body {
@@ -179,7 +193,7 @@ mod tests {
         // Testa se a clonagem funciona corretamente
         let input = "bug CloneBug\n  gene z Bool\nend".to_string();
 
-        let bug = Bug::from_string(input);
+        let bug = Bug::from_string(input).expect("Bug should parse");
         let cloned_bug = bug.clone();
 
         // Verifica que ambos são iguais
@@ -204,7 +218,7 @@ mod tests {
         let pair = pairs.peek().expect("No pairs found");
 
         // Cria o Bug a partir do par
-        let bug = Bug::from_pair(pair);
+        let bug = Bug::from_pair(pair).expect("Failed to build bug");
 
         // Verifica o conteúdo básico
         assert_eq!(bug.specie.raw, "CloneBug", "Bug species should be CloneBug");
@@ -217,7 +231,7 @@ mod tests {
         // Testa acesso direto aos atributos
         let input = "bug DirectBug\n  gene a Int\n  gene b String\nend".to_string();
 
-        let bug = Bug::from_string(input);
+        let bug = Bug::from_string(input).expect("Bug should parse");
 
         // Testa acesso direto aos atributos
         assert_eq!(bug.specie.raw, "DirectBug");
@@ -230,7 +244,7 @@ mod tests {
         // Testa bug sem genes e ethics
         let input = "bug EmptyBug\nend".to_string();
 
-        let bug = Bug::from_string(input);
+        let bug = Bug::from_string(input).expect("Bug should parse");
 
         assert_eq!(bug.specie.raw, "EmptyBug");
         assert!(bug.genes.is_empty(), "Should have no genes");
@@ -238,5 +252,32 @@ mod tests {
         assert_eq!(bug.genes.len(), 0);
         assert_eq!(bug.ethics.len(), 0);
     }
-}
 
+    #[test]
+    fn test_bug_spans() {
+        // Cada nó guarda a posição de onde veio no código fonte
+        let input = "bug Dog\n  gene energia Int\n  ethics latir(alto: Bool) String\n    energia = 1\n  end\nend".to_string();
+
+        let bug = Bug::from_string(input.clone()).expect("Bug should parse");
+
+        assert_eq!((bug.span.line, bug.span.col), (1, 1));
+        assert_eq!(bug.span.slice(&input), input);
+        assert_eq!((bug.specie.span.line, bug.specie.span.col), (1, 5));
+
+        let gene = &bug.genes[0];
+        assert_eq!((gene.span.line, gene.span.col), (2, 3));
+        assert_eq!(gene.tag.span.slice(&input), "energia");
+        assert_eq!((gene.specie.span.line, gene.specie.span.col), (2, 16));
+
+        let ethics = &bug.ethics[0];
+        assert_eq!((ethics.span.line, ethics.span.col), (3, 3));
+        let bind = &ethics.signature.as_ref().unwrap().binds.as_ref().unwrap()[0];
+        assert_eq!(bind.tag.span.slice(&input), "alto");
+        assert_eq!(ethics.feedback.as_ref().unwrap().span.slice(&input), "String");
+
+        let body = ethics.body.as_ref().expect("ethics deve ter corpo");
+        let signal_span = body.signals[0].span();
+        assert_eq!((signal_span.line, signal_span.col), (4, 5));
+        assert_eq!(signal_span.slice(&input), "energia = 1");
+    }
+}
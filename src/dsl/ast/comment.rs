@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+use crate::dsl::ast::span::Span;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CommentKind {
+    /// `# até o fim da linha`
+    Line,
+    /// `#[ pode ocupar várias linhas ]#`
+    Block,
+}
+
+/// Comentário do código fonte, guardado quando a análise pede para retê-los.
+///
+/// A gramática trata comentários como espaço, então eles não aparecem dentro
+/// dos nós; ficam em `Tree::comments`, ligados aos nós pelo span.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Comment {
+    pub kind: CommentKind,
+    pub raw: String,
+    pub span: Span,
+}
+
+impl Comment {
+    /// Texto sem os marcadores `#`, `#[` e `]#`, sem espaços nas pontas
+    pub fn text(&self) -> &str {
+        let inner = match self.kind {
+            CommentKind::Line => &self.raw[1..],
+            CommentKind::Block => &self.raw[2..self.raw.len() - 2],
+        };
+        inner.trim()
+    }
+}
@@ -2,23 +2,29 @@ use pest::iterators::Pair;
 use serde::{Deserialize, Serialize};
 use crate::dsl::parser::parser::Rule;
 use crate::dsl::ast::behavior::oop::Oop;
+use crate::dsl::ast::expr::Expr;
+use crate::dsl::ast::error::{expect_rule, AstBuildError, Children};
+use crate::dsl::ast::span::Span;
 
 /// Condition represents a conditional expression in control flow statements
-/// Based on the grammar: condition = { oop }
+/// Based on the grammar: condition = { expr }
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Condition {
     /// A conditional expression containing an oop
-    Oop(Oop)
+    Oop(Oop),
+    /// A conditional expression using operators (`a + 1 > b`)
+    Expr(Expr),
 }
 
 impl Condition {
-    pub fn from_pair(pair: Pair<Rule>) -> Self {
-        assert_eq!(pair.as_rule(), Rule::condition);
+    pub fn from_pair(pair: Pair<Rule>) -> Result<Self, AstBuildError> {
+        expect_rule(&pair, Rule::condition)?;
 
-        let oop_pair = pair.into_inner().next().expect("Condition must have an oop");
-        let oop = Oop::from_pair(oop_pair);
-
-        Condition::Oop(oop)
+        // Sem operadores a condição continua sendo um simples Oop
+        match Expr::from_pair(Children::of(pair).expect(Rule::expr)?)? {
+            Expr::Oop(oop) => Ok(Condition::Oop(oop)),
+            expr => Ok(Condition::Expr(expr)),
+        }
     }
 
     pub fn from_string(input: String) -> Result<Self, Box<dyn std::error::Error>> {
@@ -27,6 +33,21 @@ impl Condition {
 
         let mut pairs = SCP::parse(Rule::condition, &input)?;
         let pair = pairs.next().ok_or("No pair found")?;
-        Ok(Condition::from_pair(pair))
+        Ok(Condition::from_pair(pair)?)
+    }
+
+    pub fn span(&self) -> Span {
+        match self {
+            Condition::Oop(oop) => oop.span,
+            Condition::Expr(expr) => expr.span(),
+        }
+    }
+
+    /// A condição como um único Oop, desaçucarando operadores (ver `Expr::desugar`)
+    pub fn desugar(&self) -> Oop {
+        match self {
+            Condition::Oop(oop) => oop.clone(),
+            Condition::Expr(expr) => expr.desugar(),
+        }
     }
 }
@@ -0,0 +1,68 @@
+use pest::iterators::Pair;
+use pest::Parser;
+use serde::{Deserialize, Serialize};
+use crate::dsl::ast::emitter::Specie;
+use crate::dsl::ast::error::{expect_rule, AstBuildError, Children};
+use crate::dsl::ast::ethics::Ethics;
+use crate::dsl::ast::span::Span;
+use crate::dsl::parser::parser::{Rule, SCP};
+
+/// `contract Falante ethics falar(volume: Int) String end`: assinaturas de
+/// ethics, sem corpo, que um bug promete implementar ao citar o contrato
+/// (`bug Dog < Falante`). O contrato também serve de espécie para genes e
+/// parâmetros.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Contract {
+    pub specie: Specie,
+    pub ethics: Vec<Ethics>,
+    pub span: Span,
+}
+
+impl Contract {
+    pub fn from_pair(pair: Pair<Rule>) -> Result<Self, AstBuildError> {
+        expect_rule(&pair, Rule::contract)?;
+        let span = Span::from_pair(&pair);
+
+        let mut inner = Children::of(pair);
+        let specie = Specie::from_pair(inner.expect(Rule::specie)?)?;
+        let ethics = inner.map(Ethics::from_pair).collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Contract { specie, ethics, span })
+    }
+
+    pub fn from_string(input: String) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut pairs = SCP::parse(Rule::contract, &input)?;
+        let pair = pairs.next().ok_or("No pair found")?;
+        Ok(Contract::from_pair(pair)?)
+    }
+
+    pub fn ethics(&self, name: &str) -> Option<&Ethics> {
+        self.ethics.iter().find(|ethics| ethics.tag.raw == name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Contract;
+    use crate::dsl::parser::parser::{Rule, SCP};
+    use pest::Parser;
+
+    #[test]
+    fn test_contract_signatures() {
+        let contract = Contract::from_string("contract Falante\n  ethics falar(volume: Int) String\n  ethics calar\nend".to_string())
+            .expect("Contract should parse");
+
+        assert_eq!(contract.specie.raw, "Falante");
+        let names: Vec<&str> = contract.ethics.iter().map(|ethics| ethics.tag.raw.as_str()).collect();
+        assert_eq!(names, vec!["falar", "calar"]);
+        assert!(contract.ethics.iter().all(|ethics| ethics.body.is_none()));
+        assert_eq!(contract.ethics("falar").and_then(|ethics| ethics.feedback.as_ref()).map(|f| f.name.raw.as_str()), Some("String"));
+    }
+
+    #[test]
+    fn test_contract_ethics_have_no_body() {
+        let source = "contract Falante\n  ethics falar\n    \"au\"\n  end\nend";
+
+        assert!(SCP::parse(Rule::contract, source).is_err());
+    }
+}
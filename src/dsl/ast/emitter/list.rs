@@ -0,0 +1,59 @@
+use pest::iterators::Pair;
+use serde::{Deserialize, Serialize};
+use crate::dsl::parser::parser::Rule;
+use crate::dsl::ast::behavior::oop::Oop;
+use crate::dsl::ast::behavior::sequence::Sequence;
+use crate::dsl::ast::error::{expect_rule, AstBuildError, Children};
+use crate::dsl::ast::span::Span;
+
+/// `[a, b.plus(1)]`: uma lista escrita no código
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct List {
+    pub items: Vec<Oop>,
+    pub span: Span,
+}
+
+impl List {
+    pub fn from_pair(pair: Pair<Rule>) -> Result<Self, AstBuildError> {
+        expect_rule(&pair, Rule::list)?;
+        let span = Span::from_pair(&pair);
+
+        let items = match Children::of(pair).next() {
+            Some(sequence) => Sequence::from_pair(sequence)?.oops,
+            None => Vec::new(),
+        };
+
+        Ok(List { items, span })
+    }
+
+    pub fn from_string(input: String) -> Result<Self, Box<dyn std::error::Error>> {
+        use pest::Parser;
+        use crate::dsl::parser::parser::SCP;
+
+        let mut pairs = SCP::parse(Rule::list, &input)?;
+        let pair = pairs.next().ok_or("No pair found")?;
+        Ok(List::from_pair(pair)?)
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::List;
+
+    #[test]
+    fn test_list_items() {
+        let list = List::from_string("[1, x.plus(2), [true]]".to_string()).expect("Failed to parse list");
+
+        assert_eq!(list.len(), 3);
+        assert_eq!(list.items[1].trails.len(), 1);
+        assert!(List::from_string("[ ]".to_string()).expect("Failed to parse empty list").is_empty());
+    }
+}
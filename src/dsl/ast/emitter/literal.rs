@@ -1,32 +1,58 @@
+use std::fmt;
 use pest::iterators::Pair;
 use serde::{Deserialize, Serialize};
 use crate::dsl::parser::parser::Rule;
+use crate::dsl::ast::error::{AstBuildError, Children};
+use crate::dsl::ast::span::Span;
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Literal {
-    Bit { raw: String },
-    Hex { raw: String },
-    Int { raw: String },
-    Str { raw: String },
-    Decimal { raw: String },
+    /// `value` já decodificado (sem `_` nem sufixo); `width` vem do sufixo (`0b1010u8`)
+    Bit { raw: String, value: i128, width: Option<IntWidth>, span: Span },
+    Hex { raw: String, value: i128, width: Option<IntWidth>, span: Span },
+    Int { raw: String, value: i128, width: Option<IntWidth>, span: Span },
+    Str { raw: String, span: Span },
+    Decimal { raw: String, value: DecimalValue, span: Span },
+    /// `true` ou `false`
+    Bool { raw: String, span: Span },
+    Nil { raw: String, span: Span },
 }
 
 impl Literal {
-    pub fn from_pair(pair: Pair<Rule>) -> Self {
+    pub fn from_pair(pair: Pair<Rule>) -> Result<Self, AstBuildError> {
         // O pair pode ser diretamente uma das regras de literal ou uma regra literal intermediária
         let actual_pair = if pair.as_rule() == Rule::literal {
-            pair.into_inner().next().expect("Literal deve ter uma regra interna")
+            Children::of(pair).require(Rule::literal)?
         } else {
             pair
         };
 
+        let span = Span::from_pair(&actual_pair);
+        let raw = actual_pair.as_str().to_string();
         match actual_pair.as_rule() {
-            Rule::bit => Literal::Bit { raw: actual_pair.as_str().to_string() },
-            Rule::hex => Literal::Hex { raw: actual_pair.as_str().to_string() },
-            Rule::int => Literal::Int { raw: actual_pair.as_str().to_string() },
-            Rule::str => Literal::Str { raw: actual_pair.as_str().to_string() },
-            Rule::decimal => Literal::Decimal { raw: actual_pair.as_str().to_string() },
-            _ => panic!("Tipo de literal inesperado: {:?}", actual_pair.as_rule()),
+            Rule::bit => {
+                let (value, width) = integer(&raw, span)?;
+                Ok(Literal::Bit { raw, value, width, span })
+            }
+            Rule::hex => {
+                let (value, width) = integer(&raw, span)?;
+                Ok(Literal::Hex { raw, value, width, span })
+            }
+            Rule::int => {
+                let (value, width) = integer(&raw, span)?;
+                Ok(Literal::Int { raw, value, width, span })
+            }
+            Rule::str => Ok(Literal::Str { raw, span }),
+            Rule::decimal => match DecimalValue::parse(&raw) {
+                Some(value) => Ok(Literal::Decimal { raw, value, span }),
+                None => Err(AstBuildError::NumberOutOfRange { raw, expected: DecimalValue::RANGE.to_string(), span }),
+            },
+            Rule::boolean => Ok(Literal::Bool { raw, span }),
+            Rule::nil => Ok(Literal::Nil { raw, span }),
+            _ => Err(AstBuildError::unexpected(
+                &actual_pair,
+                &[Rule::bit, Rule::hex, Rule::int, Rule::str, Rule::decimal, Rule::boolean, Rule::nil],
+            )),
         }
     }
 
@@ -36,16 +62,42 @@ impl Literal {
 
         let mut pairs = SCP::parse(Rule::literal, &input)?;
         let pair = pairs.next().ok_or("No pair found")?;
-        Ok(Literal::from_pair(pair))
+        Ok(Literal::from_pair(pair)?)
     }
 
     pub fn get_raw(&self) -> &str {
         match self {
-            Literal::Bit { raw } => raw,
-            Literal::Hex { raw } => raw,
-            Literal::Int { raw } => raw,
-            Literal::Str { raw } => raw,
-            Literal::Decimal { raw } => raw,
+            Literal::Bit { raw, .. } => raw,
+            Literal::Hex { raw, .. } => raw,
+            Literal::Int { raw, .. } => raw,
+            Literal::Str { raw, .. } => raw,
+            Literal::Decimal { raw, .. } => raw,
+            Literal::Bool { raw, .. } => raw,
+            Literal::Nil { raw, .. } => raw,
+        }
+    }
+
+    pub fn span(&self) -> Span {
+        match self {
+            Literal::Bit { span, .. }
+            | Literal::Hex { span, .. }
+            | Literal::Int { span, .. }
+            | Literal::Str { span, .. }
+            | Literal::Decimal { span, .. }
+            | Literal::Bool { span, .. }
+            | Literal::Nil { span, .. } => *span,
+        }
+    }
+
+    pub fn span_mut(&mut self) -> &mut Span {
+        match self {
+            Literal::Bit { span, .. }
+            | Literal::Hex { span, .. }
+            | Literal::Int { span, .. }
+            | Literal::Str { span, .. }
+            | Literal::Decimal { span, .. }
+            | Literal::Bool { span, .. }
+            | Literal::Nil { span, .. } => span,
         }
     }
 
@@ -69,16 +121,25 @@ impl Literal {
         matches!(self, Literal::Decimal { .. })
     }
 
+    pub fn is_bool(&self) -> bool {
+        matches!(self, Literal::Bool { .. })
+    }
+
+    pub fn is_nil(&self) -> bool {
+        matches!(self, Literal::Nil { .. })
+    }
+
     pub fn is_numeric(&self) -> bool {
         matches!(self, Literal::Bit { .. } | Literal::Hex { .. } | Literal::Int { .. } | Literal::Decimal { .. })
     }
 
-    /// Retorna o valor string sem as aspas (apenas para literais string)
+    /// Retorna o valor string sem as aspas e com os escapes resolvidos (apenas
+    /// para literais string)
     pub fn get_string_value(&self) -> Option<String> {
         match self {
-            Literal::Str { raw } => {
+            Literal::Str { raw, .. } => {
                 if raw.len() >= 2 && raw.starts_with('"') && raw.ends_with('"') {
-                    Some(raw[1..raw.len()-1].to_string())
+                    Some(unescape(&raw[1..raw.len()-1]))
                 } else {
                     None
                 }
@@ -87,33 +148,285 @@ impl Literal {
         }
     }
 
-    /// Retorna o valor inteiro (para int, hex e bit)
+    /// Retorna o valor inteiro (para int, hex e bit), se couber em um `i64`;
+    /// um `u64` acima de `i64::MAX` só está disponível em [`Literal::get_integer`]
     pub fn get_int_value(&self) -> Option<i64> {
+        self.get_integer().and_then(|value| i64::try_from(value).ok())
+    }
+
+    /// Valor exato de um int, hex ou bit
+    pub fn get_integer(&self) -> Option<i128> {
         match self {
-            Literal::Int { raw } => raw.parse().ok(),
-            Literal::Hex { raw } => {
-                if raw.starts_with("0x") || raw.starts_with("0X") {
-                    i64::from_str_radix(&raw[2..], 16).ok()
-                } else {
-                    None
-                }
-            },
-            Literal::Bit { raw } => {
-                if raw.starts_with("0b") || raw.starts_with("0B") {
-                    i64::from_str_radix(&raw[2..], 2).ok()
-                } else {
-                    None
-                }
-            },
+            Literal::Int { value, .. } | Literal::Hex { value, .. } | Literal::Bit { value, .. } => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Largura pedida pelo sufixo do literal inteiro (`255u8`)
+    pub fn get_width(&self) -> Option<IntWidth> {
+        match self {
+            Literal::Int { width, .. } | Literal::Hex { width, .. } | Literal::Bit { width, .. } => *width,
             _ => None,
         }
     }
 
     /// Retorna o valor decimal (para decimal)
     pub fn get_decimal_value(&self) -> Option<f64> {
+        self.get_exact_decimal().map(|value| value.to_f64())
+    }
+
+    /// Valor exato de um decimal, sem arredondamento de ponto flutuante
+    pub fn get_exact_decimal(&self) -> Option<DecimalValue> {
         match self {
-            Literal::Decimal { raw } => raw.parse().ok(),
+            Literal::Decimal { value, .. } => Some(*value),
             _ => None,
         }
     }
+
+    /// Retorna o valor booleano (para `true` e `false`)
+    pub fn get_bool_value(&self) -> Option<bool> {
+        match self {
+            Literal::Bool { raw, .. } => raw.parse().ok(),
+            _ => None,
+        }
+    }
+}
+
+/// Largura de um inteiro com sufixo (`255u8`, `-1i32`, `0xFFu16`). Sem
+/// sufixo, o literal precisa caber em um `i64`, o `Int` do interpretador.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum IntWidth {
+    I8,
+    I16,
+    I32,
+    I64,
+    U8,
+    U16,
+    U32,
+    U64,
+}
+
+impl IntWidth {
+    pub const ALL: [IntWidth; 8] = [
+        IntWidth::I8,
+        IntWidth::I16,
+        IntWidth::I32,
+        IntWidth::I64,
+        IntWidth::U8,
+        IntWidth::U16,
+        IntWidth::U32,
+        IntWidth::U64,
+    ];
+
+    pub fn suffix(self) -> &'static str {
+        match self {
+            IntWidth::I8 => "i8",
+            IntWidth::I16 => "i16",
+            IntWidth::I32 => "i32",
+            IntWidth::I64 => "i64",
+            IntWidth::U8 => "u8",
+            IntWidth::U16 => "u16",
+            IntWidth::U32 => "u32",
+            IntWidth::U64 => "u64",
+        }
+    }
+
+    pub fn from_suffix(suffix: &str) -> Option<Self> {
+        IntWidth::ALL.into_iter().find(|width| width.suffix() == suffix)
+    }
+
+    pub fn min(self) -> i128 {
+        match self {
+            IntWidth::I8 => i8::MIN.into(),
+            IntWidth::I16 => i16::MIN.into(),
+            IntWidth::I32 => i32::MIN.into(),
+            IntWidth::I64 => i64::MIN.into(),
+            IntWidth::U8 | IntWidth::U16 | IntWidth::U32 | IntWidth::U64 => 0,
+        }
+    }
+
+    pub fn max(self) -> i128 {
+        match self {
+            IntWidth::I8 => i8::MAX.into(),
+            IntWidth::I16 => i16::MAX.into(),
+            IntWidth::I32 => i32::MAX.into(),
+            IntWidth::I64 => i64::MAX.into(),
+            IntWidth::U8 => u8::MAX.into(),
+            IntWidth::U16 => u16::MAX.into(),
+            IntWidth::U32 => u32::MAX.into(),
+            IntWidth::U64 => u64::MAX.into(),
+        }
+    }
+
+    pub fn contains(self, value: i128) -> bool {
+        (self.min()..=self.max()).contains(&value)
+    }
+}
+
+impl fmt::Display for IntWidth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.suffix())
+    }
+}
+
+/// Separa sinal, base, dígitos (sem `_`) e sufixo de um int, hex ou bit e
+/// confere se o valor cabe na largura pedida.
+fn integer(raw: &str, span: Span) -> Result<(i128, Option<IntWidth>), AstBuildError> {
+    let (body, width) = match raw.find(['i', 'u']) {
+        Some(at) => (&raw[..at], IntWidth::from_suffix(&raw[at..])),
+        None => (raw, None),
+    };
+    let (negative, body) = match body.strip_prefix('-') {
+        Some(body) => (true, body),
+        None => (false, body),
+    };
+    let (radix, digits) = if let Some(digits) = body.strip_prefix("0x") {
+        (16, digits)
+    } else if let Some(digits) = body.strip_prefix("0b") {
+        (2, digits)
+    } else {
+        (10, body)
+    };
+
+    let range = width.unwrap_or(IntWidth::I64);
+    i128::from_str_radix(&digits.replace('_', ""), radix)
+        .ok()
+        .map(|value| if negative { -value } else { value })
+        .filter(|value| range.contains(*value))
+        .map(|value| (value, width))
+        .ok_or(AstBuildError::NumberOutOfRange { raw: raw.to_string(), expected: range.to_string(), span })
+}
+
+/// Decimal exato: `digits * 10^-scale` (`1_000.50` é `100050` com escala 2)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct DecimalValue {
+    pub digits: i128,
+    pub scale: u32,
+}
+
+impl DecimalValue {
+    /// Como o limite aparece nos diagnósticos
+    pub const RANGE: &'static str = "a decimal of at most 38 digits";
+
+    /// Lê um literal decimal (`-3.14`, `1_000.5`); `None` se passar de 38 dígitos
+    pub fn parse(raw: &str) -> Option<Self> {
+        let raw = raw.replace('_', "");
+        let (whole, fraction) = raw.split_once('.').unwrap_or((&raw, ""));
+        let digits = format!("{}{}", whole, fraction).parse().ok()?;
+        Some(DecimalValue { digits, scale: u32::try_from(fraction.len()).ok()? })
+    }
+
+    pub fn to_f64(self) -> f64 {
+        self.to_string().parse().unwrap_or_default()
+    }
+}
+
+impl fmt::Display for DecimalValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let scale = self.scale as usize;
+        let digits = format!("{:0>width$}", self.digits.unsigned_abs(), width = scale + 1);
+        let (whole, fraction) = digits.split_at(digits.len() - scale);
+        let sign = if self.digits < 0 { "-" } else { "" };
+        if fraction.is_empty() {
+            write!(f, "{}{}", sign, whole)
+        } else {
+            write!(f, "{}{}.{}", sign, whole, fraction)
+        }
+    }
+}
+
+/// Resolve os escapes de um trecho de string: `\n`, `\t`, `\r`, `\0`, `\"`,
+/// `\\`, `\#` e `\u{..}`. A gramática só aceita esses; o que não for
+/// reconhecido fica como está.
+pub fn unescape(text: &str) -> String {
+    let mut value = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            value.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => value.push('\n'),
+            Some('t') => value.push('\t'),
+            Some('r') => value.push('\r'),
+            Some('0') => value.push('\0'),
+            Some('u') => {
+                let rest = chars.as_str();
+                let code = rest
+                    .strip_prefix('{')
+                    .and_then(|rest| rest.split_once('}'))
+                    .and_then(|(hex, _)| u32::from_str_radix(hex, 16).ok().map(|code| (hex.len(), code)));
+                match code.and_then(|(len, code)| char::from_u32(code).map(|c| (len, c))) {
+                    Some((len, c)) => {
+                        value.push(c);
+                        chars = rest[len + 2..].chars();
+                    }
+                    None => value.push_str("\\u"),
+                }
+            }
+            Some(other) => value.push(other),
+            None => value.push('\\'),
+        }
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{unescape, DecimalValue, IntWidth, Literal};
+    use crate::dsl::ast::error::AstBuildError;
+
+    #[test]
+    fn test_string_escapes() {
+        let literal = Literal::from_string(r#""diz \"oi\"\t\\ \#{x} \u{e9}\u{1F41B}""#.to_string()).expect("Failed to parse string");
+
+        assert_eq!(literal.get_string_value().as_deref(), Some("diz \"oi\"\t\\ #{x} é🐛"));
+        assert_eq!(unescape(r"a\u{110000}b"), r"a\u{110000}b");
+        assert!(Literal::from_string(r#""\q""#.to_string()).is_err());
+    }
+
+    #[test]
+    fn test_bool_and_nil() {
+        let literal = Literal::from_string("false".to_string()).expect("Failed to parse bool");
+
+        assert_eq!(literal.get_bool_value(), Some(false));
+        assert!(Literal::from_string("nil".to_string()).expect("Failed to parse nil").is_nil());
+    }
+
+    #[test]
+    fn test_numeric_values() {
+        let value = |input: &str| Literal::from_string(input.to_string()).expect("Failed to parse number");
+
+        assert_eq!(value("1_000").get_int_value(), Some(1000));
+        assert_eq!(value("0xFF_FFu16").get_integer(), Some(0xFFFF));
+        assert_eq!(value("0xFF_FFu16").get_width(), Some(IntWidth::U16));
+        assert_eq!(value("0b1010_0101").get_int_value(), Some(0b1010_0101));
+        assert_eq!(value("-128i8").get_int_value(), Some(-128));
+        assert_eq!(value("-9223372036854775808").get_int_value(), Some(i64::MIN));
+        assert_eq!(value("18446744073709551615u64").get_integer(), Some(u64::MAX.into()));
+        assert_eq!(value("18446744073709551615u64").get_int_value(), None);
+
+        let decimal = value("-1_000.050");
+        assert_eq!(decimal.get_exact_decimal(), Some(DecimalValue { digits: -1000050, scale: 3 }));
+        assert_eq!(decimal.get_exact_decimal().map(|d| d.to_string()).as_deref(), Some("-1000.050"));
+        assert_eq!(value("0.25").get_decimal_value(), Some(0.25));
+    }
+
+    #[test]
+    fn test_numeric_overflow() {
+        let error = |input: &str| {
+            let error = Literal::from_string(input.to_string()).expect_err("Number should not fit");
+            match error.downcast_ref::<AstBuildError>() {
+                Some(AstBuildError::NumberOutOfRange { expected, .. }) => expected.clone(),
+                other => panic!("Esperava NumberOutOfRange, veio {:?}", other),
+            }
+        };
+
+        assert_eq!(error("256u8"), "u8");
+        assert_eq!(error("-1u32"), "u32");
+        assert_eq!(error("9223372036854775808"), "i64");
+        assert_eq!(error("0xFFFF_FFFF_FFFF_FFFF"), "i64");
+        assert_eq!(error("1.000000000000000000000000000000000000001"), DecimalValue::RANGE);
+    }
 }
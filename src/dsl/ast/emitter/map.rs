@@ -0,0 +1,70 @@
+use pest::iterators::Pair;
+use serde::{Deserialize, Serialize};
+use crate::dsl::parser::parser::Rule;
+use crate::dsl::ast::behavior::oop::Oop;
+use crate::dsl::ast::behavior::{Bind, Binds};
+use crate::dsl::ast::error::{expect_rule, AstBuildError, Children};
+use crate::dsl::ast::span::Span;
+
+/// `{nome: "Rex", idade: 3}`: um mapa escrito no código. As chaves são
+/// escritas como os argumentos nomeados e viram strings.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Map {
+    pub entries: Vec<Bind>,
+    pub span: Span,
+}
+
+impl Map {
+    pub fn from_pair(pair: Pair<Rule>) -> Result<Self, AstBuildError> {
+        expect_rule(&pair, Rule::map)?;
+        let span = Span::from_pair(&pair);
+
+        let entries = match Children::of(pair).next() {
+            Some(binds) => Binds::from_pair(binds)?.binds,
+            None => Vec::new(),
+        };
+
+        Ok(Map { entries, span })
+    }
+
+    pub fn from_string(input: String) -> Result<Self, Box<dyn std::error::Error>> {
+        use pest::Parser;
+        use crate::dsl::parser::parser::SCP;
+
+        let mut pairs = SCP::parse(Rule::map, &input)?;
+        let pair = pairs.next().ok_or("No pair found")?;
+        Ok(Map::from_pair(pair)?)
+    }
+
+    /// Valor da última entrada com a chave `key`
+    pub fn get(&self, key: &str) -> Option<&Oop> {
+        self.entries.iter().rev().find(|entry| entry.tag.raw == key).map(|entry| &entry.oop)
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().map(|entry| entry.tag.raw.as_str())
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Map;
+
+    #[test]
+    fn test_map_entries() {
+        let map = Map::from_string("{nome: \"Rex\",\n  idade: 3}".to_string()).expect("Failed to parse map");
+
+        assert_eq!(map.keys().collect::<Vec<_>>(), vec!["nome", "idade"]);
+        assert!(map.get("idade").is_some());
+        assert!(map.get("peso").is_none());
+        assert!(Map::from_string("{}".to_string()).expect("Failed to parse empty map").is_empty());
+    }
+}
@@ -1,18 +1,44 @@
 pub mod literal;
 pub mod specie;
+pub mod specie_ref;
 pub mod tag;
 pub mod self_ref;
+pub mod list;
+pub mod map;
+pub mod template;
 
 use serde::{Deserialize, Serialize};
-pub use literal::Literal;
+use crate::dsl::ast::span::Span;
+pub use literal::{DecimalValue, IntWidth, Literal};
 pub use specie::Specie;
+pub use specie_ref::SpecieRef;
 pub use tag::Tag;
 pub use self_ref::SelfRef;
+pub use list::List;
+pub use map::Map;
+pub use template::{Template, TemplatePart};
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Emitter {
     Specie(Specie),
     Tag(Tag),
     Literal(Literal),
+    Template(Template),
     SelfRef(SelfRef),
+    List(List),
+    Map(Map),
+}
+
+impl Emitter {
+    pub fn span(&self) -> Span {
+        match self {
+            Emitter::Specie(specie) => specie.span,
+            Emitter::Tag(tag) => tag.span,
+            Emitter::Literal(literal) => literal.span(),
+            Emitter::Template(template) => template.span,
+            Emitter::SelfRef(self_ref) => self_ref.span,
+            Emitter::List(list) => list.span,
+            Emitter::Map(map) => map.span,
+        }
+    }
 }
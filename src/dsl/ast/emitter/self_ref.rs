@@ -1,18 +1,22 @@
 use pest::iterators::Pair;
 use serde::{Deserialize, Serialize};
 use crate::dsl::parser::parser::Rule;
+use crate::dsl::ast::error::{expect_rule, AstBuildError};
+use crate::dsl::ast::span::Span;
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct SelfRef {
     pub raw: String,
+    pub span: Span,
 }
 
 impl SelfRef {
-    pub fn from_pair(pair: Pair<Rule>) -> Self {
-        assert_eq!(pair.as_rule(), Rule::self_ref);
+    pub fn from_pair(pair: Pair<Rule>) -> Result<Self, AstBuildError> {
+        expect_rule(&pair, Rule::self_ref)?;
+        let span = Span::from_pair(&pair);
         let raw = pair.as_str().to_string();
 
-        SelfRef { raw }
+        Ok(SelfRef { raw, span })
     }
 
     pub fn from_string(input: String) -> Result<Self, Box<dyn std::error::Error>> {
@@ -21,12 +25,13 @@ impl SelfRef {
 
         let mut pairs = SCP::parse(Rule::self_ref, &input)?;
         let pair = pairs.next().ok_or("No pair found")?;
-        Ok(SelfRef::from_pair(pair))
+        Ok(SelfRef::from_pair(pair)?)
     }
 
     pub fn new() -> Self {
         SelfRef {
             raw: "$".to_string(),
+            span: Span::default(),
         }
     }
 
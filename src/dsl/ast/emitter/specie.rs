@@ -1,19 +1,23 @@
 use serde::{Deserialize, Serialize};
+use crate::dsl::ast::error::{expect_rule, AstBuildError};
+use crate::dsl::ast::span::Span;
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Specie {
     // { ASCII_ALPHA_UPPER ~ (ASCII_ALPHANUMERIC | "_")* }
     pub raw: String,
+    pub span: Span,
 }
 
 impl Specie {
     pub fn new(raw: String) -> Self {
-        Self { raw }
+        Self { raw, span: Span::default() }
     }
 
-    pub fn from_pair(pair: pest::iterators::Pair<crate::dsl::parser::parser::Rule>) -> Self {
-        assert_eq!(pair.as_rule(), crate::dsl::parser::parser::Rule::specie);
+    pub fn from_pair(pair: pest::iterators::Pair<crate::dsl::parser::parser::Rule>) -> Result<Self, AstBuildError> {
+        expect_rule(&pair, crate::dsl::parser::parser::Rule::specie)?;
+        let span = Span::from_pair(&pair);
         let raw = pair.as_str().to_string();
-        Self { raw }
+        Ok(Self { raw, span })
     }
 }
@@ -0,0 +1,72 @@
+use pest::iterators::Pair;
+use serde::{Deserialize, Serialize};
+use crate::dsl::parser::parser::Rule;
+use crate::dsl::ast::emitter::Specie;
+use crate::dsl::ast::error::{expect_rule, AstBuildError, Children};
+use crate::dsl::ast::span::Span;
+
+/// Espécie declarada em um gene, parâmetro ou feedback, com os argumentos
+/// de tipo: `Int`, `List<Int>`, `Map<String, Dog>`, `Caixa<T>`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpecieRef {
+    pub name: Specie,
+    pub args: Vec<SpecieRef>,
+    pub span: Span,
+}
+
+impl SpecieRef {
+    /// Espécie sem argumentos, sem posição no código
+    pub fn new(name: String) -> Self {
+        SpecieRef { name: Specie::new(name), args: Vec::new(), span: Span::default() }
+    }
+
+    pub fn from_pair(pair: Pair<Rule>) -> Result<Self, AstBuildError> {
+        expect_rule(&pair, Rule::specie_ref)?;
+        let span = Span::from_pair(&pair);
+
+        let mut inner = Children::of(pair);
+        let name = Specie::from_pair(inner.expect(Rule::specie)?)?;
+        let args = inner.map(SpecieRef::from_pair).collect::<Result<_, _>>()?;
+
+        Ok(SpecieRef { name, args, span })
+    }
+
+    pub fn from_string(input: String) -> Result<Self, Box<dyn std::error::Error>> {
+        use pest::Parser;
+        use crate::dsl::parser::parser::SCP;
+
+        let mut pairs = SCP::parse(Rule::specie_ref, &input)?;
+        let pair = pairs.next().ok_or("No pair found")?;
+        Ok(SpecieRef::from_pair(pair)?)
+    }
+
+    pub fn is_generic(&self) -> bool {
+        !self.args.is_empty()
+    }
+
+    /// Esta espécie e todas as que aparecem nos argumentos, na ordem do código
+    pub fn species(&self) -> Vec<&Specie> {
+        let mut species = vec![&self.name];
+        for arg in &self.args {
+            species.extend(arg.species());
+        }
+        species
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SpecieRef;
+
+    #[test]
+    fn test_nested_arguments() {
+        let specie = SpecieRef::from_string("Map<String, List< Dog >>".to_string()).expect("Failed to parse specie");
+
+        assert_eq!(specie.name.raw, "Map");
+        assert_eq!(specie.args.len(), 2);
+        assert_eq!(specie.args[1].args[0].name.raw, "Dog");
+        let names: Vec<&str> = specie.species().iter().map(|s| s.raw.as_str()).collect();
+        assert_eq!(names, vec!["Map", "String", "List", "Dog"]);
+        assert!(!SpecieRef::from_string("Int".to_string()).expect("Failed to parse specie").is_generic());
+    }
+}
@@ -0,0 +1,100 @@
+use pest::iterators::Pair;
+use serde::{Deserialize, Serialize};
+use crate::dsl::parser::parser::Rule;
+use crate::dsl::ast::behavior::oop::Oop;
+use crate::dsl::ast::emitter::literal::unescape;
+use crate::dsl::ast::error::{expect_rule, AstBuildError, Children};
+use crate::dsl::ast::span::Span;
+
+/// `"energia: #{energia}"`: uma string com oops embutidos. Strings sem `#{`
+/// continuam sendo `Literal::Str`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Template {
+    pub parts: Vec<TemplatePart>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TemplatePart {
+    /// Trecho de texto como escrito, com os escapes
+    Text { raw: String, span: Span },
+    /// O oop de um `#{ }`
+    Oop(Oop),
+}
+
+impl Template {
+    pub fn from_pair(pair: Pair<Rule>) -> Result<Self, AstBuildError> {
+        expect_rule(&pair, Rule::template)?;
+        let span = Span::from_pair(&pair);
+
+        let mut parts = Vec::new();
+        for part in Children::of(pair) {
+            match part.as_rule() {
+                Rule::str_text => parts.push(TemplatePart::Text { raw: part.as_str().to_string(), span: Span::from_pair(&part) }),
+                Rule::interpolation => parts.push(TemplatePart::Oop(Oop::from_pair(Children::of(part).expect(Rule::oop)?)?)),
+                _ => return Err(AstBuildError::unexpected(&part, &[Rule::str_text, Rule::interpolation])),
+            }
+        }
+
+        Ok(Template { parts, span })
+    }
+
+    pub fn from_string(input: String) -> Result<Self, Box<dyn std::error::Error>> {
+        use pest::Parser;
+        use crate::dsl::parser::parser::SCP;
+
+        let mut pairs = SCP::parse(Rule::template, &input)?;
+        let pair = pairs.next().ok_or("No pair found")?;
+        Ok(Template::from_pair(pair)?)
+    }
+
+    /// Oops embutidos, na ordem do texto
+    pub fn oops(&self) -> impl Iterator<Item = &Oop> {
+        self.parts.iter().filter_map(|part| match part {
+            TemplatePart::Oop(oop) => Some(oop),
+            TemplatePart::Text { .. } => None,
+        })
+    }
+}
+
+impl TemplatePart {
+    pub fn span(&self) -> Span {
+        match self {
+            TemplatePart::Text { span, .. } => *span,
+            TemplatePart::Oop(oop) => oop.span,
+        }
+    }
+
+    /// Texto já sem os escapes; `None` para um oop
+    pub fn text(&self) -> Option<String> {
+        match self {
+            TemplatePart::Text { raw, .. } => Some(unescape(raw)),
+            TemplatePart::Oop(_) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Template, TemplatePart};
+
+    #[test]
+    fn test_template_parts() {
+        let template = Template::from_string("\"energia: #{energia.plus(1)}\\n#{ $ }\"".to_string())
+            .expect("Failed to parse template");
+
+        assert_eq!(template.parts.len(), 4);
+        assert_eq!(template.parts[0].text().as_deref(), Some("energia: "));
+        assert_eq!(template.parts[2].text().as_deref(), Some("\n"));
+        assert!(matches!(&template.parts[3], TemplatePart::Oop(oop) if oop.span.col == 34));
+        assert_eq!(template.oops().count(), 2);
+    }
+
+    #[test]
+    fn test_nested_strings() {
+        let template = Template::from_string("\"#{nome.plus(\"!\")}\"".to_string()).expect("Failed to parse template");
+
+        assert_eq!(template.parts.len(), 1);
+        assert!(Template::from_string("\"sem oops\"".to_string()).is_err());
+    }
+}
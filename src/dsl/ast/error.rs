@@ -0,0 +1,154 @@
+use pest::iterators::{Pair, Pairs};
+use thiserror::Error;
+use crate::dsl::ast::span::Span;
+use crate::dsl::parser::parser::Rule;
+
+/// Falha ao montar um nó da AST a partir da árvore do pest.
+///
+/// Fora [`AstBuildError::NumberOutOfRange`], só acontece quando a gramática e
+/// os construtores `from_pair` divergem, por isso carrega as regras envolvidas
+/// e a posição do problema.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum AstBuildError {
+    #[error("regra inesperada {found:?} em {span}, esperado {}", rules(expected))]
+    UnexpectedRule {
+        found: Rule,
+        expected: Vec<Rule>,
+        span: Span,
+    },
+    #[error("regra {expected:?} ausente em {parent:?} ({span})")]
+    MissingRule {
+        expected: Rule,
+        parent: Rule,
+        span: Span,
+    },
+    /// Literal numérico que não cabe em `expected` (`256u8`, inteiro sem sufixo além de `i64`)
+    #[error("literal {raw} não cabe em {expected} ({span})")]
+    NumberOutOfRange {
+        raw: String,
+        expected: String,
+        span: Span,
+    },
+}
+
+impl AstBuildError {
+    pub fn unexpected(pair: &Pair<Rule>, expected: &[Rule]) -> Self {
+        AstBuildError::UnexpectedRule {
+            found: pair.as_rule(),
+            expected: expected.to_vec(),
+            span: Span::from_pair(pair),
+        }
+    }
+
+    pub fn span(&self) -> Span {
+        match self {
+            AstBuildError::UnexpectedRule { span, .. } => *span,
+            AstBuildError::MissingRule { span, .. } => *span,
+            AstBuildError::NumberOutOfRange { span, .. } => *span,
+        }
+    }
+}
+
+fn rules(expected: &[Rule]) -> String {
+    expected
+        .iter()
+        .map(|rule| format!("{:?}", rule))
+        .collect::<Vec<_>>()
+        .join(" | ")
+}
+
+/// Garante que o par é da regra esperada.
+pub fn expect_rule(pair: &Pair<Rule>, expected: Rule) -> Result<(), AstBuildError> {
+    if pair.as_rule() == expected {
+        Ok(())
+    } else {
+        Err(AstBuildError::unexpected(pair, &[expected]))
+    }
+}
+
+/// Filhos de um par, lembrando a regra e o span do pai para reportar ausências.
+///
+/// O span do pai só vira [`Span`] se algum filho faltar.
+pub struct Children<'i> {
+    pairs: Pairs<'i, Rule>,
+    parent: Rule,
+    span: pest::Span<'i>,
+}
+
+impl<'i> Children<'i> {
+    pub fn of(pair: Pair<'i, Rule>) -> Self {
+        let parent = pair.as_rule();
+        let span = pair.as_span();
+        Children { pairs: pair.into_inner(), parent, span }
+    }
+
+    /// Próximo filho, que precisa existir e ser da regra `expected`.
+    pub fn expect(&mut self, expected: Rule) -> Result<Pair<'i, Rule>, AstBuildError> {
+        let pair = self.require(expected)?;
+        expect_rule(&pair, expected)?;
+        Ok(pair)
+    }
+
+    /// Próximo filho de qualquer regra; `expected` só descreve o erro de ausência.
+    pub fn require(&mut self, expected: Rule) -> Result<Pair<'i, Rule>, AstBuildError> {
+        self.pairs.next().ok_or_else(|| AstBuildError::MissingRule {
+            expected,
+            parent: self.parent,
+            span: Span::from_pest(&self.span),
+        })
+    }
+}
+
+impl<'i> Iterator for Children<'i> {
+    type Item = Pair<'i, Rule>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.pairs.next()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pest::Parser;
+    use crate::dsl::ast::span::Span;
+    use crate::dsl::ast::bug::Bug;
+    use crate::dsl::parser::parser::{Rule, SCP};
+    use super::{AstBuildError, Children};
+
+    #[test]
+    fn test_unexpected_rule_is_reported() {
+        // Um par de gene entregue ao construtor de bug não deve derrubar o processo
+        let pair = SCP::parse(Rule::gene, "gene x Int")
+            .expect("Failed to parse gene")
+            .next()
+            .expect("No pair found");
+
+        let error = Bug::from_pair(pair).expect_err("Bug não pode ser montado a partir de gene");
+
+        match error {
+            AstBuildError::UnexpectedRule { found, expected, span } => {
+                assert_eq!(found, Rule::gene);
+                assert_eq!(expected, vec![Rule::bug]);
+                assert_eq!((span.line, span.col), (1, 1));
+            }
+            other => panic!("Esperava UnexpectedRule, veio {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_missing_rule_is_reported() {
+        // Um tag não tem filhos: pedir uma espécie dentro dele deve virar MissingRule
+        let pair = SCP::parse(Rule::tag, "energia")
+            .expect("Failed to parse tag")
+            .next()
+            .expect("No pair found");
+
+        let error = Children::of(pair).expect(Rule::specie).expect_err("tag não tem filhos");
+
+        assert_eq!(
+            error,
+            AstBuildError::MissingRule { expected: Rule::specie, parent: Rule::tag, span: Span::new(0, 7, 1, 1) }
+        );
+        assert_eq!(error.to_string(), "regra specie ausente em tag (1:1)");
+    }
+}
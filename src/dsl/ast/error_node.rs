@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+use crate::dsl::ast::span::Span;
+
+/// Trecho que não pôde ser analisado no modo de recuperação.
+///
+/// Ocupa o lugar do genoma (ou membro de bug) quebrado para que o resto da
+/// árvore continue utilizável; `message` resume o diagnóstico correspondente.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ErrorNode {
+    pub raw: String,
+    pub message: String,
+    pub span: Span,
+}
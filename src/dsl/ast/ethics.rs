@@ -1,57 +1,69 @@
 use pest::iterators::Pair;
 use serde::{Deserialize, Serialize};
 use crate::dsl::parser::parser::Rule;
-use crate::dsl::ast::emitter::{Tag, Specie};
+use crate::dsl::ast::emitter::{Tag, SpecieRef};
 use crate::dsl::ast::behavior::bind::EthicsBind;
 use crate::dsl::ast::matrix::Matrix;
+use crate::dsl::ast::error::{expect_rule, AstBuildError};
+use crate::dsl::ast::span::Span;
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Ethics {
     pub tag: Tag,
     pub signature: Option<Signature>,
-    pub feedback: Option<Specie>,
+    pub feedback: Option<SpecieRef>,
     pub body: Option<Matrix>,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Signature {
     pub binds: Option<Vec<EthicsBind>>,
+    pub span: Span,
 }
 
 impl Ethics {
-    pub fn from_pair(pair: Pair<Rule>) -> Self {
-        assert_eq!(pair.as_rule(), Rule::ethics);
+    pub fn from_pair(pair: Pair<Rule>) -> Result<Self, AstBuildError> {
+        expect_rule(&pair, Rule::ethics)?;
+        let span = Span::from_pair(&pair);
 
-        let mut inner = pair.into_inner();
+        let inner = pair.into_inner();
         let mut tag = None;
         let mut signature = None;
         let mut feedback = None;
         let mut body = None;
 
-        while let Some(inner_pair) = inner.next() {
+        for inner_pair in inner {
             match inner_pair.as_rule() {
                 Rule::tag => {
-                    tag = Some(Tag::new(inner_pair.as_str().to_string()));
+                    tag = Some(Tag::from_pair(inner_pair)?);
                 }
                 Rule::signature => {
-                    signature = Some(Signature::from_pair(inner_pair));
+                    signature = Some(Signature::from_pair(inner_pair)?);
                 }
-                Rule::specie => {
-                    feedback = Some(Specie::from_pair(inner_pair));
+                Rule::specie_ref => {
+                    feedback = Some(SpecieRef::from_pair(inner_pair)?);
                 }
                 Rule::matrix => {
-                    body = Some(Matrix::from_pair(inner_pair));
+                    body = Some(Matrix::from_pair(inner_pair)?);
+                }
+                _ => {
+                    return Err(AstBuildError::unexpected(
+                        &inner_pair,
+                        &[Rule::tag, Rule::signature, Rule::specie_ref, Rule::matrix],
+                    ));
                 }
-                _ => { panic!("Unexpected rule in ethics: {:?}", inner_pair.as_rule()); }
             }
         }
 
-        Ethics {
-            tag: tag.expect("Ethics must have a tag"),
+        let tag = tag.ok_or(AstBuildError::MissingRule { expected: Rule::tag, parent: Rule::ethics, span })?;
+        Ok(Ethics {
+            tag,
             signature,
             feedback,
-            body
-        }
+            body,
+            span,
+        })
     }
 
     pub fn from_string(input: String) -> Result<Self, Box<dyn std::error::Error>> {
@@ -60,13 +72,14 @@ impl Ethics {
 
         let mut pairs = SCP::parse(Rule::ethics, &input)?;
         let pair = pairs.next().ok_or("No pair found")?;
-        Ok(Ethics::from_pair(pair))
+        Ok(Ethics::from_pair(pair)?)
     }
 }
 
 impl Signature {
-    pub fn from_pair(pair: Pair<Rule>) -> Self {
-        assert_eq!(pair.as_rule(), Rule::signature);
+    pub fn from_pair(pair: Pair<Rule>) -> Result<Self, AstBuildError> {
+        expect_rule(&pair, Rule::signature)?;
+        let span = Span::from_pair(&pair);
 
         let mut ethics_binds = Vec::new();
         for inner_pair in pair.into_inner() {
@@ -74,18 +87,18 @@ impl Signature {
                 Rule::ethics_binds => {
                     for ethics_bind_pair in inner_pair.into_inner() {
                         if ethics_bind_pair.as_rule() == Rule::ethics_bind {
-                            ethics_binds.push(EthicsBind::from_pair(ethics_bind_pair));
+                            ethics_binds.push(EthicsBind::from_pair(ethics_bind_pair)?);
                         }
                     }
                 }
                 Rule::ethics_bind => {
-                    ethics_binds.push(EthicsBind::from_pair(inner_pair));
+                    ethics_binds.push(EthicsBind::from_pair(inner_pair)?);
                 }
                 _ => {}
             }
         }
         let binds = if ethics_binds.is_empty() { None } else { Some(ethics_binds) };
-        Signature { binds }
+        Ok(Signature { binds, span })
     }
 }
 
@@ -118,6 +131,42 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_ethics_with_self_ref() {
+        use crate::dsl::ast::behavior::{Behavior, Trail};
+        use crate::dsl::ast::emitter::Emitter;
+        use crate::dsl::ast::signal::Signal;
+
+        let input = fs::read_to_string("tests/fixtures/fragments/ethics/ethics_self_ref.sc").expect("Failed to read ethics_self_ref fragment");
+        let ethics = Ethics::from_string(input.clone()).expect("Failed to parse ethics");
+
+        let body = ethics.body.expect("Ethics deve ter um corpo");
+        assert_eq!(body.signals.len(), 4);
+
+        // `$.energia.plus(horas)` e `$.latir(...)` começam pelo próprio bug
+        for signal in &body.signals[..2] {
+            let Signal::Behavior(Behavior::Oop(oop)) = signal else {
+                panic!("Esperava um oop, veio {:?}", signal);
+            };
+            assert!(matches!(oop.emitter, Emitter::SelfRef(_)));
+            assert!(matches!(oop.trails[0], Trail::Catalysis(_)));
+        }
+
+        // `dono = $` e `$` sozinho
+        let Signal::Behavior(Behavior::Assign(assign)) = &body.signals[2] else {
+            panic!("Esperava uma atribuição");
+        };
+        assert!(matches!(assign.oop.emitter, Emitter::SelfRef(_)));
+        let Signal::Behavior(Behavior::Oop(oop)) = &body.signals[3] else {
+            panic!("Esperava um oop");
+        };
+        let Emitter::SelfRef(self_ref) = &oop.emitter else {
+            panic!("Esperava $");
+        };
+        assert!(oop.trails.is_empty());
+        assert_eq!((self_ref.span.line, self_ref.span.col), (5, 3));
+    }
+
     #[test]
     fn test_simple_ethics() {
         let input = fs::read_to_string("tests/fixtures/fragments/ethics/simple_ethics.sc").expect("Failed to read simple_ethics fragment");
@@ -140,7 +189,7 @@ mod tests {
         assert!(ethics.body.is_none());
 
         let feedback = ethics.feedback.as_ref().expect("Should have feedback");
-        assert_eq!(feedback.raw, "String");
+        assert_eq!(feedback.name.raw, "String");
     }
 
     #[test]
@@ -159,7 +208,7 @@ mod tests {
         let binds = signature.binds.as_ref().expect("Should have binds");
         assert_eq!(binds.len(), 1);
         assert_eq!(binds[0].tag.raw, "param1");
-        assert_eq!(binds[0].specie.raw, "Int");
+        assert_eq!(binds[0].specie.name.raw, "Int");
     }
 
     #[test]
@@ -174,11 +223,11 @@ mod tests {
         // Test individual bind methods
         let first_bind = &binds[0];
         assert_eq!(first_bind.tag.raw, "param1");
-        assert_eq!(first_bind.specie.raw, "Int");
+        assert_eq!(first_bind.specie.name.raw, "Int");
 
         let second_bind = &binds[1];
         assert_eq!(second_bind.tag.raw, "param2");
-        assert_eq!(second_bind.specie.raw, "String");
+        assert_eq!(second_bind.specie.name.raw, "String");
     }
 
     #[test]
@@ -200,7 +249,7 @@ mod tests {
 
         // Test feedback
         let feedback = ethics.feedback.as_ref().expect("Should have feedback");
-        assert_eq!(feedback.raw, "Boolean");
+        assert_eq!(feedback.name.raw, "Boolean");
     }
 
     #[test]
@@ -224,7 +273,7 @@ mod tests {
         let feedback = ethics.feedback.as_ref().expect("Should have feedback");
 
         // Test feedback methods
-        assert_eq!(feedback.raw, "Boolean");
+        assert_eq!(feedback.name.raw, "Boolean");
     }
 
     #[test]
@@ -270,9 +319,7 @@ mod tests {
         // Podemos verificar mais detalhes dos sinais quando necessário:
         for (i, signal) in body.signals.iter().enumerate() {
             // Como nosso Signal enum atualmente só tem uma variante (Behavior), podemos usar um match direto
-            let behavior = match signal {
-                crate::dsl::ast::signal::Signal::Behavior(b) => b
-            };
+            let crate::dsl::ast::signal::Signal::Behavior(behavior) = signal;
 
             // Verifica se o comportamento é do tipo Assign
             match behavior {
@@ -0,0 +1,353 @@
+use std::sync::LazyLock;
+use pest::iterators::{Pair, Pairs};
+use pest::pratt_parser::{Assoc, Op, PrattParser};
+use serde::{Deserialize, Serialize};
+use crate::dsl::ast::behavior::oop::Oop;
+use crate::dsl::ast::behavior::sequence::Sequence;
+use crate::dsl::ast::behavior::transport::Transport;
+use crate::dsl::ast::behavior::{Carrier, Catalysis, Trail};
+use crate::dsl::ast::emitter::Tag;
+use crate::dsl::ast::error::{expect_rule, AstBuildError, Children};
+use crate::dsl::ast::span::Span;
+use crate::dsl::parser::parser::Rule;
+
+/// Expressão com operadores, usada nas condições de `if`/`elsif`/`while`.
+///
+/// Based on the grammar: expr = { prefix* ~ primary ~ (infix ~ prefix* ~ primary)* }
+/// As folhas são `Oop`; parênteses só agrupam e não geram nó próprio.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Expr {
+    Oop(Oop),
+    Binary {
+        op: BinaryOp,
+        left: Box<Expr>,
+        right: Box<Expr>,
+        span: Span,
+    },
+    Unary {
+        op: UnaryOp,
+        operand: Box<Expr>,
+        span: Span,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BinaryOp {
+    Or,
+    And,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum UnaryOp {
+    Not,
+    Neg,
+}
+
+impl BinaryOp {
+    fn from_rule(rule: Rule) -> Option<Self> {
+        match rule {
+            Rule::op_or => Some(BinaryOp::Or),
+            Rule::op_and => Some(BinaryOp::And),
+            Rule::op_eq => Some(BinaryOp::Eq),
+            Rule::op_ne => Some(BinaryOp::Ne),
+            Rule::op_lt => Some(BinaryOp::Lt),
+            Rule::op_le => Some(BinaryOp::Le),
+            Rule::op_gt => Some(BinaryOp::Gt),
+            Rule::op_ge => Some(BinaryOp::Ge),
+            Rule::op_add => Some(BinaryOp::Add),
+            Rule::op_sub => Some(BinaryOp::Sub),
+            Rule::op_mul => Some(BinaryOp::Mul),
+            Rule::op_div => Some(BinaryOp::Div),
+            Rule::op_mod => Some(BinaryOp::Mod),
+            _ => None,
+        }
+    }
+
+    /// Como o operador aparece no código
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            BinaryOp::Or => "or",
+            BinaryOp::And => "and",
+            BinaryOp::Eq => "==",
+            BinaryOp::Ne => "!=",
+            BinaryOp::Lt => "<",
+            BinaryOp::Le => "<=",
+            BinaryOp::Gt => ">",
+            BinaryOp::Ge => ">=",
+            BinaryOp::Add => "+",
+            BinaryOp::Sub => "-",
+            BinaryOp::Mul => "*",
+            BinaryOp::Div => "/",
+            BinaryOp::Mod => "%",
+        }
+    }
+
+    /// Método equivalente na forma `Oop`: `a + b` vira `a.plus(b)`
+    pub fn method(&self) -> &'static str {
+        match self {
+            BinaryOp::Or => "or",
+            BinaryOp::And => "and",
+            BinaryOp::Eq => "eq",
+            BinaryOp::Ne => "ne",
+            BinaryOp::Lt => "lt",
+            BinaryOp::Le => "le",
+            BinaryOp::Gt => "bt",
+            BinaryOp::Ge => "be",
+            BinaryOp::Add => "plus",
+            BinaryOp::Sub => "minus",
+            BinaryOp::Mul => "times",
+            BinaryOp::Div => "div",
+            BinaryOp::Mod => "mod",
+        }
+    }
+
+    /// Precedência, do menor (1, `or`) ao maior (6, `*`); todos associam à esquerda
+    pub fn precedence(&self) -> u8 {
+        match self {
+            BinaryOp::Or => 1,
+            BinaryOp::And => 2,
+            BinaryOp::Eq | BinaryOp::Ne => 3,
+            BinaryOp::Lt | BinaryOp::Le | BinaryOp::Gt | BinaryOp::Ge => 4,
+            BinaryOp::Add | BinaryOp::Sub => 5,
+            BinaryOp::Mul | BinaryOp::Div | BinaryOp::Mod => 6,
+        }
+    }
+}
+
+impl UnaryOp {
+    fn from_rule(rule: Rule) -> Option<Self> {
+        match rule {
+            Rule::op_not => Some(UnaryOp::Not),
+            Rule::op_neg => Some(UnaryOp::Neg),
+            _ => None,
+        }
+    }
+
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            UnaryOp::Not => "not",
+            UnaryOp::Neg => "-",
+        }
+    }
+
+    /// Método equivalente na forma `Oop`: `not a` vira `a.not`, `-a` vira `a.neg`
+    pub fn method(&self) -> &'static str {
+        match self {
+            UnaryOp::Not => "not",
+            UnaryOp::Neg => "neg",
+        }
+    }
+
+    /// `not` fica abaixo das comparações (`not a == b` é `not (a == b)`);
+    /// `-` fica acima de tudo
+    pub fn precedence(&self) -> u8 {
+        match self {
+            UnaryOp::Not => 2,
+            UnaryOp::Neg => 7,
+        }
+    }
+}
+
+static PRATT: LazyLock<PrattParser<Rule>> = LazyLock::new(|| {
+    PrattParser::new()
+        .op(Op::infix(Rule::op_or, Assoc::Left))
+        .op(Op::infix(Rule::op_and, Assoc::Left))
+        .op(Op::prefix(Rule::op_not))
+        .op(Op::infix(Rule::op_eq, Assoc::Left) | Op::infix(Rule::op_ne, Assoc::Left))
+        .op(Op::infix(Rule::op_lt, Assoc::Left)
+            | Op::infix(Rule::op_le, Assoc::Left)
+            | Op::infix(Rule::op_gt, Assoc::Left)
+            | Op::infix(Rule::op_ge, Assoc::Left))
+        .op(Op::infix(Rule::op_add, Assoc::Left) | Op::infix(Rule::op_sub, Assoc::Left))
+        .op(Op::infix(Rule::op_mul, Assoc::Left)
+            | Op::infix(Rule::op_div, Assoc::Left)
+            | Op::infix(Rule::op_mod, Assoc::Left))
+        .op(Op::prefix(Rule::op_neg))
+});
+
+impl Expr {
+    pub fn from_pair(pair: Pair<Rule>) -> Result<Self, AstBuildError> {
+        expect_rule(&pair, Rule::expr)?;
+        Expr::climb(pair.into_inner())
+    }
+
+    fn climb(pairs: Pairs<Rule>) -> Result<Self, AstBuildError> {
+        PRATT
+            .map_primary(|primary| match primary.as_rule() {
+                Rule::oop => Ok(Expr::Oop(Oop::from_pair(primary)?)),
+                Rule::group => Expr::from_pair(Children::of(primary).expect(Rule::expr)?),
+                _ => Err(AstBuildError::unexpected(&primary, &[Rule::oop, Rule::group])),
+            })
+            .map_prefix(|op, operand| {
+                let operand = operand?;
+                let span = Span::from_pair(&op).merge(&operand.span());
+                let op = UnaryOp::from_rule(op.as_rule())
+                    .ok_or_else(|| AstBuildError::unexpected(&op, &[Rule::op_not, Rule::op_neg]))?;
+                Ok(Expr::Unary { op, operand: Box::new(operand), span })
+            })
+            .map_infix(|left, op, right| {
+                let (left, right) = (left?, right?);
+                let span = left.span().merge(&right.span());
+                let op = BinaryOp::from_rule(op.as_rule())
+                    .ok_or_else(|| AstBuildError::unexpected(&op, &[Rule::op_add]))?;
+                Ok(Expr::Binary { op, left: Box::new(left), right: Box::new(right), span })
+            })
+            .parse(pairs)
+    }
+
+    pub fn from_string(input: String) -> Result<Self, Box<dyn std::error::Error>> {
+        use pest::Parser;
+        use crate::dsl::parser::parser::SCP;
+
+        let mut pairs = SCP::parse(Rule::expr, &input)?;
+        let pair = pairs.next().ok_or("No pair found")?;
+        Ok(Expr::from_pair(pair)?)
+    }
+
+    pub fn span(&self) -> Span {
+        match self {
+            Expr::Oop(oop) => oop.span,
+            Expr::Binary { span, .. } | Expr::Unary { span, .. } => *span,
+        }
+    }
+
+    pub fn is_oop(&self) -> bool {
+        matches!(self, Expr::Oop(_))
+    }
+
+    /// Reescreve a expressão como chamadas de método, para backends que só
+    /// entendem `Catalysis`: `a + b * c` vira `a.plus(b.times(c))`.
+    ///
+    /// `and`/`or` viram `.and(...)`/`.or(...)` e perdem o curto-circuito.
+    pub fn desugar(&self) -> Oop {
+        match self {
+            Expr::Oop(oop) => oop.clone(),
+            Expr::Binary { op, left, right, span } => {
+                let right = right.desugar();
+                let argument = Sequence { span: right.span, oops: vec![right] };
+                let carrier = Carrier { transport: Some(Transport::Sequence(argument)), span: *span };
+                call(left.desugar(), op.method(), Some(carrier), *span)
+            }
+            Expr::Unary { op, operand, span } => call(operand.desugar(), op.method(), None, *span),
+        }
+    }
+}
+
+fn call(mut receiver: Oop, method: &str, carrier: Option<Carrier>, span: Span) -> Oop {
+    let tag = Tag { raw: method.to_string(), span };
+    receiver.trails.push(Trail::Catalysis(Catalysis { tag, carrier, span }));
+    receiver.span = span;
+    receiver
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BinaryOp, Expr, UnaryOp};
+    use crate::dsl::ast::behavior::oop::Oop;
+    use crate::dsl::ast::behavior::Trail;
+    use crate::dsl::ast::emitter::Emitter;
+
+    fn tree(expr: &Expr) -> String {
+        match expr {
+            Expr::Oop(oop) => oop_text(oop),
+            Expr::Binary { op, left, right, .. } => format!("({} {} {})", tree(left), op.symbol(), tree(right)),
+            Expr::Unary { op, operand, .. } => format!("({} {})", op.symbol(), tree(operand)),
+        }
+    }
+
+    fn oop_text(oop: &Oop) -> String {
+        let mut text = match &oop.emitter {
+            Emitter::Specie(specie) => specie.raw.clone(),
+            Emitter::Tag(tag) => tag.raw.clone(),
+            Emitter::Literal(literal) => literal.get_raw().to_string(),
+            Emitter::SelfRef(_) => "$".to_string(),
+            Emitter::List(list) => format!("[{}]", list.items.iter().map(oop_text).collect::<Vec<_>>().join(", ")),
+            Emitter::Map(_) => "{..}".to_string(),
+            Emitter::Template(_) => "\"..\"".to_string(),
+        };
+        for trail in &oop.trails {
+            if let Trail::Catalysis(catalysis) = trail {
+                text.push('.');
+                text.push_str(&catalysis.tag.raw);
+                if let Some(carrier) = &catalysis.carrier {
+                    let args: Vec<String> = carrier
+                        .transport
+                        .iter()
+                        .flat_map(|t| t.as_sequence().map(|s| s.oops.clone()).unwrap_or_default())
+                        .map(|oop| oop_text(&oop))
+                        .collect();
+                    text.push_str(&format!("({})", args.join(", ")));
+                }
+            }
+        }
+        text
+    }
+
+    #[test]
+    fn test_precedence() {
+        let expr = Expr::from_string("a + b * c == d or not e and f".to_string()).expect("Should parse expr");
+
+        assert_eq!(tree(&expr), "(((a + (b * c)) == d) or ((not e) and f))");
+    }
+
+    #[test]
+    fn test_left_associative_and_groups() {
+        let expr = Expr::from_string("a - b - c".to_string()).expect("Should parse expr");
+        assert_eq!(tree(&expr), "((a - b) - c)");
+
+        let expr = Expr::from_string("a - (b - c)".to_string()).expect("Should parse expr");
+        assert_eq!(tree(&expr), "(a - (b - c))");
+    }
+
+    #[test]
+    fn test_prefix_operators() {
+        let expr = Expr::from_string("-x * 2 < 0".to_string()).expect("Should parse expr");
+        assert_eq!(tree(&expr), "(((- x) * 2) < 0)");
+
+        // `-1` continua sendo um literal inteiro
+        let expr = Expr::from_string("y - -1".to_string()).expect("Should parse expr");
+        assert_eq!(tree(&expr), "(y - -1)");
+
+        let expr = Expr::from_string("not a == b".to_string()).expect("Should parse expr");
+        assert!(matches!(expr, Expr::Unary { op: UnaryOp::Not, .. }));
+    }
+
+    #[test]
+    fn test_operator_spans() {
+        let expr = Expr::from_string("energia.minus(1) >= 10".to_string()).expect("Should parse expr");
+
+        let Expr::Binary { op, left, span, .. } = &expr else {
+            panic!("Esperava expressão binária");
+        };
+        assert_eq!(*op, BinaryOp::Ge);
+        assert_eq!((span.start, span.end), (0, 22));
+        assert_eq!(left.span().end, 16);
+    }
+
+    #[test]
+    fn test_desugar_to_method_calls() {
+        let expr = Expr::from_string("energia.minus(1) + b * c >= 10".to_string()).expect("Should parse expr");
+
+        let oop = expr.desugar();
+
+        assert_eq!(oop_text(&oop), "energia.minus(1).plus(b.times(c)).be(10)");
+    }
+
+    #[test]
+    fn test_desugar_unary() {
+        let expr = Expr::from_string("not -x".to_string()).expect("Should parse expr");
+
+        assert_eq!(oop_text(&expr.desugar()), "x.neg.not");
+    }
+}
@@ -2,30 +2,30 @@ use pest::iterators::Pair;
 use pest::Parser;
 use serde::{Deserialize, Serialize};
 use crate::dsl::ast::strand::Strand;
+use crate::dsl::ast::error::{expect_rule, AstBuildError, Children};
+use crate::dsl::ast::span::{with_line_table, Span};
 use crate::dsl::parser::parser::{Rule, SCP};
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Fly {
     pub strand: Strand,
+    pub span: Span,
 }
 
 impl Fly {
-    pub fn from_pair(pair: Pair<Rule>) -> Self {
-        assert_eq!(pair.as_rule(), Rule::fly);
+    pub fn from_pair(pair: Pair<Rule>) -> Result<Self, AstBuildError> {
+        expect_rule(&pair, Rule::fly)?;
+        let span = Span::from_pair(&pair);
 
-        let strand_pair = pair.into_inner().next().expect("Fly should have a strand");
-        assert_eq!(strand_pair.as_rule(), Rule::strand);
-        let strand = Strand::from_pair(strand_pair);
+        let strand = Strand::from_pair(Children::of(pair).expect(Rule::strand)?)?;
 
-        Fly { strand }
+        Ok(Fly { strand, span })
     }
 
-    pub fn from_string(input: String) -> Self {
-        let pair = SCP::parse(Rule::fly, &input)
-            .expect("Failed to parse input")
-            .next()
-            .expect("No pair found");
-        Fly::from_pair(pair)
+    pub fn from_string(input: String) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut pairs = SCP::parse(Rule::fly, &input)?;
+        let pair = pairs.next().ok_or("No pair found")?;
+        Ok(with_line_table(&input, || Fly::from_pair(pair))?)
     }
 }
 
@@ -45,7 +45,7 @@ mod tests {
         assert!(!input.is_empty(), "Fixture file should not be empty");
 
         // Testa o parse
-        let fly = Fly::from_string(input.clone());
+        let fly = Fly::from_string(input.clone()).expect("Should parse");
 
         // Verifica se o strand contém dois genomes
         assert_eq!(fly.strand.genome.len(), 2, "Strand should contain two genomes");
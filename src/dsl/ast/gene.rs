@@ -2,40 +2,33 @@ use pest::iterators::Pair;
 use pest::Parser;
 use serde::{Deserialize, Serialize};
 use crate::dsl::parser::parser::{Rule, SCP};
-use crate::dsl::ast::emitter::{Tag, Specie};
+use crate::dsl::ast::emitter::{Tag, SpecieRef};
+use crate::dsl::ast::error::{expect_rule, AstBuildError, Children};
+use crate::dsl::ast::span::Span;
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Gene {
     pub tag: Tag,
-    pub specie: Specie,
+    pub specie: SpecieRef,
+    pub span: Span,
 }
 
 impl Gene {
-    pub fn from_pair(pair: Pair<Rule>) -> Self {
-        assert_eq!(pair.as_rule(), Rule::gene);
+    pub fn from_pair(pair: Pair<Rule>) -> Result<Self, AstBuildError> {
+        expect_rule(&pair, Rule::gene)?;
+        let span = Span::from_pair(&pair);
 
-        let mut inner = pair.into_inner();
+        let mut inner = Children::of(pair);
 
-        let tag_str = inner.next()
-            .expect("Gene deve ter uma tag")
-            .as_str()
-            .to_string();
-        let tag = Tag::new(tag_str);
+        let tag = Tag::from_pair(inner.expect(Rule::tag)?)?;
+        let specie = SpecieRef::from_pair(inner.expect(Rule::specie_ref)?)?;
 
-        let specie_str = inner.next()
-            .expect("Gene deve ter uma espécie")
-            .as_str()
-            .to_string();
-        let specie = Specie::new(specie_str);
-
-        Gene { tag, specie }
+        Ok(Gene { tag, specie, span })
     }
 
-    pub fn from_string(input: String) -> Self {
-        let pair = SCP::parse(Rule::gene, &input)
-            .expect("Failed to parse input")
-            .next()
-            .expect("No pair found");
-        Gene::from_pair(pair)
+    pub fn from_string(input: String) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut pairs = SCP::parse(Rule::gene, &input)?;
+        let pair = pairs.next().ok_or("No pair found")?;
+        Ok(Gene::from_pair(pair)?)
     }
 }
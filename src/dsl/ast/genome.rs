@@ -3,30 +3,48 @@ use pest::Parser;
 use serde::{Deserialize, Serialize};
 use crate::dsl::ast::anatomy::Anatomy;
 use crate::dsl::ast::behavior::Behavior;
+use crate::dsl::ast::error_node::ErrorNode;
+use crate::dsl::ast::import::Import;
+use crate::dsl::ast::error::{expect_rule, AstBuildError, Children};
+use crate::dsl::ast::span::Span;
 use crate::dsl::parser::parser::{Rule, SCP};
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[allow(clippy::large_enum_variant)]
 pub enum Genome {
+    Import(Import),
     Anatomy(Anatomy),
     Behavior(Behavior),
+    /// Só aparece em árvores produzidas por `Tree::parse_recovering`
+    Error(ErrorNode),
 }
 
 impl Genome {
-    pub fn from_pair(pair: Pair<Rule>) -> Self {
-        assert_eq!(pair.as_rule(), Rule::genome);
+    pub fn from_pair(pair: Pair<Rule>) -> Result<Self, AstBuildError> {
+        expect_rule(&pair, Rule::genome)?;
 
-        let inner_pair = pair.into_inner().next().unwrap();
+        let inner_pair = Children::of(pair).require(Rule::anatomy)?;
         match inner_pair.as_rule() {
-            Rule::anatomy => Genome::Anatomy(Anatomy::from_pair(inner_pair)),
-            Rule::behavior => Genome::Behavior(Behavior::from_pair(inner_pair)),
-            _ => panic!("Regra inesperada dentro de genome: {:?}", inner_pair.as_rule()),
+            Rule::import => Ok(Genome::Import(Import::from_pair(inner_pair)?)),
+            Rule::anatomy => Ok(Genome::Anatomy(Anatomy::from_pair(inner_pair)?)),
+            Rule::behavior => Ok(Genome::Behavior(Behavior::from_pair(inner_pair)?)),
+            _ => Err(AstBuildError::unexpected(&inner_pair, &[Rule::import, Rule::anatomy, Rule::behavior])),
         }
     }
 
     pub fn from_string(input: String) -> Result<Self, Box<dyn std::error::Error>> {
         let mut pairs = SCP::parse(Rule::genome, &input)?;
         let pair = pairs.next().ok_or("No pair found")?;
-        Ok(Genome::from_pair(pair))
+        Ok(Genome::from_pair(pair)?)
+    }
+
+    pub fn span(&self) -> Span {
+        match self {
+            Genome::Import(import) => import.span,
+            Genome::Anatomy(anatomy) => anatomy.span(),
+            Genome::Behavior(behavior) => behavior.span(),
+            Genome::Error(error) => error.span,
+        }
     }
 
     pub fn is_anatomy(&self) -> bool {
@@ -37,6 +55,10 @@ impl Genome {
         matches!(self, Genome::Behavior(_))
     }
 
+    pub fn is_error(&self) -> bool {
+        matches!(self, Genome::Error(_))
+    }
+
     pub fn as_anatomy(&self) -> Option<&Anatomy> {
         match self {
             Genome::Anatomy(anatomy) => Some(anatomy),
@@ -80,7 +102,6 @@ mod tests {
         // Verifica o conteúdo
         let anatomy = genome.as_anatomy().expect("Should be anatomy");
 
-        // Como Anatomy é um enum com apenas Bug, podemos verificar através do pattern matching
         match anatomy {
             Anatomy::Bug(bug) => {
                 assert_eq!(bug.specie.raw, "Cat", "Bug species should be Cat");
@@ -89,6 +110,7 @@ mod tests {
                 assert_eq!(bug.genes[1].tag.raw, "folego", "Second gene should be folego");
                 assert_eq!(bug.ethics.len(), 0, "Bug should have no ethics");
             }
+            other => panic!("Expected a bug, got {:?}", other),
         }
     }
 
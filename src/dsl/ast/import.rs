@@ -0,0 +1,105 @@
+use pest::iterators::Pair;
+use pest::Parser;
+use serde::{Deserialize, Serialize};
+use crate::dsl::ast::emitter::Specie;
+use crate::dsl::ast::error::{expect_rule, AstBuildError, Children};
+use crate::dsl::ast::span::Span;
+use crate::dsl::parser::parser::{Rule, SCP};
+
+/// `use "animais.sc"` ou `use Dog, Cat as Gato from "animais.sc"`.
+///
+/// Sem `names`, todos os bugs do arquivo são importados com o próprio nome.
+/// Quem lê os arquivos e liga os nomes é o [`module`](crate::dsl::module).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Import {
+    pub names: Vec<ImportName>,
+    /// Caminho sem as aspas, relativo ao arquivo que importa
+    pub path: String,
+    pub path_span: Span,
+    pub span: Span,
+}
+
+/// `Dog` ou `Dog as Cao`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImportName {
+    pub specie: Specie,
+    pub alias: Option<Specie>,
+    pub span: Span,
+}
+
+impl Import {
+    pub fn from_pair(pair: Pair<Rule>) -> Result<Self, AstBuildError> {
+        expect_rule(&pair, Rule::import)?;
+        let span = Span::from_pair(&pair);
+
+        let mut names = Vec::new();
+        let mut path = None;
+        for inner in Children::of(pair) {
+            match inner.as_rule() {
+                Rule::import_name => names.push(ImportName::from_pair(inner)?),
+                Rule::path => path = Some(inner),
+                _ => return Err(AstBuildError::unexpected(&inner, &[Rule::import_name, Rule::path])),
+            }
+        }
+        let path = path.ok_or(AstBuildError::MissingRule { expected: Rule::path, parent: Rule::import, span })?;
+        let path_span = Span::from_pair(&path);
+        let text = path.as_str();
+
+        Ok(Import { names, path: text[1..text.len() - 1].to_string(), path_span, span })
+    }
+
+    pub fn from_string(input: String) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut pairs = SCP::parse(Rule::import, &input)?;
+        let pair = pairs.next().ok_or("No pair found")?;
+        Ok(Import::from_pair(pair)?)
+    }
+}
+
+impl ImportName {
+    pub fn from_pair(pair: Pair<Rule>) -> Result<Self, AstBuildError> {
+        expect_rule(&pair, Rule::import_name)?;
+        let span = Span::from_pair(&pair);
+
+        let mut inner = Children::of(pair);
+        let specie = Specie::from_pair(inner.expect(Rule::specie)?)?;
+        let alias = inner.next().map(Specie::from_pair).transpose()?;
+
+        Ok(ImportName { specie, alias, span })
+    }
+
+    /// Nome pelo qual o bug é conhecido no arquivo que importa
+    pub fn local(&self) -> &Specie {
+        self.alias.as_ref().unwrap_or(&self.specie)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Import;
+    use crate::dsl::ast::genome::Genome;
+
+    #[test]
+    fn test_import_everything() {
+        let import = Import::from_string("use \"zoo/animais.sc\"".to_string()).expect("Import should parse");
+
+        assert!(import.names.is_empty());
+        assert_eq!(import.path, "zoo/animais.sc");
+        assert_eq!((import.path_span.start, import.path_span.end), (4, 20));
+    }
+
+    #[test]
+    fn test_import_names_with_alias() {
+        let import = Import::from_string("use Dog, Dog as Cao from \"b.sc\"".to_string()).expect("Import should parse");
+
+        let names: Vec<(&str, &str)> =
+            import.names.iter().map(|name| (name.specie.raw.as_str(), name.local().raw.as_str())).collect();
+        assert_eq!(names, vec![("Dog", "Dog"), ("Dog", "Cao")]);
+    }
+
+    #[test]
+    fn test_use_is_reserved_but_prefixes_are_not() {
+        assert!(matches!(Genome::from_string("use \"a.sc\"".to_string()), Ok(Genome::Import(_))));
+        assert!(matches!(Genome::from_string("used = 1".to_string()), Ok(Genome::Behavior(_))));
+        assert!(Genome::from_string("use = 1".to_string()).is_err());
+    }
+}
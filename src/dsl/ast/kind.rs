@@ -0,0 +1,106 @@
+use pest::iterators::Pair;
+use pest::Parser;
+use serde::{Deserialize, Serialize};
+use crate::dsl::ast::behavior::EthicsBind;
+use crate::dsl::ast::emitter::Specie;
+use crate::dsl::ast::error::{expect_rule, AstBuildError, Children};
+use crate::dsl::ast::gene::Gene;
+use crate::dsl::ast::span::Span;
+use crate::dsl::parser::parser::{Rule, SCP};
+
+/// `kind Color Red Green Blue end`: uma espécie cujos valores são uma das
+/// variantes listadas. As variantes são usadas pelo nome, como espécies
+/// (`cor = Red`, `Circulo(raio: 2.0)`), e comparadas com `match`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Kind {
+    pub specie: Specie,
+    pub variants: Vec<Variant>,
+    pub span: Span,
+}
+
+/// `Red` ou `Circulo(raio: Decimal)`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Variant {
+    pub specie: Specie,
+    /// Genes carregados pela variante, na ordem declarada
+    pub genes: Vec<Gene>,
+    pub span: Span,
+}
+
+impl Kind {
+    pub fn from_pair(pair: Pair<Rule>) -> Result<Self, AstBuildError> {
+        expect_rule(&pair, Rule::kind)?;
+        let span = Span::from_pair(&pair);
+
+        let mut inner = Children::of(pair);
+        let specie = Specie::from_pair(inner.expect(Rule::specie)?)?;
+        let variants = inner.map(Variant::from_pair).collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Kind { specie, variants, span })
+    }
+
+    pub fn from_string(input: String) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut pairs = SCP::parse(Rule::kind, &input)?;
+        let pair = pairs.next().ok_or("No pair found")?;
+        Ok(Kind::from_pair(pair)?)
+    }
+
+    pub fn variant(&self, name: &str) -> Option<&Variant> {
+        self.variants.iter().find(|variant| variant.specie.raw == name)
+    }
+}
+
+impl Variant {
+    pub fn from_pair(pair: Pair<Rule>) -> Result<Self, AstBuildError> {
+        expect_rule(&pair, Rule::variant)?;
+        let span = Span::from_pair(&pair);
+
+        let mut inner = Children::of(pair);
+        let specie = Specie::from_pair(inner.expect(Rule::specie)?)?;
+        let mut genes = Vec::new();
+        for bind in inner {
+            let EthicsBind { tag, specie, span } = EthicsBind::from_pair(bind)?;
+            genes.push(Gene { tag, specie, span });
+        }
+
+        Ok(Variant { specie, genes, span })
+    }
+
+    pub fn gene(&self, name: &str) -> Option<&Gene> {
+        self.genes.iter().find(|gene| gene.tag.raw == name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Kind;
+    use crate::dsl::ast::anatomy::Anatomy;
+    use crate::tests::load_fragment;
+
+    #[test]
+    fn test_kind_on_one_line() {
+        let kind = Kind::from_string("kind Color Red Green Blue end".to_string()).expect("Kind should parse");
+
+        assert_eq!(kind.specie.raw, "Color");
+        let names: Vec<&str> = kind.variants.iter().map(|variant| variant.specie.raw.as_str()).collect();
+        assert_eq!(names, vec!["Red", "Green", "Blue"]);
+        assert!(kind.variants.iter().all(|variant| variant.genes.is_empty()));
+    }
+
+    #[test]
+    fn test_variants_with_genes() {
+        let kind = Kind::from_string(load_fragment("kind/forma.sc")).expect("Kind should parse");
+
+        let circulo = kind.variant("Circulo").expect("Variant should exist");
+        assert_eq!(circulo.gene("raio").map(|gene| gene.specie.name.raw.as_str()), Some("Decimal"));
+        let retangulo = kind.variant("Retangulo").expect("Variant should exist");
+        assert_eq!(retangulo.genes.len(), 2);
+        assert!(kind.variant("Ponto").is_some_and(|ponto| ponto.genes.is_empty()));
+    }
+
+    #[test]
+    fn test_kind_is_an_anatomy_and_needs_a_variant() {
+        assert!(matches!(Anatomy::from_string("kind Sinal Verde end".to_string()), Ok(Anatomy::Kind(_))));
+        assert!(Kind::from_string("kind Vazio end".to_string()).is_err());
+    }
+}
@@ -2,26 +2,30 @@ use pest::iterators::Pair;
 use serde::{Deserialize, Serialize};
 use crate::dsl::parser::parser::Rule;
 use crate::dsl::ast::signal::Signal;
+use crate::dsl::ast::error::{expect_rule, AstBuildError};
+use crate::dsl::ast::span::Span;
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Matrix {
     pub raw: String,
     pub signals: Vec<Signal>,
+    pub span: Span,
 }
 
 impl Matrix {
-    pub fn from_pair(pair: Pair<Rule>) -> Self {
-        assert_eq!(pair.as_rule(), Rule::matrix);
+    pub fn from_pair(pair: Pair<Rule>) -> Result<Self, AstBuildError> {
+        expect_rule(&pair, Rule::matrix)?;
+        let span = Span::from_pair(&pair);
         let raw = pair.as_str().to_string();
 
         let mut signals = Vec::new();
         for signal_pair in pair.into_inner() {
             if signal_pair.as_rule() == Rule::signal {
-                signals.push(Signal::from_pair(signal_pair));
+                signals.push(Signal::from_pair(signal_pair)?);
             }
         }
 
-        Matrix { raw, signals }
+        Ok(Matrix { raw, signals, span })
     }
 
     pub fn from_string(input: String) -> Result<Self, Box<dyn std::error::Error>> {
@@ -30,6 +34,6 @@ impl Matrix {
 
         let mut pairs = SCP::parse(Rule::matrix, &input)?;
         let pair = pairs.next().ok_or("No pair found")?;
-        Ok(Matrix::from_pair(pair))
+        Ok(Matrix::from_pair(pair)?)
     }
 }
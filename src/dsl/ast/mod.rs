@@ -2,7 +2,10 @@ pub mod sc;
 pub mod fly;
 pub mod strand;
 pub mod genome;
+pub mod import;
 pub mod anatomy;
+pub mod kind;
+pub mod contract;
 pub mod behavior;
 pub mod bug;
 pub mod gene;
@@ -13,4 +16,11 @@ pub mod matrix;
 pub mod signal;
 pub mod nucleus;
 pub mod condition;
+pub mod expr;
+pub mod span;
+pub mod error;
+pub mod error_node;
+pub mod comment;
 
+pub mod visit;
+pub mod visit_mut;
@@ -2,25 +2,29 @@ use pest::iterators::Pair;
 use serde::{Deserialize, Serialize};
 use crate::dsl::parser::parser::Rule;
 use crate::dsl::ast::matrix::Matrix;
+use crate::dsl::ast::error::{expect_rule, AstBuildError};
+use crate::dsl::ast::span::Span;
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Nucleus {
     pub raw: String,
     pub matrix: Matrix,
+    pub span: Span,
 }
 
 impl Nucleus {
-    pub fn from_pair(pair: Pair<Rule>) -> Self {
-        assert_eq!(pair.as_rule(), Rule::nucleus);
+    pub fn from_pair(pair: Pair<Rule>) -> Result<Self, AstBuildError> {
+        expect_rule(&pair, Rule::nucleus)?;
+        let span = Span::from_pair(&pair);
         let raw = pair.as_str().to_string();
 
         let matrix_pair = pair.into_inner()
             .find(|p| p.as_rule() == Rule::matrix)
-            .expect("Nucleus must have a matrix");
+            .ok_or(AstBuildError::MissingRule { expected: Rule::matrix, parent: Rule::nucleus, span })?;
 
-        let matrix = Matrix::from_pair(matrix_pair);
+        let matrix = Matrix::from_pair(matrix_pair)?;
 
-        Nucleus { raw, matrix }
+        Ok(Nucleus { raw, matrix, span })
     }
 
     pub fn from_string(input: String) -> Result<Self, Box<dyn std::error::Error>> {
@@ -29,6 +33,6 @@ impl Nucleus {
 
         let mut pairs = SCP::parse(Rule::nucleus, &input)?;
         let pair = pairs.next().ok_or("No pair found")?;
-        Ok(Nucleus::from_pair(pair))
+        Ok(Nucleus::from_pair(pair)?)
     }
 }
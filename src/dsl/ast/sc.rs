@@ -1,4 +1,6 @@
 use crate::dsl::ast::fly::Fly;
+use crate::dsl::ast::error::{expect_rule, AstBuildError, Children};
+use crate::dsl::ast::span::{with_line_table, Span};
 use crate::dsl::parser::parser::{Rule, SCP};
 use pest::Parser;
 use pest::iterators::Pair;
@@ -7,23 +9,23 @@ use serde::{Deserialize, Serialize};
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct SC {
     pub fly: Fly,
+    pub span: Span,
 }
 
 impl SC {
-    pub fn from_pair(pair: Pair<Rule>) -> Self {
-        assert_eq!(pair.as_rule(), Rule::sc);
+    pub fn from_pair(pair: Pair<Rule>) -> Result<Self, AstBuildError> {
+        expect_rule(&pair, Rule::sc)?;
+        let span = Span::from_pair(&pair);
 
-        let fly = Fly::from_pair(pair.into_inner().next().unwrap());
+        let fly = Fly::from_pair(Children::of(pair).expect(Rule::fly)?)?;
 
-        SC { fly }
+        Ok(SC { fly, span })
     }
 
-    pub fn parse(input: String) -> Self {
-        let pair = SCP::parse(Rule::sc, &input)
-            .expect("Failed to parse input")
-            .next()
-            .expect("No pair found");
-        SC::from_pair(pair)
+    pub fn parse(input: String) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut pairs = SCP::parse(Rule::sc, &input)?;
+        let pair = pairs.next().ok_or("No pair found")?;
+        Ok(with_line_table(&input, || SC::from_pair(pair))?)
     }
 }
 
@@ -48,7 +50,7 @@ mod tests {
         assert!(!input.is_empty(), "Fixture file should not be empty");
 
         // Testa o parse
-        let sc = SC::parse(input.clone());
+        let sc = SC::parse(input.clone()).expect("Should parse");
 
         // Verifica se o fly foi parseado corretamente
         assert!(!sc.fly.strand.genome.is_empty(), "Fly should have genomes");
@@ -66,7 +68,7 @@ mod tests {
         assert!(!input.is_empty(), "Fixture file should not be empty");
 
         // Testa o parse
-        let sc = SC::parse(input.clone());
+        let sc = SC::parse(input.clone()).expect("Should parse");
 
         // Verifica se o fly foi parseado corretamente
         assert!(!sc.fly.strand.genome.is_empty(), "Fly should have genomes");
@@ -75,7 +77,7 @@ mod tests {
     #[test]
     fn test_sc_parse_validation() {
         let input = load_fragment("program/behavior.sc");
-        let sc = SC::parse(input.clone());
+        let sc = SC::parse(input.clone()).expect("Should parse");
 
         // Verifica se temos um fly válido
         assert!(!sc.fly.strand.genome.is_empty(), "Fly should have genomes");
@@ -86,7 +88,7 @@ mod tests {
         let input = load_fragment("program/anatomy.sc");
 
         // Parse usando parse
-        let sc_parse = SC::parse(input.clone());
+        let sc_parse = SC::parse(input.clone()).expect("Should parse");
 
         // Parse manual para comparação
         let pair = SCP::parse(Rule::sc, &input)
@@ -94,7 +96,7 @@ mod tests {
             .next()
             .expect("No pair found");
 
-        let sc_from_pair = SC::from_pair(pair);
+        let sc_from_pair = SC::from_pair(pair).expect("Failed to build SC");
 
         // Ambos devem produzir o mesmo resultado
         assert_eq!(
@@ -102,4 +104,11 @@ mod tests {
             sc_from_pair.fly.strand.genome.len()
         );
     }
+
+    #[test]
+    fn test_sc_parse_invalid_input_is_an_error() {
+        let input = load_fragment("bug/invalid_syntax.sc");
+
+        assert!(SC::parse(input).is_err(), "Invalid input must not parse");
+    }
 }
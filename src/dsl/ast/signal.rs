@@ -2,6 +2,8 @@ use pest::iterators::Pair;
 use serde::{Deserialize, Serialize};
 use crate::dsl::parser::parser::Rule;
 use crate::dsl::ast::behavior::Behavior;
+use crate::dsl::ast::error::{expect_rule, AstBuildError, Children};
+use crate::dsl::ast::span::Span;
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Signal {
@@ -9,14 +11,11 @@ pub enum Signal {
 }
 
 impl Signal {
-    pub fn from_pair(pair: Pair<Rule>) -> Self {
-        assert_eq!(pair.as_rule(), Rule::signal);
+    pub fn from_pair(pair: Pair<Rule>) -> Result<Self, AstBuildError> {
+        expect_rule(&pair, Rule::signal)?;
 
-        let inner_pair = pair.into_inner().next().unwrap();
-        match inner_pair.as_rule() {
-            Rule::behavior => Signal::Behavior(Behavior::from_pair(inner_pair)),
-            _ => panic!("Unexpected rule in signal: {:?}", inner_pair.as_rule()),
-        }
+        let inner_pair = Children::of(pair).expect(Rule::behavior)?;
+        Ok(Signal::Behavior(Behavior::from_pair(inner_pair)?))
     }
 
     pub fn from_string(input: String) -> Result<Self, Box<dyn std::error::Error>> {
@@ -25,6 +24,12 @@ impl Signal {
 
         let mut pairs = SCP::parse(Rule::signal, &input)?;
         let pair = pairs.next().ok_or("No pair found")?;
-        Ok(Signal::from_pair(pair))
+        Ok(Signal::from_pair(pair)?)
+    }
+
+    pub fn span(&self) -> Span {
+        match self {
+            Signal::Behavior(behavior) => behavior.span(),
+        }
     }
 }
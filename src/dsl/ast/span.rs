@@ -0,0 +1,210 @@
+use std::cell::RefCell;
+use std::fmt;
+use pest::iterators::Pair;
+use serde::{Deserialize, Serialize};
+use crate::dsl::ast::visit_mut::VisitMut;
+use crate::dsl::parser::parser::Rule;
+
+/// Localização de um nó no código fonte.
+///
+/// `start` e `end` são offsets em bytes (`end` exclusivo); `line` e `col`
+/// apontam para o início do nó e começam em 1, como no pest.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+    pub col: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize, line: usize, col: usize) -> Self {
+        Span { start, end, line, col }
+    }
+
+    pub fn from_pair(pair: &Pair<Rule>) -> Self {
+        Span::from_pest(&pair.as_span())
+    }
+
+    /// Converte um span do pest, buscando linha e coluna na [`LineTable`] da análise
+    /// em andamento (ver [`with_line_table`]) ou, fora dela, varrendo a entrada.
+    pub fn from_pest(span: &pest::Span) -> Self {
+        let input = span.get_input();
+        let start = span.start();
+        let found = LINES.with(|lines| {
+            lines.borrow().as_ref().filter(|table| table.is_for(input)).map(|table| table.line_col(input, start))
+        });
+        let (line, col) = found.unwrap_or_else(|| span.start_pos().line_col());
+        Span { start, end: span.end(), line, col }
+    }
+
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    pub fn contains(&self, offset: usize) -> bool {
+        self.start <= offset && offset < self.end
+    }
+
+    /// Menor span que cobre `self` e `other`
+    pub fn merge(&self, other: &Span) -> Span {
+        let (first, last) = if self.start <= other.start { (self, other) } else { (other, self) };
+        Span {
+            start: first.start,
+            end: first.end.max(last.end),
+            line: first.line,
+            col: first.col,
+        }
+    }
+
+    /// Trecho do código fonte coberto pelo span
+    pub fn slice<'a>(&self, source: &'a str) -> &'a str {
+        &source[self.start..self.end]
+    }
+}
+
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.col)
+    }
+}
+
+/// Move os spans de uma árvore analisada a partir de um trecho do código para
+/// onde o trecho fica no código inteiro.
+///
+/// `offset` soma em `start`/`end` e `line` nas linhas; `col` só vale para os nós
+/// na primeira linha do trecho, as únicas que não começam no início de uma linha.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Shift {
+    pub offset: usize,
+    pub line: usize,
+    pub col: usize,
+}
+
+impl VisitMut for Shift {
+    fn visit_span(&mut self, span: &mut Span) {
+        if span.line == 1 {
+            span.col += self.col;
+        }
+        span.start += self.offset;
+        span.end += self.offset;
+        span.line += self.line;
+    }
+}
+
+/// Offsets em que cada linha de uma entrada começa.
+///
+/// O `line_col` do pest varre a entrada desde o início a cada chamada; montar
+/// a tabela uma vez por análise deixa cada consulta em uma busca binária.
+#[derive(Debug, Clone)]
+pub struct LineTable {
+    input: (*const u8, usize),
+    starts: Vec<usize>,
+}
+
+impl LineTable {
+    pub fn new(input: &str) -> Self {
+        let starts = std::iter::once(0)
+            .chain(input.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        LineTable { input: (input.as_ptr(), input.len()), starts }
+    }
+
+    fn is_for(&self, input: &str) -> bool {
+        self.input == (input.as_ptr(), input.len())
+    }
+
+    /// Linha e coluna (a partir de 1, em caracteres) de um offset em bytes.
+    pub fn line_col(&self, input: &str, offset: usize) -> (usize, usize) {
+        let (line, line_start) = self.line_of(offset);
+        (line + 1, input[line_start..offset].chars().count() + 1)
+    }
+
+    /// Linha (a partir de 0) que contém o offset e o offset em que ela começa.
+    pub fn line_of(&self, offset: usize) -> (usize, usize) {
+        let line = self.starts.partition_point(|start| *start <= offset) - 1;
+        (line, self.starts[line])
+    }
+
+    /// Início e fim (antes do `\n`) da linha `line`, a partir de 0.
+    pub fn line_bounds(&self, input: &str, line: usize) -> Option<(usize, usize)> {
+        let start = *self.starts.get(line)?;
+        Some((start, self.starts.get(line + 1).map_or(input.len(), |next| next - 1)))
+    }
+}
+
+thread_local! {
+    static LINES: RefCell<Option<LineTable>> = const { RefCell::new(None) };
+}
+
+/// Roda `f` com a tabela de linhas de `input` disponível para [`Span::from_pair`].
+///
+/// Os pontos de entrada que montam a AST de um texto inteiro (`Tree::parse_with`,
+/// `SC::parse`) passam por aqui; a tabela anterior volta ao final.
+pub fn with_line_table<R>(input: &str, f: impl FnOnce() -> R) -> R {
+    struct Restore(Option<LineTable>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            LINES.with(|lines| *lines.borrow_mut() = self.0.take());
+        }
+    }
+
+    let previous = LINES.with(|lines| lines.borrow_mut().replace(LineTable::new(input)));
+    let _restore = Restore(previous);
+    f()
+}
+
+#[cfg(test)]
+mod tests {
+    use pest::Parser;
+    use crate::dsl::parser::parser::{Rule, SCP};
+    use super::{with_line_table, LineTable, Span};
+
+    #[test]
+    fn test_span_from_pair() {
+        let input = "bug Dog\n  gene x Int\nend";
+        let pair = SCP::parse(Rule::bug, input)
+            .expect("Failed to parse bug")
+            .next()
+            .expect("No pair found");
+
+        let gene = pair.into_inner().find(|p| p.as_rule() == Rule::gene).expect("gene not found");
+        let span = Span::from_pair(&gene);
+
+        assert_eq!(span.line, 2);
+        assert_eq!(span.col, 3);
+        assert!(span.slice(input).starts_with("gene x Int"));
+    }
+
+    #[test]
+    fn test_line_table_matches_pest() {
+        let input = "bug Dog\n  gene x Int\n\n  ethics y\n    z = [\"ção\", 1]\n  end\nend";
+        let pair = SCP::parse(Rule::bug, input)
+            .expect("Failed to parse bug")
+            .next()
+            .expect("No pair found");
+
+        let table = LineTable::new(input);
+        for inner in pair.clone().into_inner().flatten() {
+            let span = inner.as_span();
+            assert_eq!(table.line_col(input, span.start()), span.start_pos().line_col());
+        }
+        let gene = pair.into_inner().find(|p| p.as_rule() == Rule::gene).expect("gene not found");
+        assert_eq!(with_line_table(input, || Span::from_pair(&gene)), Span::from_pair(&gene));
+    }
+
+    #[test]
+    fn test_span_merge() {
+        let a = Span::new(4, 7, 1, 5);
+        let b = Span::new(10, 14, 2, 3);
+
+        assert_eq!(a.merge(&b), Span::new(4, 14, 1, 5));
+        assert_eq!(b.merge(&a), Span::new(4, 14, 1, 5));
+        assert!(a.merge(&b).contains(12));
+        assert!(!a.contains(7));
+    }
+}
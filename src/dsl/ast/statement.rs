@@ -1,7 +1,9 @@
 use crate::dsl::ast::behavior::oop::Oop;
 use crate::dsl::ast::condition::Condition;
-use crate::dsl::ast::emitter::Tag;
+use crate::dsl::ast::emitter::{Specie, Tag};
 use crate::dsl::ast::matrix::Matrix;
+use crate::dsl::ast::error::{expect_rule, AstBuildError, Children};
+use crate::dsl::ast::span::Span;
 use crate::dsl::parser::parser::Rule;
 use pest::iterators::Pair;
 use serde::{Deserialize, Serialize};
@@ -11,6 +13,7 @@ pub enum Statement {
     If(IfStatement),
     While(WhileStatement),
     For(ForStatement),
+    Match(MatchStatement),
     Return(ReturnStatement),
 }
 
@@ -21,6 +24,7 @@ pub struct IfStatement {
     pub then_block: Matrix,
     pub elsif_blocks: Vec<ElsifBlock>,
     pub else_block: Option<Matrix>,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -28,6 +32,7 @@ pub struct ElsifBlock {
     pub raw: String,
     pub condition: Condition,
     pub block: Matrix,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -35,6 +40,7 @@ pub struct WhileStatement {
     pub raw: String,
     pub condition: Condition,
     pub block: Matrix,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -43,26 +49,51 @@ pub struct ForStatement {
     pub variable: Tag,
     pub iterable: Oop,
     pub block: Matrix,
+    pub span: Span,
+}
+
+/// `match forma when Circulo ... when Ponto, Linha ... else ... end`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MatchStatement {
+    pub raw: String,
+    pub subject: Oop,
+    pub arms: Vec<WhenArm>,
+    pub else_block: Option<Matrix>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WhenArm {
+    pub raw: String,
+    /// Variantes que entram neste braço
+    pub variants: Vec<Specie>,
+    pub block: Matrix,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ReturnStatement {
     pub raw: String,
     pub value: Oop,
+    pub span: Span,
 }
 
 impl Statement {
-    pub fn from_pair(pair: Pair<Rule>) -> Self {
-        assert_eq!(pair.as_rule(), Rule::statement);
+    pub fn from_pair(pair: Pair<Rule>) -> Result<Self, AstBuildError> {
+        expect_rule(&pair, Rule::statement)?;
 
-        let inner_pair = pair.into_inner().next().unwrap();
+        let inner_pair = Children::of(pair).require(Rule::statement)?;
         match inner_pair.as_rule() {
-            Rule::r#if => Statement::If(IfStatement::from_pair(inner_pair)),
-            Rule::r#while => Statement::While(WhileStatement::from_pair(inner_pair)),
-            Rule::r#for => Statement::For(ForStatement::from_pair(inner_pair)),
-            Rule::r#return => Statement::Return(ReturnStatement::from_pair(inner_pair)),
-
-            _ => panic!("Unexpected rule in statement: {:?}", inner_pair.as_rule()),
+            Rule::r#if => Ok(Statement::If(IfStatement::from_pair(inner_pair)?)),
+            Rule::r#while => Ok(Statement::While(WhileStatement::from_pair(inner_pair)?)),
+            Rule::r#for => Ok(Statement::For(ForStatement::from_pair(inner_pair)?)),
+            Rule::r#match => Ok(Statement::Match(MatchStatement::from_pair(inner_pair)?)),
+            Rule::r#return => Ok(Statement::Return(ReturnStatement::from_pair(inner_pair)?)),
+
+            _ => Err(AstBuildError::unexpected(
+                &inner_pair,
+                &[Rule::r#if, Rule::r#while, Rule::r#for, Rule::r#match, Rule::r#return],
+            )),
         }
     }
 
@@ -78,6 +109,10 @@ impl Statement {
         matches!(self, Statement::For(_))
     }
 
+    pub fn is_match(&self) -> bool {
+        matches!(self, Statement::Match(_))
+    }
+
     pub fn is_return(&self) -> bool {
         matches!(self, Statement::Return(_))
     }
@@ -87,91 +122,70 @@ impl Statement {
             Statement::If(if_stmt) => &if_stmt.raw,
             Statement::While(while_stmt) => &while_stmt.raw,
             Statement::For(for_stmt) => &for_stmt.raw,
+            Statement::Match(match_stmt) => &match_stmt.raw,
             Statement::Return(return_stmt) => &return_stmt.raw,
         }
     }
+
+    pub fn span(&self) -> Span {
+        match self {
+            Statement::If(if_stmt) => if_stmt.span,
+            Statement::While(while_stmt) => while_stmt.span,
+            Statement::For(for_stmt) => for_stmt.span,
+            Statement::Match(match_stmt) => match_stmt.span,
+            Statement::Return(return_stmt) => return_stmt.span,
+        }
+    }
 }
 
 impl IfStatement {
-    pub fn from_pair(pair: Pair<Rule>) -> Self {
-        assert_eq!(pair.as_rule(), Rule::r#if);
+    pub fn from_pair(pair: Pair<Rule>) -> Result<Self, AstBuildError> {
+        expect_rule(&pair, Rule::r#if)?;
+        let span = Span::from_pair(&pair);
         let raw = pair.as_str().to_string();
-        println!("IfStatement raw: {}", raw);
 
-        let mut pairs = pair.into_inner();
+        let mut pairs = Children::of(pair);
 
         // Parse condition
-        let condition_pair = pairs.next().expect("If statement should have a condition");
-        println!("Condition pair rule: {:?}", condition_pair.as_rule());
-        let condition = Condition::from_pair(condition_pair);
+        let condition = Condition::from_pair(pairs.expect(Rule::condition)?)?;
 
         // Parse then block
-        let matrix_pair = pairs.next().expect("If statement should have a then block");
-        println!("Matrix pair rule: {:?}", matrix_pair.as_rule());
-        let then_block = Matrix::from_pair(matrix_pair);
+        let then_block = Matrix::from_pair(pairs.expect(Rule::matrix)?)?;
 
         let mut elsif_blocks = Vec::new();
         let mut else_block = None;
 
         // Parse elsif and else blocks
-        while let Some(current_pair) = pairs.next() {
-            println!("Current pair rule: {:?}", current_pair.as_rule());
+        for current_pair in pairs {
             match current_pair.as_rule() {
                 Rule::elsif => {
-                    let raw_elsif = current_pair.as_str().to_string();
-                    println!("Elsif raw: {}", raw_elsif);
-                    let mut elsif_pairs = current_pair.into_inner();
-
-                    // Parse elsif condition
-                    let elsif_condition_pair = elsif_pairs
-                        .next()
-                        .expect("Elsif block should have a condition");
-                    println!(
-                        "Elsif condition pair rule: {:?}",
-                        elsif_condition_pair.as_rule()
-                    );
-                    let elsif_condition = Condition::from_pair(elsif_condition_pair);
-
-                    // Parse elsif matrix block
-                    let elsif_matrix_pair = elsif_pairs
-                        .next()
-                        .expect("Elsif block should have a matrix block");
-                    println!("Elsif matrix pair rule: {:?}", elsif_matrix_pair.as_rule());
-                    let elsif_matrix = Matrix::from_pair(elsif_matrix_pair);
-
-                    elsif_blocks.push(ElsifBlock {
-                        raw: raw_elsif,
-                        condition: elsif_condition,
-                        block: elsif_matrix,
-                    });
+                    elsif_blocks.push(ElsifBlock::from_pair(current_pair)?);
                 }
                 Rule::r#else => {
-                    let else_matrix_pair = current_pair
-                        .into_inner()
-                        .next()
-                        .expect("Else block should have a matrix");
-                    else_block = Some(Matrix::from_pair(else_matrix_pair));
+                    let else_matrix_pair = Children::of(current_pair).expect(Rule::matrix)?;
+                    else_block = Some(Matrix::from_pair(else_matrix_pair)?);
                 }
                 Rule::if_ends => {
                     // Ignore the end token
                     continue;
                 }
-                _ => panic!(
-                    "Unexpected rule in if statement: {:?}",
-                    current_pair.as_rule()
-                ),
+                _ => {
+                    return Err(AstBuildError::unexpected(
+                        &current_pair,
+                        &[Rule::elsif, Rule::r#else, Rule::if_ends],
+                    ));
+                }
             }
         }
 
-        println!("Number of elsif blocks: {}", elsif_blocks.len());
-
-        IfStatement {
+        Ok(IfStatement {
             raw,
             condition,
             then_block,
             elsif_blocks,
             else_block,
-        }
+            span,
+        })
     }
 
     pub fn has_elsif(&self) -> bool {
@@ -184,66 +198,53 @@ impl IfStatement {
 }
 
 impl WhileStatement {
-    pub fn from_pair(pair: Pair<Rule>) -> Self {
-        assert_eq!(pair.as_rule(), Rule::r#while);
+    pub fn from_pair(pair: Pair<Rule>) -> Result<Self, AstBuildError> {
+        expect_rule(&pair, Rule::r#while)?;
+        let span = Span::from_pair(&pair);
         let raw = pair.as_str().to_string();
 
-        let mut pairs = pair.into_inner();
+        let mut pairs = Children::of(pair);
 
         // Parse condition
-        let condition_pair = pairs
-            .next()
-            .expect("While statement should have a condition");
-        let condition = Condition::from_pair(condition_pair);
+        let condition = Condition::from_pair(pairs.expect(Rule::condition)?)?;
 
         // Parse loop body block
-        let matrix_pair = pairs
-            .next()
-            .expect("While statement should have a body block");
-        let block = Matrix::from_pair(matrix_pair);
+        let block = Matrix::from_pair(pairs.expect(Rule::matrix)?)?;
 
-        WhileStatement {
+        Ok(WhileStatement {
             raw,
             condition,
             block,
-        }
+            span,
+        })
     }
 }
 
 impl ForStatement {
-    pub fn from_pair(pair: Pair<Rule>) -> Self {
-        assert_eq!(pair.as_rule(), Rule::r#for);
+    pub fn from_pair(pair: Pair<Rule>) -> Result<Self, AstBuildError> {
+        expect_rule(&pair, Rule::r#for)?;
+        let span = Span::from_pair(&pair);
         let raw = pair.as_str().to_string();
 
-        let mut pairs = pair.into_inner();
+        let mut pairs = Children::of(pair);
 
         // Parse variable (each)
-        let variable_pair = pairs.next().expect("For statement should have a variable");
-        assert_eq!(variable_pair.as_rule(), Rule::each);
-        let tag_pair = variable_pair
-            .into_inner()
-            .next()
-            .expect("Each should have a tag");
-        let variable = Tag::new(tag_pair.as_str().to_string());
+        let variable_pair = pairs.expect(Rule::each)?;
+        let variable = Tag::from_pair(Children::of(variable_pair).expect(Rule::tag)?)?;
 
         // Parse iterable (oop)
-        let oop_pair = pairs
-            .next()
-            .expect("For statement should have an iterable oop");
-        let iterable = Oop::from_pair(oop_pair);
+        let iterable = Oop::from_pair(pairs.expect(Rule::oop)?)?;
 
         // Parse loop body block
-        let matrix_pair = pairs
-            .next()
-            .expect("For statement should have a body block");
-        let block = Matrix::from_pair(matrix_pair);
+        let block = Matrix::from_pair(pairs.expect(Rule::matrix)?)?;
 
-        ForStatement {
+        Ok(ForStatement {
             raw,
             variable,
             iterable,
             block,
-        }
+            span,
+        })
     }
 
     pub fn get_variable(&self) -> &Tag {
@@ -252,43 +253,85 @@ impl ForStatement {
 }
 
 impl ElsifBlock {
-    pub fn from_pair(pair: Pair<Rule>) -> Self {
-        assert_eq!(pair.as_rule(), Rule::elsif);
+    pub fn from_pair(pair: Pair<Rule>) -> Result<Self, AstBuildError> {
+        expect_rule(&pair, Rule::elsif)?;
+        let span = Span::from_pair(&pair);
         let raw = pair.as_str().to_string();
 
-        let mut pairs = pair.into_inner();
+        let mut pairs = Children::of(pair);
 
         // Parse condition
-        let condition_pair = pairs.next().expect("Elsif block should have a condition");
-        let condition = Condition::from_pair(condition_pair);
+        let condition = Condition::from_pair(pairs.expect(Rule::condition)?)?;
 
         // Parse block
-        let matrix_pair = pairs
-            .next()
-            .expect("Elsif block should have a matrix block");
-        let block = Matrix::from_pair(matrix_pair);
+        let block = Matrix::from_pair(pairs.expect(Rule::matrix)?)?;
 
-        ElsifBlock {
+        Ok(ElsifBlock {
             raw,
             condition,
             block,
+            span,
+        })
+    }
+}
+
+impl MatchStatement {
+    pub fn from_pair(pair: Pair<Rule>) -> Result<Self, AstBuildError> {
+        expect_rule(&pair, Rule::r#match)?;
+        let span = Span::from_pair(&pair);
+        let raw = pair.as_str().to_string();
+
+        let mut pairs = Children::of(pair);
+        let subject = Oop::from_pair(pairs.expect(Rule::oop)?)?;
+
+        let mut arms = Vec::new();
+        let mut else_block = None;
+        for current_pair in pairs {
+            match current_pair.as_rule() {
+                Rule::when => arms.push(WhenArm::from_pair(current_pair)?),
+                Rule::r#else => {
+                    let else_matrix_pair = Children::of(current_pair).expect(Rule::matrix)?;
+                    else_block = Some(Matrix::from_pair(else_matrix_pair)?);
+                }
+                _ => return Err(AstBuildError::unexpected(&current_pair, &[Rule::when, Rule::r#else])),
+            }
         }
+
+        Ok(MatchStatement { raw, subject, arms, else_block, span })
+    }
+}
+
+impl WhenArm {
+    pub fn from_pair(pair: Pair<Rule>) -> Result<Self, AstBuildError> {
+        expect_rule(&pair, Rule::when)?;
+        let span = Span::from_pair(&pair);
+        let raw = pair.as_str().to_string();
+
+        let mut variants = Vec::new();
+        let mut block = None;
+        for current_pair in Children::of(pair) {
+            match current_pair.as_rule() {
+                Rule::specie => variants.push(Specie::from_pair(current_pair)?),
+                Rule::matrix => block = Some(Matrix::from_pair(current_pair)?),
+                _ => return Err(AstBuildError::unexpected(&current_pair, &[Rule::specie, Rule::matrix])),
+            }
+        }
+        let block = block.ok_or(AstBuildError::MissingRule { expected: Rule::matrix, parent: Rule::when, span })?;
+
+        Ok(WhenArm { raw, variants, block, span })
     }
 }
 
 impl ReturnStatement {
-    pub fn from_pair(pair: Pair<Rule>) -> Self {
-        assert_eq!(pair.as_rule(), Rule::r#return);
+    pub fn from_pair(pair: Pair<Rule>) -> Result<Self, AstBuildError> {
+        expect_rule(&pair, Rule::r#return)?;
+        let span = Span::from_pair(&pair);
         let raw = pair.as_str().to_string();
 
         // A regra de retorno contém um oop que é o valor a ser retornado
-        let oop_pair = pair
-            .into_inner()
-            .next()
-            .expect("Return statement should have a value");
-        let value = Oop::from_pair(oop_pair);
+        let value = Oop::from_pair(Children::of(pair).expect(Rule::oop)?)?;
 
-        ReturnStatement { raw, value }
+        Ok(ReturnStatement { raw, value, span })
     }
 
     pub fn get_value(&self) -> &Oop {
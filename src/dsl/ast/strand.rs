@@ -2,36 +2,38 @@ use pest::iterators::Pair;
 use pest::Parser;
 use serde::{Deserialize, Serialize};
 use crate::dsl::ast::genome::Genome;
+use crate::dsl::ast::error::{expect_rule, AstBuildError};
+use crate::dsl::ast::span::{with_line_table, Span};
 use crate::dsl::parser::parser::{Rule, SCP};
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Strand {
     pub genome: Vec<Genome>,
+    pub span: Span,
 }
 
 impl Strand {
-    pub fn from_pair(pair: Pair<Rule>) -> Self {
-        assert_eq!(pair.as_rule(), Rule::strand);
+    pub fn from_pair(pair: Pair<Rule>) -> Result<Self, AstBuildError> {
+        expect_rule(&pair, Rule::strand)?;
+        let span = Span::from_pair(&pair);
 
         let mut genome = Vec::new();
         for genome_pair in pair.into_inner() {
             match genome_pair.as_rule() {
                 Rule::genome => {
-                    let genome_instance = Genome::from_pair(genome_pair);
+                    let genome_instance = Genome::from_pair(genome_pair)?;
                     genome.push(genome_instance);
                 },
-                _ => unreachable!("Unexpected rule in Strand::from_pair"),
+                _ => return Err(AstBuildError::unexpected(&genome_pair, &[Rule::genome])),
             }
         }
-        Strand { genome }
+        Ok(Strand { genome, span })
     }
 
-    pub fn from_string(input: String) -> Self {
-        let pair = SCP::parse(Rule::strand, &input)
-            .expect("Failed to parse input")
-            .next()
-            .expect("No pair found");
-        Strand::from_pair(pair)
+    pub fn from_string(input: String) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut pairs = SCP::parse(Rule::strand, &input)?;
+        let pair = pairs.next().ok_or("No pair found")?;
+        Ok(with_line_table(&input, || Strand::from_pair(pair))?)
     }
 }
 
@@ -49,7 +51,7 @@ mod tests {
 
         assert!(!input.is_empty(), "Fixture file should not be empty");
 
-        let strand = Strand::from_string(input.clone());
+        let strand = Strand::from_string(input.clone()).expect("Should parse");
 
         // Verifica se o strand contém dois genomes
         assert_eq!(strand.genome.len(), 2, "Strand should contain two genomes");
@@ -0,0 +1,759 @@
+//! Percurso somente leitura da AST.
+//!
+//! Cada nó tem um método `visit_*` em [`Visit`] cujo padrão chama o `walk_*`
+//! correspondente, que visita os filhos. Um passo sobrescreve só os nós que
+//! lhe interessam e chama `walk_*` para continuar descendo:
+//!
+//! ```
+//! use sc_dsl::dsl::ast::behavior::Catalysis;
+//! use sc_dsl::dsl::ast::visit::{self, Visit};
+//! use sc_dsl::dsl::parser::tree::Tree;
+//!
+//! struct Calls(Vec<String>);
+//!
+//! impl<'ast> Visit<'ast> for Calls {
+//!     fn visit_catalysis(&mut self, node: &'ast Catalysis) {
+//!         self.0.push(node.tag.raw.clone());
+//!         visit::walk_catalysis(self, node);
+//!     }
+//! }
+//!
+//! let tree = Tree::parse_input("rex.latir(2.plus(1))".to_string()).unwrap();
+//! let mut calls = Calls(Vec::new());
+//! calls.visit_tree(&tree);
+//! assert_eq!(calls.0, vec!["latir", "plus"]);
+//! ```
+//!
+//! Folhas (`Tag`, `Specie`, literais, `$` e nós de erro) não têm filhos e não
+//! têm `walk_*`. Para alterar a árvore, veja [`visit_mut`](super::visit_mut).
+
+use crate::dsl::ast::anatomy::Anatomy;
+use crate::dsl::ast::behavior::assign::Assign;
+use crate::dsl::ast::behavior::oop::Oop;
+use crate::dsl::ast::behavior::sequence::Sequence;
+use crate::dsl::ast::behavior::transport::Transport;
+use crate::dsl::ast::behavior::{Behavior, Bind, Binds, Carrier, Catalysis, EthicsBind, Trail};
+use crate::dsl::ast::bug::Bug;
+use crate::dsl::ast::contract::Contract;
+use crate::dsl::ast::condition::Condition;
+use crate::dsl::ast::emitter::{Emitter, List, Literal, Map, SelfRef, Specie, SpecieRef, Tag, Template, TemplatePart};
+use crate::dsl::ast::error_node::ErrorNode;
+use crate::dsl::ast::ethics::{Ethics, Signature};
+use crate::dsl::ast::expr::Expr;
+use crate::dsl::ast::fly::Fly;
+use crate::dsl::ast::gene::Gene;
+use crate::dsl::ast::genome::Genome;
+use crate::dsl::ast::import::{Import, ImportName};
+use crate::dsl::ast::kind::{Kind, Variant};
+use crate::dsl::ast::matrix::Matrix;
+use crate::dsl::ast::nucleus::Nucleus;
+use crate::dsl::ast::sc::SC;
+use crate::dsl::ast::signal::Signal;
+use crate::dsl::ast::statement::{
+    ElsifBlock, ForStatement, IfStatement, MatchStatement, ReturnStatement, Statement, WhenArm, WhileStatement,
+};
+use crate::dsl::ast::strand::Strand;
+use crate::dsl::parser::tree::Tree;
+
+pub trait Visit<'ast> {
+    fn visit_tree(&mut self, node: &'ast Tree) {
+        walk_tree(self, node);
+    }
+
+    fn visit_sc(&mut self, node: &'ast SC) {
+        walk_sc(self, node);
+    }
+
+    fn visit_fly(&mut self, node: &'ast Fly) {
+        walk_fly(self, node);
+    }
+
+    fn visit_strand(&mut self, node: &'ast Strand) {
+        walk_strand(self, node);
+    }
+
+    fn visit_genome(&mut self, node: &'ast Genome) {
+        walk_genome(self, node);
+    }
+
+    fn visit_import(&mut self, node: &'ast Import) {
+        walk_import(self, node);
+    }
+
+    fn visit_import_name(&mut self, node: &'ast ImportName) {
+        walk_import_name(self, node);
+    }
+
+    fn visit_anatomy(&mut self, node: &'ast Anatomy) {
+        walk_anatomy(self, node);
+    }
+
+    fn visit_bug(&mut self, node: &'ast Bug) {
+        walk_bug(self, node);
+    }
+
+    fn visit_kind(&mut self, node: &'ast Kind) {
+        walk_kind(self, node);
+    }
+
+    fn visit_contract(&mut self, node: &'ast Contract) {
+        walk_contract(self, node);
+    }
+
+    fn visit_variant(&mut self, node: &'ast Variant) {
+        walk_variant(self, node);
+    }
+
+    fn visit_gene(&mut self, node: &'ast Gene) {
+        walk_gene(self, node);
+    }
+
+    fn visit_ethics(&mut self, node: &'ast Ethics) {
+        walk_ethics(self, node);
+    }
+
+    fn visit_signature(&mut self, node: &'ast Signature) {
+        walk_signature(self, node);
+    }
+
+    fn visit_ethics_bind(&mut self, node: &'ast EthicsBind) {
+        walk_ethics_bind(self, node);
+    }
+
+    fn visit_specie_ref(&mut self, node: &'ast SpecieRef) {
+        walk_specie_ref(self, node);
+    }
+
+    fn visit_nucleus(&mut self, node: &'ast Nucleus) {
+        walk_nucleus(self, node);
+    }
+
+    fn visit_matrix(&mut self, node: &'ast Matrix) {
+        walk_matrix(self, node);
+    }
+
+    fn visit_signal(&mut self, node: &'ast Signal) {
+        walk_signal(self, node);
+    }
+
+    fn visit_behavior(&mut self, node: &'ast Behavior) {
+        walk_behavior(self, node);
+    }
+
+    fn visit_assign(&mut self, node: &'ast Assign) {
+        walk_assign(self, node);
+    }
+
+    fn visit_statement(&mut self, node: &'ast Statement) {
+        walk_statement(self, node);
+    }
+
+    fn visit_if(&mut self, node: &'ast IfStatement) {
+        walk_if(self, node);
+    }
+
+    fn visit_elsif(&mut self, node: &'ast ElsifBlock) {
+        walk_elsif(self, node);
+    }
+
+    fn visit_while(&mut self, node: &'ast WhileStatement) {
+        walk_while(self, node);
+    }
+
+    fn visit_for(&mut self, node: &'ast ForStatement) {
+        walk_for(self, node);
+    }
+
+    fn visit_match(&mut self, node: &'ast MatchStatement) {
+        walk_match(self, node);
+    }
+
+    fn visit_when(&mut self, node: &'ast WhenArm) {
+        walk_when(self, node);
+    }
+
+    fn visit_return(&mut self, node: &'ast ReturnStatement) {
+        walk_return(self, node);
+    }
+
+    fn visit_condition(&mut self, node: &'ast Condition) {
+        walk_condition(self, node);
+    }
+
+    fn visit_expr(&mut self, node: &'ast Expr) {
+        walk_expr(self, node);
+    }
+
+    fn visit_oop(&mut self, node: &'ast Oop) {
+        walk_oop(self, node);
+    }
+
+    fn visit_emitter(&mut self, node: &'ast Emitter) {
+        walk_emitter(self, node);
+    }
+
+    fn visit_template(&mut self, node: &'ast Template) {
+        walk_template(self, node);
+    }
+
+    fn visit_list(&mut self, node: &'ast List) {
+        walk_list(self, node);
+    }
+
+    fn visit_map(&mut self, node: &'ast Map) {
+        walk_map(self, node);
+    }
+
+    fn visit_trail(&mut self, node: &'ast Trail) {
+        walk_trail(self, node);
+    }
+
+    fn visit_catalysis(&mut self, node: &'ast Catalysis) {
+        walk_catalysis(self, node);
+    }
+
+    fn visit_carrier(&mut self, node: &'ast Carrier) {
+        walk_carrier(self, node);
+    }
+
+    fn visit_transport(&mut self, node: &'ast Transport) {
+        walk_transport(self, node);
+    }
+
+    fn visit_binds(&mut self, node: &'ast Binds) {
+        walk_binds(self, node);
+    }
+
+    fn visit_bind(&mut self, node: &'ast Bind) {
+        walk_bind(self, node);
+    }
+
+    fn visit_sequence(&mut self, node: &'ast Sequence) {
+        walk_sequence(self, node);
+    }
+
+    fn visit_tag(&mut self, _node: &'ast Tag) {}
+
+    fn visit_specie(&mut self, _node: &'ast Specie) {}
+
+    fn visit_literal(&mut self, _node: &'ast Literal) {}
+
+    fn visit_self_ref(&mut self, _node: &'ast SelfRef) {}
+
+    fn visit_error_node(&mut self, _node: &'ast ErrorNode) {}
+}
+
+pub fn walk_tree<'ast, V>(v: &mut V, node: &'ast Tree)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    v.visit_sc(&node.sc);
+}
+
+pub fn walk_sc<'ast, V>(v: &mut V, node: &'ast SC)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    v.visit_fly(&node.fly);
+}
+
+pub fn walk_fly<'ast, V>(v: &mut V, node: &'ast Fly)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    v.visit_strand(&node.strand);
+}
+
+pub fn walk_strand<'ast, V>(v: &mut V, node: &'ast Strand)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    for genome in &node.genome {
+        v.visit_genome(genome);
+    }
+}
+
+pub fn walk_genome<'ast, V>(v: &mut V, node: &'ast Genome)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    match node {
+        Genome::Import(import) => v.visit_import(import),
+        Genome::Anatomy(anatomy) => v.visit_anatomy(anatomy),
+        Genome::Behavior(behavior) => v.visit_behavior(behavior),
+        Genome::Error(error) => v.visit_error_node(error),
+    }
+}
+
+pub fn walk_import<'ast, V>(v: &mut V, node: &'ast Import)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    for name in &node.names {
+        v.visit_import_name(name);
+    }
+}
+
+pub fn walk_import_name<'ast, V>(v: &mut V, node: &'ast ImportName)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    v.visit_specie(&node.specie);
+    if let Some(alias) = &node.alias {
+        v.visit_specie(alias);
+    }
+}
+
+pub fn walk_anatomy<'ast, V>(v: &mut V, node: &'ast Anatomy)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    match node {
+        Anatomy::Bug(bug) => v.visit_bug(bug),
+        Anatomy::Kind(kind) => v.visit_kind(kind),
+        Anatomy::Contract(contract) => v.visit_contract(contract),
+    }
+}
+
+pub fn walk_bug<'ast, V>(v: &mut V, node: &'ast Bug)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    v.visit_specie(&node.specie);
+    for param in &node.params {
+        v.visit_specie(param);
+    }
+    for parent in &node.parents {
+        v.visit_specie(parent);
+    }
+    for gene in &node.genes {
+        v.visit_gene(gene);
+    }
+    for ethics in &node.ethics {
+        v.visit_ethics(ethics);
+    }
+}
+
+pub fn walk_kind<'ast, V>(v: &mut V, node: &'ast Kind)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    v.visit_specie(&node.specie);
+    for variant in &node.variants {
+        v.visit_variant(variant);
+    }
+}
+
+pub fn walk_contract<'ast, V>(v: &mut V, node: &'ast Contract)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    v.visit_specie(&node.specie);
+    for ethics in &node.ethics {
+        v.visit_ethics(ethics);
+    }
+}
+
+pub fn walk_variant<'ast, V>(v: &mut V, node: &'ast Variant)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    v.visit_specie(&node.specie);
+    for gene in &node.genes {
+        v.visit_gene(gene);
+    }
+}
+
+pub fn walk_gene<'ast, V>(v: &mut V, node: &'ast Gene)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    v.visit_tag(&node.tag);
+    v.visit_specie_ref(&node.specie);
+}
+
+pub fn walk_ethics<'ast, V>(v: &mut V, node: &'ast Ethics)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    v.visit_tag(&node.tag);
+    if let Some(signature) = &node.signature {
+        v.visit_signature(signature);
+    }
+    if let Some(feedback) = &node.feedback {
+        v.visit_specie_ref(feedback);
+    }
+    if let Some(body) = &node.body {
+        v.visit_matrix(body);
+    }
+}
+
+pub fn walk_signature<'ast, V>(v: &mut V, node: &'ast Signature)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    for bind in node.binds.iter().flatten() {
+        v.visit_ethics_bind(bind);
+    }
+}
+
+pub fn walk_ethics_bind<'ast, V>(v: &mut V, node: &'ast EthicsBind)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    v.visit_tag(&node.tag);
+    v.visit_specie_ref(&node.specie);
+}
+
+pub fn walk_specie_ref<'ast, V>(v: &mut V, node: &'ast SpecieRef)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    v.visit_specie(&node.name);
+    for arg in &node.args {
+        v.visit_specie_ref(arg);
+    }
+}
+
+pub fn walk_nucleus<'ast, V>(v: &mut V, node: &'ast Nucleus)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    v.visit_matrix(&node.matrix);
+}
+
+pub fn walk_matrix<'ast, V>(v: &mut V, node: &'ast Matrix)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    for signal in &node.signals {
+        v.visit_signal(signal);
+    }
+}
+
+pub fn walk_signal<'ast, V>(v: &mut V, node: &'ast Signal)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    match node {
+        Signal::Behavior(behavior) => v.visit_behavior(behavior),
+    }
+}
+
+pub fn walk_behavior<'ast, V>(v: &mut V, node: &'ast Behavior)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    match node {
+        Behavior::Statement(statement) => v.visit_statement(statement),
+        Behavior::Assign(assign) => v.visit_assign(assign),
+        Behavior::Oop(oop) => v.visit_oop(oop),
+    }
+}
+
+pub fn walk_assign<'ast, V>(v: &mut V, node: &'ast Assign)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    v.visit_tag(&node.tag);
+    v.visit_oop(&node.oop);
+}
+
+pub fn walk_statement<'ast, V>(v: &mut V, node: &'ast Statement)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    match node {
+        Statement::If(if_stmt) => v.visit_if(if_stmt),
+        Statement::While(while_stmt) => v.visit_while(while_stmt),
+        Statement::For(for_stmt) => v.visit_for(for_stmt),
+        Statement::Match(match_stmt) => v.visit_match(match_stmt),
+        Statement::Return(return_stmt) => v.visit_return(return_stmt),
+    }
+}
+
+pub fn walk_if<'ast, V>(v: &mut V, node: &'ast IfStatement)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    v.visit_condition(&node.condition);
+    v.visit_matrix(&node.then_block);
+    for elsif in &node.elsif_blocks {
+        v.visit_elsif(elsif);
+    }
+    if let Some(else_block) = &node.else_block {
+        v.visit_matrix(else_block);
+    }
+}
+
+pub fn walk_elsif<'ast, V>(v: &mut V, node: &'ast ElsifBlock)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    v.visit_condition(&node.condition);
+    v.visit_matrix(&node.block);
+}
+
+pub fn walk_while<'ast, V>(v: &mut V, node: &'ast WhileStatement)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    v.visit_condition(&node.condition);
+    v.visit_matrix(&node.block);
+}
+
+pub fn walk_for<'ast, V>(v: &mut V, node: &'ast ForStatement)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    v.visit_tag(&node.variable);
+    v.visit_oop(&node.iterable);
+    v.visit_matrix(&node.block);
+}
+
+pub fn walk_match<'ast, V>(v: &mut V, node: &'ast MatchStatement)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    v.visit_oop(&node.subject);
+    for arm in &node.arms {
+        v.visit_when(arm);
+    }
+    if let Some(else_block) = &node.else_block {
+        v.visit_matrix(else_block);
+    }
+}
+
+pub fn walk_when<'ast, V>(v: &mut V, node: &'ast WhenArm)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    for variant in &node.variants {
+        v.visit_specie(variant);
+    }
+    v.visit_matrix(&node.block);
+}
+
+pub fn walk_return<'ast, V>(v: &mut V, node: &'ast ReturnStatement)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    v.visit_oop(&node.value);
+}
+
+pub fn walk_condition<'ast, V>(v: &mut V, node: &'ast Condition)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    match node {
+        Condition::Oop(oop) => v.visit_oop(oop),
+        Condition::Expr(expr) => v.visit_expr(expr),
+    }
+}
+
+pub fn walk_expr<'ast, V>(v: &mut V, node: &'ast Expr)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    match node {
+        Expr::Oop(oop) => v.visit_oop(oop),
+        Expr::Binary { left, right, .. } => {
+            v.visit_expr(left);
+            v.visit_expr(right);
+        }
+        Expr::Unary { operand, .. } => v.visit_expr(operand),
+    }
+}
+
+pub fn walk_oop<'ast, V>(v: &mut V, node: &'ast Oop)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    v.visit_emitter(&node.emitter);
+    for trail in &node.trails {
+        v.visit_trail(trail);
+    }
+}
+
+pub fn walk_emitter<'ast, V>(v: &mut V, node: &'ast Emitter)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    match node {
+        Emitter::Specie(specie) => v.visit_specie(specie),
+        Emitter::Tag(tag) => v.visit_tag(tag),
+        Emitter::Literal(literal) => v.visit_literal(literal),
+        Emitter::SelfRef(self_ref) => v.visit_self_ref(self_ref),
+        Emitter::Template(template) => v.visit_template(template),
+        Emitter::List(list) => v.visit_list(list),
+        Emitter::Map(map) => v.visit_map(map),
+    }
+}
+
+pub fn walk_template<'ast, V>(v: &mut V, node: &'ast Template)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    for part in &node.parts {
+        if let TemplatePart::Oop(oop) = part {
+            v.visit_oop(oop);
+        }
+    }
+}
+
+pub fn walk_list<'ast, V>(v: &mut V, node: &'ast List)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    for item in &node.items {
+        v.visit_oop(item);
+    }
+}
+
+pub fn walk_map<'ast, V>(v: &mut V, node: &'ast Map)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    for entry in &node.entries {
+        v.visit_bind(entry);
+    }
+}
+
+pub fn walk_trail<'ast, V>(v: &mut V, node: &'ast Trail)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    match node {
+        Trail::Catalysis(catalysis) => v.visit_catalysis(catalysis),
+        Trail::Carrier(carrier) => v.visit_carrier(carrier),
+    }
+}
+
+pub fn walk_catalysis<'ast, V>(v: &mut V, node: &'ast Catalysis)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    v.visit_tag(&node.tag);
+    if let Some(carrier) = &node.carrier {
+        v.visit_carrier(carrier);
+    }
+}
+
+pub fn walk_carrier<'ast, V>(v: &mut V, node: &'ast Carrier)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    if let Some(transport) = &node.transport {
+        v.visit_transport(transport);
+    }
+}
+
+pub fn walk_transport<'ast, V>(v: &mut V, node: &'ast Transport)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    match node {
+        Transport::Binds(binds) => v.visit_binds(binds),
+        Transport::Sequence(sequence) => v.visit_sequence(sequence),
+    }
+}
+
+pub fn walk_binds<'ast, V>(v: &mut V, node: &'ast Binds)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    for bind in &node.binds {
+        v.visit_bind(bind);
+    }
+}
+
+pub fn walk_bind<'ast, V>(v: &mut V, node: &'ast Bind)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    v.visit_tag(&node.tag);
+    v.visit_oop(&node.oop);
+}
+
+pub fn walk_sequence<'ast, V>(v: &mut V, node: &'ast Sequence)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    for oop in &node.oops {
+        v.visit_oop(oop);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{walk_ethics, Visit};
+    use crate::dsl::ast::emitter::{Specie, Tag};
+    use crate::dsl::ast::ethics::Ethics;
+    use crate::dsl::ast::statement::IfStatement;
+    use crate::dsl::parser::tree::Tree;
+    use crate::tests::load_fragment;
+
+    /// Conta nomes usados dentro de ethics e `if`s, com o ethics onde aparecem
+    #[derive(Default)]
+    struct Census<'ast> {
+        ethics: Vec<&'ast str>,
+        tags: usize,
+        species: Vec<&'ast str>,
+        ifs: usize,
+    }
+
+    impl<'ast> Visit<'ast> for Census<'ast> {
+        fn visit_ethics(&mut self, node: &'ast Ethics) {
+            self.ethics.push(&node.tag.raw);
+            walk_ethics(self, node);
+        }
+
+        fn visit_if(&mut self, node: &'ast IfStatement) {
+            self.ifs += 1;
+            super::walk_if(self, node);
+        }
+
+        fn visit_tag(&mut self, _node: &'ast Tag) {
+            self.tags += 1;
+        }
+
+        fn visit_specie(&mut self, node: &'ast Specie) {
+            self.species.push(&node.raw);
+        }
+    }
+
+    #[test]
+    fn test_default_walk_reaches_every_node() {
+        let tree = Tree::parse_input(load_fragment("resolve/scopes.sc")).expect("Fixture should parse");
+        let mut census = Census::default();
+
+        census.visit_tree(&tree);
+
+        assert_eq!(census.ethics, vec!["latir"]);
+        assert_eq!(census.species, vec!["Dog", "Int", "Person", "Int", "String", "Dog"]);
+        assert_eq!(census.tags, 26);
+        assert_eq!(census.ifs, 0);
+    }
+
+    #[test]
+    fn test_overriding_without_walk_skips_children() {
+        struct Shallow(usize);
+
+        impl<'ast> Visit<'ast> for Shallow {
+            fn visit_ethics(&mut self, _node: &'ast Ethics) {}
+
+            fn visit_if(&mut self, _node: &'ast IfStatement) {
+                self.0 += 1;
+            }
+        }
+
+        let source = "bug Dog\n  ethics latir\n    if x\n      1\n    end\n  end\nend\nif a\n  if b\n    2\n  end\nend\n";
+        let tree = Tree::parse_input(source.to_string()).expect("Source should parse");
+        let mut shallow = Shallow(0);
+
+        shallow.visit_tree(&tree);
+
+        // Só o `if` de fora: o de dentro do ethics e o aninhado não são visitados
+        assert_eq!(shallow.0, 1);
+    }
+}
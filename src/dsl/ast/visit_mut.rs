@@ -0,0 +1,755 @@
+//! Percurso da AST com acesso mutável, para passos que reescrevem a árvore
+//! (renomear, desaçucarar...).
+//!
+//! Mesmos nomes e a mesma ordem de [`visit`](super::visit), com `&mut`, e mais
+//! [`VisitMut::visit_span`] para passos que só mexem nas posições. Quem sobrescreve
+//! uma folha (`visit_tag`, `visit_specie`...) e ainda quer os spans dela chama
+//! `visit_span` por conta própria.
+
+use crate::dsl::ast::anatomy::Anatomy;
+use crate::dsl::ast::behavior::assign::Assign;
+use crate::dsl::ast::behavior::oop::Oop;
+use crate::dsl::ast::behavior::sequence::Sequence;
+use crate::dsl::ast::behavior::transport::Transport;
+use crate::dsl::ast::behavior::{Behavior, Bind, Binds, Carrier, Catalysis, EthicsBind, Trail};
+use crate::dsl::ast::bug::Bug;
+use crate::dsl::ast::contract::Contract;
+use crate::dsl::ast::condition::Condition;
+use crate::dsl::ast::emitter::{Emitter, List, Literal, Map, SelfRef, Specie, SpecieRef, Tag, Template, TemplatePart};
+use crate::dsl::ast::error_node::ErrorNode;
+use crate::dsl::ast::ethics::{Ethics, Signature};
+use crate::dsl::ast::expr::Expr;
+use crate::dsl::ast::fly::Fly;
+use crate::dsl::ast::gene::Gene;
+use crate::dsl::ast::genome::Genome;
+use crate::dsl::ast::import::{Import, ImportName};
+use crate::dsl::ast::kind::{Kind, Variant};
+use crate::dsl::ast::matrix::Matrix;
+use crate::dsl::ast::nucleus::Nucleus;
+use crate::dsl::ast::sc::SC;
+use crate::dsl::ast::signal::Signal;
+use crate::dsl::ast::span::Span;
+use crate::dsl::ast::statement::{
+    ElsifBlock, ForStatement, IfStatement, MatchStatement, ReturnStatement, Statement, WhenArm, WhileStatement,
+};
+use crate::dsl::ast::strand::Strand;
+use crate::dsl::parser::tree::Tree;
+
+pub trait VisitMut {
+    fn visit_tree(&mut self, node: &mut Tree) {
+        walk_tree(self, node);
+    }
+
+    fn visit_sc(&mut self, node: &mut SC) {
+        walk_sc(self, node);
+    }
+
+    fn visit_fly(&mut self, node: &mut Fly) {
+        walk_fly(self, node);
+    }
+
+    fn visit_strand(&mut self, node: &mut Strand) {
+        walk_strand(self, node);
+    }
+
+    fn visit_genome(&mut self, node: &mut Genome) {
+        walk_genome(self, node);
+    }
+
+    fn visit_import(&mut self, node: &mut Import) {
+        walk_import(self, node);
+    }
+
+    fn visit_import_name(&mut self, node: &mut ImportName) {
+        walk_import_name(self, node);
+    }
+
+    fn visit_anatomy(&mut self, node: &mut Anatomy) {
+        walk_anatomy(self, node);
+    }
+
+    fn visit_bug(&mut self, node: &mut Bug) {
+        walk_bug(self, node);
+    }
+
+    fn visit_kind(&mut self, node: &mut Kind) {
+        walk_kind(self, node);
+    }
+
+    fn visit_contract(&mut self, node: &mut Contract) {
+        walk_contract(self, node);
+    }
+
+    fn visit_variant(&mut self, node: &mut Variant) {
+        walk_variant(self, node);
+    }
+
+    fn visit_gene(&mut self, node: &mut Gene) {
+        walk_gene(self, node);
+    }
+
+    fn visit_ethics(&mut self, node: &mut Ethics) {
+        walk_ethics(self, node);
+    }
+
+    fn visit_signature(&mut self, node: &mut Signature) {
+        walk_signature(self, node);
+    }
+
+    fn visit_ethics_bind(&mut self, node: &mut EthicsBind) {
+        walk_ethics_bind(self, node);
+    }
+
+    fn visit_specie_ref(&mut self, node: &mut SpecieRef) {
+        walk_specie_ref(self, node);
+    }
+
+    fn visit_nucleus(&mut self, node: &mut Nucleus) {
+        walk_nucleus(self, node);
+    }
+
+    fn visit_matrix(&mut self, node: &mut Matrix) {
+        walk_matrix(self, node);
+    }
+
+    fn visit_signal(&mut self, node: &mut Signal) {
+        walk_signal(self, node);
+    }
+
+    fn visit_behavior(&mut self, node: &mut Behavior) {
+        walk_behavior(self, node);
+    }
+
+    fn visit_assign(&mut self, node: &mut Assign) {
+        walk_assign(self, node);
+    }
+
+    fn visit_statement(&mut self, node: &mut Statement) {
+        walk_statement(self, node);
+    }
+
+    fn visit_if(&mut self, node: &mut IfStatement) {
+        walk_if(self, node);
+    }
+
+    fn visit_elsif(&mut self, node: &mut ElsifBlock) {
+        walk_elsif(self, node);
+    }
+
+    fn visit_while(&mut self, node: &mut WhileStatement) {
+        walk_while(self, node);
+    }
+
+    fn visit_for(&mut self, node: &mut ForStatement) {
+        walk_for(self, node);
+    }
+
+    fn visit_match(&mut self, node: &mut MatchStatement) {
+        walk_match(self, node);
+    }
+
+    fn visit_when(&mut self, node: &mut WhenArm) {
+        walk_when(self, node);
+    }
+
+    fn visit_return(&mut self, node: &mut ReturnStatement) {
+        walk_return(self, node);
+    }
+
+    fn visit_condition(&mut self, node: &mut Condition) {
+        walk_condition(self, node);
+    }
+
+    fn visit_expr(&mut self, node: &mut Expr) {
+        walk_expr(self, node);
+    }
+
+    fn visit_oop(&mut self, node: &mut Oop) {
+        walk_oop(self, node);
+    }
+
+    fn visit_emitter(&mut self, node: &mut Emitter) {
+        walk_emitter(self, node);
+    }
+
+    fn visit_template(&mut self, node: &mut Template) {
+        walk_template(self, node);
+    }
+
+    fn visit_list(&mut self, node: &mut List) {
+        walk_list(self, node);
+    }
+
+    fn visit_map(&mut self, node: &mut Map) {
+        walk_map(self, node);
+    }
+
+    fn visit_trail(&mut self, node: &mut Trail) {
+        walk_trail(self, node);
+    }
+
+    fn visit_catalysis(&mut self, node: &mut Catalysis) {
+        walk_catalysis(self, node);
+    }
+
+    fn visit_carrier(&mut self, node: &mut Carrier) {
+        walk_carrier(self, node);
+    }
+
+    fn visit_transport(&mut self, node: &mut Transport) {
+        walk_transport(self, node);
+    }
+
+    fn visit_binds(&mut self, node: &mut Binds) {
+        walk_binds(self, node);
+    }
+
+    fn visit_bind(&mut self, node: &mut Bind) {
+        walk_bind(self, node);
+    }
+
+    fn visit_sequence(&mut self, node: &mut Sequence) {
+        walk_sequence(self, node);
+    }
+
+    fn visit_tag(&mut self, node: &mut Tag) {
+        self.visit_span(&mut node.span);
+    }
+
+    fn visit_specie(&mut self, node: &mut Specie) {
+        self.visit_span(&mut node.span);
+    }
+
+    fn visit_literal(&mut self, node: &mut Literal) {
+        self.visit_span(node.span_mut());
+    }
+
+    fn visit_self_ref(&mut self, node: &mut SelfRef) {
+        self.visit_span(&mut node.span);
+    }
+
+    fn visit_error_node(&mut self, node: &mut ErrorNode) {
+        self.visit_span(&mut node.span);
+    }
+
+    /// Cada span da árvore, inclusive os das folhas e dos comentários
+    fn visit_span(&mut self, _span: &mut Span) {}
+}
+
+pub fn walk_tree<V>(v: &mut V, node: &mut Tree)
+where
+    V: VisitMut + ?Sized,
+{
+    v.visit_sc(&mut node.sc);
+    for comment in &mut node.comments {
+        v.visit_span(&mut comment.span);
+    }
+}
+
+pub fn walk_sc<V>(v: &mut V, node: &mut SC)
+where
+    V: VisitMut + ?Sized,
+{
+    v.visit_span(&mut node.span);
+    v.visit_fly(&mut node.fly);
+}
+
+pub fn walk_fly<V>(v: &mut V, node: &mut Fly)
+where
+    V: VisitMut + ?Sized,
+{
+    v.visit_span(&mut node.span);
+    v.visit_strand(&mut node.strand);
+}
+
+pub fn walk_strand<V>(v: &mut V, node: &mut Strand)
+where
+    V: VisitMut + ?Sized,
+{
+    v.visit_span(&mut node.span);
+    for genome in &mut node.genome {
+        v.visit_genome(genome);
+    }
+}
+
+pub fn walk_genome<V>(v: &mut V, node: &mut Genome)
+where
+    V: VisitMut + ?Sized,
+{
+    match node {
+        Genome::Import(import) => v.visit_import(import),
+        Genome::Anatomy(anatomy) => v.visit_anatomy(anatomy),
+        Genome::Behavior(behavior) => v.visit_behavior(behavior),
+        Genome::Error(error) => v.visit_error_node(error),
+    }
+}
+
+pub fn walk_import<V>(v: &mut V, node: &mut Import)
+where
+    V: VisitMut + ?Sized,
+{
+    v.visit_span(&mut node.span);
+    v.visit_span(&mut node.path_span);
+    for name in &mut node.names {
+        v.visit_import_name(name);
+    }
+}
+
+pub fn walk_import_name<V>(v: &mut V, node: &mut ImportName)
+where
+    V: VisitMut + ?Sized,
+{
+    v.visit_span(&mut node.span);
+    v.visit_specie(&mut node.specie);
+    if let Some(alias) = &mut node.alias {
+        v.visit_specie(alias);
+    }
+}
+
+pub fn walk_anatomy<V>(v: &mut V, node: &mut Anatomy)
+where
+    V: VisitMut + ?Sized,
+{
+    match node {
+        Anatomy::Bug(bug) => v.visit_bug(bug),
+        Anatomy::Kind(kind) => v.visit_kind(kind),
+        Anatomy::Contract(contract) => v.visit_contract(contract),
+    }
+}
+
+pub fn walk_bug<V>(v: &mut V, node: &mut Bug)
+where
+    V: VisitMut + ?Sized,
+{
+    v.visit_span(&mut node.span);
+    v.visit_specie(&mut node.specie);
+    for param in &mut node.params {
+        v.visit_specie(param);
+    }
+    for parent in &mut node.parents {
+        v.visit_specie(parent);
+    }
+    for gene in &mut node.genes {
+        v.visit_gene(gene);
+    }
+    for ethics in &mut node.ethics {
+        v.visit_ethics(ethics);
+    }
+}
+
+pub fn walk_kind<V>(v: &mut V, node: &mut Kind)
+where
+    V: VisitMut + ?Sized,
+{
+    v.visit_span(&mut node.span);
+    v.visit_specie(&mut node.specie);
+    for variant in &mut node.variants {
+        v.visit_variant(variant);
+    }
+}
+
+pub fn walk_contract<V>(v: &mut V, node: &mut Contract)
+where
+    V: VisitMut + ?Sized,
+{
+    v.visit_span(&mut node.span);
+    v.visit_specie(&mut node.specie);
+    for ethics in &mut node.ethics {
+        v.visit_ethics(ethics);
+    }
+}
+
+pub fn walk_variant<V>(v: &mut V, node: &mut Variant)
+where
+    V: VisitMut + ?Sized,
+{
+    v.visit_span(&mut node.span);
+    v.visit_specie(&mut node.specie);
+    for gene in &mut node.genes {
+        v.visit_gene(gene);
+    }
+}
+
+pub fn walk_gene<V>(v: &mut V, node: &mut Gene)
+where
+    V: VisitMut + ?Sized,
+{
+    v.visit_span(&mut node.span);
+    v.visit_tag(&mut node.tag);
+    v.visit_specie_ref(&mut node.specie);
+}
+
+pub fn walk_ethics<V>(v: &mut V, node: &mut Ethics)
+where
+    V: VisitMut + ?Sized,
+{
+    v.visit_span(&mut node.span);
+    v.visit_tag(&mut node.tag);
+    if let Some(signature) = &mut node.signature {
+        v.visit_signature(signature);
+    }
+    if let Some(feedback) = &mut node.feedback {
+        v.visit_specie_ref(feedback);
+    }
+    if let Some(body) = &mut node.body {
+        v.visit_matrix(body);
+    }
+}
+
+pub fn walk_signature<V>(v: &mut V, node: &mut Signature)
+where
+    V: VisitMut + ?Sized,
+{
+    v.visit_span(&mut node.span);
+    for bind in node.binds.iter_mut().flatten() {
+        v.visit_ethics_bind(bind);
+    }
+}
+
+pub fn walk_ethics_bind<V>(v: &mut V, node: &mut EthicsBind)
+where
+    V: VisitMut + ?Sized,
+{
+    v.visit_span(&mut node.span);
+    v.visit_tag(&mut node.tag);
+    v.visit_specie_ref(&mut node.specie);
+}
+
+pub fn walk_specie_ref<V>(v: &mut V, node: &mut SpecieRef)
+where
+    V: VisitMut + ?Sized,
+{
+    v.visit_span(&mut node.span);
+    v.visit_specie(&mut node.name);
+    for arg in &mut node.args {
+        v.visit_specie_ref(arg);
+    }
+}
+
+pub fn walk_nucleus<V>(v: &mut V, node: &mut Nucleus)
+where
+    V: VisitMut + ?Sized,
+{
+    v.visit_span(&mut node.span);
+    v.visit_matrix(&mut node.matrix);
+}
+
+pub fn walk_matrix<V>(v: &mut V, node: &mut Matrix)
+where
+    V: VisitMut + ?Sized,
+{
+    v.visit_span(&mut node.span);
+    for signal in &mut node.signals {
+        v.visit_signal(signal);
+    }
+}
+
+pub fn walk_signal<V>(v: &mut V, node: &mut Signal)
+where
+    V: VisitMut + ?Sized,
+{
+    match node {
+        Signal::Behavior(behavior) => v.visit_behavior(behavior),
+    }
+}
+
+pub fn walk_behavior<V>(v: &mut V, node: &mut Behavior)
+where
+    V: VisitMut + ?Sized,
+{
+    match node {
+        Behavior::Statement(statement) => v.visit_statement(statement),
+        Behavior::Assign(assign) => v.visit_assign(assign),
+        Behavior::Oop(oop) => v.visit_oop(oop),
+    }
+}
+
+pub fn walk_assign<V>(v: &mut V, node: &mut Assign)
+where
+    V: VisitMut + ?Sized,
+{
+    v.visit_span(&mut node.span);
+    v.visit_tag(&mut node.tag);
+    v.visit_oop(&mut node.oop);
+}
+
+pub fn walk_statement<V>(v: &mut V, node: &mut Statement)
+where
+    V: VisitMut + ?Sized,
+{
+    match node {
+        Statement::If(if_stmt) => v.visit_if(if_stmt),
+        Statement::While(while_stmt) => v.visit_while(while_stmt),
+        Statement::For(for_stmt) => v.visit_for(for_stmt),
+        Statement::Match(match_stmt) => v.visit_match(match_stmt),
+        Statement::Return(return_stmt) => v.visit_return(return_stmt),
+    }
+}
+
+pub fn walk_if<V>(v: &mut V, node: &mut IfStatement)
+where
+    V: VisitMut + ?Sized,
+{
+    v.visit_span(&mut node.span);
+    v.visit_condition(&mut node.condition);
+    v.visit_matrix(&mut node.then_block);
+    for elsif in &mut node.elsif_blocks {
+        v.visit_elsif(elsif);
+    }
+    if let Some(else_block) = &mut node.else_block {
+        v.visit_matrix(else_block);
+    }
+}
+
+pub fn walk_elsif<V>(v: &mut V, node: &mut ElsifBlock)
+where
+    V: VisitMut + ?Sized,
+{
+    v.visit_span(&mut node.span);
+    v.visit_condition(&mut node.condition);
+    v.visit_matrix(&mut node.block);
+}
+
+pub fn walk_while<V>(v: &mut V, node: &mut WhileStatement)
+where
+    V: VisitMut + ?Sized,
+{
+    v.visit_span(&mut node.span);
+    v.visit_condition(&mut node.condition);
+    v.visit_matrix(&mut node.block);
+}
+
+pub fn walk_for<V>(v: &mut V, node: &mut ForStatement)
+where
+    V: VisitMut + ?Sized,
+{
+    v.visit_span(&mut node.span);
+    v.visit_tag(&mut node.variable);
+    v.visit_oop(&mut node.iterable);
+    v.visit_matrix(&mut node.block);
+}
+
+pub fn walk_match<V>(v: &mut V, node: &mut MatchStatement)
+where
+    V: VisitMut + ?Sized,
+{
+    v.visit_span(&mut node.span);
+    v.visit_oop(&mut node.subject);
+    for arm in &mut node.arms {
+        v.visit_when(arm);
+    }
+    if let Some(else_block) = &mut node.else_block {
+        v.visit_matrix(else_block);
+    }
+}
+
+pub fn walk_when<V>(v: &mut V, node: &mut WhenArm)
+where
+    V: VisitMut + ?Sized,
+{
+    v.visit_span(&mut node.span);
+    for variant in &mut node.variants {
+        v.visit_specie(variant);
+    }
+    v.visit_matrix(&mut node.block);
+}
+
+pub fn walk_return<V>(v: &mut V, node: &mut ReturnStatement)
+where
+    V: VisitMut + ?Sized,
+{
+    v.visit_span(&mut node.span);
+    v.visit_oop(&mut node.value);
+}
+
+pub fn walk_condition<V>(v: &mut V, node: &mut Condition)
+where
+    V: VisitMut + ?Sized,
+{
+    match node {
+        Condition::Oop(oop) => v.visit_oop(oop),
+        Condition::Expr(expr) => v.visit_expr(expr),
+    }
+}
+
+pub fn walk_expr<V>(v: &mut V, node: &mut Expr)
+where
+    V: VisitMut + ?Sized,
+{
+    match node {
+        Expr::Oop(oop) => v.visit_oop(oop),
+        Expr::Binary { left, right, span, .. } => {
+            v.visit_span(span);
+            v.visit_expr(left);
+            v.visit_expr(right);
+        }
+        Expr::Unary { operand, span, .. } => {
+            v.visit_span(span);
+            v.visit_expr(operand);
+        }
+    }
+}
+
+pub fn walk_oop<V>(v: &mut V, node: &mut Oop)
+where
+    V: VisitMut + ?Sized,
+{
+    v.visit_span(&mut node.span);
+    v.visit_emitter(&mut node.emitter);
+    for trail in &mut node.trails {
+        v.visit_trail(trail);
+    }
+}
+
+pub fn walk_emitter<V>(v: &mut V, node: &mut Emitter)
+where
+    V: VisitMut + ?Sized,
+{
+    match node {
+        Emitter::Specie(specie) => v.visit_specie(specie),
+        Emitter::Tag(tag) => v.visit_tag(tag),
+        Emitter::Literal(literal) => v.visit_literal(literal),
+        Emitter::SelfRef(self_ref) => v.visit_self_ref(self_ref),
+        Emitter::Template(template) => v.visit_template(template),
+        Emitter::List(list) => v.visit_list(list),
+        Emitter::Map(map) => v.visit_map(map),
+    }
+}
+
+pub fn walk_template<V>(v: &mut V, node: &mut Template)
+where
+    V: VisitMut + ?Sized,
+{
+    v.visit_span(&mut node.span);
+    for part in &mut node.parts {
+        match part {
+            TemplatePart::Text { span, .. } => v.visit_span(span),
+            TemplatePart::Oop(oop) => v.visit_oop(oop),
+        }
+    }
+}
+
+pub fn walk_list<V>(v: &mut V, node: &mut List)
+where
+    V: VisitMut + ?Sized,
+{
+    v.visit_span(&mut node.span);
+    for item in &mut node.items {
+        v.visit_oop(item);
+    }
+}
+
+pub fn walk_map<V>(v: &mut V, node: &mut Map)
+where
+    V: VisitMut + ?Sized,
+{
+    v.visit_span(&mut node.span);
+    for entry in &mut node.entries {
+        v.visit_bind(entry);
+    }
+}
+
+pub fn walk_trail<V>(v: &mut V, node: &mut Trail)
+where
+    V: VisitMut + ?Sized,
+{
+    match node {
+        Trail::Catalysis(catalysis) => v.visit_catalysis(catalysis),
+        Trail::Carrier(carrier) => v.visit_carrier(carrier),
+    }
+}
+
+pub fn walk_catalysis<V>(v: &mut V, node: &mut Catalysis)
+where
+    V: VisitMut + ?Sized,
+{
+    v.visit_span(&mut node.span);
+    v.visit_tag(&mut node.tag);
+    if let Some(carrier) = &mut node.carrier {
+        v.visit_carrier(carrier);
+    }
+}
+
+pub fn walk_carrier<V>(v: &mut V, node: &mut Carrier)
+where
+    V: VisitMut + ?Sized,
+{
+    v.visit_span(&mut node.span);
+    if let Some(transport) = &mut node.transport {
+        v.visit_transport(transport);
+    }
+}
+
+pub fn walk_transport<V>(v: &mut V, node: &mut Transport)
+where
+    V: VisitMut + ?Sized,
+{
+    match node {
+        Transport::Binds(binds) => v.visit_binds(binds),
+        Transport::Sequence(sequence) => v.visit_sequence(sequence),
+    }
+}
+
+pub fn walk_binds<V>(v: &mut V, node: &mut Binds)
+where
+    V: VisitMut + ?Sized,
+{
+    v.visit_span(&mut node.span);
+    for bind in &mut node.binds {
+        v.visit_bind(bind);
+    }
+}
+
+pub fn walk_bind<V>(v: &mut V, node: &mut Bind)
+where
+    V: VisitMut + ?Sized,
+{
+    v.visit_span(&mut node.span);
+    v.visit_tag(&mut node.tag);
+    v.visit_oop(&mut node.oop);
+}
+
+pub fn walk_sequence<V>(v: &mut V, node: &mut Sequence)
+where
+    V: VisitMut + ?Sized,
+{
+    v.visit_span(&mut node.span);
+    for oop in &mut node.oops {
+        v.visit_oop(oop);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::VisitMut;
+    use crate::dsl::ast::emitter::Tag;
+    use crate::dsl::parser::tree::Tree;
+    use crate::dsl::printer::print_tree;
+
+    /// Renomeia uma variável em todos os lugares
+    struct Rename<'a> {
+        from: &'a str,
+        to: &'a str,
+    }
+
+    impl VisitMut for Rename<'_> {
+        fn visit_tag(&mut self, node: &mut Tag) {
+            if node.raw == self.from {
+                node.raw = self.to.to_string();
+            }
+        }
+    }
+
+    #[test]
+    fn test_renamer_touches_every_tag() {
+        let source = "total = 0\nfor item in list(1, 2)\n  total = total.plus(item)\nend\nprint(total: total)\n";
+        let mut tree = Tree::parse_input(source.to_string()).expect("Source should parse");
+
+        Rename { from: "total", to: "soma" }.visit_tree(&mut tree);
+
+        assert_eq!(
+            print_tree(&tree),
+            "soma = 0\nfor item in list(1, 2)\n  soma = soma.plus(item)\nend\nprint(soma: soma)\n"
+        );
+    }
+}
@@ -0,0 +1,72 @@
+//! Passo que remove os operadores da árvore, trocando cada `Condition::Expr`
+//! pela chamada de método equivalente (`Condition::Oop`).
+//!
+//! Serve para backends que só entendem `Catalysis`; veja `Expr::desugar`
+//! para a tabela de operadores e métodos.
+
+use crate::dsl::ast::condition::Condition;
+use crate::dsl::ast::visit_mut::{self, VisitMut};
+use crate::dsl::parser::tree::Tree;
+
+/// Desaçucara todas as condições da árvore, no lugar.
+pub fn desugar(tree: &mut Tree) {
+    Desugar.visit_tree(tree);
+}
+
+struct Desugar;
+
+impl VisitMut for Desugar {
+    fn visit_condition(&mut self, condition: &mut Condition) {
+        if let Condition::Expr(_) = condition {
+            *condition = Condition::Oop(condition.desugar());
+        }
+        visit_mut::walk_condition(self, condition);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::desugar;
+    use crate::dsl::ast::behavior::{Behavior, Trail};
+    use crate::dsl::ast::condition::Condition;
+    use crate::dsl::ast::genome::Genome;
+    use crate::dsl::ast::signal::Signal;
+    use crate::dsl::ast::statement::Statement;
+    use crate::dsl::parser::tree::Tree;
+    use crate::tests::load_fragment;
+
+    #[test]
+    fn test_desugar_replaces_operator_conditions() {
+        let input = load_fragment("statement/while_operators.sc");
+        let mut tree = Tree::parse_input(input).expect("Should parse operators");
+
+        let Genome::Behavior(Behavior::Statement(Statement::While(while_stmt))) = &tree.sc.fly.strand.genome[0] else {
+            panic!("Esperava um while");
+        };
+        assert!(matches!(while_stmt.condition, Condition::Expr(_)));
+
+        desugar(&mut tree);
+
+        let Genome::Behavior(Behavior::Statement(Statement::While(while_stmt))) = &tree.sc.fly.strand.genome[0] else {
+            panic!("Esperava um while");
+        };
+        let Condition::Oop(oop) = &while_stmt.condition else {
+            panic!("Condição deveria ter virado Oop");
+        };
+        let methods: Vec<&str> = oop
+            .trails
+            .iter()
+            .filter_map(|trail| match trail {
+                Trail::Catalysis(catalysis) => Some(catalysis.tag.raw.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(methods, vec!["lt", "and"]);
+
+        // O if aninhado também é desaçucarado
+        let Signal::Behavior(Behavior::Statement(Statement::If(if_stmt))) = &while_stmt.block.signals[1] else {
+            panic!("Esperava um if");
+        };
+        assert!(matches!(if_stmt.condition, Condition::Oop(_)));
+    }
+}
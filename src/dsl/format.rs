@@ -0,0 +1,126 @@
+//! Formatador de código SC: `printer` com os comentários devolvidos ao lugar.
+//!
+//! Normaliza a indentação dentro de `bug`, `ethics` e blocos, o espaço em volta
+//! de `=`, `:` e `,` e as linhas em branco entre genes e ethics. Uma linha em
+//! branco entre statements é mantida; várias viram uma.
+
+use thiserror::Error;
+use crate::dsl::ast::span::Span;
+use crate::dsl::parser::diagnostic::Diagnostic;
+use crate::dsl::parser::scan;
+use crate::dsl::parser::tree::{ParseOptions, Tree, TreeParseError};
+use crate::dsl::printer::{print_with_comments, same_ast};
+
+#[derive(Debug, Error)]
+pub enum FormatError {
+    #[error(transparent)]
+    Parse(#[from] TreeParseError),
+    /// O texto formatado não volta à mesma árvore ou perdeu comentários: bug do formatador
+    #[error("formatting would change the program")]
+    Unstable,
+}
+
+impl FormatError {
+    pub fn diagnostic(&self, source: &str) -> Diagnostic {
+        match self {
+            FormatError::Parse(error) => error.diagnostic(source),
+            FormatError::Unstable => Diagnostic::error(self.to_string(), scan::span_at(source, 0, 0))
+                .with_note("the file was left untouched; please report this"),
+        }
+    }
+}
+
+/// Código em forma canônica. Falha se o código não for válido.
+pub fn format_source(source: &str) -> Result<String, FormatError> {
+    let options = ParseOptions { retain_comments: true };
+    let tree = Tree::parse_with(source.to_string(), options)?;
+    let formatted = print_with_comments(&tree, source);
+
+    // Confere o resultado antes de devolvê-lo: nada pode sumir nem mudar de sentido
+    let reparsed = Tree::parse_with(formatted.clone(), options).map_err(|_| FormatError::Unstable)?;
+    let comments = |tree: &Tree| tree.comments.iter().map(|comment| comment.raw.trim_end().to_string()).collect::<Vec<_>>();
+    if !same_ast(&tree.sc, &reparsed.sc) || comments(&tree) != comments(&reparsed) {
+        return Err(FormatError::Unstable);
+    }
+    Ok(formatted)
+}
+
+/// Modo `--check`: `None` se o código já está formatado, senão o span da
+/// primeira linha que mudaria.
+pub fn check(source: &str) -> Result<Option<Span>, FormatError> {
+    let formatted = format_source(source)?;
+    if formatted == source {
+        return Ok(None);
+    }
+    let mut offset = 0;
+    for (line, expected) in source.split_inclusive('\n').zip(formatted.split_inclusive('\n')) {
+        if line != expected {
+            break;
+        }
+        offset += line.len();
+    }
+    let end = source[offset..].find('\n').map_or(source.len(), |end| offset + end);
+    Ok(Some(scan::span_at(source, offset, end)))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use super::{check, format_source, FormatError};
+    use crate::tests::load_fragment;
+
+    #[test]
+    fn test_formats_and_keeps_comments() {
+        let source = load_fragment("format/messy.sc");
+
+        let formatted = format_source(&source).expect("Fixture should format");
+
+        assert_eq!(formatted, load_fragment("format/formatted.sc"));
+    }
+
+    #[test]
+    fn test_formatting_is_idempotent() {
+        let formatted = load_fragment("format/formatted.sc");
+
+        assert_eq!(format_source(&formatted).expect("Fixture should format"), formatted);
+        assert_eq!(check(&formatted).expect("Fixture should parse"), None);
+    }
+
+    #[test]
+    fn test_every_fixture_formats_stably() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/fragments");
+        let mut formatted_any = 0;
+        for area in std::fs::read_dir(dir).expect("Fixture dir should be readable") {
+            for entry in std::fs::read_dir(area.expect("Entry should be readable").path()).expect("Area should be a dir") {
+                let source = std::fs::read_to_string(entry.expect("Entry should be readable").path())
+                    .expect("Fixture should be readable");
+                match format_source(&source) {
+                    Ok(formatted) => {
+                        assert_eq!(format_source(&formatted).ok().as_ref(), Some(&formatted), "not stable:\n{}", formatted);
+                        formatted_any += 1;
+                    }
+                    Err(FormatError::Parse(_)) => {}
+                    Err(FormatError::Unstable) => panic!("formatting changed the program:\n{}", source),
+                }
+            }
+        }
+        assert!(formatted_any > 30);
+    }
+
+    #[test]
+    fn test_check_points_at_first_changed_line() {
+        let source = "bug Dog\n  gene energia Int\n   ethics latir\nend\n";
+
+        let span = check(source).expect("Source should parse").expect("Source is not formatted");
+
+        assert_eq!((span.line, span.col), (3, 1));
+    }
+
+    #[test]
+    fn test_invalid_source_is_not_formatted() {
+        let error = format_source("bug Dog\n").expect_err("Source should not parse");
+
+        assert!(matches!(error, FormatError::Parse(_)));
+        assert!(error.diagnostic("bug Dog\n").message.contains("`end`"));
+    }
+}
@@ -0,0 +1,59 @@
+use thiserror::Error;
+use crate::dsl::ast::span::Span;
+use crate::dsl::parser::diagnostic::Diagnostic;
+
+/// Falha durante a execução; sempre aponta para o trecho que a causou.
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum RuntimeError {
+    #[error("cannot find `{name}` in this scope")]
+    UndefinedName { name: String, span: Span },
+    #[error("cannot find species `{name}`")]
+    UnknownSpecie { name: String, span: Span },
+    #[error("no method `{method}` on {receiver}")]
+    UnknownMethod { receiver: String, method: String, span: Span },
+    #[error("{found} is not callable")]
+    NotCallable { found: String, span: Span },
+    #[error("`{ethics}` takes {expected} argument(s) but {found} were given")]
+    Arity { ethics: String, expected: usize, found: usize, span: Span },
+    #[error("`{ethics}` has no parameter `{name}`")]
+    UnknownArgument { ethics: String, name: String, span: Span },
+    #[error("{specie} has no gene `{name}`")]
+    UnknownGene { specie: String, name: String, span: Span },
+    #[error("expected {expected}, found {found}")]
+    TypeMismatch { expected: String, found: String, span: Span },
+    #[error("division by zero")]
+    DivisionByZero { span: Span },
+    #[error("integer overflow")]
+    Overflow { span: Span },
+    #[error("cannot iterate over {found}")]
+    NotIterable { found: String, span: Span },
+    #[error("too many nested calls (limit is {limit})")]
+    StackOverflow { limit: usize, span: Span },
+    /// Só da VM, ao compilar: a instrução guarda a quantidade em 16 bits
+    #[error("too many items ({found}); a list, map, template or call takes at most {limit}")]
+    TooManyItems { found: usize, limit: usize, span: Span },
+}
+
+impl RuntimeError {
+    pub fn span(&self) -> Span {
+        match self {
+            RuntimeError::UndefinedName { span, .. }
+            | RuntimeError::UnknownSpecie { span, .. }
+            | RuntimeError::UnknownMethod { span, .. }
+            | RuntimeError::NotCallable { span, .. }
+            | RuntimeError::Arity { span, .. }
+            | RuntimeError::UnknownArgument { span, .. }
+            | RuntimeError::UnknownGene { span, .. }
+            | RuntimeError::TypeMismatch { span, .. }
+            | RuntimeError::DivisionByZero { span }
+            | RuntimeError::Overflow { span }
+            | RuntimeError::NotIterable { span, .. }
+            | RuntimeError::StackOverflow { span, .. }
+            | RuntimeError::TooManyItems { span, .. } => *span,
+        }
+    }
+
+    pub fn diagnostic(&self) -> Diagnostic {
+        Diagnostic::error(self.to_string(), self.span())
+    }
+}
@@ -0,0 +1,320 @@
+use std::collections::BTreeMap;
+use crate::dsl::ast::span::Span;
+use super::error::RuntimeError;
+use super::value::Value;
+
+/// O que o interpretador não sabe fazer sozinho: funções livres (`print`),
+/// métodos das espécies embutidas (`Int`, `String`, `Bool`...) e valores
+/// iniciais de genes.
+///
+/// `Ok(None)` quer dizer "não conheço esse nome" e vira `UnknownMethod` ou
+/// `UndefinedName` no interpretador. Um host próprio pode guardar um
+/// `Builtins` e delegar a ele o que não tratar.
+pub trait Host {
+    fn call_function(&mut self, name: &str, args: &[Value], span: Span) -> Result<Option<Value>, RuntimeError>;
+
+    fn call_method(&mut self, receiver: &Value, name: &str, args: &[Value], span: Span) -> Result<Option<Value>, RuntimeError>;
+
+    /// Valor de um gene recém-criado da espécie `specie`
+    fn default_value(&self, _specie: &str) -> Value {
+        Value::Nil
+    }
+
+    /// Texto de um valor em `#{...}` (e, no `Builtins`, em `print` e `to_s`)
+    fn show(&self, value: &Value) -> String {
+        value.to_string()
+    }
+
+    /// Itens percorridos por `for`; `None` quando o valor não é iterável
+    fn iterate(&self, value: &Value) -> Option<Vec<Value>> {
+        match value {
+            Value::List(items) => Some(items.clone()),
+            _ => None,
+        }
+    }
+}
+
+/// Host padrão com as espécies `Int`, `Decimal`, `String`, `Bool`, `List`, `Map` e `Nil`.
+///
+/// Tudo o que `print` escreve fica em `printed`; com `echo` também vai para a saída padrão.
+/// `names` troca o nome das espécies ao mostrar valores (veja [`Value::shown`]).
+#[derive(Debug, Clone)]
+pub struct Builtins {
+    pub printed: Vec<String>,
+    pub echo: bool,
+    pub names: BTreeMap<String, String>,
+}
+
+impl Builtins {
+    pub fn new() -> Self {
+        Builtins { printed: Vec::new(), echo: true, names: BTreeMap::new() }
+    }
+
+    /// Não escreve na saída padrão, só guarda em `printed`
+    pub fn silent() -> Self {
+        Builtins { printed: Vec::new(), echo: false, names: BTreeMap::new() }
+    }
+}
+
+impl Default for Builtins {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Host for Builtins {
+    fn call_function(&mut self, name: &str, args: &[Value], _span: Span) -> Result<Option<Value>, RuntimeError> {
+        match name {
+            "print" => {
+                let line: Vec<String> = args.iter().map(|arg| self.show(arg)).collect();
+                let line = line.join(" ");
+                if self.echo {
+                    println!("{}", line);
+                }
+                self.printed.push(line);
+                Ok(Some(Value::Nil))
+            }
+            "list" => Ok(Some(Value::List(args.to_vec()))),
+            _ => Ok(None),
+        }
+    }
+
+    fn call_method(&mut self, receiver: &Value, name: &str, args: &[Value], span: Span) -> Result<Option<Value>, RuntimeError> {
+        let value = match (receiver, name) {
+            (_, "eq") => Value::Bool(*receiver == *arg(name, args, span)?),
+            (_, "ne") => Value::Bool(*receiver != *arg(name, args, span)?),
+            (_, "to_s") => Value::Str(self.show(receiver)),
+            (_, "is_nil") => Value::Bool(receiver.is_nil()),
+            (Value::Int(_) | Value::Decimal(_), _) => return number_method(receiver, name, args, span),
+            (Value::Str(text), _) => return string_method(text, name, args, span),
+            (Value::Bool(value), "not") => Value::Bool(!value),
+            (Value::Bool(value), "and") => Value::Bool(*value && arg(name, args, span)?.is_truthy()),
+            (Value::Bool(value), "or") => Value::Bool(*value || arg(name, args, span)?.is_truthy()),
+            (Value::Nil, "not") => Value::Bool(true),
+            (Value::List(items), _) => return list_method(items, name, args, span),
+            (Value::Map(entries), _) => return map_method(entries, name, args, span),
+            _ => return Ok(None),
+        };
+        Ok(Some(value))
+    }
+
+    fn default_value(&self, specie: &str) -> Value {
+        match specie {
+            "Int" => Value::Int(0),
+            "Decimal" => Value::Decimal(0.0),
+            "String" => Value::Str(String::new()),
+            "Bool" => Value::Bool(false),
+            "List" => Value::List(Vec::new()),
+            "Map" => Value::Map(BTreeMap::new()),
+            _ => Value::Nil,
+        }
+    }
+
+    fn show(&self, value: &Value) -> String {
+        value.shown(&self.names).to_string()
+    }
+
+    fn iterate(&self, value: &Value) -> Option<Vec<Value>> {
+        match value {
+            Value::List(items) => Some(items.clone()),
+            Value::Int(count) => Some((0..*count).map(Value::Int).collect()),
+            Value::Str(text) => Some(text.chars().map(|c| Value::Str(c.to_string())).collect()),
+            Value::Map(entries) => Some(entries.keys().cloned().map(Value::Str).collect()),
+            _ => None,
+        }
+    }
+}
+
+/// Único argumento de um método embutido
+fn arg<'a>(name: &str, args: &'a [Value], span: Span) -> Result<&'a Value, RuntimeError> {
+    match args {
+        [value] => Ok(value),
+        _ => Err(RuntimeError::Arity { ethics: name.to_string(), expected: 1, found: args.len(), span }),
+    }
+}
+
+fn no_args(name: &str, args: &[Value], span: Span) -> Result<(), RuntimeError> {
+    match args {
+        [] => Ok(()),
+        _ => Err(RuntimeError::Arity { ethics: name.to_string(), expected: 0, found: args.len(), span }),
+    }
+}
+
+fn number_method(receiver: &Value, name: &str, args: &[Value], span: Span) -> Result<Option<Value>, RuntimeError> {
+    let value = match name {
+        "neg" => {
+            no_args(name, args, span)?;
+            match receiver {
+                Value::Int(a) => Value::Int(a.checked_neg().ok_or(RuntimeError::Overflow { span })?),
+                Value::Decimal(a) => Value::Decimal(-a),
+                _ => return Ok(None),
+            }
+        }
+        "abs" => {
+            no_args(name, args, span)?;
+            match receiver {
+                Value::Int(a) => Value::Int(a.checked_abs().ok_or(RuntimeError::Overflow { span })?),
+                Value::Decimal(a) => Value::Decimal(a.abs()),
+                _ => return Ok(None),
+            }
+        }
+        "plus" | "minus" | "times" | "div" | "mod" | "lt" | "le" | "bt" | "be" => {
+            let other = arg(name, args, span)?;
+            match (receiver, other) {
+                (Value::Int(a), Value::Int(b)) => int_op(*a, *b, name, span)?,
+                (Value::Int(_) | Value::Decimal(_), Value::Int(_) | Value::Decimal(_)) => {
+                    decimal_op(as_f64(receiver), as_f64(other), name, span)?
+                }
+                _ => {
+                    return Err(RuntimeError::TypeMismatch {
+                        expected: "Int or Decimal".to_string(),
+                        found: other.specie(),
+                        span,
+                    })
+                }
+            }
+        }
+        _ => return Ok(None),
+    };
+    Ok(Some(value))
+}
+
+fn as_f64(value: &Value) -> f64 {
+    match value {
+        Value::Int(value) => *value as f64,
+        Value::Decimal(value) => *value,
+        _ => 0.0,
+    }
+}
+
+fn int_op(a: i64, b: i64, name: &str, span: Span) -> Result<Value, RuntimeError> {
+    let checked = match name {
+        "plus" => a.checked_add(b),
+        "minus" => a.checked_sub(b),
+        "times" => a.checked_mul(b),
+        "div" | "mod" if b == 0 => return Err(RuntimeError::DivisionByZero { span }),
+        "div" => a.checked_div(b),
+        "mod" => a.checked_rem(b),
+        _ => return Ok(Value::Bool(compare(a.cmp(&b), name))),
+    };
+    checked.map(Value::Int).ok_or(RuntimeError::Overflow { span })
+}
+
+fn decimal_op(a: f64, b: f64, name: &str, span: Span) -> Result<Value, RuntimeError> {
+    let value = match name {
+        "plus" => a + b,
+        "minus" => a - b,
+        "times" => a * b,
+        "div" | "mod" if b == 0.0 => return Err(RuntimeError::DivisionByZero { span }),
+        "div" => a / b,
+        "mod" => a % b,
+        _ => {
+            let ordering = a.partial_cmp(&b).unwrap_or(std::cmp::Ordering::Equal);
+            return Ok(Value::Bool(compare(ordering, name)));
+        }
+    };
+    Ok(Value::Decimal(value))
+}
+
+/// `lt`, `le`, `bt` (bigger than) e `be` (bigger or equal)
+fn compare(ordering: std::cmp::Ordering, name: &str) -> bool {
+    match name {
+        "lt" => ordering.is_lt(),
+        "le" => ordering.is_le(),
+        "bt" => ordering.is_gt(),
+        _ => ordering.is_ge(),
+    }
+}
+
+fn string_method(text: &str, name: &str, args: &[Value], span: Span) -> Result<Option<Value>, RuntimeError> {
+    let value = match name {
+        "plus" => match arg(name, args, span)? {
+            Value::Str(other) => Value::Str(format!("{}{}", text, other)),
+            other => {
+                return Err(RuntimeError::TypeMismatch { expected: "String".to_string(), found: other.specie(), span })
+            }
+        },
+        "size" => {
+            no_args(name, args, span)?;
+            Value::Int(text.chars().count() as i64)
+        }
+        "upcase" => {
+            no_args(name, args, span)?;
+            Value::Str(text.to_uppercase())
+        }
+        "downcase" => {
+            no_args(name, args, span)?;
+            Value::Str(text.to_lowercase())
+        }
+        "includes" => match arg(name, args, span)? {
+            Value::Str(other) => Value::Bool(text.contains(other.as_str())),
+            other => {
+                return Err(RuntimeError::TypeMismatch { expected: "String".to_string(), found: other.specie(), span })
+            }
+        },
+        _ => return Ok(None),
+    };
+    Ok(Some(value))
+}
+
+fn list_method(items: &[Value], name: &str, args: &[Value], span: Span) -> Result<Option<Value>, RuntimeError> {
+    let value = match name {
+        "size" => {
+            no_args(name, args, span)?;
+            Value::Int(items.len() as i64)
+        }
+        "first" => {
+            no_args(name, args, span)?;
+            items.first().cloned().unwrap_or(Value::Nil)
+        }
+        "last" => {
+            no_args(name, args, span)?;
+            items.last().cloned().unwrap_or(Value::Nil)
+        }
+        "at" => match arg(name, args, span)? {
+            Value::Int(index) => usize::try_from(*index).ok().and_then(|i| items.get(i)).cloned().unwrap_or(Value::Nil),
+            other => return Err(RuntimeError::TypeMismatch { expected: "Int".to_string(), found: other.specie(), span }),
+        },
+        "push" => {
+            let mut items = items.to_vec();
+            items.push(arg(name, args, span)?.clone());
+            Value::List(items)
+        }
+        "includes" => Value::Bool(items.contains(arg(name, args, span)?)),
+        _ => return Ok(None),
+    };
+    Ok(Some(value))
+}
+
+fn map_method(entries: &BTreeMap<String, Value>, name: &str, args: &[Value], span: Span) -> Result<Option<Value>, RuntimeError> {
+    let key = |value: &Value| match value {
+        Value::Str(key) => Ok(key.clone()),
+        other => Err(RuntimeError::TypeMismatch { expected: "String".to_string(), found: other.specie(), span }),
+    };
+    let value = match name {
+        "size" => {
+            no_args(name, args, span)?;
+            Value::Int(entries.len() as i64)
+        }
+        "keys" => {
+            no_args(name, args, span)?;
+            Value::List(entries.keys().cloned().map(Value::Str).collect())
+        }
+        "values" => {
+            no_args(name, args, span)?;
+            Value::List(entries.values().cloned().collect())
+        }
+        "at" => entries.get(&key(arg(name, args, span)?)?).cloned().unwrap_or(Value::Nil),
+        "has" => Value::Bool(entries.contains_key(&key(arg(name, args, span)?)?)),
+        "put" => match args {
+            [at, value] => {
+                let mut entries = entries.clone();
+                entries.insert(key(at)?, value.clone());
+                Value::Map(entries)
+            }
+            _ => return Err(RuntimeError::Arity { ethics: name.to_string(), expected: 2, found: args.len(), span }),
+        },
+        _ => return Ok(None),
+    };
+    Ok(Some(value))
+}
@@ -0,0 +1,757 @@
+//! Interpretador que percorre a AST e executa programas SC.
+//!
+//! Os `bug`s de um `Strand` são registrados antes de tudo; os genomas de
+//! comportamento rodam em ordem e o valor do último é o resultado do programa.
+//! Os `kind`s também: cada variante vira uma espécie que cria um
+//! [`Value::Variant`], e `match` escolhe o braço pelo nome da variante.
+//! Dentro de um `ethics`, nomes soltos procuram primeiro as variáveis locais,
+//! depois os genes e ethics da própria instância (`$`).
+
+pub mod error;
+pub mod host;
+pub(crate) mod species;
+pub mod value;
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::iter::Peekable;
+use std::rc::Rc;
+use std::slice;
+use crate::dsl::ast::anatomy::Anatomy;
+use crate::dsl::ast::behavior::oop::Oop;
+use crate::dsl::ast::behavior::transport::Transport;
+use crate::dsl::ast::behavior::{Behavior, Carrier, Trail};
+use crate::dsl::ast::bug::Bug;
+use crate::dsl::ast::condition::Condition;
+use crate::dsl::ast::emitter::literal::unescape;
+use crate::dsl::ast::emitter::{Emitter, Literal, TemplatePart};
+use crate::dsl::ast::ethics::Ethics;
+use crate::dsl::ast::expr::{BinaryOp, Expr};
+use crate::dsl::ast::fly::Fly;
+use crate::dsl::ast::genome::Genome;
+use crate::dsl::ast::kind::Kind;
+use crate::dsl::ast::matrix::Matrix;
+use crate::dsl::ast::signal::Signal;
+use crate::dsl::ast::span::Span;
+use crate::dsl::ast::statement::Statement;
+use crate::dsl::parser::tree::Tree;
+pub use error::RuntimeError;
+pub use host::{Builtins, Host};
+pub use value::{Instance, Shown, Value, Variant};
+use species::{bind_genes, default_genes, lineage, Args};
+
+/// Limite padrão de chamadas de ethics aninhadas antes de `StackOverflow`
+///
+/// Cada chamada desce pela pilha nativa (uns 11 KiB por nível em debug, mais
+/// com blocos aninhados no corpo); este limite cabe na pilha de 2 MiB de uma
+/// thread comum. Para ir além, use [`Interpreter::with_max_depth`] numa thread
+/// com mais pilha.
+pub const MAX_DEPTH: usize = 100;
+
+/// Pilha nativa da thread em que o `sc` roda os programas
+pub const STACK_SIZE: usize = 256 << 20;
+
+/// Limite de chamadas que cabe em [`STACK_SIZE`] mesmo em debug
+pub const STACK_MAX_DEPTH: usize = 5_000;
+
+pub struct Interpreter<H: Host = Builtins> {
+    pub host: H,
+    species: HashMap<String, Rc<Bug>>,
+    /// Kind de cada variante, pelo nome da variante
+    kinds: HashMap<String, Rc<Kind>>,
+    globals: Frame,
+    depth: usize,
+    max_depth: usize,
+}
+
+/// Variáveis visíveis em um trecho de código e a instância dona do ethics, se houver.
+#[derive(Debug, Default)]
+struct Frame {
+    locals: HashMap<String, Value>,
+    this: Option<Rc<RefCell<Instance>>>,
+}
+
+/// Como um bloco terminou: normalmente, com o valor do último sinal, ou por `return`.
+enum Flow {
+    Next(Value),
+    Return(Value),
+}
+
+impl Interpreter<Builtins> {
+    pub fn new() -> Self {
+        Interpreter::with_host(Builtins::new())
+    }
+}
+
+impl Default for Interpreter<Builtins> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<H: Host> Interpreter<H> {
+    pub fn with_host(host: H) -> Self {
+        Interpreter {
+            host,
+            species: HashMap::new(),
+            kinds: HashMap::new(),
+            globals: Frame::default(),
+            depth: 0,
+            max_depth: MAX_DEPTH,
+        }
+    }
+
+    /// Troca o limite de chamadas aninhadas ([`MAX_DEPTH`]); a thread que roda
+    /// o interpretador precisa de pilha para ele (veja [`STACK_MAX_DEPTH`])
+    pub fn with_max_depth(mut self, limit: usize) -> Self {
+        self.max_depth = limit;
+        self
+    }
+
+    /// Registra a espécie de um bug; uma nova definição substitui a anterior
+    pub fn define(&mut self, bug: &Bug) {
+        self.species.insert(bug.specie.raw.clone(), Rc::new(bug.clone()));
+    }
+
+    /// Registra as variantes de um kind, como `define` faz com um bug
+    pub fn define_kind(&mut self, kind: &Kind) {
+        let kind = Rc::new(kind.clone());
+        for variant in &kind.variants {
+            self.kinds.insert(variant.specie.raw.clone(), kind.clone());
+        }
+    }
+
+    pub fn bug(&self, specie: &str) -> Option<&Bug> {
+        self.species.get(specie).map(|bug| bug.as_ref())
+    }
+
+    /// Variável de nível superior, como ficou depois das execuções anteriores
+    pub fn global(&self, name: &str) -> Option<&Value> {
+        self.globals.locals.get(name)
+    }
+
+    pub fn run_tree(&mut self, tree: &Tree) -> Result<Value, RuntimeError> {
+        self.run(&tree.sc.fly)
+    }
+
+    /// Executa um `Fly`. As variáveis e espécies ficam guardadas para as próximas execuções.
+    pub fn run(&mut self, fly: &Fly) -> Result<Value, RuntimeError> {
+        let genome = &fly.strand.genome;
+        for item in genome {
+            match item {
+                Genome::Anatomy(Anatomy::Bug(bug)) => self.define(bug),
+                Genome::Anatomy(Anatomy::Kind(kind)) => self.define_kind(kind),
+                _ => {}
+            }
+        }
+
+        let mut frame = std::mem::take(&mut self.globals);
+        let result = self.run_genome(genome, &mut frame);
+        self.globals = frame;
+        result
+    }
+
+    /// Executa um único behavior no nível superior, como uma linha do REPL
+    pub fn eval(&mut self, behavior: &Behavior) -> Result<Value, RuntimeError> {
+        let mut frame = std::mem::take(&mut self.globals);
+        let result = self.exec_behavior(behavior, &mut frame);
+        self.globals = frame;
+        match result? {
+            Flow::Next(value) | Flow::Return(value) => Ok(value),
+        }
+    }
+
+    fn run_genome(&mut self, genome: &[Genome], frame: &mut Frame) -> Result<Value, RuntimeError> {
+        let mut last = Value::Nil;
+        for item in genome {
+            if let Genome::Behavior(behavior) = item {
+                match self.exec_behavior(behavior, frame)? {
+                    Flow::Next(value) => last = value,
+                    Flow::Return(value) => return Ok(value),
+                }
+            }
+        }
+        Ok(last)
+    }
+
+    fn exec_matrix(&mut self, matrix: &Matrix, frame: &mut Frame) -> Result<Flow, RuntimeError> {
+        let mut last = Value::Nil;
+        for Signal::Behavior(behavior) in &matrix.signals {
+            match self.exec_behavior(behavior, frame)? {
+                Flow::Next(value) => last = value,
+                flow @ Flow::Return(_) => return Ok(flow),
+            }
+        }
+        Ok(Flow::Next(last))
+    }
+
+    fn exec_behavior(&mut self, behavior: &Behavior, frame: &mut Frame) -> Result<Flow, RuntimeError> {
+        match behavior {
+            Behavior::Statement(statement) => self.exec_statement(statement, frame),
+            Behavior::Assign(assign) => {
+                let value = self.eval_oop(&assign.oop, frame)?;
+                assign_name(frame, &assign.tag.raw, value.clone());
+                Ok(Flow::Next(value))
+            }
+            Behavior::Oop(oop) => Ok(Flow::Next(self.eval_oop(oop, frame)?)),
+        }
+    }
+
+    fn exec_statement(&mut self, statement: &Statement, frame: &mut Frame) -> Result<Flow, RuntimeError> {
+        match statement {
+            Statement::If(if_stmt) => {
+                if self.eval_condition(&if_stmt.condition, frame)?.is_truthy() {
+                    return self.exec_matrix(&if_stmt.then_block, frame);
+                }
+                for elsif in &if_stmt.elsif_blocks {
+                    if self.eval_condition(&elsif.condition, frame)?.is_truthy() {
+                        return self.exec_matrix(&elsif.block, frame);
+                    }
+                }
+                match &if_stmt.else_block {
+                    Some(block) => self.exec_matrix(block, frame),
+                    None => Ok(Flow::Next(Value::Nil)),
+                }
+            }
+            Statement::While(while_stmt) => {
+                while self.eval_condition(&while_stmt.condition, frame)?.is_truthy() {
+                    if let flow @ Flow::Return(_) = self.exec_matrix(&while_stmt.block, frame)? {
+                        return Ok(flow);
+                    }
+                }
+                Ok(Flow::Next(Value::Nil))
+            }
+            Statement::For(for_stmt) => {
+                let iterable = self.eval_oop(&for_stmt.iterable, frame)?;
+                let items = self.host.iterate(&iterable).ok_or_else(|| RuntimeError::NotIterable {
+                    found: iterable.specie(),
+                    span: for_stmt.iterable.span,
+                })?;
+                for item in items {
+                    frame.locals.insert(for_stmt.variable.raw.clone(), item);
+                    if let flow @ Flow::Return(_) = self.exec_matrix(&for_stmt.block, frame)? {
+                        return Ok(flow);
+                    }
+                }
+                Ok(Flow::Next(Value::Nil))
+            }
+            Statement::Match(match_stmt) => {
+                let subject = self.eval_oop(&match_stmt.subject, frame)?;
+                let Value::Variant(variant) = &subject else {
+                    return Err(RuntimeError::TypeMismatch {
+                        expected: "a kind".to_string(),
+                        found: subject.specie(),
+                        span: match_stmt.subject.span,
+                    });
+                };
+                for arm in &match_stmt.arms {
+                    if arm.variants.iter().any(|name| name.raw == variant.name) {
+                        return self.exec_matrix(&arm.block, frame);
+                    }
+                }
+                match &match_stmt.else_block {
+                    Some(block) => self.exec_matrix(block, frame),
+                    None => Ok(Flow::Next(Value::Nil)),
+                }
+            }
+            Statement::Return(return_stmt) => Ok(Flow::Return(self.eval_oop(&return_stmt.value, frame)?)),
+        }
+    }
+
+    fn eval_condition(&mut self, condition: &Condition, frame: &mut Frame) -> Result<Value, RuntimeError> {
+        match condition {
+            Condition::Oop(oop) => self.eval_oop(oop, frame),
+            Condition::Expr(expr) => self.eval_expr(expr, frame),
+        }
+    }
+
+    /// Operadores viram chamadas de método, como em `Expr::desugar`, exceto
+    /// `and`/`or`, que só avaliam o lado direito quando precisam.
+    fn eval_expr(&mut self, expr: &Expr, frame: &mut Frame) -> Result<Value, RuntimeError> {
+        match expr {
+            Expr::Oop(oop) => self.eval_oop(oop, frame),
+            Expr::Binary { op: BinaryOp::And, left, right, .. } => {
+                let left = self.eval_expr(left, frame)?;
+                if left.is_truthy() { self.eval_expr(right, frame) } else { Ok(left) }
+            }
+            Expr::Binary { op: BinaryOp::Or, left, right, .. } => {
+                let left = self.eval_expr(left, frame)?;
+                if left.is_truthy() { Ok(left) } else { self.eval_expr(right, frame) }
+            }
+            Expr::Binary { op, left, right, span } => {
+                let left = self.eval_expr(left, frame)?;
+                let right = self.eval_expr(right, frame)?;
+                self.send(left, op.method(), Args::Positional(vec![right]), true, *span)
+            }
+            Expr::Unary { op, operand, span } => {
+                let operand = self.eval_expr(operand, frame)?;
+                self.send(operand, op.method(), Args::Positional(Vec::new()), false, *span)
+            }
+        }
+    }
+
+    fn eval_oop(&mut self, oop: &Oop, frame: &mut Frame) -> Result<Value, RuntimeError> {
+        let mut trails = oop.trails.iter().peekable();
+
+        let mut value = match &oop.emitter {
+            Emitter::Literal(literal) => literal_value(literal)?,
+            Emitter::Template(template) => {
+                let mut text = String::new();
+                for part in &template.parts {
+                    match part {
+                        TemplatePart::Text { raw, .. } => text.push_str(&unescape(raw)),
+                        TemplatePart::Oop(oop) => {
+                            let value = self.eval_oop(oop, frame)?;
+                            text.push_str(&self.host.show(&value));
+                        }
+                    }
+                }
+                Value::Str(text)
+            }
+            Emitter::List(list) => {
+                let items = list.items.iter().map(|item| self.eval_oop(item, frame)).collect::<Result<_, _>>()?;
+                Value::List(items)
+            }
+            Emitter::Map(map) => {
+                let mut entries = BTreeMap::new();
+                for entry in &map.entries {
+                    entries.insert(entry.tag.raw.clone(), self.eval_oop(&entry.oop, frame)?);
+                }
+                Value::Map(entries)
+            }
+            Emitter::SelfRef(self_ref) => match &frame.this {
+                Some(this) => Value::Object(this.clone()),
+                None => return Err(RuntimeError::UndefinedName { name: "$".to_string(), span: self_ref.span }),
+            },
+            // `Dog(energia: 1)` é o mesmo que `Dog.new(energia: 1)`
+            Emitter::Specie(specie) => match leading_carrier(&mut trails) {
+                Some(carrier) => {
+                    let args = self.eval_carrier(carrier, frame)?;
+                    self.instantiate(&specie.raw, args, carrier.span)?
+                }
+                None => self.unit(&specie.raw).unwrap_or_else(|| Value::Specie(specie.raw.clone())),
+            },
+            Emitter::Tag(tag) => match leading_carrier(&mut trails) {
+                Some(carrier) => {
+                    let args = self.eval_carrier(carrier, frame)?;
+                    self.call_function(&tag.raw, args, frame, tag.span.merge(&carrier.span))?
+                }
+                None => self.lookup(&tag.raw, frame, tag.span)?,
+            },
+        };
+
+        for trail in trails {
+            value = match trail {
+                Trail::Catalysis(catalysis) => {
+                    let args = match &catalysis.carrier {
+                        Some(carrier) => self.eval_carrier(carrier, frame)?,
+                        None => Args::Positional(Vec::new()),
+                    };
+                    self.send(value, &catalysis.tag.raw, args, catalysis.carrier.is_some(), catalysis.span)?
+                }
+                Trail::Carrier(carrier) => {
+                    return Err(RuntimeError::NotCallable { found: value.specie(), span: carrier.span });
+                }
+            };
+        }
+
+        Ok(value)
+    }
+
+    fn eval_carrier(&mut self, carrier: &Carrier, frame: &mut Frame) -> Result<Args, RuntimeError> {
+        match &carrier.transport {
+            None => Ok(Args::Positional(Vec::new())),
+            Some(Transport::Sequence(sequence)) => {
+                let mut values = Vec::new();
+                for oop in &sequence.oops {
+                    values.push(self.eval_oop(oop, frame)?);
+                }
+                Ok(Args::Positional(values))
+            }
+            Some(Transport::Binds(binds)) => {
+                let mut named = Vec::new();
+                for bind in &binds.binds {
+                    named.push((bind.tag.raw.clone(), self.eval_oop(&bind.oop, frame)?, bind.span));
+                }
+                Ok(Args::Named(named))
+            }
+        }
+    }
+
+    /// Nome solto: variável local, gene ou ethics sem argumentos da instância
+    fn lookup(&mut self, name: &str, frame: &mut Frame, span: Span) -> Result<Value, RuntimeError> {
+        if let Some(value) = frame.locals.get(name) {
+            return Ok(value.clone());
+        }
+        if let Some(this) = frame.this.clone() {
+            if let Some(value) = this.borrow().genes.get(name) {
+                return Ok(value.clone());
+            }
+            if let Some(value) = self.call_own_ethics(&this, name, Args::Positional(Vec::new()), span)? {
+                return Ok(value);
+            }
+        }
+        Err(RuntimeError::UndefinedName { name: name.to_string(), span })
+    }
+
+    /// `nome(args)`: ethics da própria instância ou função do host
+    fn call_function(&mut self, name: &str, args: Args, frame: &mut Frame, span: Span) -> Result<Value, RuntimeError> {
+        if let Some(this) = frame.this.clone() {
+            let specie = this.borrow().specie.clone();
+            if let Some((bug, index)) = self.ethics_of(&specie, name) {
+                return self.invoke(this, &bug.ethics[index], args, span);
+            }
+        }
+        match self.host.call_function(name, &args.into_values(), span)? {
+            Some(value) => Ok(value),
+            None => Err(RuntimeError::UndefinedName { name: name.to_string(), span }),
+        }
+    }
+
+    fn call_own_ethics(
+        &mut self,
+        this: &Rc<RefCell<Instance>>,
+        name: &str,
+        args: Args,
+        span: Span,
+    ) -> Result<Option<Value>, RuntimeError> {
+        let specie = this.borrow().specie.clone();
+        match self.ethics_of(&specie, name) {
+            Some((bug, index)) => Ok(Some(self.invoke(this.clone(), &bug.ethics[index], args, span)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Ethics da espécie ou, se ela não tem, do ancestral mais próximo que tem
+    fn ethics_of(&self, specie: &str, name: &str) -> Option<(Rc<Bug>, usize)> {
+        self.lineage(specie).into_iter().find_map(|bug| {
+            let index = bug.ethics.iter().position(|ethics| ethics.tag.raw == name)?;
+            Some((bug, index))
+        })
+    }
+
+    /// O bug e seus ancestrais, do mais próximo ao mais distante. O pai é o
+    /// primeiro de `parents` que é um bug definido (os outros são contratos);
+    /// um ciclo para na primeira repetição.
+    fn lineage(&self, specie: &str) -> Vec<Rc<Bug>> {
+        lineage(self.species.get(specie), |bug| bug.parents.iter().find_map(|parent| self.species.get(&parent.raw)))
+    }
+
+    /// `valor.nome(args)`: ethics ou gene de um bug, `new` de uma espécie, ou método do host
+    fn send(&mut self, receiver: Value, name: &str, args: Args, called: bool, span: Span) -> Result<Value, RuntimeError> {
+        match &receiver {
+            Value::Object(this) => {
+                let specie = this.borrow().specie.clone();
+                if let Some((bug, index)) = self.ethics_of(&specie, name) {
+                    return self.invoke(this.clone(), &bug.ethics[index], args, span);
+                }
+                if !called && let Some(value) = this.borrow().genes.get(name) {
+                    return Ok(value.clone());
+                }
+            }
+            Value::Specie(specie) if name == "new" => return self.instantiate(specie, args, span),
+            Value::Variant(variant) if !called => {
+                if let Some(value) = variant.gene(name) {
+                    return Ok(value.clone());
+                }
+            }
+            _ => {}
+        }
+        match self.host.call_method(&receiver, name, &args.into_values(), span)? {
+            Some(value) => Ok(value),
+            None => Err(RuntimeError::UnknownMethod { receiver: receiver.specie(), method: name.to_string(), span }),
+        }
+    }
+
+    /// Variante sem genes pelo nome: `Red` já é o valor
+    fn unit(&self, specie: &str) -> Option<Value> {
+        let kind = self.kinds.get(specie)?;
+        let variant = kind.variant(specie).filter(|variant| variant.genes.is_empty())?;
+        Some(Value::Variant(Rc::new(Variant { kind: kind.specie.raw.clone(), name: variant.specie.raw.clone(), genes: Vec::new() })))
+    }
+
+    /// Cria um bug (ou uma variante de kind) com os genes nos valores padrão
+    /// do host, sobrescritos pelos argumentos
+    fn instantiate(&mut self, specie: &str, args: Args, span: Span) -> Result<Value, RuntimeError> {
+        if let Some(kind) = self.kinds.get(specie).cloned()
+            && let Some(variant) = kind.variant(specie)
+        {
+            let genes = default_genes(&self.host, variant.genes.iter().map(|gene| (gene.tag.raw.as_str(), gene.specie.name.raw.as_str())));
+            let genes = bind_genes(specie, genes, args, span)?;
+            return Ok(Value::Variant(Rc::new(Variant { kind: kind.specie.raw.clone(), name: specie.to_string(), genes })));
+        }
+
+        let lineage = self.lineage(specie);
+        if lineage.is_empty() {
+            return Err(RuntimeError::UnknownSpecie { name: specie.to_string(), span });
+        }
+
+        // Genes herdados primeiro, na ordem em que os ancestrais os declaram
+        let genes = lineage.iter().rev().flat_map(|bug| &bug.genes);
+        let genes = default_genes(&self.host, genes.map(|gene| (gene.tag.raw.as_str(), gene.specie.name.raw.as_str())));
+        let genes = bind_genes(specie, genes, args, span)?;
+
+        Ok(Value::object(Instance { specie: specie.to_string(), genes: genes.into_iter().collect() }))
+    }
+
+    /// Executa o corpo de um ethics com os parâmetros da `Signature` ligados aos argumentos
+    fn invoke(&mut self, this: Rc<RefCell<Instance>>, ethics: &Ethics, args: Args, span: Span) -> Result<Value, RuntimeError> {
+        let params: Vec<&str> = ethics
+            .signature
+            .iter()
+            .flat_map(|signature| signature.binds.iter().flatten())
+            .map(|bind| bind.tag.raw.as_str())
+            .collect();
+
+        if args.len() != params.len() {
+            return Err(RuntimeError::Arity {
+                ethics: ethics.tag.raw.clone(),
+                expected: params.len(),
+                found: args.len(),
+                span,
+            });
+        }
+
+        let mut locals = HashMap::new();
+        match args {
+            Args::Positional(values) => {
+                for (param, value) in params.iter().zip(values) {
+                    locals.insert(param.to_string(), value);
+                }
+            }
+            Args::Named(binds) => {
+                for (name, value, bind_span) in binds {
+                    if !params.contains(&name.as_str()) {
+                        return Err(RuntimeError::UnknownArgument { ethics: ethics.tag.raw.clone(), name, span: bind_span });
+                    }
+                    locals.insert(name, value);
+                }
+            }
+        }
+
+        let Some(body) = &ethics.body else {
+            return Ok(Value::Nil);
+        };
+        if self.depth >= self.max_depth {
+            return Err(RuntimeError::StackOverflow { limit: self.max_depth, span });
+        }
+
+        self.depth += 1;
+        let mut frame = Frame { locals, this: Some(this) };
+        let result = self.exec_matrix(body, &mut frame);
+        self.depth -= 1;
+
+        match result? {
+            Flow::Next(value) | Flow::Return(value) => Ok(value),
+        }
+    }
+}
+
+/// Consome o `Carrier` logo após o emissor, como em `print(x)` ou `Dog(energia: 1)`
+pub(crate) fn leading_carrier<'a>(trails: &mut Peekable<slice::Iter<'a, Trail>>) -> Option<&'a Carrier> {
+    match trails.peek() {
+        Some(Trail::Carrier(carrier)) => {
+            trails.next();
+            Some(carrier)
+        }
+        _ => None,
+    }
+}
+
+/// Atribuição: variável local já existente, senão gene da instância, senão nova variável local
+fn assign_name(frame: &mut Frame, name: &str, value: Value) {
+    if !frame.locals.contains_key(name)
+        && let Some(this) = &frame.this
+        && let Some(gene) = this.borrow_mut().genes.get_mut(name)
+    {
+        *gene = value;
+        return;
+    }
+    frame.locals.insert(name.to_string(), value);
+}
+
+pub(crate) fn literal_value(literal: &Literal) -> Result<Value, RuntimeError> {
+    let value = match literal {
+        Literal::Int { .. } | Literal::Hex { .. } | Literal::Bit { .. } => literal
+            .get_int_value()
+            .map(Value::Int)
+            .ok_or(RuntimeError::Overflow { span: literal.span() })?,
+        Literal::Decimal { .. } => Value::Decimal(literal.get_decimal_value().unwrap_or_default()),
+        Literal::Str { .. } => Value::Str(literal.get_string_value().unwrap_or_default()),
+        Literal::Bool { .. } => Value::Bool(literal.get_bool_value().unwrap_or_default()),
+        Literal::Nil { .. } => Value::Nil,
+    };
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Builtins, Interpreter, RuntimeError, Value, MAX_DEPTH, STACK_MAX_DEPTH, STACK_SIZE};
+    use crate::dsl::parser::tree::Tree;
+    use crate::tests::load_fragment;
+
+    fn run(path: &str) -> (Result<Value, RuntimeError>, Vec<String>) {
+        let tree = Tree::parse_input(load_fragment(path)).expect("Fixture should parse");
+        let mut interpreter = Interpreter::with_host(Builtins::silent());
+        let result = interpreter.run_tree(&tree);
+        (result, interpreter.host.printed)
+    }
+
+    #[test]
+    fn test_run_bug_with_ethics() {
+        let (result, printed) = run("interp/dog.sc");
+
+        assert_eq!(printed, vec!["Rex AU AU", "au au", "0"]);
+        let rex = result.expect("Program should run");
+        assert_eq!(rex.to_string(), "Dog(energia: 0, nome: Rex)");
+    }
+
+    #[test]
+    fn test_run_control_flow() {
+        let (result, printed) = run("interp/loops.sc");
+
+        // 1 -> -1, 2 -> +2, 3 -> +100, 4 -> +4
+        assert_eq!(result.expect("Program should run"), Value::Int(105));
+        assert_eq!(printed, vec!["0", "1", "2"]);
+    }
+
+    #[test]
+    fn test_run_kinds_and_match() {
+        let (result, printed) = run("interp/kinds.sc");
+
+        assert_eq!(
+            printed,
+            vec!["Circulo(raio: 1.0) 3.0", "Retangulo(largura: 2.0, altura: 3.0) 6.0", "Ponto 0.0"]
+        );
+        assert_eq!(result.expect("Program should run"), Value::Str("vazio".to_string()));
+    }
+
+    #[test]
+    fn test_run_inheritance() {
+        let (result, printed) = run("typeck/inheritance.sc");
+
+        assert_eq!(printed, vec!["7 MIAU miaugato Mia, 7 vidas"]);
+        assert_eq!(result.expect("Program should run"), Value::Str("gato Tom, 0 vidas".to_string()));
+    }
+
+    #[test]
+    fn test_run_collection_literals() {
+        let (result, printed) = run("literal/collections.sc");
+
+        assert_eq!(printed, vec!["Ana 2 true", "ativo", "idade", "nome", "pets"]);
+        assert_eq!(result.expect("Program should run").to_string(), "[1, [2, 3], {x: nil}]");
+    }
+
+    #[test]
+    fn test_run_numeric_literals() {
+        let (result, printed) = run("literal/numbers.sc");
+
+        assert!(result.is_ok());
+        assert_eq!(printed, vec!["1000000 65535 165 -128 1234.5"]);
+    }
+
+    #[test]
+    fn test_run_string_templates() {
+        let (result, printed) = run("literal/template.sc");
+
+        assert_eq!(printed, vec!["Rex: energia 3", "aspas \"REX\"\tfim\\ #{nada} é", "lista [1, dois] e nil"]);
+        assert_eq!(result.expect("Program should run").to_string(), "a1b");
+    }
+
+    #[test]
+    fn test_run_reports_unknown_argument() {
+        let (result, _) = run("interp/errors.sc");
+
+        let error = result.expect_err("Program must fail");
+        assert_eq!(error.to_string(), "`miar` has no parameter `forte`");
+        assert_eq!((error.span().line, error.span().col), (10, 11));
+    }
+
+    #[test]
+    fn test_print_cyclic_object() {
+        let source = "bug N\n  gene next N\n  ethics link\n    next = $\n  end\nend\nn = N.new\nn.link\nprint(n)\n[n, n]";
+        let tree = Tree::parse_input(source.to_string()).expect("Should parse");
+        let mut interpreter = Interpreter::with_host(Builtins::silent());
+
+        let value = interpreter.run_tree(&tree).expect("Program should run");
+
+        assert_eq!(interpreter.host.printed, vec!["N(next: N(…))"]);
+        assert_eq!(value.to_string(), "[N(next: N(…)), N(next: N(…))]");
+    }
+
+    #[test]
+    fn test_globals_survive_between_runs() {
+        let mut interpreter = Interpreter::with_host(Builtins::silent());
+        let first = Tree::parse_input("x = 40".to_string()).expect("Should parse");
+        let second = Tree::parse_input("x.plus(2)".to_string()).expect("Should parse");
+
+        interpreter.run_tree(&first).expect("First run");
+        let value = interpreter.run_tree(&second).expect("Second run");
+
+        assert_eq!(value, Value::Int(42));
+        assert_eq!(interpreter.global("x"), Some(&Value::Int(40)));
+    }
+
+    #[test]
+    fn test_runtime_errors() {
+        let cases = [
+            ("y.plus(1)", "cannot find `y` in this scope"),
+            ("Fish.new", "cannot find species `Fish`"),
+            ("1.div(0)", "division by zero"),
+            ("\"a\".plus(1)", "expected String, found Int"),
+            ("for x in 1.5\n  print(x)\nend", "cannot iterate over Decimal"),
+            ("3.voar", "no method `voar` on Int"),
+            ("match 3\nwhen Ponto\n  1\nend", "expected a kind, found Int"),
+            ("{a: 1}.at(1)", "expected String, found Int"),
+        ];
+
+        for (source, message) in cases {
+            let tree = Tree::parse_input(source.to_string()).expect("Should parse");
+            let error = Interpreter::with_host(Builtins::silent()).run_tree(&tree).expect_err(source);
+            assert_eq!(error.to_string(), message, "source: {}", source);
+        }
+    }
+
+    const LOOP: &str = "bug Loop\n  ethics sempre\n    sempre\n  end\nend\nLoop.new.sempre";
+
+    fn deep(depth: usize) -> String {
+        format!(
+            "bug R\n  ethics f(n: Int) Int\n    if n == 0\n      return 0\n    end\n    r = f(n.minus(1))\n    r.plus(1)\n  end\nend\nR.new.f({})",
+            depth
+        )
+    }
+
+    /// Roda `source` numa thread com a pilha e o limite que o `sc` usa
+    fn run_deep(source: &str) -> Result<String, String> {
+        let source = source.to_string();
+        std::thread::Builder::new()
+            .stack_size(STACK_SIZE)
+            .spawn(move || {
+                let tree = Tree::parse_input(source).expect("Should parse");
+                let result = Interpreter::with_host(Builtins::silent()).with_max_depth(STACK_MAX_DEPTH).run_tree(&tree);
+                result.map(|value| value.to_string()).map_err(|error| error.to_string())
+            })
+            .expect("Thread should start")
+            .join()
+            .expect("Interpreter must not overflow the native stack")
+    }
+
+    #[test]
+    fn test_recursion_limit_fits_a_default_thread() {
+        let tree = Tree::parse_input(LOOP.to_string()).expect("Should parse");
+        let error = Interpreter::with_host(Builtins::silent()).run_tree(&tree).expect_err("Must overflow");
+        assert_eq!(error.to_string(), format!("too many nested calls (limit is {})", MAX_DEPTH));
+
+        let tree = Tree::parse_input(deep(MAX_DEPTH - 1)).expect("Should parse");
+        let value = Interpreter::with_host(Builtins::silent()).run_tree(&tree).expect("Should run");
+        assert_eq!(value, Value::Int(MAX_DEPTH as i64 - 1));
+    }
+
+    #[test]
+    fn test_raised_recursion_limit() {
+        let error = run_deep(LOOP).expect_err("Must overflow");
+        assert_eq!(error, format!("too many nested calls (limit is {})", STACK_MAX_DEPTH));
+
+        assert_eq!(run_deep(&deep(STACK_MAX_DEPTH - 1)), Ok((STACK_MAX_DEPTH - 1).to_string()));
+    }
+}
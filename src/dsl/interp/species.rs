@@ -0,0 +1,94 @@
+//! Regras de criação de bugs comuns ao interpretador e à VM: a linhagem, os
+//! genes com seus valores padrão e a ligação dos argumentos de `Especie.new(...)`.
+
+use std::rc::Rc;
+use crate::dsl::ast::span::Span;
+use super::error::RuntimeError;
+use super::host::Host;
+use super::value::Value;
+
+/// Argumentos já avaliados de uma chamada: `f(a, b)` ou `f(x: a, y: b)`.
+pub(crate) enum Args {
+    Positional(Vec<Value>),
+    Named(Vec<(String, Value, Span)>),
+}
+
+impl Args {
+    pub(crate) fn len(&self) -> usize {
+        match self {
+            Args::Positional(values) => values.len(),
+            Args::Named(binds) => binds.len(),
+        }
+    }
+
+    pub(crate) fn into_values(self) -> Vec<Value> {
+        match self {
+            Args::Positional(values) => values,
+            Args::Named(binds) => binds.into_iter().map(|(_, value, _)| value).collect(),
+        }
+    }
+}
+
+/// A espécie e seus ancestrais, do mais próximo ao mais distante. `parent` dá
+/// o pai de cada um (o primeiro de `parents` que é um bug; os outros são
+/// contratos); um ciclo para na primeira repetição.
+pub(crate) fn lineage<'a, T>(first: Option<&'a Rc<T>>, parent: impl Fn(&T) -> Option<&'a Rc<T>>) -> Vec<Rc<T>> {
+    let mut lineage: Vec<Rc<T>> = Vec::new();
+    let mut next = first;
+    while let Some(specie) = next.filter(|specie| !lineage.iter().any(|known| Rc::ptr_eq(known, specie))) {
+        lineage.push(specie.clone());
+        next = parent(specie);
+    }
+    lineage
+}
+
+/// Genes `(nome, espécie)` do ancestral mais distante ao mais próximo, nos
+/// valores padrão do host. Um gene redeclarado fica na posição original com o
+/// valor padrão novo.
+pub(crate) fn default_genes<'a, H: Host + ?Sized>(
+    host: &H,
+    genes: impl Iterator<Item = (&'a str, &'a str)>,
+) -> Vec<(String, Value)> {
+    let mut defaults: Vec<(String, Value)> = Vec::new();
+    for (name, specie) in genes {
+        let value = host.default_value(specie);
+        match defaults.iter_mut().find(|(gene, _)| gene == name) {
+            Some(slot) => slot.1 = value,
+            None => defaults.push((name.to_string(), value)),
+        }
+    }
+    defaults
+}
+
+/// Troca os valores padrão dos genes pelos argumentos de `Especie.new(...)`
+pub(crate) fn bind_genes(
+    specie: &str,
+    mut genes: Vec<(String, Value)>,
+    args: Args,
+    span: Span,
+) -> Result<Vec<(String, Value)>, RuntimeError> {
+    match args {
+        Args::Positional(values) => {
+            if values.len() > genes.len() {
+                return Err(RuntimeError::Arity {
+                    ethics: format!("{}.new", specie),
+                    expected: genes.len(),
+                    found: values.len(),
+                    span,
+                });
+            }
+            for (gene, value) in genes.iter_mut().zip(values) {
+                gene.1 = value;
+            }
+        }
+        Args::Named(binds) => {
+            for (name, value, bind_span) in binds {
+                let gene = genes.iter_mut().find(|(gene, _)| *gene == name).ok_or_else(|| {
+                    RuntimeError::UnknownGene { specie: specie.to_string(), name: name.clone(), span: bind_span }
+                })?;
+                gene.1 = value;
+            }
+        }
+    }
+    Ok(genes)
+}
@@ -0,0 +1,191 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::rc::Rc;
+
+/// Valor produzido durante a execução de um programa SC.
+#[derive(Debug, Clone)]
+pub enum Value {
+    Nil,
+    Bool(bool),
+    Int(i64),
+    Decimal(f64),
+    Str(String),
+    List(Vec<Value>),
+    /// `{nome: "Rex"}`, com as chaves em ordem
+    Map(BTreeMap<String, Value>),
+    /// Instância de um bug; cópias do valor apontam para a mesma instância
+    Object(Rc<RefCell<Instance>>),
+    /// A própria espécie, como em `Dog.new(...)`
+    Specie(String),
+    /// Valor de um `kind`: `Red` ou `Circulo(raio: 2.0)`
+    Variant(Rc<Variant>),
+}
+
+/// Estado de um bug instanciado: sua espécie e o valor de cada gene.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Instance {
+    pub specie: String,
+    pub genes: BTreeMap<String, Value>,
+}
+
+/// Variante de um `kind` com os valores dos seus genes, na ordem declarada.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Variant {
+    pub kind: String,
+    pub name: String,
+    pub genes: Vec<(String, Value)>,
+}
+
+impl Variant {
+    pub fn gene(&self, name: &str) -> Option<&Value> {
+        self.genes.iter().find(|(gene, _)| gene == name).map(|(_, value)| value)
+    }
+}
+
+impl Value {
+    pub fn object(instance: Instance) -> Self {
+        Value::Object(Rc::new(RefCell::new(instance)))
+    }
+
+    /// Nome da espécie do valor, usado em mensagens de erro e no despacho do host
+    pub fn specie(&self) -> String {
+        match self {
+            Value::Nil => "Nil".to_string(),
+            Value::Bool(_) => "Bool".to_string(),
+            Value::Int(_) => "Int".to_string(),
+            Value::Decimal(_) => "Decimal".to_string(),
+            Value::Str(_) => "String".to_string(),
+            Value::List(_) => "List".to_string(),
+            Value::Map(_) => "Map".to_string(),
+            Value::Object(instance) => instance.borrow().specie.clone(),
+            Value::Specie(name) => name.clone(),
+            Value::Variant(variant) => variant.kind.clone(),
+        }
+    }
+
+    /// Só `nil` e `false` são falsos
+    pub fn is_truthy(&self) -> bool {
+        !matches!(self, Value::Nil | Value::Bool(false))
+    }
+
+    pub fn is_nil(&self) -> bool {
+        matches!(self, Value::Nil)
+    }
+}
+
+impl PartialEq for Value {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Value::Nil, Value::Nil) => true,
+            (Value::Bool(a), Value::Bool(b)) => a == b,
+            (Value::Int(a), Value::Int(b)) => a == b,
+            (Value::Decimal(a), Value::Decimal(b)) => a == b,
+            (Value::Int(a), Value::Decimal(b)) | (Value::Decimal(b), Value::Int(a)) => *a as f64 == *b,
+            (Value::Str(a), Value::Str(b)) => a == b,
+            (Value::List(a), Value::List(b)) => a == b,
+            (Value::Map(a), Value::Map(b)) => a == b,
+            (Value::Object(a), Value::Object(b)) => Rc::ptr_eq(a, b),
+            (Value::Specie(a), Value::Specie(b)) => a == b,
+            (Value::Variant(a), Value::Variant(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        display(self, f, &BTreeMap::new(), &mut Vec::new())
+    }
+}
+
+impl Value {
+    /// Como o `Display`, mas com as espécies de `names` (nome no programa ->
+    /// nome mostrado) trocadas, como em um programa ligado de vários arquivos
+    pub fn shown<'a>(&'a self, names: &'a BTreeMap<String, String>) -> Shown<'a> {
+        Shown { value: self, names }
+    }
+}
+
+/// Resultado de [`Value::shown`]
+pub struct Shown<'a> {
+    value: &'a Value,
+    names: &'a BTreeMap<String, String>,
+}
+
+impl fmt::Display for Shown<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        display(self.value, f, self.names, &mut Vec::new())
+    }
+}
+
+/// Escreve o valor lembrando as instâncias em aberto: um gene que aponta de
+/// volta para uma delas (`n.next = n`) vira `N(…)` em vez de recursão sem fim.
+fn display(
+    value: &Value,
+    f: &mut fmt::Formatter<'_>,
+    names: &BTreeMap<String, String>,
+    open: &mut Vec<*const RefCell<Instance>>,
+) -> fmt::Result {
+    let name = |specie| shown_name(names, specie);
+    match value {
+        Value::Nil => write!(f, "nil"),
+        Value::Bool(value) => write!(f, "{}", value),
+        Value::Int(value) => write!(f, "{}", value),
+        Value::Decimal(value) => write!(f, "{:?}", value),
+        Value::Str(value) => write!(f, "{}", value),
+        Value::List(items) => {
+            write!(f, "[")?;
+            for (n, item) in items.iter().enumerate() {
+                if n > 0 {
+                    write!(f, ", ")?;
+                }
+                display(item, f, names, open)?;
+            }
+            write!(f, "]")
+        }
+        Value::Map(entries) => {
+            write!(f, "{{")?;
+            genes(f, entries.iter(), names, open)?;
+            write!(f, "}}")
+        }
+        Value::Object(instance) if open.contains(&Rc::as_ptr(instance)) => {
+            write!(f, "{}(…)", name(&instance.borrow().specie))
+        }
+        Value::Object(instance) => {
+            open.push(Rc::as_ptr(instance));
+            let instance = instance.borrow();
+            write!(f, "{}(", name(&instance.specie))?;
+            genes(f, instance.genes.iter(), names, open)?;
+            open.pop();
+            write!(f, ")")
+        }
+        Value::Specie(specie) => write!(f, "{}", name(specie)),
+        Value::Variant(variant) if variant.genes.is_empty() => write!(f, "{}", name(&variant.name)),
+        Value::Variant(variant) => {
+            write!(f, "{}(", name(&variant.name))?;
+            genes(f, variant.genes.iter().map(|(name, value)| (name, value)), names, open)?;
+            write!(f, ")")
+        }
+    }
+}
+
+fn shown_name<'a>(names: &'a BTreeMap<String, String>, specie: &'a str) -> &'a str {
+    names.get(specie).map_or(specie, String::as_str)
+}
+
+fn genes<'a>(
+    f: &mut fmt::Formatter<'_>,
+    genes: impl Iterator<Item = (&'a String, &'a Value)>,
+    names: &BTreeMap<String, String>,
+    open: &mut Vec<*const RefCell<Instance>>,
+) -> fmt::Result {
+    for (n, (name, value)) in genes.enumerate() {
+        if n > 0 {
+            write!(f, ", ")?;
+        }
+        write!(f, "{}: ", name)?;
+        display(value, f, names, open)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,395 @@
+//! Formato JSON estável e versionado da AST, para ferramentas fora do Rust.
+//!
+//! Diferente do `Serialize` dos nós (que segue os structs e muda junto com
+//! eles), este formato é escrito à mão e só muda com uma nova
+//! [`FORMAT_VERSION`]. Todo nó é um objeto com `kind` e `span`; veja
+//! `doc/json.md` e o JSON Schema gerado por [`schema::schema`].
+//!
+//! ```
+//! use sc_dsl::dsl::json;
+//! use sc_dsl::dsl::parser::tree::Tree;
+//!
+//! let tree = Tree::parse_input("x = 1".to_string()).unwrap();
+//! let document = json::export(&tree);
+//! assert_eq!(document["format"], "sc-ast");
+//! assert_eq!(document["version"], 8);
+//! assert_eq!(document["program"]["kind"], "Program");
+//! assert_eq!(document["program"]["body"][0]["kind"], "Assign");
+//! ```
+
+pub mod schema;
+
+use serde_json::{json, Map, Value};
+use crate::dsl::ast::anatomy::Anatomy;
+use crate::dsl::ast::behavior::oop::Oop;
+use crate::dsl::ast::behavior::transport::Transport;
+use crate::dsl::ast::behavior::{Behavior, Bind, Carrier, Trail};
+use crate::dsl::ast::bug::Bug;
+use crate::dsl::ast::contract::Contract;
+use crate::dsl::ast::comment::{Comment, CommentKind};
+use crate::dsl::ast::condition::Condition;
+use crate::dsl::ast::emitter::{Emitter, Literal, Specie, SpecieRef, Tag, TemplatePart};
+use crate::dsl::ast::ethics::Ethics;
+use crate::dsl::ast::expr::Expr;
+use crate::dsl::ast::genome::Genome;
+use crate::dsl::ast::import::Import;
+use crate::dsl::ast::kind::Kind;
+use crate::dsl::ast::matrix::Matrix;
+use crate::dsl::ast::signal::Signal;
+use crate::dsl::ast::span::Span;
+use crate::dsl::ast::statement::Statement;
+use crate::dsl::parser::tree::Tree;
+
+/// Nome do formato, no campo `format` do documento
+pub const FORMAT: &str = "sc-ast";
+
+/// Sobe a cada mudança incompatível no formato
+pub const FORMAT_VERSION: u64 = 8;
+
+/// Documento completo: `{ "format", "version", "program" }`
+pub fn export(tree: &Tree) -> Value {
+    json!({
+        "format": FORMAT,
+        "version": FORMAT_VERSION,
+        "program": program(tree),
+    })
+}
+
+pub fn to_string_pretty(tree: &Tree) -> String {
+    // Um `Value` montado aqui sempre serializa
+    serde_json::to_string_pretty(&export(tree)).unwrap_or_default()
+}
+
+fn node(kind: &str, span: Span, fields: Value) -> Value {
+    let mut map = Map::new();
+    map.insert("kind".to_string(), json!(kind));
+    if let Value::Object(fields) = fields {
+        map.extend(fields);
+    }
+    map.insert("span".to_string(), self::span(span));
+    Value::Object(map)
+}
+
+fn span(span: Span) -> Value {
+    json!({ "start": span.start, "end": span.end, "line": span.line, "col": span.col })
+}
+
+fn program(tree: &Tree) -> Value {
+    let body: Vec<Value> = tree.sc.fly.strand.genome.iter().map(genome).collect();
+    let mut program = node("Program", tree.sc.span, json!({ "body": body }));
+    if !tree.comments.is_empty() {
+        program["comments"] = tree.comments.iter().map(comment).collect();
+    }
+    program
+}
+
+fn comment(comment: &Comment) -> Value {
+    let style = match comment.kind {
+        CommentKind::Line => "line",
+        CommentKind::Block => "block",
+    };
+    node("Comment", comment.span, json!({ "style": style, "text": comment.raw }))
+}
+
+fn genome(genome: &Genome) -> Value {
+    match genome {
+        Genome::Import(node) => import(node),
+        Genome::Anatomy(Anatomy::Bug(node)) => bug(node),
+        Genome::Anatomy(Anatomy::Kind(node)) => kind(node),
+        Genome::Anatomy(Anatomy::Contract(node)) => contract(node),
+        Genome::Behavior(node) => behavior(node),
+        Genome::Error(error) => node("Error", error.span, json!({ "message": error.message, "text": error.raw })),
+    }
+}
+
+fn tag(tag: &Tag) -> Value {
+    node("Tag", tag.span, json!({ "name": tag.raw }))
+}
+
+fn specie(specie: &Specie) -> Value {
+    node("Specie", specie.span, json!({ "name": specie.raw }))
+}
+
+fn specie_ref(specie_ref: &SpecieRef) -> Value {
+    let args: Vec<Value> = specie_ref.args.iter().map(self::specie_ref).collect();
+    node("SpecieRef", specie_ref.span, json!({ "name": specie(&specie_ref.name), "args": args }))
+}
+
+fn import(import: &Import) -> Value {
+    let names: Vec<Value> = import
+        .names
+        .iter()
+        .map(|name| node("ImportName", name.span, json!({ "name": specie(&name.specie), "alias": name.alias.as_ref().map(specie) })))
+        .collect();
+    node("Import", import.span, json!({ "path": import.path, "names": names }))
+}
+
+fn bug(bug: &Bug) -> Value {
+    let genes: Vec<Value> = bug
+        .genes
+        .iter()
+        .map(|gene| node("Gene", gene.span, json!({ "name": tag(&gene.tag), "type": specie_ref(&gene.specie) })))
+        .collect();
+    let ethics: Vec<Value> = bug.ethics.iter().map(self::ethics).collect();
+    let params: Vec<Value> = bug.params.iter().map(specie).collect();
+    let parents: Vec<Value> = bug.parents.iter().map(specie).collect();
+    node(
+        "Bug",
+        bug.span,
+        json!({ "name": specie(&bug.specie), "params": params, "parents": parents, "genes": genes, "ethics": ethics }),
+    )
+}
+
+fn contract(contract: &Contract) -> Value {
+    let ethics: Vec<Value> = contract.ethics.iter().map(self::ethics).collect();
+    node("Contract", contract.span, json!({ "name": specie(&contract.specie), "ethics": ethics }))
+}
+
+fn kind(kind: &Kind) -> Value {
+    let variants: Vec<Value> = kind
+        .variants
+        .iter()
+        .map(|variant| {
+            let genes: Vec<Value> = variant
+                .genes
+                .iter()
+                .map(|gene| node("Gene", gene.span, json!({ "name": tag(&gene.tag), "type": specie_ref(&gene.specie) })))
+                .collect();
+            node("Variant", variant.span, json!({ "name": specie(&variant.specie), "genes": genes }))
+        })
+        .collect();
+    node("Kind", kind.span, json!({ "name": specie(&kind.specie), "variants": variants }))
+}
+
+fn ethics(ethics: &Ethics) -> Value {
+    let params = ethics.signature.as_ref().map(|signature| {
+        signature
+            .binds
+            .iter()
+            .flatten()
+            .map(|bind| node("Param", bind.span, json!({ "name": tag(&bind.tag), "type": specie_ref(&bind.specie) })))
+            .collect::<Vec<_>>()
+    });
+    node(
+        "Ethics",
+        ethics.span,
+        json!({
+            "name": tag(&ethics.tag),
+            "params": params,
+            "returns": ethics.feedback.as_ref().map(specie_ref),
+            "body": ethics.body.as_ref().map(matrix),
+        }),
+    )
+}
+
+fn matrix(matrix: &Matrix) -> Vec<Value> {
+    matrix.signals.iter().map(|Signal::Behavior(node)| behavior(node)).collect()
+}
+
+fn behavior(behavior: &Behavior) -> Value {
+    match behavior {
+        Behavior::Assign(assign) => node("Assign", assign.span, json!({ "target": tag(&assign.tag), "value": oop(&assign.oop) })),
+        Behavior::Oop(node) => oop(node),
+        Behavior::Statement(node) => statement(node),
+    }
+}
+
+fn statement(statement: &Statement) -> Value {
+    match statement {
+        Statement::If(if_stmt) => {
+            let elsif: Vec<Value> = if_stmt
+                .elsif_blocks
+                .iter()
+                .map(|elsif| node("Elsif", elsif.span, json!({ "condition": condition(&elsif.condition), "body": matrix(&elsif.block) })))
+                .collect();
+            node(
+                "If",
+                if_stmt.span,
+                json!({
+                    "condition": condition(&if_stmt.condition),
+                    "body": matrix(&if_stmt.then_block),
+                    "elsif": elsif,
+                    "else": if_stmt.else_block.as_ref().map(matrix),
+                }),
+            )
+        }
+        Statement::While(while_stmt) => node(
+            "While",
+            while_stmt.span,
+            json!({ "condition": condition(&while_stmt.condition), "body": matrix(&while_stmt.block) }),
+        ),
+        Statement::For(for_stmt) => node(
+            "For",
+            for_stmt.span,
+            json!({ "variable": tag(&for_stmt.variable), "iterable": oop(&for_stmt.iterable), "body": matrix(&for_stmt.block) }),
+        ),
+        Statement::Match(match_stmt) => {
+            let arms: Vec<Value> = match_stmt
+                .arms
+                .iter()
+                .map(|arm| {
+                    let variants: Vec<Value> = arm.variants.iter().map(specie).collect();
+                    node("When", arm.span, json!({ "variants": variants, "body": matrix(&arm.block) }))
+                })
+                .collect();
+            node(
+                "Match",
+                match_stmt.span,
+                json!({
+                    "subject": oop(&match_stmt.subject),
+                    "arms": arms,
+                    "else": match_stmt.else_block.as_ref().map(matrix),
+                }),
+            )
+        }
+        Statement::Return(return_stmt) => node("Return", return_stmt.span, json!({ "value": oop(&return_stmt.value) })),
+    }
+}
+
+fn condition(condition: &Condition) -> Value {
+    match condition {
+        Condition::Oop(node) => oop(node),
+        Condition::Expr(node) => expr(node),
+    }
+}
+
+fn expr(expr: &Expr) -> Value {
+    match expr {
+        Expr::Oop(node) => oop(node),
+        Expr::Binary { op, left, right, span } => {
+            node("Binary", *span, json!({ "op": op.symbol(), "left": self::expr(left), "right": self::expr(right) }))
+        }
+        Expr::Unary { op, operand, span } => node("Unary", *span, json!({ "op": op.symbol(), "operand": self::expr(operand) })),
+    }
+}
+
+fn oop(oop: &Oop) -> Value {
+    let trails: Vec<Value> = oop.trails.iter().map(trail).collect();
+    node("Oop", oop.span, json!({ "emitter": emitter(&oop.emitter), "trails": trails }))
+}
+
+fn emitter(emitter: &Emitter) -> Value {
+    match emitter {
+        Emitter::Specie(node) => specie(node),
+        Emitter::Tag(node) => tag(node),
+        Emitter::SelfRef(self_ref) => node("SelfRef", self_ref.span, json!({})),
+        Emitter::Literal(literal) => {
+            let kind = match literal {
+                Literal::Int { .. } => "int",
+                Literal::Decimal { .. } => "decimal",
+                Literal::Hex { .. } => "hex",
+                Literal::Bit { .. } => "bit",
+                Literal::Str { .. } => "string",
+                Literal::Bool { .. } => "bool",
+                Literal::Nil { .. } => "nil",
+            };
+            let value = match literal {
+                Literal::Int { value, .. } | Literal::Hex { value, .. } | Literal::Bit { value, .. } => {
+                    i64::try_from(*value).map(Value::from).unwrap_or_else(|_| json!(u64::try_from(*value).unwrap_or_default()))
+                }
+                Literal::Decimal { value, .. } => json!(value.to_string()),
+                Literal::Str { .. } => json!(literal.get_string_value()),
+                Literal::Bool { .. } => json!(literal.get_bool_value()),
+                Literal::Nil { .. } => Value::Null,
+            };
+            let width = literal.get_width().map(|width| width.suffix());
+            node("Literal", literal.span(), json!({ "type": kind, "raw": literal.get_raw(), "value": value, "width": width }))
+        }
+        Emitter::Template(template) => {
+            let parts: Vec<Value> = template
+                .parts
+                .iter()
+                .map(|part| match part {
+                    TemplatePart::Text { raw, span } => node("Text", *span, json!({ "raw": raw, "value": part.text() })),
+                    TemplatePart::Oop(node) => oop(node),
+                })
+                .collect();
+            node("Template", template.span, json!({ "parts": parts }))
+        }
+        Emitter::List(list) => {
+            let items: Vec<Value> = list.items.iter().map(oop).collect();
+            node("List", list.span, json!({ "items": items }))
+        }
+        Emitter::Map(map) => {
+            let entries: Vec<Value> = map.entries.iter().map(bind).collect();
+            node("Map", map.span, json!({ "entries": entries }))
+        }
+    }
+}
+
+fn trail(trail: &Trail) -> Value {
+    match trail {
+        Trail::Catalysis(catalysis) => node(
+            "Catalysis",
+            catalysis.span,
+            json!({ "method": tag(&catalysis.tag), "arguments": catalysis.carrier.as_ref().map(carrier) }),
+        ),
+        Trail::Carrier(node) => carrier(node),
+    }
+}
+
+fn carrier(carrier: &Carrier) -> Value {
+    let (style, items): (&str, Vec<Value>) = match &carrier.transport {
+        None => ("positional", Vec::new()),
+        Some(Transport::Sequence(sequence)) => ("positional", sequence.oops.iter().map(oop).collect()),
+        Some(Transport::Binds(binds)) => ("named", binds.binds.iter().map(bind).collect()),
+    };
+    node("Carrier", carrier.span, json!({ "style": style, "items": items }))
+}
+
+fn bind(bind: &Bind) -> Value {
+    node("Bind", bind.span, json!({ "name": tag(&bind.tag), "value": oop(&bind.oop) }))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use super::{export, FORMAT_VERSION};
+    use crate::dsl::parser::tree::{ParseOptions, Tree};
+
+    #[test]
+    fn test_document_is_versioned_and_tagged() {
+        let tree = Tree::parse_input("rex.latir(volume: 2)".to_string()).expect("Source should parse");
+
+        let document = export(&tree);
+
+        assert_eq!(document["format"], "sc-ast");
+        assert_eq!(document["version"], FORMAT_VERSION);
+        let oop = &document["program"]["body"][0];
+        assert_eq!(oop["kind"], "Oop");
+        assert_eq!(oop["emitter"], json!({ "kind": "Tag", "name": "rex", "span": { "start": 0, "end": 3, "line": 1, "col": 1 } }));
+        let call = &oop["trails"][0];
+        assert_eq!(call["kind"], "Catalysis");
+        assert_eq!(call["method"]["name"], "latir");
+        assert_eq!(call["arguments"]["style"], "named");
+        assert_eq!(call["arguments"]["items"][0]["value"]["emitter"]["raw"], "2");
+    }
+
+    #[test]
+    fn test_literals_carry_decoded_values() {
+        let source = "print(0xFF_FFu64, 18446744073709551615u64, 1_0.50, \"a\\tb\", nil)";
+        let tree = Tree::parse_input(source.to_string()).expect("Source should parse");
+
+        let document = export(&tree);
+
+        let items = &document["program"]["body"][0]["trails"][0]["items"];
+        let values: Vec<_> = (0..5).map(|i| items[i]["emitter"]["value"].clone()).collect();
+        assert_eq!(values, vec![json!(65535), json!(u64::MAX), json!("10.50"), json!("a\tb"), json!(null)]);
+        assert_eq!(items[0]["emitter"]["width"], "u64");
+        assert_eq!(items[2]["emitter"].get("width"), Some(&json!(null)));
+    }
+
+    #[test]
+    fn test_optional_parts_are_null() {
+        let source = "bug Dog\n  ethics a\n  ethics b() Int\nend\n# fim";
+        let tree = Tree::parse_with(source.to_string(), ParseOptions { retain_comments: true }).expect("Source should parse");
+
+        let document = export(&tree);
+
+        let ethics = &document["program"]["body"][0]["ethics"];
+        assert_eq!((&ethics[0]["params"], &ethics[0]["returns"], &ethics[0]["body"]), (&json!(null), &json!(null), &json!(null)));
+        assert_eq!(ethics[1]["params"], json!([]));
+        assert_eq!(ethics[1]["returns"]["name"]["name"], "Int");
+        assert_eq!(document["program"]["comments"][0]["style"], "line");
+    }
+}
@@ -0,0 +1,337 @@
+//! JSON Schema (draft 2020-12) do formato de [`export`](super::export).
+//!
+//! É gerado aqui, ao lado do exportador, e gravado em `doc/sc-ast.schema.json`;
+//! um teste garante que o arquivo e o exportador não se afastam.
+
+use serde_json::{json, Map, Value};
+use super::{FORMAT, FORMAT_VERSION};
+
+pub fn schema() -> Value {
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "SC abstract syntax tree",
+        "description": format!("Parsed SC program, format `{}` version {}", FORMAT, FORMAT_VERSION),
+        "type": "object",
+        "properties": {
+            "format": { "const": FORMAT },
+            "version": { "const": FORMAT_VERSION },
+            "program": reference("Program"),
+        },
+        "required": ["format", "version", "program"],
+        "additionalProperties": false,
+        "$defs": definitions(),
+    })
+}
+
+pub fn to_string_pretty() -> String {
+    serde_json::to_string_pretty(&schema()).unwrap_or_default()
+}
+
+fn reference(name: &str) -> Value {
+    json!({ "$ref": format!("#/$defs/{}", name) })
+}
+
+fn one_of(names: &[&str]) -> Value {
+    json!({ "oneOf": names.iter().map(|name| reference(name)).collect::<Vec<_>>() })
+}
+
+fn nullable(schema: Value) -> Value {
+    json!({ "oneOf": [schema, { "type": "null" }] })
+}
+
+fn array(items: Value) -> Value {
+    json!({ "type": "array", "items": items })
+}
+
+fn string() -> Value {
+    json!({ "type": "string" })
+}
+
+/// Objeto `{ kind, ...fields, span }`; todos os campos são obrigatórios
+/// (os opcionais valem `null`).
+fn node(kind: &str, description: &str, fields: Value) -> Value {
+    let mut properties = Map::new();
+    properties.insert("kind".to_string(), json!({ "const": kind }));
+    let mut required = vec![json!("kind")];
+    if let Value::Object(fields) = fields {
+        for (name, schema) in fields {
+            required.push(json!(name));
+            properties.insert(name, schema);
+        }
+    }
+    properties.insert("span".to_string(), reference("Span"));
+    required.push(json!("span"));
+    json!({
+        "description": description,
+        "type": "object",
+        "properties": properties,
+        "required": required,
+        "additionalProperties": false,
+    })
+}
+
+fn definitions() -> Value {
+    let position = json!({ "type": "integer", "minimum": 0 });
+    let mut program = node("Program", "The whole file", json!({ "body": array(reference("Genome")) }));
+    program["properties"]["comments"] = array(reference("Comment"));
+
+    json!({
+        "Span": {
+            "description": "Byte offsets (`end` exclusive) and 1-based line/column of the start",
+            "type": "object",
+            "properties": { "start": position, "end": position, "line": position, "col": position },
+            "required": ["start", "end", "line", "col"],
+            "additionalProperties": false,
+        },
+        "Program": program,
+        "Comment": node("Comment", "Only present when comments were retained", json!({
+            "style": { "enum": ["line", "block"] },
+            "text": string(),
+        })),
+        "Genome": one_of(&["Import", "Bug", "Kind", "Contract", "Assign", "Oop", "If", "While", "For", "Match", "Return", "Error"]),
+        "Behavior": one_of(&["Assign", "Oop", "If", "While", "For", "Match", "Return"]),
+        "Error": node("Error", "Source that could not be parsed (recovery mode)", json!({
+            "message": string(),
+            "text": string(),
+        })),
+        "Import": node("Import", "`use \"path\"` (every bug) or `use A, B as C from \"path\"`", json!({
+            "path": string(),
+            "names": array(reference("ImportName")),
+        })),
+        "ImportName": node("ImportName", "A species brought in by `use`, optionally renamed", json!({
+            "name": reference("Specie"),
+            "alias": nullable(reference("Specie")),
+        })),
+        "Bug": node("Bug", "`bug Name ... end`, `bug Name<T, U> ... end` or `bug Name < Parent, Contract ... end`", json!({
+            "name": reference("Specie"),
+            "params": array(reference("Specie")),
+            "parents": array(reference("Specie")),
+            "genes": array(reference("Gene")),
+            "ethics": array(reference("Ethics")),
+        })),
+        "Kind": node("Kind", "`kind Name Variant ... end`", json!({
+            "name": reference("Specie"),
+            "variants": array(reference("Variant")),
+        })),
+        "Contract": node("Contract", "`contract Name ethics ... end`; its ethics have no body", json!({
+            "name": reference("Specie"),
+            "ethics": array(reference("Ethics")),
+        })),
+        "Variant": node("Variant", "`Name` or `Name(gene: Type, ...)` inside a kind", json!({
+            "name": reference("Specie"),
+            "genes": array(reference("Gene")),
+        })),
+        "Gene": node("Gene", "`gene name Type`", json!({
+            "name": reference("Tag"),
+            "type": reference("SpecieRef"),
+        })),
+        "Ethics": node("Ethics", "`ethics name(params) Returns ... end`; `params` is null without parentheses", json!({
+            "name": reference("Tag"),
+            "params": nullable(array(reference("Param"))),
+            "returns": nullable(reference("SpecieRef")),
+            "body": nullable(array(reference("Behavior"))),
+        })),
+        "Param": node("Param", "`name: Type` in an ethics signature", json!({
+            "name": reference("Tag"),
+            "type": reference("SpecieRef"),
+        })),
+        "Assign": node("Assign", "`target = value`", json!({
+            "target": reference("Tag"),
+            "value": reference("Oop"),
+        })),
+        "If": node("If", "`if ... elsif ... else ... end`", json!({
+            "condition": reference("Condition"),
+            "body": array(reference("Behavior")),
+            "elsif": array(reference("Elsif")),
+            "else": nullable(array(reference("Behavior"))),
+        })),
+        "Elsif": node("Elsif", "`elsif condition` branch", json!({
+            "condition": reference("Condition"),
+            "body": array(reference("Behavior")),
+        })),
+        "While": node("While", "`while condition ... end`", json!({
+            "condition": reference("Condition"),
+            "body": array(reference("Behavior")),
+        })),
+        "For": node("For", "`for variable in iterable ... end`", json!({
+            "variable": reference("Tag"),
+            "iterable": reference("Oop"),
+            "body": array(reference("Behavior")),
+        })),
+        "Match": node("Match", "`match subject when A, B ... else ... end`", json!({
+            "subject": reference("Oop"),
+            "arms": array(reference("When")),
+            "else": nullable(array(reference("Behavior"))),
+        })),
+        "When": node("When", "`when A, B` arm of a match", json!({
+            "variants": array(reference("Specie")),
+            "body": array(reference("Behavior")),
+        })),
+        "Return": node("Return", "`return value`", json!({ "value": reference("Oop") })),
+        "Condition": one_of(&["Oop", "Binary", "Unary"]),
+        "Binary": node("Binary", "Operator expression; only found in conditions", json!({
+            "op": { "enum": ["or", "and", "==", "!=", "<", "<=", ">", ">=", "+", "-", "*", "/", "%"] },
+            "left": reference("Condition"),
+            "right": reference("Condition"),
+        })),
+        "Unary": node("Unary", "Prefix operator; only found in conditions", json!({
+            "op": { "enum": ["not", "-"] },
+            "operand": reference("Condition"),
+        })),
+        "Oop": node("Oop", "An emitter followed by a chain of calls", json!({
+            "emitter": one_of(&["Tag", "Specie", "SelfRef", "Literal", "Template", "List", "Map"]),
+            "trails": array(one_of(&["Catalysis", "Carrier"])),
+        })),
+        "Literal": node("Literal", "`raw` is the source text, quotes included for strings; `value` is decoded (decimals as exact strings)", json!({
+            "type": { "enum": ["int", "decimal", "hex", "bit", "string", "bool", "nil"] },
+            "raw": string(),
+            "value": { "type": ["integer", "string", "boolean", "null"] },
+            "width": nullable(json!({ "enum": ["i8", "i16", "i32", "i64", "u8", "u16", "u32", "u64"] })),
+        })),
+        "Template": node("Template", "String with `#{oop}` interpolations", json!({
+            "parts": array(one_of(&["Text", "Oop"])),
+        })),
+        "Text": node("Text", "Literal text of a template: `raw` keeps the escapes, `value` resolves them", json!({
+            "raw": string(),
+            "value": string(),
+        })),
+        "List": node("List", "`[a, b]`", json!({ "items": array(reference("Oop")) })),
+        "Map": node("Map", "`{key: value}`; keys are written like named arguments", json!({
+            "entries": array(reference("Bind")),
+        })),
+        "Catalysis": node("Catalysis", "`.method` or `.method(arguments)`", json!({
+            "method": reference("Tag"),
+            "arguments": nullable(reference("Carrier")),
+        })),
+        "Carrier": node("Carrier", "Parenthesised arguments: `Oop` items when positional, `Bind` items when named", json!({
+            "style": { "enum": ["positional", "named"] },
+            "items": array(one_of(&["Oop", "Bind"])),
+        })),
+        "Bind": node("Bind", "`name: value` argument or map entry", json!({
+            "name": reference("Tag"),
+            "value": reference("Oop"),
+        })),
+        "Tag": node("Tag", "A lowercase name", json!({ "name": string() })),
+        "Specie": node("Specie", "A capitalised species name", json!({ "name": string() })),
+        "SpecieRef": node("SpecieRef", "Declared type: `Int`, `List<Int>`, `Map<String, Dog>`", json!({
+            "name": reference("Specie"),
+            "args": array(reference("SpecieRef")),
+        })),
+        "SelfRef": node("SelfRef", "`$`, the current instance", json!({})),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use serde_json::Value;
+    use super::{schema, to_string_pretty};
+    use crate::dsl::json::export;
+    use crate::dsl::parser::tree::{ParseOptions, Tree};
+
+    /// Validador mínimo, só com as palavras-chave que `schema()` usa
+    fn validate(root: &Value, schema: &Value, value: &Value, path: &str) -> Result<(), String> {
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            let name = reference.trim_start_matches("#/$defs/");
+            return validate(root, &root["$defs"][name], value, path);
+        }
+        if let Some(expected) = schema.get("const")
+            && expected != value
+        {
+            return Err(format!("{}: expected {}, found {}", path, expected, value));
+        }
+        if let Some(options) = schema.get("enum").and_then(Value::as_array)
+            && !options.contains(value)
+        {
+            return Err(format!("{}: {} is not one of {:?}", path, value, options));
+        }
+        if let Some(options) = schema.get("oneOf").and_then(Value::as_array) {
+            let matches = options.iter().filter(|option| validate(root, option, value, path).is_ok()).count();
+            if matches != 1 {
+                return Err(format!("{}: {} schemas match {}", path, matches, value));
+            }
+        }
+        let type_ok = match schema.get("type").and_then(Value::as_str) {
+            Some("object") => value.is_object(),
+            Some("array") => value.is_array(),
+            Some("string") => value.is_string(),
+            Some("integer") => value.is_u64() || value.is_i64(),
+            Some("null") => value.is_null(),
+            _ => true,
+        };
+        if !type_ok {
+            return Err(format!("{}: expected {}, found {}", path, schema["type"], value));
+        }
+        if let Some(minimum) = schema.get("minimum").and_then(Value::as_i64)
+            && value.as_i64().is_some_and(|number| number < minimum)
+        {
+            return Err(format!("{}: {} is below {}", path, value, minimum));
+        }
+        if let (Some(items), Some(values)) = (schema.get("items"), value.as_array()) {
+            for (index, item) in values.iter().enumerate() {
+                validate(root, items, item, &format!("{}[{}]", path, index))?;
+            }
+        }
+        if let Some(object) = value.as_object().filter(|_| schema.get("properties").is_some()) {
+            let properties = schema["properties"].as_object().expect("properties is an object");
+            for required in schema["required"].as_array().into_iter().flatten() {
+                let name = required.as_str().expect("required holds names");
+                if !object.contains_key(name) {
+                    return Err(format!("{}: missing `{}`", path, name));
+                }
+            }
+            for (name, field) in object {
+                match properties.get(name) {
+                    Some(property) => validate(root, property, field, &format!("{}.{}", path, name))?,
+                    None if schema["additionalProperties"] == false => {
+                        return Err(format!("{}: unexpected `{}`", path, name));
+                    }
+                    None => {}
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_every_fixture_export_matches_the_schema() {
+        let schema = schema();
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/fragments");
+        let mut checked = 0;
+        for area in std::fs::read_dir(dir).expect("Fixture dir should be readable") {
+            for entry in std::fs::read_dir(area.expect("Entry should be readable").path()).expect("Area should be a dir") {
+                let path = entry.expect("Entry should be readable").path();
+                let source = std::fs::read_to_string(&path).expect("Fixture should be readable");
+                let options = ParseOptions { retain_comments: true };
+                let tree = match Tree::parse_with(source.clone(), options) {
+                    Ok(tree) => tree,
+                    // Programas quebrados exercitam os nós `Error`
+                    Err(_) => Tree::parse_recovering(source).tree,
+                };
+                if let Err(error) = validate(&schema, &schema, &export(&tree), "$") {
+                    panic!("{}: {}", path.display(), error);
+                }
+                checked += 1;
+            }
+        }
+        assert!(checked > 40);
+    }
+
+    #[test]
+    fn test_validator_rejects_a_broken_document() {
+        let schema = schema();
+        let tree = Tree::parse_input("x = 1".to_string()).expect("Source should parse");
+        let mut document = export(&tree);
+        document["program"]["body"][0]["value"]["trails"] = serde_json::json!([{ "kind": "Dot" }]);
+
+        assert!(validate(&schema, &schema, &document, "$").is_err());
+    }
+
+    #[test]
+    fn test_committed_schema_is_up_to_date() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("doc/sc-ast.schema.json");
+        let committed = std::fs::read_to_string(&path).unwrap_or_default();
+
+        assert_eq!(committed, to_string_pretty() + "\n", "run `sc schema > doc/sc-ast.schema.json`");
+    }
+}
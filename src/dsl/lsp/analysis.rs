@@ -0,0 +1,260 @@
+//! O que o servidor sabe de um documento aberto: diagnósticos, o programa
+//! ligado com os arquivos importados e a resolução de nomes dele.
+
+use std::path::{Path, PathBuf};
+use crate::dsl::ast::anatomy::Anatomy;
+use crate::dsl::ast::bug::Bug;
+use crate::dsl::ast::contract::Contract;
+use crate::dsl::ast::fly::Fly;
+use crate::dsl::ast::genome::Genome;
+use crate::dsl::ast::kind::Kind;
+use crate::dsl::ast::span::Span;
+use crate::dsl::module::{self, Program};
+use crate::dsl::parser::diagnostic::Diagnostic;
+use crate::dsl::parser::tree::Tree;
+use crate::dsl::printer;
+use crate::dsl::resolve::{self, Resolution, ScopeKind, Symbol, SymbolKind};
+use crate::dsl::typeck;
+
+/// Programa ligado de um documento; com erros de sintaxe, o da árvore parcial
+#[derive(Debug)]
+pub struct Linked {
+    pub program: Program,
+    pub fly: Fly,
+    pub resolution: Resolution,
+}
+
+#[derive(Debug)]
+pub struct Analysis {
+    /// Árvore do próprio documento, parcial se houver erros de sintaxe
+    pub tree: Tree,
+    /// Só os diagnósticos do próprio documento
+    pub diagnostics: Vec<Diagnostic>,
+    pub linked: Option<Linked>,
+}
+
+/// Item de `Analysis::members`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Member {
+    pub name: String,
+    pub kind: SymbolKind,
+    /// Cabeça do ethics ou `gene nome Espécie`
+    pub detail: String,
+}
+
+impl Analysis {
+    /// `path` é o arquivo do documento; os `use` são relativos a ele. Com
+    /// erros de sintaxe ou imports que não carregam, a navegação usa o que
+    /// sobrou da árvore parcial.
+    pub fn new(path: &Path, text: &str) -> Self {
+        let recovery = Tree::parse_recovering(text.to_string());
+        let syntax_ok = recovery.is_ok();
+        let mut diagnostics = recovery.diagnostics;
+
+        let program = match module::load_tree(path, text.to_string(), recovery.tree.clone()) {
+            Ok(program) => program,
+            Err(error) => {
+                if syntax_ok {
+                    diagnostics.push(if error.path == path {
+                        error.diagnostic()
+                    } else {
+                        // O problema está em um arquivo importado: aponta para o primeiro `use`
+                        let span = recovery.tree.sc.fly.strand.genome.iter().find_map(|genome| match genome {
+                            Genome::Import(import) => Some(import.span),
+                            _ => None,
+                        });
+                        Diagnostic::error(error.to_string(), span.unwrap_or_default())
+                    });
+                }
+                // Sem os `use`, o documento sozinho ainda se resolve
+                let mut tree = recovery.tree.clone();
+                tree.sc.fly.strand.genome.retain(|genome| !matches!(genome, Genome::Import(_)));
+                match module::load_tree(path, text.to_string(), tree) {
+                    Ok(program) => program,
+                    Err(_) => return Analysis { tree: recovery.tree, diagnostics, linked: None },
+                }
+            }
+        };
+
+        let fly = program.link();
+        let resolution = resolve::resolve(&fly);
+        // Erros de nome e tipo só fazem sentido num documento completo
+        if syntax_ok && diagnostics.is_empty() {
+            diagnostics = resolution.errors.iter().map(|error| error.diagnostic()).collect();
+            for diagnostic in typeck::check(&fly).iter().map(|error| error.diagnostic()) {
+                // Nomes e espécies desconhecidos são vistos pelos dois passos
+                if !diagnostics.iter().any(|seen| seen.message == diagnostic.message && seen.span == diagnostic.span) {
+                    diagnostics.push(diagnostic);
+                }
+            }
+            // O documento é a entrada do programa, a única parte com offset 0
+            diagnostics.retain(|diagnostic| diagnostic.span.end <= text.len());
+            diagnostics.sort_by_key(|diagnostic| diagnostic.span.start);
+            for diagnostic in &mut diagnostics {
+                diagnostic.message = program.unmangle(&diagnostic.message);
+                diagnostic.notes = diagnostic.notes.iter().map(|note| program.unmangle(note)).collect();
+            }
+        }
+
+        Analysis { tree: recovery.tree, diagnostics, linked: Some(Linked { program, fly, resolution }) }
+    }
+
+    /// Nome sob `offset` (ou logo antes dele) e o símbolo a que ele se refere
+    pub fn symbol_at(&self, offset: usize) -> Option<(Span, &Symbol)> {
+        let resolution = &self.linked.as_ref()?.resolution;
+        let at = |offset: usize| {
+            let reference = resolution
+                .references
+                .iter()
+                .find(|reference| reference.span.contains(offset))
+                .map(|reference| (reference.span, resolution.symbol(reference.symbol)));
+            reference.or_else(|| {
+                resolution.symbols.iter().find(|symbol| symbol.span.contains(offset)).map(|symbol| (symbol.span, symbol))
+            })
+        };
+        at(offset).or_else(|| at(offset.checked_sub(1)?))
+    }
+
+    /// Arquivo e span da definição do nome em `offset`; nada para os embutidos
+    pub fn definition(&self, offset: usize) -> Option<(PathBuf, Span)> {
+        let (_, symbol) = self.symbol_at(offset)?;
+        if symbol.span == Span::default() {
+            return None;
+        }
+        let (module, span) = self.linked.as_ref()?.program.locate(symbol.span)?;
+        Some((module.path.clone(), span))
+    }
+
+    /// Descrição do nome em `offset`, em código SC
+    pub fn hover(&self, offset: usize) -> Option<(Span, String)> {
+        let (span, symbol) = self.symbol_at(offset)?;
+        let typed = |label: &str| match &symbol.specie {
+            Some(specie) => format!("{} {} {}", label, symbol.name, specie),
+            None => format!("{} {}", label, symbol.name),
+        };
+        let text = match symbol.kind {
+            SymbolKind::Specie => match (self.bug(&symbol.name), self.kind(&symbol.name)) {
+                (Some(bug), _) => outline(bug),
+                (None, Some(kind)) => kind.to_string(),
+                (None, None) => match self.contract(&symbol.name) {
+                    Some(contract) => contract.to_string(),
+                    None => format!("{} (builtin)", symbol.name),
+                },
+            },
+            SymbolKind::Variant => {
+                let kind = self.kinds().find(|kind| kind.variants.iter().any(|variant| variant.specie.span == symbol.span))?;
+                kind.to_string()
+            }
+            SymbolKind::Gene => typed("gene"),
+            SymbolKind::Ethics => {
+                let ethics = self.bugs().flat_map(|bug| &bug.ethics).find(|ethics| ethics.tag.span == symbol.span)?;
+                printer::head(ethics)
+            }
+            SymbolKind::Param => typed("param"),
+            SymbolKind::TypeParam => format!("type {}", symbol.name),
+            SymbolKind::Global | SymbolKind::Local | SymbolKind::ForVariable => typed("var"),
+            SymbolKind::Builtin => format!("{} (builtin)", symbol.name),
+        };
+        Some((span, self.linked.as_ref()?.program.unmangle(&text)))
+    }
+
+    /// Genes e ethics do bug `specie` (nome no programa ligado); de um kind
+    /// ou variante, os genes das variantes
+    pub fn members(&self, specie: &str) -> Vec<Member> {
+        let Some(bug) = self.bug(specie) else {
+            let variants = self.kinds().flat_map(|kind| &kind.variants);
+            let mut members: Vec<Member> = Vec::new();
+            let genes = match self.kind(specie) {
+                Some(kind) => kind.variants.iter().flat_map(|variant| &variant.genes).collect::<Vec<_>>(),
+                None => variants.filter(|variant| variant.specie.raw == specie).flat_map(|variant| &variant.genes).collect(),
+            };
+            for gene in genes {
+                if !members.iter().any(|member| member.name == gene.tag.raw) {
+                    members.push(Member {
+                        name: gene.tag.raw.clone(),
+                        kind: SymbolKind::Gene,
+                        detail: format!("gene {} {}", gene.tag.raw, gene.specie),
+                    });
+                }
+            }
+            return members;
+        };
+        let genes = bug.genes.iter().map(|gene| Member {
+            name: gene.tag.raw.clone(),
+            kind: SymbolKind::Gene,
+            detail: format!("gene {} {}", gene.tag.raw, gene.specie),
+        });
+        let ethics = bug.ethics.iter().map(|ethics| Member {
+            name: ethics.tag.raw.clone(),
+            kind: SymbolKind::Ethics,
+            detail: printer::head(ethics),
+        });
+        ethics.chain(genes).collect()
+    }
+
+    /// Espécie do valor que termina em `offset` (o receptor antes de um `.`),
+    /// e se ele é a própria espécie (`Dog` em `Dog.new`) em vez de uma instância.
+    pub fn receiver(&self, text: &str, offset: usize) -> Option<(String, bool)> {
+        let before = text.get(..offset)?;
+        if before.ends_with('$') {
+            let bug = self.bugs().find(|bug| bug.span.contains(offset))?;
+            return Some((bug.specie.raw.clone(), false));
+        }
+        let (_, symbol) = self.symbol_at(offset.checked_sub(1)?)?;
+        match symbol.kind {
+            SymbolKind::Specie => Some((symbol.name.clone(), true)),
+            _ => Some((symbol.specie.clone()?, false)),
+        }
+    }
+
+    fn bugs(&self) -> impl Iterator<Item = &Bug> {
+        let genome = self.linked.as_ref().map_or(&[][..], |linked| &linked.fly.strand.genome);
+        genome.iter().filter_map(|genome| match genome {
+            Genome::Anatomy(Anatomy::Bug(bug)) => Some(bug),
+            _ => None,
+        })
+    }
+
+    fn kinds(&self) -> impl Iterator<Item = &Kind> {
+        let genome = self.linked.as_ref().map_or(&[][..], |linked| &linked.fly.strand.genome);
+        genome.iter().filter_map(|genome| match genome {
+            Genome::Anatomy(Anatomy::Kind(kind)) => Some(kind),
+            _ => None,
+        })
+    }
+
+    fn contract(&self, specie: &str) -> Option<&Contract> {
+        let genome = self.linked.as_ref().map_or(&[][..], |linked| &linked.fly.strand.genome);
+        genome.iter().find_map(|genome| match genome {
+            Genome::Anatomy(Anatomy::Contract(contract)) if contract.specie.raw == specie => Some(contract),
+            _ => None,
+        })
+    }
+
+    fn kind(&self, specie: &str) -> Option<&Kind> {
+        self.kinds().find(|kind| kind.specie.raw == specie)
+    }
+
+    fn bug(&self, specie: &str) -> Option<&Bug> {
+        let resolution = &self.linked.as_ref()?.resolution;
+        // A primeira definição, como na resolução de nomes
+        let symbol = resolution.symbols.iter().find(|symbol| symbol.kind == SymbolKind::Specie && symbol.name == specie)?;
+        let scope = resolution.scopes.iter().find(|scope| scope.kind == ScopeKind::Bug && scope.span.contains(symbol.span.start))?;
+        self.bugs().find(|bug| bug.span == scope.span)
+    }
+}
+
+/// `bug Dog` com os pais, os genes e as cabeças dos ethics
+fn outline(bug: &Bug) -> String {
+    let parents: Vec<&str> = bug.parents.iter().map(|parent| parent.raw.as_str()).collect();
+    let head = match parents.as_slice() {
+        [] => format!("bug {}", bug.specie.raw),
+        parents => format!("bug {} < {}", bug.specie.raw, parents.join(", ")),
+    };
+    let mut lines = vec![head];
+    lines.extend(bug.genes.iter().map(|gene| format!("  gene {} {}", gene.tag.raw, gene.specie)));
+    lines.extend(bug.ethics.iter().map(|ethics| format!("  {}", printer::head(ethics))));
+    lines.push("end".to_string());
+    lines.join("\n")
+}
+
@@ -0,0 +1,519 @@
+//! Servidor de linguagem (LSP) para arquivos `.sc`, falando JSON-RPC pela
+//! entrada e saída padrão (`sc-lsp`).
+//!
+//! Oferece diagnósticos ao abrir e ao salvar (sintaxe, nomes e tipos, com os
+//! arquivos importados por `use`), ir para a definição de espécies, ethics e
+//! variáveis, hover com a espécie de um gene ou a assinatura de um ethics,
+//! a lista de bugs, genes e ethics do documento e, depois de um `.`, os
+//! ethics e genes do receptor quando a espécie dele é conhecida.
+//!
+//! ```
+//! use std::io::Cursor;
+//! use serde_json::json;
+//! use sc_dsl::dsl::lsp::{rpc, Server};
+//!
+//! let mut input = Vec::new();
+//! rpc::write_message(&mut input, &json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {} })).unwrap();
+//! rpc::write_message(&mut input, &json!({ "jsonrpc": "2.0", "id": 2, "method": "shutdown" })).unwrap();
+//! rpc::write_message(&mut input, &json!({ "jsonrpc": "2.0", "method": "exit" })).unwrap();
+//!
+//! let mut output = Vec::new();
+//! assert_eq!(Server::new().serve(&mut Cursor::new(input), &mut output).unwrap(), 0);
+//! ```
+
+pub mod analysis;
+pub mod position;
+pub mod rpc;
+
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+use serde_json::{json, Value as Json};
+use crate::dsl::ast::anatomy::Anatomy;
+use crate::dsl::ast::gene::Gene;
+use crate::dsl::ast::genome::Genome;
+use crate::dsl::ast::span::LineTable;
+use crate::dsl::parser::diagnostic::{Diagnostic, Severity};
+use crate::dsl::printer;
+use crate::dsl::resolve::SymbolKind;
+use self::analysis::Analysis;
+use self::position::Utf16;
+
+/// Um documento aberto no editor
+#[derive(Debug)]
+pub struct Document {
+    pub text: String,
+    pub lines: LineTable,
+    pub analysis: Analysis,
+}
+
+impl Document {
+    fn new(uri: &str, text: String) -> Self {
+        let lines = LineTable::new(&text);
+        let analysis = Analysis::new(&path_of(uri), &text);
+        Document { text, lines, analysis }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Server {
+    pub documents: HashMap<String, Document>,
+    initialized: bool,
+    shutdown: bool,
+    /// Código de saída, depois de `exit`
+    exit: Option<i32>,
+}
+
+impl Server {
+    pub fn new() -> Self {
+        Server::default()
+    }
+
+    /// Atende mensagens até `exit` ou o fim da entrada; devolve o código de saída
+    pub fn serve(&mut self, reader: &mut impl BufRead, writer: &mut impl Write) -> io::Result<i32> {
+        while let Some(message) = rpc::read_message(reader)? {
+            for reply in self.handle(&message) {
+                rpc::write_message(writer, &reply)?;
+            }
+            if let Some(code) = self.exit {
+                return Ok(code);
+            }
+        }
+        Ok(1)
+    }
+
+    /// Respostas e notificações produzidas por uma mensagem do cliente
+    pub fn handle(&mut self, message: &Json) -> Vec<Json> {
+        let Some(method) = message.get("method").and_then(Json::as_str) else {
+            // Respostas a pedidos do servidor (nunca feitos) são ignoradas
+            return match message.get("id") {
+                Some(_) => Vec::new(),
+                None => vec![rpc::error(&Json::Null, rpc::PARSE_ERROR, "invalid message")],
+            };
+        };
+        let params = message.get("params").cloned().unwrap_or(Json::Null);
+        match message.get("id") {
+            Some(id) => vec![match self.request(method, &params) {
+                Ok(result) => rpc::response(id, result),
+                Err((code, text)) => rpc::error(id, code, text),
+            }],
+            None => self.notification(method, &params),
+        }
+    }
+
+    fn request(&mut self, method: &str, params: &Json) -> Result<Json, (i64, String)> {
+        if !self.initialized && method != "initialize" {
+            return Err((rpc::SERVER_NOT_INITIALIZED, "the server is not initialized".to_string()));
+        }
+        if self.shutdown {
+            return Err((rpc::INVALID_REQUEST, "the server is shutting down".to_string()));
+        }
+        match method {
+            "initialize" => {
+                self.initialized = true;
+                Ok(json!({
+                    "capabilities": {
+                        "textDocumentSync": { "openClose": true, "change": 1, "save": { "includeText": true } },
+                        "definitionProvider": true,
+                        "hoverProvider": true,
+                        "documentSymbolProvider": true,
+                        "completionProvider": { "triggerCharacters": ["."] },
+                    },
+                    "serverInfo": { "name": "sc-lsp", "version": env!("CARGO_PKG_VERSION") },
+                }))
+            }
+            "shutdown" => {
+                self.shutdown = true;
+                Ok(Json::Null)
+            }
+            "textDocument/definition" => self.at(params, definition),
+            "textDocument/hover" => self.at(params, hover),
+            "textDocument/completion" => self.at(params, completion),
+            "textDocument/documentSymbol" => {
+                let document = self.document(params)?;
+                Ok(Json::Array(symbols(document)))
+            }
+            _ => Err((rpc::METHOD_NOT_FOUND, format!("unknown method `{}`", method))),
+        }
+    }
+
+    fn notification(&mut self, method: &str, params: &Json) -> Vec<Json> {
+        let uri = params.pointer("/textDocument/uri").and_then(Json::as_str).unwrap_or_default().to_string();
+        match method {
+            "exit" => {
+                self.exit = Some(if self.shutdown { 0 } else { 1 });
+                Vec::new()
+            }
+            "textDocument/didOpen" => {
+                let text = params.pointer("/textDocument/text").and_then(Json::as_str).unwrap_or_default();
+                self.documents.insert(uri.clone(), Document::new(&uri, text.to_string()));
+                vec![self.publish(&uri)]
+            }
+            // Sincronização completa: a última mudança traz o texto inteiro
+            "textDocument/didChange" => {
+                let changes = params.get("contentChanges").and_then(Json::as_array);
+                if let Some(text) = changes.and_then(|changes| changes.last()?.get("text")?.as_str()) {
+                    self.documents.insert(uri.clone(), Document::new(&uri, text.to_string()));
+                }
+                Vec::new()
+            }
+            "textDocument/didSave" => {
+                let text = match params.get("text").and_then(Json::as_str) {
+                    Some(text) => Some(text.to_string()),
+                    None => self.documents.get(&uri).map(|document| document.text.clone()),
+                };
+                match text {
+                    Some(text) => {
+                        self.documents.insert(uri.clone(), Document::new(&uri, text));
+                        vec![self.publish(&uri)]
+                    }
+                    None => Vec::new(),
+                }
+            }
+            "textDocument/didClose" => {
+                self.documents.remove(&uri);
+                vec![rpc::notification("textDocument/publishDiagnostics", json!({ "uri": uri, "diagnostics": [] }))]
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn document(&self, params: &Json) -> Result<&Document, (i64, String)> {
+        let uri = params.pointer("/textDocument/uri").and_then(Json::as_str);
+        let uri = uri.ok_or((rpc::INVALID_PARAMS, "missing textDocument.uri".to_string()))?;
+        self.documents.get(uri).ok_or_else(|| (rpc::INVALID_PARAMS, format!("document `{}` is not open", uri)))
+    }
+
+    /// Pedidos sobre uma posição do documento
+    fn at(&self, params: &Json, answer: fn(&Document, &str, usize) -> Json) -> Result<Json, (i64, String)> {
+        let document = self.document(params)?;
+        let uri = params.pointer("/textDocument/uri").and_then(Json::as_str).unwrap_or_default();
+        let offset = params
+            .get("position")
+            .and_then(|position| document.lines.offset(&document.text, position))
+            .ok_or((rpc::INVALID_PARAMS, "missing position".to_string()))?;
+        Ok(answer(document, uri, offset))
+    }
+
+    fn publish(&self, uri: &str) -> Json {
+        let diagnostics: Vec<Json> = self.documents.get(uri).map_or_else(Vec::new, |document| {
+            document.analysis.diagnostics.iter().map(|diagnostic| lsp_diagnostic(document, diagnostic)).collect()
+        });
+        rpc::notification("textDocument/publishDiagnostics", json!({ "uri": uri, "diagnostics": diagnostics }))
+    }
+}
+
+fn lsp_diagnostic(document: &Document, diagnostic: &Diagnostic) -> Json {
+    let severity = match diagnostic.severity {
+        Severity::Error => 1,
+        Severity::Warning => 2,
+    };
+    let mut message = diagnostic.message.clone();
+    for note in &diagnostic.notes {
+        message.push_str("\nnote: ");
+        message.push_str(note);
+    }
+    json!({
+        "range": document.lines.range(&document.text, diagnostic.span),
+        "severity": severity,
+        "source": "sc",
+        "message": message,
+    })
+}
+
+fn definition(document: &Document, uri: &str, offset: usize) -> Json {
+    let Some((path, span)) = document.analysis.definition(offset) else {
+        return Json::Null;
+    };
+    // Definição no próprio documento ou em um arquivo importado
+    if path == path_of(uri) {
+        return json!({ "uri": uri, "range": document.lines.range(&document.text, span) });
+    }
+    let Ok(text) = std::fs::read_to_string(&path) else {
+        return Json::Null;
+    };
+    json!({ "uri": uri_of(&path), "range": LineTable::new(&text).range(&text, span) })
+}
+
+fn hover(document: &Document, _uri: &str, offset: usize) -> Json {
+    match document.analysis.hover(offset) {
+        Some((span, text)) => json!({
+            "contents": { "kind": "markdown", "value": format!("```sc\n{}\n```", text) },
+            "range": document.lines.range(&document.text, span),
+        }),
+        None => Json::Null,
+    }
+}
+
+/// Ethics e genes depois de `receptor.`; o texto digitado depois do ponto
+/// filtra a lista.
+fn completion(document: &Document, uri: &str, offset: usize) -> Json {
+    let text = &document.text;
+    let typed = text[..offset].chars().rev().take_while(|c| c.is_ascii_alphanumeric() || *c == '_').count();
+    let dot = offset - typed;
+    let prefix = &text[dot..offset];
+    let Some(receiver) = dot.checked_sub(1).filter(|dot| text[*dot..].starts_with('.')) else {
+        return json!([]);
+    };
+    let Some(last) = text[..receiver].chars().next_back() else {
+        return json!([]);
+    };
+    if !(last.is_ascii_alphanumeric() || last == '_' || last == '$') {
+        return json!([]);
+    }
+
+    // Enquanto se digita, `rex.` não é um programa válido: analisa o texto
+    // sem o `.` e o nome que vem depois dele.
+    let rest = text[offset..].trim_start_matches(|c: char| c.is_ascii_alphanumeric() || c == '_');
+    let patched = format!("{}{}", &text[..receiver], rest);
+    let analysis = Analysis::new(&path_of(uri), &patched);
+    let Some((specie, class)) = analysis.receiver(&patched, receiver) else {
+        return json!([]);
+    };
+
+    let mut items = Vec::new();
+    if class {
+        items.push(json!({ "label": "new", "kind": 4, "detail": format!("{}.new", specie) }));
+    } else {
+        for member in analysis.members(&specie) {
+            let kind = if member.kind == SymbolKind::Ethics { 2 } else { 5 };
+            items.push(json!({ "label": member.name, "kind": kind, "detail": member.detail }));
+        }
+    }
+    items.retain(|item| item["label"].as_str().is_some_and(|label| label.starts_with(prefix)));
+    Json::Array(items)
+}
+
+/// Bugs do documento, cada um com seus genes e ethics, e kinds com suas variantes
+fn symbols(document: &Document) -> Vec<Json> {
+    let range = |span| document.lines.range(&document.text, span);
+    let gene = |gene: &Gene| {
+        json!({
+            "name": gene.tag.raw,
+            "detail": gene.specie.to_string(),
+            "kind": 8,
+            "range": range(gene.span),
+            "selectionRange": range(gene.tag.span),
+        })
+    };
+    document
+        .analysis
+        .tree
+        .sc
+        .fly
+        .strand
+        .genome
+        .iter()
+        .filter_map(|genome| match genome {
+            Genome::Anatomy(Anatomy::Bug(bug)) => {
+                let ethics = bug.ethics.iter().map(|ethics| {
+                    json!({
+                        "name": ethics.tag.raw,
+                        "detail": printer::head(ethics),
+                        "kind": 6,
+                        "range": range(ethics.span),
+                        "selectionRange": range(ethics.tag.span),
+                    })
+                });
+                Some(json!({
+                    "name": bug.specie.raw,
+                    "kind": 5,
+                    "range": range(bug.span),
+                    "selectionRange": range(bug.specie.span),
+                    "children": bug.genes.iter().map(gene).chain(ethics).collect::<Vec<_>>(),
+                }))
+            }
+            Genome::Anatomy(Anatomy::Contract(contract)) => {
+                let ethics = contract.ethics.iter().map(|ethics| {
+                    json!({
+                        "name": ethics.tag.raw,
+                        "detail": printer::head(ethics),
+                        "kind": 6,
+                        "range": range(ethics.span),
+                        "selectionRange": range(ethics.tag.span),
+                    })
+                });
+                Some(json!({
+                    "name": contract.specie.raw,
+                    "kind": 11,
+                    "range": range(contract.span),
+                    "selectionRange": range(contract.specie.span),
+                    "children": ethics.collect::<Vec<_>>(),
+                }))
+            }
+            Genome::Anatomy(Anatomy::Kind(kind)) => {
+                let variants = kind.variants.iter().map(|variant| {
+                    json!({
+                        "name": variant.specie.raw,
+                        "kind": 22,
+                        "range": range(variant.span),
+                        "selectionRange": range(variant.specie.span),
+                        "children": variant.genes.iter().map(gene).collect::<Vec<_>>(),
+                    })
+                });
+                Some(json!({
+                    "name": kind.specie.raw,
+                    "kind": 10,
+                    "range": range(kind.span),
+                    "selectionRange": range(kind.specie.span),
+                    "children": variants.collect::<Vec<_>>(),
+                }))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Caminho de uma URI `file://`; outras URIs (`untitled:...`) viram um
+/// caminho relativo ao diretório atual.
+pub fn path_of(uri: &str) -> PathBuf {
+    let Some(path) = uri.strip_prefix("file://") else {
+        return PathBuf::from(uri);
+    };
+    let bytes = path.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes.get(i + 1..i + 3).and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    PathBuf::from(String::from_utf8_lossy(&decoded).to_string())
+}
+
+pub fn uri_of(path: &Path) -> String {
+    let path = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let mut uri = String::from("file://");
+    for byte in path.to_string_lossy().bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'/' | b'-' | b'_' | b'.' | b'~' => uri.push(byte as char),
+            _ => uri.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    uri
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use serde_json::{json, Value as Json};
+    use super::{uri_of, Server};
+    use crate::tests::load_fragment;
+
+    const DOG: &str = "bug Dog\n  gene nome String\n\n  ethics latir(volume: Int) String\n    \"au\"\n  end\nend\n\nrex = Dog.new(nome: \"Rex\")\nrex.latir(1)\n";
+
+    fn uri(name: &str) -> String {
+        let directory = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/fragments/module");
+        format!("{}/{}", uri_of(&directory), name)
+    }
+
+    fn server(uri: &str, text: &str) -> (Server, Json) {
+        let mut server = Server::new();
+        server.handle(&json!({ "jsonrpc": "2.0", "id": 0, "method": "initialize", "params": {} }));
+        let open = json!({ "jsonrpc": "2.0", "method": "textDocument/didOpen", "params": { "textDocument": { "uri": uri, "text": text } } });
+        let mut published = server.handle(&open);
+        (server, published.remove(0))
+    }
+
+    fn request(server: &mut Server, method: &str, uri: &str, line: usize, character: usize) -> Json {
+        let params = json!({ "textDocument": { "uri": uri }, "position": { "line": line, "character": character } });
+        let mut replies = server.handle(&json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params }));
+        replies.remove(0)["result"].take()
+    }
+
+    #[test]
+    fn test_diagnostics_on_open_and_save() {
+        let uri = uri("lsp.sc");
+        let (mut server, published) = server(&uri, "x = 1\nprint(y)\n");
+        assert_eq!(published["method"], "textDocument/publishDiagnostics");
+        let diagnostics = published["params"]["diagnostics"].as_array().unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0]["range"]["start"], json!({ "line": 1, "character": 6 }));
+        assert_eq!(diagnostics[0]["severity"], 1);
+
+        let save = json!({ "jsonrpc": "2.0", "method": "textDocument/didSave", "params": { "textDocument": { "uri": uri }, "text": "x = 1\nprint(x)\n" } });
+        let published = server.handle(&save);
+        assert_eq!(published[0]["params"]["diagnostics"], json!([]));
+    }
+
+    #[test]
+    fn test_definition_hover_and_symbols() {
+        let uri = uri("lsp.sc");
+        let (mut server, published) = server(&uri, DOG);
+        assert_eq!(published["params"]["diagnostics"], json!([]));
+
+        let definition = request(&mut server, "textDocument/definition", &uri, 9, 5);
+        assert_eq!(definition["uri"], uri);
+        assert_eq!(definition["range"]["start"], json!({ "line": 3, "character": 9 }));
+        let definition = request(&mut server, "textDocument/definition", &uri, 8, 7);
+        assert_eq!(definition["range"]["start"], json!({ "line": 0, "character": 4 }));
+
+        let hover = request(&mut server, "textDocument/hover", &uri, 9, 5);
+        assert_eq!(hover["contents"]["value"], "```sc\nethics latir(volume: Int) String\n```");
+        let hover = request(&mut server, "textDocument/hover", &uri, 1, 8);
+        assert_eq!(hover["contents"]["value"], "```sc\ngene nome String\n```");
+
+        let symbols = request(&mut server, "textDocument/documentSymbol", &uri, 0, 0);
+        assert_eq!(symbols[0]["name"], "Dog");
+        let children: Vec<&str> = symbols[0]["children"].as_array().unwrap().iter().map(|child| child["name"].as_str().unwrap()).collect();
+        assert_eq!(children, vec!["nome", "latir"]);
+    }
+
+    #[test]
+    fn test_definition_with_a_broken_ethics() {
+        let uri = uri("lsp.sc");
+        let text = DOG.replace("    \"au\"\n  end\n", "    \"au\"\n  end\n\n  ethics uivar String\n    \"auuu\" +\n  end\n");
+        let (mut server, published) = server(&uri, &text);
+        let diagnostics = published["params"]["diagnostics"].as_array().unwrap();
+        assert_eq!(diagnostics.len(), 1, "{}", published);
+        assert_eq!(diagnostics[0]["range"]["start"]["line"], 8, "{}", published);
+
+        // `latir` em `rex.latir(1)`
+        let definition = request(&mut server, "textDocument/definition", &uri, 13, 5);
+        assert_eq!(definition["uri"], uri);
+        assert_eq!(definition["range"]["start"], json!({ "line": 3, "character": 9 }));
+        let hover = request(&mut server, "textDocument/hover", &uri, 12, 1);
+        assert_eq!(hover["contents"]["value"], "```sc\nvar rex Dog\n```");
+    }
+
+    #[test]
+    fn test_completion_after_dot() {
+        let uri = uri("lsp.sc");
+        let text = format!("{}rex.la", DOG);
+        let (mut server, _) = server(&uri, &text);
+
+        let items = request(&mut server, "textDocument/completion", &uri, 10, 6);
+        assert_eq!(items, json!([{ "label": "latir", "kind": 2, "detail": "ethics latir(volume: Int) String" }]));
+        let items = request(&mut server, "textDocument/completion", &uri, 10, 4);
+        let labels: Vec<&str> = items.as_array().unwrap().iter().map(|item| item["label"].as_str().unwrap()).collect();
+        assert_eq!(labels, vec!["latir", "nome"]);
+    }
+
+    #[test]
+    fn test_definition_in_imported_file() {
+        let uri = uri("main.sc");
+        let (mut server, published) = server(&uri, &load_fragment("module/main.sc"));
+        assert_eq!(published["params"]["diagnostics"], json!([]));
+
+        // `Lobo` em `lobo = Lobo(presa: Cat.new)`
+        let definition = request(&mut server, "textDocument/definition", &uri, 4, 8);
+        assert!(definition["uri"].as_str().unwrap().ends_with("/selvagens.sc"), "{}", definition);
+        assert_eq!(definition["range"]["start"], json!({ "line": 2, "character": 4 }));
+    }
+
+    #[test]
+    fn test_requests_need_initialize() {
+        let mut server = Server::new();
+        let reply = server.handle(&json!({ "jsonrpc": "2.0", "id": 7, "method": "textDocument/hover", "params": {} }));
+        assert_eq!(reply[0]["error"]["code"], super::rpc::SERVER_NOT_INITIALIZED);
+        assert_eq!(reply[0]["id"], 7);
+    }
+}
@@ -0,0 +1,66 @@
+//! Conversão entre offsets em bytes (os spans da AST) e posições do LSP, que
+//! contam linhas a partir de 0 e colunas em unidades UTF-16.
+
+use serde_json::{json, Value as Json};
+use crate::dsl::ast::span::{LineTable, Span};
+
+/// Posições do LSP sobre a [`LineTable`] do documento
+pub trait Utf16 {
+    /// Posição `{ line, character }` do offset
+    fn position(&self, text: &str, offset: usize) -> Json;
+
+    fn range(&self, text: &str, span: Span) -> Json {
+        json!({ "start": self.position(text, span.start), "end": self.position(text, span.end) })
+    }
+
+    /// Offset de uma posição do LSP; posições além do fim da linha ficam no fim dela
+    fn offset(&self, text: &str, position: &Json) -> Option<usize>;
+}
+
+impl Utf16 for LineTable {
+    fn position(&self, text: &str, offset: usize) -> Json {
+        let offset = offset.min(text.len());
+        let (line, start) = self.line_of(offset);
+        let character: usize = text[start..offset].chars().map(char::len_utf16).sum();
+        json!({ "line": line, "character": character })
+    }
+
+    fn offset(&self, text: &str, position: &Json) -> Option<usize> {
+        let line = position.get("line")?.as_u64()? as usize;
+        let character = position.get("character")?.as_u64()? as usize;
+        let Some((start, end)) = self.line_bounds(text, line) else {
+            return Some(text.len());
+        };
+
+        let mut units = 0;
+        for (index, c) in text[start..end].char_indices() {
+            if units >= character {
+                return Some(start + index);
+            }
+            units += c.len_utf16();
+        }
+        Some(end)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use crate::dsl::ast::span::LineTable;
+    use super::Utf16;
+
+    #[test]
+    fn test_positions_count_utf16_units() {
+        let text = "nome = \"ação\"\nx = 1\n";
+        let index = LineTable::new(text);
+
+        assert_eq!(index.position(text, 0), json!({ "line": 0, "character": 0 }));
+        let x = text.find('x').unwrap();
+        assert_eq!(index.position(text, x), json!({ "line": 1, "character": 0 }));
+        let quote = text.rfind('"').unwrap();
+        assert_eq!(index.position(text, quote), json!({ "line": 0, "character": 12 }));
+
+        assert_eq!(index.offset(text, &json!({ "line": 0, "character": 12 })), Some(quote));
+        assert_eq!(index.offset(text, &json!({ "line": 1, "character": 99 })), Some(text.len() - 1));
+    }
+}
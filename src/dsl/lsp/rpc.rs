@@ -0,0 +1,76 @@
+//! Mensagens JSON-RPC com o cabeçalho `Content-Length` do LSP.
+
+use std::io::{self, BufRead, Write};
+use serde_json::{json, Value as Json};
+
+/// Códigos de erro do JSON-RPC e do LSP
+pub const PARSE_ERROR: i64 = -32700;
+pub const INVALID_REQUEST: i64 = -32600;
+pub const METHOD_NOT_FOUND: i64 = -32601;
+pub const INVALID_PARAMS: i64 = -32602;
+pub const SERVER_NOT_INITIALIZED: i64 = -32002;
+
+/// Próxima mensagem, ou `None` no fim da entrada
+pub fn read_message(reader: &mut impl BufRead) -> io::Result<Option<Json>> {
+    let mut length = None;
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':')
+            && name.eq_ignore_ascii_case("Content-Length")
+        {
+            length = value.trim().parse::<usize>().ok();
+        }
+    }
+
+    let length = length.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "missing Content-Length header"))?;
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+    // JSON inválido vira `null`, que `Server::handle` responde com PARSE_ERROR
+    Ok(Some(serde_json::from_slice(&body).unwrap_or(Json::Null)))
+}
+
+pub fn write_message(writer: &mut impl Write, message: &Json) -> io::Result<()> {
+    let body = message.to_string();
+    write!(writer, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+    writer.flush()
+}
+
+pub fn response(id: &Json, result: Json) -> Json {
+    json!({ "jsonrpc": "2.0", "id": id, "result": result })
+}
+
+pub fn error(id: &Json, code: i64, message: impl Into<String>) -> Json {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message.into() } })
+}
+
+pub fn notification(method: &str, params: Json) -> Json {
+    json!({ "jsonrpc": "2.0", "method": method, "params": params })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+    use serde_json::json;
+    use super::{read_message, write_message};
+
+    #[test]
+    fn test_messages_round_trip() {
+        let mut out = Vec::new();
+        write_message(&mut out, &json!({ "id": 1, "method": "initialize" })).unwrap();
+        write_message(&mut out, &json!({ "method": "exit", "params": "ação" })).unwrap();
+        assert!(out.starts_with(b"Content-Length: 30\r\n\r\n{"));
+
+        let mut reader = Cursor::new(out);
+        assert_eq!(read_message(&mut reader).unwrap(), Some(json!({ "id": 1, "method": "initialize" })));
+        assert_eq!(read_message(&mut reader).unwrap(), Some(json!({ "method": "exit", "params": "ação" })));
+        assert_eq!(read_message(&mut reader).unwrap(), None);
+    }
+}
@@ -1,2 +1,14 @@
 pub mod ast;
 pub mod parser;
+pub mod desugar;
+pub mod interp;
+pub mod typeck;
+pub mod resolve;
+pub mod printer;
+pub mod format;
+pub mod sexpr;
+pub mod json;
+pub mod vm;
+pub mod module;
+pub mod lsp;
+pub mod repl;
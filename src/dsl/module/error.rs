@@ -0,0 +1,66 @@
+use std::path::PathBuf;
+use thiserror::Error;
+use crate::dsl::ast::span::Span;
+use crate::dsl::parser::diagnostic::{ColorMode, Diagnostic};
+use crate::dsl::parser::tree::TreeParseError;
+
+/// Problema ao montar o programa a partir dos arquivos; o span é do arquivo
+/// em que o problema está (em geral, o `use` que o causou).
+#[derive(Debug, Error)]
+pub enum ModuleError {
+    #[error("cannot read `{path}`: {message}")]
+    Read { path: String, message: String, span: Span },
+    #[error(transparent)]
+    Parse(#[from] TreeParseError),
+    #[error("import cycle: {}", .chain.join(" -> "))]
+    Cycle { chain: Vec<String>, span: Span },
+    #[error("`{path}` has no bug `{name}`")]
+    UnknownImport { name: String, path: String, span: Span },
+    #[error("`{name}` is already defined in this file")]
+    Conflict { name: String, span: Span },
+    #[error("`{name}` is not defined or imported in this file")]
+    NotImported { name: String, file: String, span: Span },
+    #[error("imported files can only define bugs and kinds")]
+    NotAModule { span: Span },
+}
+
+impl ModuleError {
+    pub fn diagnostic(&self, source: &str) -> Diagnostic {
+        let diagnostic = |span: Span| Diagnostic::error(self.to_string(), span);
+        match self {
+            ModuleError::Parse(error) => error.diagnostic(source),
+            ModuleError::Read { span, .. } | ModuleError::Cycle { span, .. } | ModuleError::UnknownImport { span, .. } => {
+                diagnostic(*span)
+            }
+            ModuleError::Conflict { span, .. } => {
+                diagnostic(*span).with_note("rename one of them with `use Name as Other from \"file.sc\"`")
+            }
+            ModuleError::NotImported { name, file, span } => {
+                diagnostic(*span).with_note(format!("`{}` is defined in `{}`; bring it in with `use {} from \"...\"`", name, file, name))
+            }
+            ModuleError::NotAModule { span } => {
+                diagnostic(*span).with_note("statements only run in the file given to `sc run`")
+            }
+        }
+    }
+}
+
+/// Um [`ModuleError`] com o arquivo em que ele aconteceu.
+#[derive(Debug, Error)]
+#[error("{}: {error}", .path.display())]
+pub struct LoadError {
+    pub error: Box<ModuleError>,
+    pub path: PathBuf,
+    /// Código do arquivo, vazio se ele não pôde ser lido
+    pub code: String,
+}
+
+impl LoadError {
+    pub fn diagnostic(&self) -> Diagnostic {
+        self.error.diagnostic(&self.code)
+    }
+
+    pub fn render(&self, mode: ColorMode) -> String {
+        self.diagnostic().render(&self.code, &self.path.display().to_string(), mode)
+    }
+}
@@ -0,0 +1,572 @@
+//! Programas com mais de um arquivo: `use` entre arquivos `.sc`.
+//!
+//! [`load`] lê o arquivo de entrada e, a partir dos `use`, todos os arquivos
+//! de que ele depende (caminhos relativos ao arquivo que importa), uma vez
+//! cada, e recusa ciclos. Cada arquivo tem o próprio espaço de nomes: os bugs
+//! que define e os que importa. Dois arquivos podem definir um `Dog`; quem
+//! importa escolhe qual, e pode renomear com `use Dog as Lobo from "..."`.
+//!
+//! [`Program::link`] junta tudo em uma única [`Fly`] que o interpretador, a VM
+//! e os passos de análise já entendem: os bugs ganham nomes únicos no programa
+//! (`selvagens.Dog` quando o nome se repete) e os spans de cada arquivo são
+//! deslocados para faixas que não se sobrepõem, de modo que
+//! [`Program::locate`] devolve o arquivo e o span original de qualquer erro.
+//! Na saída, [`Program::unmangle`] e [`Program::render`] voltam aos nomes
+//! escritos em cada `use`.
+//!
+//! Arquivos importados só podem definir bugs e kinds; código solto só roda no arquivo
+//! de entrada.
+
+pub mod error;
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::dsl::ast::anatomy::Anatomy;
+use crate::dsl::ast::emitter::Specie;
+use crate::dsl::ast::fly::Fly;
+use crate::dsl::ast::genome::Genome;
+use crate::dsl::ast::import::Import;
+use crate::dsl::ast::span::{Shift, Span};
+use crate::dsl::ast::strand::Strand;
+use crate::dsl::ast::bug::Bug;
+use crate::dsl::ast::visit::{walk_bug, Visit};
+use crate::dsl::ast::visit_mut::VisitMut;
+use crate::dsl::parser::diagnostic::{ColorMode, Diagnostic};
+use crate::dsl::parser::tree::Tree;
+
+pub use error::{LoadError, ModuleError};
+
+/// Todos os arquivos de um programa, dependências antes de quem as usa; o
+/// arquivo de entrada é o último.
+#[derive(Debug, Clone)]
+pub struct Program {
+    pub modules: Vec<Module>,
+}
+
+/// Um arquivo do programa
+#[derive(Debug, Clone)]
+pub struct Module {
+    /// Caminho relativo ao diretório do arquivo de entrada, sem `.sc`
+    pub name: String,
+    /// Caminho usado para ler o arquivo
+    pub path: PathBuf,
+    pub source: String,
+    pub tree: Tree,
+    /// Onde os spans deste arquivo começam no programa ligado
+    pub offset: usize,
+    /// Nome local de cada bug visível no arquivo -> nome no programa ligado
+    pub namespace: BTreeMap<String, String>,
+    /// Índice em `Program::modules` de cada `use`, na ordem do código
+    pub dependencies: Vec<usize>,
+}
+
+/// Lê o arquivo de entrada e tudo o que ele importa
+pub fn load(path: impl AsRef<Path>) -> Result<Program, LoadError> {
+    let path = path.as_ref();
+    let source = fs::read_to_string(path).map_err(|error| LoadError {
+        error: Box::new(ModuleError::Read {
+            path: path.display().to_string(),
+            message: error.to_string(),
+            span: Span::new(0, 0, 1, 1),
+        }),
+        path: path.to_path_buf(),
+        code: String::new(),
+    })?;
+    load_source(path, source)
+}
+
+/// Como [`load`], com o código do arquivo de entrada já lido (da entrada
+/// padrão, por exemplo); os `use` são relativos ao diretório de `path`.
+pub fn load_source(path: impl AsRef<Path>, source: String) -> Result<Program, LoadError> {
+    let path = path.as_ref();
+    let tree = Tree::parse_input(source.clone()).map_err(|error| LoadError {
+        error: Box::new(error.into()),
+        path: path.to_path_buf(),
+        code: source.clone(),
+    })?;
+    load_tree(path, source, tree)
+}
+
+/// Como [`load_source`], com a árvore do arquivo de entrada já montada: a
+/// parcial de [`Tree::parse_recovering`], por exemplo.
+pub fn load_tree(path: impl AsRef<Path>, source: String, tree: Tree) -> Result<Program, LoadError> {
+    let path = path.as_ref();
+    let directory = path.parent().unwrap_or(Path::new(""));
+    let root = canonical(directory).unwrap_or_else(|| directory.to_path_buf());
+
+    let mut loader = Loader { root, modules: Vec::new(), loaded: HashMap::new(), stack: Vec::new() };
+    let key = canonical(path).unwrap_or_else(|| path.to_path_buf());
+    loader.visit(key, path.to_path_buf(), source, tree, true)?;
+
+    let mut program = Program { modules: loader.modules };
+    program.bind()?;
+    Ok(program)
+}
+
+fn canonical(path: &Path) -> Option<PathBuf> {
+    let path = if path.as_os_str().is_empty() { Path::new(".") } else { path };
+    fs::canonicalize(path).ok()
+}
+
+struct Loader {
+    root: PathBuf,
+    modules: Vec<Module>,
+    /// Caminho canônico -> índice em `modules`
+    loaded: HashMap<PathBuf, usize>,
+    /// Arquivos sendo carregados, para achar ciclos
+    stack: Vec<(PathBuf, String)>,
+}
+
+impl Loader {
+    fn visit(&mut self, key: PathBuf, path: PathBuf, source: String, tree: Tree, entry: bool) -> Result<usize, LoadError> {
+        let fail = |error: ModuleError, source: &str| LoadError { error: Box::new(error), path: path.clone(), code: source.to_string() };
+
+        if !entry && let Some(span) = tree.sc.fly.strand.genome.iter().find_map(|genome| match genome {
+            Genome::Behavior(behavior) => Some(behavior.span()),
+            _ => None,
+        }) {
+            return Err(fail(ModuleError::NotAModule { span }, &source));
+        }
+
+        let name = match key.strip_prefix(&self.root) {
+            Ok(relative) if !entry => relative.with_extension("").display().to_string(),
+            _ => path.file_stem().map_or_else(|| path.display().to_string(), |stem| stem.to_string_lossy().to_string()),
+        };
+        self.stack.push((key.clone(), display(&key, &self.root, &path)));
+
+        let directory = path.parent().unwrap_or(Path::new("")).to_path_buf();
+        let mut dependencies = Vec::new();
+        for import in imports(&tree) {
+            let target = directory.join(&import.path);
+            let read = canonical(&target).and_then(|key| fs::read_to_string(&key).ok().map(|source| (key, source)));
+            let Some((target_key, target_source)) = read else {
+                let message = fs::read_to_string(&target).err().map_or_else(String::new, |error| error.to_string());
+                let error = ModuleError::Read { path: import.path.clone(), message, span: import.path_span };
+                return Err(fail(error, &source));
+            };
+
+            if let Some(start) = self.stack.iter().position(|(key, _)| *key == target_key) {
+                let mut chain: Vec<String> = self.stack[start..].iter().map(|(_, name)| name.clone()).collect();
+                chain.push(self.stack[start].1.clone());
+                return Err(fail(ModuleError::Cycle { chain, span: import.span }, &source));
+            }
+            let index = match self.loaded.get(&target_key) {
+                Some(index) => *index,
+                None => {
+                    let tree = Tree::parse_input(target_source.clone()).map_err(|error| LoadError {
+                        error: Box::new(error.into()),
+                        path: target.clone(),
+                        code: target_source.clone(),
+                    })?;
+                    self.visit(target_key, target, target_source, tree, false)?
+                }
+            };
+            dependencies.push(index);
+        }
+
+        self.stack.pop();
+        let index = self.modules.len();
+        self.loaded.insert(key, index);
+        self.modules.push(Module {
+            name,
+            path,
+            source,
+            tree,
+            offset: 0,
+            namespace: BTreeMap::new(),
+            dependencies,
+        });
+        Ok(index)
+    }
+}
+
+/// Nome do arquivo nas mensagens: relativo ao diretório do arquivo de entrada
+fn display(key: &Path, root: &Path, path: &Path) -> String {
+    match key.strip_prefix(root) {
+        Ok(relative) => relative.display().to_string(),
+        Err(_) => path.display().to_string(),
+    }
+}
+
+fn imports(tree: &Tree) -> impl Iterator<Item = &Import> {
+    tree.sc.fly.strand.genome.iter().filter_map(|genome| match genome {
+        Genome::Import(import) => Some(import),
+        _ => None,
+    })
+}
+
+/// Espécies definidas no próprio arquivo: bugs, contratos, kinds e as variantes de cada kind
+fn own_bugs(tree: &Tree) -> impl Iterator<Item = &Specie> {
+    tree.sc.fly.strand.genome.iter().flat_map(|genome| -> Box<dyn Iterator<Item = &Specie> + '_> {
+        match genome {
+            Genome::Anatomy(Anatomy::Bug(bug)) => Box::new(std::iter::once(&bug.specie)),
+            Genome::Anatomy(Anatomy::Contract(contract)) => Box::new(std::iter::once(&contract.specie)),
+            Genome::Anatomy(Anatomy::Kind(kind)) => {
+                Box::new(std::iter::once(&kind.specie).chain(kind.variants.iter().map(|variant| &variant.specie)))
+            }
+            _ => Box::new(std::iter::empty()),
+        }
+    })
+}
+
+impl Program {
+    /// O arquivo de entrada
+    pub fn entry(&self) -> &Module {
+        self.modules.last().expect("A program has at least its entry file")
+    }
+
+    /// Dá a cada bug um nome único no programa, monta o espaço de nomes de
+    /// cada arquivo e reserva uma faixa de spans para cada um.
+    fn bind(&mut self) -> Result<(), LoadError> {
+        let entry = self.modules.len() - 1;
+        let mut count: HashMap<&str, usize> = HashMap::new();
+        for module in &self.modules {
+            for specie in own_bugs(&module.tree) {
+                *count.entry(specie.raw.as_str()).or_default() += 1;
+            }
+        }
+        // Os bugs do arquivo de entrada mantêm o nome; os outros também, se
+        // ninguém mais usar o mesmo nome.
+        let global: Vec<HashMap<String, String>> = self
+            .modules
+            .iter()
+            .enumerate()
+            .map(|(index, module)| {
+                own_bugs(&module.tree)
+                    .map(|specie| {
+                        let unique = index == entry || count[specie.raw.as_str()] == 1;
+                        let name = if unique { specie.raw.clone() } else { format!("{}.{}", module.name, specie.raw) };
+                        (specie.raw.clone(), name)
+                    })
+                    .collect()
+            })
+            .collect();
+
+        // Primeiro arquivo que define cada nome
+        let mut owners: HashMap<String, usize> = HashMap::new();
+        for (index, module) in self.modules.iter().enumerate() {
+            for specie in own_bugs(&module.tree) {
+                owners.entry(specie.raw.clone()).or_insert(index);
+            }
+        }
+
+        let mut offset = 0;
+        for index in (0..self.modules.len()).rev() {
+            let module = &self.modules[index];
+            let fail = |error: ModuleError| LoadError { error: Box::new(error), path: module.path.clone(), code: module.source.clone() };
+
+            let mut namespace: BTreeMap<String, String> =
+                global[index].iter().map(|(local, name)| (local.clone(), name.clone())).collect();
+            let mut bind = |local: &Specie, name: &String| match namespace.get(&local.raw) {
+                Some(bound) if bound != name => Err(fail(ModuleError::Conflict { name: local.raw.clone(), span: local.span })),
+                _ => {
+                    namespace.insert(local.raw.clone(), name.clone());
+                    Ok(())
+                }
+            };
+            for (import, dependency) in imports(&module.tree).zip(&module.dependencies) {
+                let target = &self.modules[*dependency];
+                if import.names.is_empty() {
+                    for specie in own_bugs(&target.tree) {
+                        let specie = Specie { span: import.span, ..specie.clone() };
+                        bind(&specie, &global[*dependency][&specie.raw])?;
+                    }
+                }
+                for name in &import.names {
+                    let Some(program_name) = global[*dependency].get(&name.specie.raw) else {
+                        return Err(fail(ModuleError::UnknownImport {
+                            name: name.specie.raw.clone(),
+                            path: import.path.clone(),
+                            span: name.specie.span,
+                        }));
+                    };
+                    bind(name.local(), program_name)?;
+                }
+            }
+
+            // Uma espécie de outro arquivo só é vista se for importada
+            let mut hidden = Hidden { namespace: &namespace, owners: &owners, params: Vec::new(), found: None };
+            hidden.visit_fly(&module.tree.sc.fly);
+            if let Some((specie, owner)) = hidden.found {
+                let file = format!("{}.sc", self.modules[owner].name);
+                return Err(fail(ModuleError::NotImported { name: specie.raw.clone(), file, span: specie.span }));
+            }
+
+            // A entrada fica em 0 para que os spans dela não mudem
+            let module = &mut self.modules[index];
+            module.namespace = namespace;
+            module.offset = offset;
+            offset += module.source.len() + 1;
+        }
+        Ok(())
+    }
+
+    /// Uma única árvore com os bugs de todos os arquivos (dependências
+    /// primeiro) seguidos do código do arquivo de entrada. Os `use` somem.
+    pub fn link(&self) -> Fly {
+        let entry = &self.entry().tree.sc.fly;
+        let mut genome = Vec::new();
+        for module in &self.modules {
+            let mut fly = module.tree.sc.fly.clone();
+            fly.strand.genome.retain(|genome| !matches!(genome, Genome::Import(_)));
+            Rename { namespace: &module.namespace }.visit_fly(&mut fly);
+            // Linha e coluna continuam as do arquivo
+            Shift { offset: module.offset, line: 0, col: 0 }.visit_fly(&mut fly);
+            genome.extend(fly.strand.genome);
+        }
+        Fly { strand: Strand { genome, span: entry.strand.span }, span: entry.span }
+    }
+
+    /// Arquivo de um span do programa ligado, e o span dentro dele
+    pub fn locate(&self, span: Span) -> Option<(&Module, Span)> {
+        let module = self
+            .modules
+            .iter()
+            .find(|module| module.offset <= span.start && span.start <= module.offset + module.source.len())?;
+        Some((module, Span { start: span.start - module.offset, end: span.end - module.offset, ..span }))
+    }
+
+    /// Desenha um diagnóstico do programa ligado sobre o arquivo de onde ele
+    /// veio, com as espécies nos nomes que esse arquivo usa
+    pub fn render(&self, diagnostic: &Diagnostic, mode: ColorMode) -> String {
+        let (module, span) = self.locate(diagnostic.span).unwrap_or((self.entry(), diagnostic.span));
+        let diagnostic = Diagnostic {
+            message: self.unmangle_in(module, &diagnostic.message),
+            span,
+            notes: diagnostic.notes.iter().map(|note| self.unmangle_in(module, note)).collect(),
+            ..diagnostic.clone()
+        };
+        diagnostic.render(&module.source, &module.path.display().to_string(), mode)
+    }
+
+    /// Nome com que o arquivo de entrada vê cada espécie que ganhou um nome
+    /// único no programa ligado (`animais.Dog` -> `Dog`), para mostrar valores
+    /// com [`Value::shown`](crate::dsl::interp::Value::shown)
+    pub fn names(&self) -> BTreeMap<String, String> {
+        self.names_in(self.entry())
+    }
+
+    /// Como [`Program::names`], visto de `module`. Um nome que o arquivo não
+    /// importa fica com o nome do arquivo que o define.
+    fn names_in(&self, module: &Module) -> BTreeMap<String, String> {
+        let mut names = BTreeMap::new();
+        for owner in &self.modules {
+            for (local, name) in &owner.namespace {
+                if name.strip_prefix(owner.name.as_str()).and_then(|rest| rest.strip_prefix('.')) == Some(local) {
+                    names.insert(name.clone(), local.clone());
+                }
+            }
+        }
+        for (local, name) in &module.namespace {
+            if let Some(shown) = names.get_mut(name) {
+                *shown = local.clone();
+            }
+        }
+        names
+    }
+
+    /// Troca em uma mensagem os nomes únicos do programa ligado pelos nomes
+    /// escritos no arquivo de entrada. Strings entre aspas são dados do
+    /// programa e ficam como estão.
+    pub fn unmangle(&self, text: &str) -> String {
+        self.unmangle_in(self.entry(), text)
+    }
+
+    fn unmangle_in(&self, module: &Module, text: &str) -> String {
+        let names = self.names_in(module);
+        if names.is_empty() {
+            return text.to_string();
+        }
+        // Os mais longos primeiro, para que `a.Dog` não roube o começo de `a.DogHouse`
+        let mut names: Vec<(&String, &String)> = names.iter().collect();
+        names.sort_by_key(|(name, _)| std::cmp::Reverse(name.len()));
+
+        let is_name = |c: char| c.is_alphanumeric() || c == '_' || c == '.';
+        let mut out = String::with_capacity(text.len());
+        let mut rest = text;
+        'text: while let Some(c) = rest.chars().next() {
+            if c == '"' {
+                let end = string_end(rest);
+                out.push_str(&rest[..end]);
+                rest = &rest[end..];
+                continue;
+            }
+            if !out.ends_with(is_name) {
+                for (name, shown) in &names {
+                    if let Some(after) = rest.strip_prefix(name.as_str())
+                        && !after.starts_with(|c: char| c.is_alphanumeric() || c == '_')
+                    {
+                        out.push_str(shown);
+                        rest = after;
+                        continue 'text;
+                    }
+                }
+            }
+            out.push(c);
+            rest = &rest[c.len_utf8()..];
+        }
+        out
+    }
+}
+
+/// Fim da string entre aspas no começo de `text`, com as aspas; sem o
+/// fechamento, o resto do texto
+fn string_end(text: &str) -> usize {
+    let mut escaped = false;
+    for (at, c) in text.char_indices().skip(1) {
+        match c {
+            '"' if !escaped => return at + 1,
+            '\\' => escaped = !escaped,
+            _ => escaped = false,
+        }
+    }
+    text.len()
+}
+
+/// Primeira espécie citada em um arquivo que outro arquivo define e este não
+/// importa; os parâmetros de tipo do bug (`bug Caixa<T>`) não contam.
+struct Hidden<'a> {
+    namespace: &'a BTreeMap<String, String>,
+    owners: &'a HashMap<String, usize>,
+    params: Vec<&'a str>,
+    found: Option<(&'a Specie, usize)>,
+}
+
+impl<'a> Visit<'a> for Hidden<'a> {
+    fn visit_bug(&mut self, node: &'a Bug) {
+        self.params = node.params.iter().map(|param| param.raw.as_str()).collect();
+        walk_bug(self, node);
+        self.params.clear();
+    }
+
+    fn visit_specie(&mut self, node: &'a Specie) {
+        if self.found.is_some() || self.namespace.contains_key(&node.raw) || self.params.contains(&node.raw.as_str()) {
+            return;
+        }
+        if let Some(owner) = self.owners.get(&node.raw) {
+            self.found = Some((node, *owner));
+        }
+    }
+}
+
+/// Troca os nomes locais dos bugs e kinds pelos nomes no programa
+struct Rename<'a> {
+    namespace: &'a BTreeMap<String, String>,
+}
+
+impl VisitMut for Rename<'_> {
+    fn visit_specie(&mut self, node: &mut Specie) {
+        if let Some(name) = self.namespace.get(&node.raw) {
+            node.raw = name.clone();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+    use super::{load, load_source, ModuleError};
+    use crate::dsl::interp::{Builtins, Interpreter};
+    use crate::dsl::parser::diagnostic::ColorMode;
+    use crate::dsl::{resolve, typeck};
+
+    fn fixture(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/fragments/module").join(name)
+    }
+
+    #[test]
+    fn test_two_files_define_the_same_bug() {
+        let program = load(fixture("main.sc")).expect("Program should load");
+        let names: Vec<&str> = program.modules.iter().map(|module| module.name.as_str()).collect();
+        assert_eq!(names, vec!["animais", "selvagens", "main"]);
+        assert_eq!(program.entry().namespace["Lobo"], "selvagens.Dog");
+        assert_eq!(program.entry().namespace["Dog"], "animais.Dog");
+
+        let fly = program.link();
+        assert!(resolve::resolve(&fly).errors.is_empty());
+        assert!(typeck::check(&fly).is_empty());
+        let mut interpreter = Interpreter::with_host(Builtins::silent());
+        interpreter.run(&fly).expect("Program should run");
+        assert_eq!(interpreter.host.printed, vec!["au auuu miau"]);
+    }
+
+    #[test]
+    fn test_errors_point_into_the_right_file() {
+        let source = "use \"selvagens.sc\"\nDog(presa: 1)\n".to_string();
+        let program = load_source(fixture("erro.sc"), source).expect("Program should load");
+
+        let fly = program.link();
+        let error = typeck::check(&fly).into_iter().next().expect("Gene type should be checked");
+        let rendered = program.render(&error.diagnostic(), ColorMode::Plain);
+        assert!(rendered.contains("erro.sc:2:"), "{}", rendered);
+
+        let bug = fly.strand.genome.first().expect("Dependencies come first");
+        let (module, span) = program.locate(bug.span()).expect("Span should belong to a file");
+        assert_eq!(module.name, "animais");
+        assert_eq!(span.start, 0);
+    }
+
+    #[test]
+    fn test_link_keeps_big_literals() {
+        let source = "use \"numeros.sc\"\nprint(Medida.new.grande)\n".to_string();
+        let program = load_source(fixture("erro.sc"), source).expect("Program should load");
+
+        let fly = program.link();
+        let bug = fly.strand.genome.first().expect("Dependencies come first");
+        assert_eq!(bug.span().start, program.modules[0].offset);
+        let mut interpreter = Interpreter::with_host(Builtins::silent());
+        interpreter.run(&fly).expect("Program should run");
+        assert_eq!(interpreter.host.printed, vec!["1.2345678901234568e22"]);
+    }
+
+    #[test]
+    fn test_output_uses_the_names_at_the_use_site() {
+        let source = "use \"animais.sc\"\nuse Dog as Lobo from \"selvagens.sc\"\nprint(Dog.new, Lobo.new)\nprint(\"selvagens.Dog e #{Lobo.new}\")\nLobo(presa: Dog.new)\n";
+        let program = load_source(fixture("erro.sc"), source.to_string()).expect("Program should load");
+
+        let fly = program.link();
+        let error = typeck::check(&fly).into_iter().next().expect("Gene type should be checked");
+        assert_eq!(error.diagnostic().message, "mismatched types: expected Cat, found animais.Dog");
+        let rendered = program.render(&error.diagnostic(), ColorMode::Plain);
+        assert!(rendered.starts_with("error: mismatched types: expected Cat, found Dog\n"), "{}", rendered);
+
+        let mut interpreter = Interpreter::with_host(Builtins { names: program.names(), ..Builtins::silent() });
+        interpreter.run(&fly).expect("Program should run");
+        assert_eq!(interpreter.host.printed, vec!["Dog() Lobo(presa: nil)", "selvagens.Dog e Lobo(presa: nil)"]);
+        assert_eq!(program.unmangle("xanimais.Dog animais.DogHouse"), "xanimais.Dog animais.DogHouse");
+        assert_eq!(program.unmangle("found animais.Dog, not \"animais.Dog \\\" selvagens.Dog\""), "found Dog, not \"animais.Dog \\\" selvagens.Dog\"");
+    }
+
+    #[test]
+    fn test_import_cycle() {
+        let error = load(fixture("cycle_a.sc")).expect_err("Cycle should be rejected");
+        assert!(matches!(*error.error, ModuleError::Cycle { .. }));
+        assert_eq!(error.error.to_string(), "import cycle: cycle_a.sc -> cycle_b.sc -> cycle_a.sc");
+        assert!(error.path.ends_with("cycle_b.sc"));
+    }
+
+    #[test]
+    fn test_bad_imports() {
+        let load = |source: &str| load_source(fixture("erro.sc"), source.to_string()).expect_err("Import should fail");
+
+        let error = load("use Gato from \"animais.sc\"\n");
+        assert_eq!(error.error.to_string(), "`animais.sc` has no bug `Gato`");
+        let error = load("use \"animais.sc\"\nuse \"selvagens.sc\"\n");
+        assert!(matches!(*error.error, ModuleError::Conflict { ref name, .. } if name == "Dog"));
+        let error = load("use \"sumido.sc\"\n");
+        assert!(matches!(*error.error, ModuleError::Read { .. }));
+        assert!(error.render(ColorMode::Plain).contains("erro.sc:1:5"));
+        let error = load("use Dog from \"animais.sc\"\nprint(Dog.new, Cat.new)\n");
+        assert_eq!(error.error.to_string(), "`Cat` is not defined or imported in this file");
+        assert!(error.render(ColorMode::Plain).contains("erro.sc:2:16"), "{}", error.render(ColorMode::Plain));
+        let error = load("use Dog as Lobo from \"animais.sc\"\nDog.new\n");
+        assert!(matches!(*error.error, ModuleError::NotImported { ref name, .. } if name == "Dog"));
+        // Um parâmetro de tipo pode ter o nome de uma espécie não importada
+        let source = "use Dog from \"animais.sc\"\nbug Caixa<Cat>\n  gene valor Cat\nend\n";
+        load_source(fixture("erro.sc"), source.to_string()).expect("Type parameters are not imports");
+        let error = load("use \"main.sc\"\n");
+        assert!(matches!(*error.error, ModuleError::NotAModule { .. }));
+        assert!(error.path.ends_with("main.sc"));
+    }
+}
@@ -0,0 +1,335 @@
+use std::fmt::Write;
+use pest::error::{Error as PestError, ErrorVariant, InputLocation};
+use crate::dsl::ast::error::AstBuildError;
+use crate::dsl::ast::emitter::IntWidth;
+use crate::dsl::ast::span::Span;
+use crate::dsl::parser::parser::Rule;
+use crate::dsl::parser::scan::{self, BlockKind};
+use crate::dsl::parser::tree::TreeParseError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl Severity {
+    fn label(&self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        }
+    }
+
+    fn color(&self) -> &'static str {
+        match self {
+            Severity::Error => RED,
+            Severity::Warning => YELLOW,
+        }
+    }
+}
+
+/// Como o diagnóstico é desenhado: texto puro ou com cores ANSI para terminais.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorMode {
+    Plain,
+    Ansi,
+}
+
+const RED: &str = "\x1b[1;31m";
+const YELLOW: &str = "\x1b[1;33m";
+const BLUE: &str = "\x1b[1;34m";
+const BOLD: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
+
+/// Mensagem sobre um trecho do código, escrita em termos da linguagem SC
+/// e não das regras internas da gramática.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+    pub span: Span,
+    pub notes: Vec<String>,
+}
+
+impl Diagnostic {
+    pub fn error(message: impl Into<String>, span: Span) -> Self {
+        Diagnostic { severity: Severity::Error, message: message.into(), span, notes: Vec::new() }
+    }
+
+    pub fn warning(message: impl Into<String>, span: Span) -> Self {
+        Diagnostic { severity: Severity::Warning, message: message.into(), span, notes: Vec::new() }
+    }
+
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+
+    pub fn from_tree_error(error: &TreeParseError, source: &str) -> Self {
+        match error {
+            TreeParseError::PestError(error) => Diagnostic::from_pest(error, source),
+            TreeParseError::AstBuild(error) => Diagnostic::from_ast_build(error),
+            TreeParseError::EmptyTree => Diagnostic::error("the program is empty", scan::span_at(source, 0, 0)),
+        }
+    }
+
+    pub fn from_ast_build(error: &AstBuildError) -> Self {
+        if let AstBuildError::NumberOutOfRange { raw, expected, span } = error {
+            let diagnostic = Diagnostic::error(format!("literal `{}` does not fit in {}", raw, expected), *span);
+            return match IntWidth::from_suffix(expected) {
+                Some(width) if raw.ends_with(width.suffix()) => {
+                    diagnostic.with_note(format!("{} holds values from {} to {}", width, width.min(), width.max()))
+                }
+                Some(width) => diagnostic.with_note(format!("integers without a suffix are {}, from {} to {}", width, width.min(), width.max())),
+                None => diagnostic,
+            };
+        }
+        Diagnostic::error(format!("internal parser error: {}", error), error.span())
+            .with_note("the grammar and the AST builders disagree; please report this")
+    }
+
+    /// Traduz o erro do pest, olhando os blocos abertos para explicar `end` faltando.
+    pub fn from_pest(error: &PestError<Rule>, source: &str) -> Self {
+        let (start, end) = match error.location {
+            InputLocation::Pos(pos) => (pos, pos),
+            InputLocation::Span((start, end)) => (start, end),
+        };
+        let token = found_at(source, start);
+        let found = token.map(|(_, text)| text);
+        let span = match token {
+            Some((offset, text)) if end <= start => scan::span_at(source, offset, offset + text.len()),
+            _ => scan::span_at(source, start, end),
+        };
+
+        let blocks = scan::open_blocks(source, start);
+        let at_end = source[start.min(source.len())..].trim().is_empty();
+        let closing = found.is_some_and(|word| matches!(word, "bug" | "kind" | "contract" | "gene" | "ethics"));
+
+        if let Some(block) = blocks.last().filter(|_| at_end || closing) {
+            let mut diagnostic = Diagnostic::error(
+                format!("expected `end` to close {} opened on line {}", describe_block(block), block.span.line),
+                span,
+            );
+            for outer in blocks.iter().rev().skip(1) {
+                diagnostic = diagnostic.with_note(format!(
+                    "{} opened on line {} is also still open",
+                    describe_block(outer),
+                    outer.span.line
+                ));
+            }
+            return diagnostic;
+        }
+
+        let found = match found {
+            Some(text) => format!("`{}`", text),
+            None => "end of file".to_string(),
+        };
+        let message = match &error.variant {
+            ErrorVariant::ParsingError { positives, .. } => {
+                let expected = describe_rules(positives, &found);
+                if expected.is_empty() {
+                    format!("unexpected {}", found)
+                } else {
+                    format!("expected {}, found {}", expected, found)
+                }
+            }
+            ErrorVariant::CustomError { message } => message.clone(),
+        };
+        Diagnostic::error(message, span)
+    }
+
+    /// Desenha o diagnóstico no estilo `arquivo:linha:coluna` com o trecho sublinhado.
+    pub fn render(&self, source: &str, file_name: &str, mode: ColorMode) -> String {
+        let paint = |color: &str, text: &str| match mode {
+            ColorMode::Plain => text.to_string(),
+            ColorMode::Ansi => format!("{}{}{}", color, text, RESET),
+        };
+
+        let line_text = source.lines().nth(self.span.line.saturating_sub(1)).unwrap_or("");
+        let gutter = self.span.line.to_string().len();
+        let pad = " ".repeat(gutter);
+        let line_chars = line_text.chars().count();
+        let caret_start = self.span.col.saturating_sub(1).min(line_chars);
+        let span_chars = source
+            .get(self.span.start..self.span.end)
+            .map_or(0, |text| text.lines().next().unwrap_or("").chars().count());
+        let carets = span_chars.min(line_chars.saturating_sub(caret_start)).max(1);
+
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "{}{}",
+            paint(self.severity.color(), self.severity.label()),
+            paint(BOLD, &format!(": {}", self.message))
+        );
+        let _ = writeln!(out, "{}{} {}:{}:{}", pad, paint(BLUE, "-->"), file_name, self.span.line, self.span.col);
+        let _ = writeln!(out, "{} {}", pad, paint(BLUE, "|"));
+        let _ = writeln!(out, "{} {} {}", paint(BLUE, &self.span.line.to_string()), paint(BLUE, "|"), line_text);
+        let _ = writeln!(
+            out,
+            "{} {} {}{}",
+            pad,
+            paint(BLUE, "|"),
+            " ".repeat(caret_start),
+            paint(self.severity.color(), &"^".repeat(carets))
+        );
+        for note in &self.notes {
+            let _ = writeln!(out, "{} {} note: {}", pad, paint(BLUE, "="), note);
+        }
+        out
+    }
+}
+
+impl TreeParseError {
+    pub fn diagnostic(&self, source: &str) -> Diagnostic {
+        Diagnostic::from_tree_error(self, source)
+    }
+}
+
+fn describe_block(block: &scan::Block) -> String {
+    match (&block.kind, &block.name) {
+        (BlockKind::Bug | BlockKind::Kind | BlockKind::Contract | BlockKind::Ethics, Some(name)) => format!("{} `{}`", block.kind.keyword(), name),
+        _ => format!("`{}` block", block.kind.keyword()),
+    }
+}
+
+/// Primeira palavra, número, string ou símbolo a partir de `pos`, com seu offset;
+/// `None` quando só resta espaço até o fim do arquivo.
+fn found_at(source: &str, pos: usize) -> Option<(usize, &str)> {
+    let rest = source.get(pos..)?;
+    scan::tokenize(rest).first().map(|token| (pos + token.start, token.text))
+}
+
+fn describe_rule(rule: &Rule) -> &'static str {
+    match rule {
+        Rule::EOI => "end of file",
+        Rule::tag | Rule::each => "a name",
+        Rule::specie => "a species name",
+        Rule::gene => "`gene`",
+        Rule::ethics => "`ethics`",
+        Rule::bug | Rule::anatomy => "`bug`",
+        Rule::kind => "`kind`",
+        Rule::variant => "a variant",
+        Rule::when => "`when`",
+        Rule::signature | Rule::carrier => "`(`",
+        Rule::trail | Rule::catalysis => "`.`",
+        Rule::bind | Rule::binds | Rule::ethics_bind => "a parameter",
+        Rule::condition => "a condition",
+        Rule::expr | Rule::group => "an expression",
+        Rule::op_eq | Rule::op_ne | Rule::op_le | Rule::op_ge | Rule::op_lt | Rule::op_gt => "an operator",
+        Rule::op_add | Rule::op_sub | Rule::op_mul | Rule::op_div | Rule::op_mod => "an operator",
+        Rule::op_and | Rule::op_or | Rule::op_not | Rule::op_neg => "an operator",
+        Rule::oop | Rule::emitter | Rule::literal | Rule::sequence | Rule::transport | Rule::self_ref => "an expression",
+        Rule::boolean => "`true` or `false`",
+        Rule::nil => "`nil`",
+        Rule::list => "a list",
+        Rule::map => "a map",
+        Rule::int | Rule::decimal | Rule::hex | Rule::bit => "a number",
+        Rule::str | Rule::template | Rule::str_text => "a string",
+        Rule::escape => "an escape sequence",
+        Rule::interpolation => "`#{`",
+        Rule::elsif => "`elsif`",
+        Rule::r#else => "`else`",
+        Rule::r#return => "`return`",
+        Rule::r#if | Rule::r#while | Rule::r#for | Rule::r#match | Rule::statement => "a statement",
+        Rule::assign | Rule::behavior | Rule::signal | Rule::matrix | Rule::genome | Rule::strand | Rule::fly => {
+            "a statement"
+        }
+        _ => "",
+    }
+}
+
+/// Lista o que era esperado, sem repetir o que de fato foi encontrado.
+fn describe_rules(rules: &[Rule], found: &str) -> String {
+    let mut names: Vec<&str> = Vec::new();
+    for rule in rules {
+        let name = describe_rule(rule);
+        if !name.is_empty() && name != found && !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    match names.len() {
+        0 => String::new(),
+        1 => names[0].to_string(),
+        n => format!("{} or {}", names[..n - 1].join(", "), names[n - 1]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ColorMode, Diagnostic};
+    use crate::dsl::parser::tree::Tree;
+    use crate::tests::load_fragment;
+
+    fn diagnose(source: &str) -> Diagnostic {
+        let error = Tree::parse_input(source.to_string()).expect_err("Input must not parse");
+        error.diagnostic(source)
+    }
+
+    #[test]
+    fn test_missing_end_names_the_open_block() {
+        let source = "bug Dog\n  gene energia Int\n";
+
+        let diagnostic = diagnose(source);
+
+        assert_eq!(diagnostic.message, "expected `end` to close bug `Dog` opened on line 1");
+    }
+
+    #[test]
+    fn test_missing_end_mentions_outer_blocks() {
+        let source = load_fragment("bug/invalid_syntax.sc");
+
+        let diagnostic = diagnose(&source);
+
+        assert_eq!(diagnostic.message, "expected `end` to close ethics `test` opened on line 3");
+        assert_eq!(diagnostic.notes, vec!["bug `InvalidBug` opened on line 1 is also still open".to_string()]);
+    }
+
+    #[test]
+    fn test_number_out_of_range_gives_the_range() {
+        let diagnostic = diagnose("x = 18446744073709551616u64\n");
+        assert_eq!(diagnostic.message, "literal `18446744073709551616u64` does not fit in u64");
+        assert_eq!(diagnostic.notes, vec!["u64 holds values from 0 to 18446744073709551615".to_string()]);
+
+        let diagnostic = diagnose("x = 9223372036854775808\n");
+        assert_eq!(diagnostic.notes, vec!["integers without a suffix are i64, from -9223372036854775808 to 9223372036854775807".to_string()]);
+    }
+
+    #[test]
+    fn test_unexpected_token_uses_dsl_terms() {
+        let source = load_fragment("bug/invalid_lowercase_specie.sc");
+
+        let diagnostic = diagnose(&source);
+
+        assert!(diagnostic.message.contains("a species name"), "message: {}", diagnostic.message);
+        assert!(!diagnostic.message.contains("ethics_d"));
+        assert_eq!(diagnostic.span.line, 1);
+    }
+
+    #[test]
+    fn test_render_plain() {
+        let source = "bug Dog\n  gene energia int\nend";
+
+        let rendered = diagnose(source).render(source, "dog.sc", ColorMode::Plain);
+
+        assert_eq!(
+            rendered,
+            "error: expected a species name, found `int`\n \
+             --> dog.sc:2:16\n  \
+              |\n\
+             2 |   gene energia int\n  \
+              |                ^^^\n"
+        );
+    }
+
+    #[test]
+    fn test_render_ansi() {
+        let source = "bug Dog\n  gene energia int\nend";
+
+        let rendered = diagnose(source).render(source, "dog.sc", ColorMode::Ansi);
+
+        assert!(rendered.starts_with("\x1b[1;31merror\x1b[0m"));
+        assert!(rendered.contains("\x1b[1;31m^^^\x1b[0m"));
+    }
+}
@@ -1,2 +1,5 @@
+#[allow(clippy::module_inception)]
 pub mod parser;
 pub mod tree;
+pub mod scan;
+pub mod diagnostic;
@@ -0,0 +1,502 @@
+//! Varredura leve do código fonte, independente da gramática.
+//!
+//! Serve para o que o pest não responde depois de uma falha: quais blocos
+//! `bug`/`ethics`/`if`/`while`/`for` ficaram abertos até certo ponto. Também
+//! recolhe os comentários, que a gramática descarta como espaço.
+
+use crate::dsl::ast::comment::{Comment, CommentKind};
+use crate::dsl::ast::span::Span;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    Word,
+    Number,
+    Str,
+    Punct,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token<'a> {
+    pub kind: TokenKind,
+    pub text: &'a str,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Token<'_> {
+    pub fn is_word(&self, word: &str) -> bool {
+        self.kind == TokenKind::Word && self.text == word
+    }
+}
+
+/// Quebra o código em palavras, números, strings e pontuação, ignorando espaços.
+/// Strings sem fechamento vão até o fim da entrada.
+pub fn tokenize(source: &str) -> Vec<Token<'_>> {
+    let bytes = source.as_bytes();
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+
+    while pos < bytes.len() {
+        let c = bytes[pos];
+        let start = pos;
+        let kind = if c.is_ascii_whitespace() {
+            pos += 1;
+            continue;
+        } else if let Some(end) = comment_end(source, pos) {
+            pos = end;
+            continue;
+        } else if c == b'"' {
+            pos = string_end(bytes, pos);
+            TokenKind::Str
+        } else if c.is_ascii_alphabetic() || c == b'_' {
+            while pos < bytes.len() && (bytes[pos].is_ascii_alphanumeric() || bytes[pos] == b'_') {
+                pos += 1;
+            }
+            TokenKind::Word
+        } else if c.is_ascii_digit() {
+            while pos < bytes.len() {
+                let fraction = bytes[pos] == b'.' && bytes.get(pos + 1).is_some_and(u8::is_ascii_digit);
+                if !(bytes[pos].is_ascii_alphanumeric() || bytes[pos] == b'_' || fraction) {
+                    break;
+                }
+                pos += 1;
+            }
+            TokenKind::Number
+        } else {
+            pos += source[pos..].chars().next().map_or(1, char::len_utf8);
+            TokenKind::Punct
+        };
+        tokens.push(Token { kind, text: &source[start..pos], start, end: pos });
+    }
+
+    tokens
+}
+
+/// Fim da string que começa em `pos`, pulando escapes e os `#{ }` (que podem
+/// ter outras strings dentro). Sem as aspas finais, vai até o fim da entrada.
+fn string_end(bytes: &[u8], pos: usize) -> usize {
+    string_close(bytes, pos).unwrap_or(bytes.len())
+}
+
+/// Como `string_end`, mas `None` se a entrada acabar dentro da string ou de um `#{`
+fn string_close(bytes: &[u8], mut pos: usize) -> Option<usize> {
+    pos += 1;
+    while pos < bytes.len() {
+        match bytes[pos] {
+            b'"' => return Some(pos + 1),
+            b'\\' => pos += 2,
+            b'#' if bytes.get(pos + 1) == Some(&b'{') => {
+                let mut depth = 0;
+                pos += 1;
+                loop {
+                    match bytes.get(pos)? {
+                        b'"' => {
+                            pos = string_close(bytes, pos)?;
+                            continue;
+                        }
+                        b'{' => depth += 1,
+                        b'}' => depth -= 1,
+                        _ => {}
+                    }
+                    pos += 1;
+                    if depth == 0 {
+                        break;
+                    }
+                }
+            }
+            _ => pos += 1,
+        }
+    }
+    None
+}
+
+/// Uma string que a entrada deixou aberta: sem as aspas finais ou com um `#{` sem `}`
+pub fn is_open_string(token: &Token) -> bool {
+    token.kind == TokenKind::Str && string_close(token.text.as_bytes(), 0).is_none()
+}
+
+/// Fim do comentário que começa em `pos`, se houver um: `#[ ... ]#` ou `#` até o
+/// fim da linha. Um bloco sem `]#` vai até o fim da entrada.
+fn comment_end(source: &str, pos: usize) -> Option<usize> {
+    let rest = &source[pos..];
+    if let Some(body) = rest.strip_prefix("#[") {
+        Some(body.find("]#").map_or(source.len(), |end| pos + 2 + end + 2))
+    } else if rest.starts_with('#') {
+        Some(rest.find(['\n', '\r']).map_or(source.len(), |end| pos + end))
+    } else {
+        None
+    }
+}
+
+/// Todos os comentários do código, na ordem em que aparecem.
+pub fn comments(source: &str) -> Vec<Comment> {
+    let mut found = Vec::new();
+    let mut pos = 0;
+
+    while pos < source.len() {
+        if let Some(end) = comment_end(source, pos) {
+            let kind = if source[pos..].starts_with("#[") { CommentKind::Block } else { CommentKind::Line };
+            found.push(Comment { kind, raw: source[pos..end].to_string(), span: span_at(source, pos, end) });
+            pos = end;
+        } else if source.as_bytes()[pos] == b'"' {
+            pos = string_end(source.as_bytes(), pos);
+        } else {
+            pos += source[pos..].chars().next().map_or(1, char::len_utf8);
+        }
+    }
+
+    found
+}
+
+/// Linha e coluna (a partir de 1) de um offset em bytes.
+pub fn line_col(source: &str, offset: usize) -> (usize, usize) {
+    let offset = offset.min(source.len());
+    let before = &source[..offset];
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    let col = before[line_start..].chars().count() + 1;
+    (line, col)
+}
+
+/// Span de `start..end` com linha e coluna calculadas a partir do código.
+pub fn span_at(source: &str, start: usize, end: usize) -> Span {
+    let (line, col) = line_col(source, start);
+    Span::new(start, end, line, col)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockKind {
+    Bug,
+    Kind,
+    Contract,
+    Ethics,
+    If,
+    While,
+    For,
+    Match,
+    Do,
+}
+
+impl BlockKind {
+    pub fn keyword(&self) -> &'static str {
+        match self {
+            BlockKind::Bug => "bug",
+            BlockKind::Kind => "kind",
+            BlockKind::Contract => "contract",
+            BlockKind::Ethics => "ethics",
+            BlockKind::If => "if",
+            BlockKind::While => "while",
+            BlockKind::For => "for",
+            BlockKind::Match => "match",
+            BlockKind::Do => "do",
+        }
+    }
+}
+
+/// Um bloco que exige `end`, com o nome declarado (espécie do bug, kind ou
+/// contrato, tag do ethics).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Block {
+    pub kind: BlockKind,
+    pub name: Option<String>,
+    pub span: Span,
+}
+
+/// Blocos abertos e ainda não fechados antes de `upto`, do mais externo ao mais interno.
+pub fn open_blocks(source: &str, upto: usize) -> Vec<Block> {
+    let tokens = tokenize(source);
+    let mut stack: Vec<Block> = Vec::new();
+    let mut i = 0;
+
+    while i < tokens.len() && tokens[i].start < upto {
+        let token = tokens[i];
+        i += 1;
+        match opener(&tokens, i - 1) {
+            Opener::Block(kind) => {
+                let name = match kind {
+                    BlockKind::Bug | BlockKind::Kind | BlockKind::Contract | BlockKind::Ethics => tokens
+                        .get(i)
+                        .filter(|t| t.kind == TokenKind::Word)
+                        .map(|t| t.text.to_string()),
+                    _ => None,
+                };
+                stack.push(Block { kind, name, span: span_at(source, token.start, token.end) });
+            }
+            Opener::Close => {
+                stack.pop();
+            }
+            Opener::Skip(next) => i = next,
+            Opener::None => {}
+        }
+    }
+
+    stack
+}
+
+/// Trechos de nível superior (um bug, um statement, uma atribuição...) como
+/// intervalos `start..end` de bytes, usados para retomar a análise após um erro.
+///
+/// Um novo trecho começa em todo `bug`, `kind` ou `contract` e no primeiro token de cada linha fora
+/// de blocos e parênteses.
+pub fn top_level_items(source: &str) -> Vec<(usize, usize)> {
+    let tokens = tokenize(source);
+    let mut starts: Vec<usize> = Vec::new();
+    let mut depth = 0usize;
+    let mut parens = 0usize;
+    let mut last_line_end = None;
+    let mut i = 0;
+
+    while i < tokens.len() {
+        let token = tokens[i];
+        let first_on_line = last_line_end.is_none_or(|end| source[end..token.start].contains('\n'));
+        last_line_end = Some(token.end);
+
+        if token.is_word("bug") || token.is_word("kind") || token.is_word("contract") {
+            depth = 0;
+            parens = 0;
+            starts.push(token.start);
+        } else if depth == 0 && parens == 0 && (first_on_line || starts.is_empty()) {
+            starts.push(token.start);
+        }
+
+        match token.text {
+            "(" | "[" | "{" => parens += 1,
+            ")" | "]" | "}" => parens = parens.saturating_sub(1),
+            _ => {}
+        }
+        match opener(&tokens, i) {
+            Opener::Block(_) => depth += 1,
+            Opener::Close => depth = depth.saturating_sub(1),
+            Opener::Skip(next) => {
+                i = next;
+                continue;
+            }
+            Opener::None => {}
+        }
+        i += 1;
+    }
+
+    let mut items = Vec::new();
+    for (n, start) in starts.iter().enumerate() {
+        let end = starts.get(n + 1).copied().unwrap_or(source.len());
+        items.push((*start, source[..end].trim_end().len().max(*start)));
+    }
+    items
+}
+
+/// Início de cada `gene` e `ethics` entre `start` e `end`; junto com o `end`
+/// do bug, delimitam os membros que podem ser descartados na recuperação.
+pub fn member_starts(source: &str, start: usize, end: usize) -> Vec<usize> {
+    tokenize(&source[start..end])
+        .iter()
+        .filter(|t| t.is_word("gene") || t.is_word("ethics"))
+        .map(|t| start + t.start)
+        .collect()
+}
+
+enum Opener {
+    Block(BlockKind),
+    Close,
+    /// `ethics` sem corpo: continua a partir do índice dado
+    Skip(usize),
+    None,
+}
+
+fn opener(tokens: &[Token], i: usize) -> Opener {
+    let token = tokens[i];
+    if token.kind != TokenKind::Word {
+        return Opener::None;
+    }
+    match token.text {
+        "bug" => Opener::Block(BlockKind::Bug),
+        "kind" => Opener::Block(BlockKind::Kind),
+        "contract" => Opener::Block(BlockKind::Contract),
+        "if" => Opener::Block(BlockKind::If),
+        "while" => Opener::Block(BlockKind::While),
+        "for" => Opener::Block(BlockKind::For),
+        "match" => Opener::Block(BlockKind::Match),
+        "do" => Opener::Block(BlockKind::Do),
+        "ethics" => match ethics_header(tokens, i + 1) {
+            (true, _) => Opener::Block(BlockKind::Ethics),
+            (false, next) => Opener::Skip(next),
+        },
+        "end" => Opener::Close,
+        _ => Opener::None,
+    }
+}
+
+/// Pula o cabeçalho `ethics tag (binds) Feedback` começando em `i` (logo após `ethics`)
+/// e diz se ele é seguido de corpo. Sem corpo, o próximo token é outro membro ou o `end` do bug.
+fn ethics_header(tokens: &[Token], mut i: usize) -> (bool, usize) {
+    // tag
+    if tokens.get(i).is_some_and(|t| t.kind == TokenKind::Word) {
+        i += 1;
+    }
+    // signature
+    if tokens.get(i).is_some_and(|t| t.text == "(") {
+        while i < tokens.len() && tokens[i].text != ")" {
+            i += 1;
+        }
+        i += 1;
+    }
+    // feedback
+    if tokens
+        .get(i)
+        .is_some_and(|t| t.kind == TokenKind::Word && t.text.starts_with(|c: char| c.is_ascii_uppercase()))
+    {
+        i += 1;
+        // argumentos de tipo: `Map<String, List<Dog>>`
+        let mut depth = 0usize;
+        while let Some(token) = tokens.get(i).filter(|t| t.text == "<" || depth > 0) {
+            match token.text {
+                "<" => depth += 1,
+                ">" => depth -= 1,
+                _ => {}
+            }
+            i += 1;
+            if depth == 0 {
+                break;
+            }
+        }
+    }
+    let has_body = match tokens.get(i) {
+        None => false,
+        Some(next) => !(next.is_word("ethics") || next.is_word("gene") || next.is_word("end")),
+    };
+    (has_body, i)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{comments, is_open_string, line_col, member_starts, open_blocks, tokenize, top_level_items, BlockKind, TokenKind};
+    use crate::dsl::ast::comment::CommentKind;
+    use crate::tests::load_fragment;
+
+    #[test]
+    fn test_tokenize_skips_string_contents() {
+        let tokens = tokenize("x = \"end if\".size");
+
+        let kinds: Vec<TokenKind> = tokens.iter().map(|t| t.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![TokenKind::Word, TokenKind::Punct, TokenKind::Str, TokenKind::Punct, TokenKind::Word]
+        );
+        assert_eq!(tokens[2].text, "\"end if\"");
+
+        let tokens = tokenize(r#"x = "a \" #{y.plus("}\"")} end" end"#);
+        assert_eq!(tokens[2].text, r#""a \" #{y.plus("}\"")} end""#);
+        assert!(tokens[3].is_word("end"));
+    }
+
+    #[test]
+    fn test_open_strings() {
+        let last = |source| tokenize(source).last().is_some_and(is_open_string);
+        assert!(last(r#"x = "abc"#));
+        assert!(last(r#"x = "abc\""#));
+        assert!(last(r#"x = "a #{b.plus(1"#));
+        assert!(last(r#"x = "a #{"b"#));
+        assert!(!last(r#"x = "a #{"}"} c""#));
+        assert!(!last(r#"x = "abc" # "aberta""#));
+    }
+
+    #[test]
+    fn test_tokenize_skips_comments() {
+        let tokens = tokenize("bug Dog # end\n#[ if\nwhile ]# end");
+
+        let texts: Vec<&str> = tokens.iter().map(|t| t.text).collect();
+        assert_eq!(texts, vec!["bug", "Dog", "end"]);
+    }
+
+    #[test]
+    fn test_comments() {
+        let source = "x = \"# não é comentário\" # linha\n#[ bloco\n  longo ]#\ny = 1";
+
+        let found = comments(source);
+
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].kind, CommentKind::Line);
+        assert_eq!(found[0].text(), "linha");
+        assert_eq!(found[1].kind, CommentKind::Block);
+        assert_eq!(found[1].raw, "#[ bloco\n  longo ]#");
+        assert_eq!((found[1].span.line, found[1].span.col), (2, 1));
+
+        let found = comments(r#"x = "\" #{y} \#{z}" # fim"#);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].text(), "fim");
+    }
+
+    #[test]
+    fn test_line_col() {
+        let source = "bug Dog\n  gene x Int\nend";
+
+        assert_eq!(line_col(source, 0), (1, 1));
+        assert_eq!(line_col(source, 10), (2, 3));
+        assert_eq!(line_col(source, source.len()), (3, 4));
+    }
+
+    #[test]
+    fn test_open_blocks_of_complete_program_is_empty() {
+        let source = load_fragment("program/anatomy.sc");
+
+        assert!(open_blocks(&source, source.len()).is_empty());
+    }
+
+    #[test]
+    fn test_contract_ethics_without_body_do_not_open_blocks() {
+        let source = "contract Pilha\n  ethics topo Map<String, List<Int>>\n  ethics vazia() Bool\nend\nbug P < Pilha\n";
+
+        let blocks = open_blocks(source, source.len());
+
+        assert_eq!(blocks.len(), 1);
+        assert_eq!((blocks[0].kind, blocks[0].name.as_deref()), (BlockKind::Bug, Some("P")));
+    }
+
+    #[test]
+    fn test_open_blocks_reports_unclosed_ethics_inside_bug() {
+        let source = load_fragment("bug/invalid_syntax.sc");
+
+        let blocks = open_blocks(&source, source.len());
+
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0].kind, BlockKind::Bug);
+        assert_eq!(blocks[0].name.as_deref(), Some("InvalidBug"));
+        assert_eq!(blocks[1].kind, BlockKind::Ethics);
+        assert_eq!(blocks[1].name.as_deref(), Some("test"));
+        assert_eq!(blocks[1].span.line, 3);
+    }
+
+    #[test]
+    fn test_top_level_items() {
+        let source = "x = 1\nbug Dog\n  gene a Int\nend\nwhile a.b(\n  c)\n  print(a)\nend\n";
+
+        let items: Vec<&str> = top_level_items(source).iter().map(|(s, e)| &source[*s..*e]).collect();
+
+        assert_eq!(
+            items,
+            vec!["x = 1", "bug Dog\n  gene a Int\nend", "while a.b(\n  c)\n  print(a)\nend"]
+        );
+    }
+
+    #[test]
+    fn test_top_level_items_restart_at_bug_after_unclosed_block() {
+        let source = load_fragment("bug/invalid_syntax.sc") + "\nbug Other\nend";
+
+        let items = top_level_items(&source);
+
+        assert_eq!(items.len(), 2);
+        assert!(source[items[1].0..items[1].1].starts_with("bug Other"));
+    }
+
+    #[test]
+    fn test_member_starts() {
+        let source = load_fragment("bug/complete.sc");
+
+        let starts = member_starts(&source, 0, source.len());
+
+        let members: Vec<&str> = starts.iter().map(|s| source[*s..].lines().next().unwrap()).collect();
+        assert_eq!(
+            members,
+            vec!["gene x Int", "ethics simple_method", "ethics test_method", "ethics test_method2()", "ethics test_method3(a: Int) Int"]
+        );
+    }
+}
@@ -1,13 +1,25 @@
 use pest::Parser;
 use serde::{Deserialize, Serialize};
+use crate::dsl::ast::comment::Comment;
+use crate::dsl::ast::error::AstBuildError;
+use crate::dsl::ast::error_node::ErrorNode;
+use crate::dsl::ast::fly::Fly;
+use crate::dsl::ast::genome::Genome;
 use crate::dsl::ast::sc::SC;
+use crate::dsl::ast::span::{with_line_table, LineTable, Shift, Span};
+use crate::dsl::ast::visit_mut::VisitMut;
+use crate::dsl::ast::strand::Strand;
+use crate::dsl::parser::diagnostic::Diagnostic;
 use crate::dsl::parser::parser::{Rule, SCP};
+use crate::dsl::parser::scan;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
 pub enum TreeParseError {
     #[error("SC Parsing failed{0}")]
     PestError(#[from] pest::error::Error<Rule>),
+    #[error("Falha ao montar a AST: {0}")]
+    AstBuild(#[from] AstBuildError),
     #[error("Falha ao processar árvore vazia")]
     EmptyTree,
 }
@@ -16,18 +28,77 @@ pub enum TreeParseError {
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Tree {
     pub sc: SC,
+    /// Comentários do código, só preenchidos com `ParseOptions::retain_comments`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub comments: Vec<Comment>,
+}
+
+/// Ajustes de `Tree::parse_with`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ParseOptions {
+    /// Guarda os comentários em `Tree::comments` (formatadores, documentação)
+    pub retain_comments: bool,
 }
 
 impl Tree {
     pub fn parse_input(input: String) -> Result<Self, TreeParseError> {
-        let parsed = SCP::parse(Rule::sc, &input);
-        match parsed {
-            Ok(parsed) => {
-                let sc = SC::from_pair(parsed.clone().next().unwrap());
-                Ok(Tree { sc })
+        Tree::parse_with(input, ParseOptions::default())
+    }
+
+    pub fn parse_with(input: String, options: ParseOptions) -> Result<Self, TreeParseError> {
+        let mut parsed = SCP::parse(Rule::sc, &input)?;
+        let pair = parsed.next().ok_or(TreeParseError::EmptyTree)?;
+        let sc = with_line_table(&input, || SC::from_pair(pair))?;
+        let comments = if options.retain_comments { scan::comments(&input) } else { Vec::new() };
+        Ok(Tree { sc, comments })
+    }
+
+    /// Comentários logo acima de um nó, sem linha em branco nem código no meio:
+    /// a documentação de um `bug`, `gene` ou `ethics`.
+    pub fn leading_comments(&self, span: Span, source: &str) -> Vec<&Comment> {
+        let mut leading = Vec::new();
+        let mut upto = span.start;
+        for comment in self.comments.iter().rev().skip_while(|c| c.span.start >= span.start) {
+            let gap = &source[comment.span.end..upto];
+            if !gap.trim().is_empty() || gap.matches('\n').count() > 1 {
+                break;
             }
-            Err(e) => Err(TreeParseError::from(e)),
+            leading.push(comment);
+            upto = comment.span.start;
+        }
+        leading.reverse();
+        leading
+    }
+
+    /// Analisa o programa inteiro mesmo com erros de sintaxe.
+    ///
+    /// Cada trecho de nível superior (um `bug`, um statement...) é analisado
+    /// isoladamente; dentro de um bug quebrado, o membro `gene`/`ethics` com erro
+    /// é descartado e o bug é reanalisado. O que não puder ser salvo vira um
+    /// `Genome::Error`, e todos os problemas encontrados voltam como diagnósticos.
+    pub fn parse_recovering(input: String) -> Recovery {
+        let error = match Tree::parse_input(input.clone()) {
+            Ok(tree) => return Recovery { tree, diagnostics: Vec::new() },
+            Err(error) => error,
+        };
+        if !matches!(error, TreeParseError::PestError(_) | TreeParseError::AstBuild(AstBuildError::NumberOutOfRange { .. })) {
+            let diagnostic = error.diagnostic(&input);
+            let span = scan::span_at(&input, 0, input.len());
+            let genome = vec![error_genome(&input, span, &diagnostic)];
+            return Recovery { tree: tree_of(genome, span), diagnostics: vec![diagnostic] };
+        }
+
+        let mut genome = Vec::new();
+        let mut diagnostics = Vec::new();
+        let lines = LineTable::new(&input);
+        for (start, end) in scan::top_level_items(&input) {
+            recover_item(&input, &lines, start, end, &mut genome, &mut diagnostics);
         }
+        genome.sort_by_key(|g| g.span().start);
+        diagnostics.sort_by_key(|d| d.span.start);
+
+        let span = scan::span_at(&input, 0, input.len());
+        Recovery { tree: tree_of(genome, span), diagnostics }
     }
 
     // #[deprecated(since = "próxima versão", note = "Este método será removido em versões futuras")]
@@ -35,3 +106,320 @@ impl Tree {
     //     &self.sc
     // }
 }
+
+/// Resultado de `Tree::parse_recovering`: a árvore parcial e todos os diagnósticos.
+#[derive(Debug, Clone)]
+pub struct Recovery {
+    pub tree: Tree,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl Recovery {
+    pub fn is_ok(&self) -> bool {
+        self.diagnostics.is_empty()
+    }
+}
+
+fn tree_of(genome: Vec<Genome>, span: Span) -> Tree {
+    let strand = Strand { genome, span };
+    let fly = Fly { strand, span };
+    Tree { sc: SC { fly, span }, comments: Vec::new() }
+}
+
+fn error_genome(source: &str, span: Span, diagnostic: &Diagnostic) -> Genome {
+    Genome::Error(ErrorNode {
+        raw: span.slice(source).to_string(),
+        message: diagnostic.message.clone(),
+        span,
+    })
+}
+
+/// Copia `source` trocando tudo fora dos intervalos mantidos por espaços,
+/// preservando quebras de linha e offsets para que os spans continuem valendo.
+fn blank_outside(source: &str, keep: &[(usize, usize)]) -> String {
+    source
+        .char_indices()
+        .map(|(i, c)| {
+            if c == '\n' || keep.iter().any(|(start, end)| *start <= i && i < *end) {
+                c.to_string()
+            } else {
+                " ".repeat(c.len_utf8())
+            }
+        })
+        .collect()
+}
+
+fn parse_genomes(text: &str) -> Result<Vec<Genome>, TreeParseError> {
+    Ok(Tree::parse_input(text.to_string())?.sc.fly.strand.genome)
+}
+
+fn recover_item(
+    source: &str,
+    lines: &LineTable,
+    start: usize,
+    end: usize,
+    genome: &mut Vec<Genome>,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    // O trecho sozinho costuma bastar. O código inteiro, com o resto em branco, só é
+    // reanalisado quando o trecho tem erro, para as mensagens citarem as linhas certas.
+    if let Ok(mut parsed) = parse_genomes(&source[start..end]) {
+        let (line, col) = lines.line_col(source, start);
+        let mut shift = Shift { offset: start, line: line - 1, col: col - 1 };
+        parsed.iter_mut().for_each(|item| shift.visit_genome(item));
+        genome.extend(parsed);
+        return;
+    }
+
+    let mut keep = vec![(start, end)];
+    let members = scan::member_starts(source, start, end);
+    let tokens = scan::tokenize(&source[start..end]);
+    let bug_end = tokens.last().filter(|t| t.is_word("end")).map(|t| start + t.start);
+
+    loop {
+        let text = blank_outside(source, &keep);
+        let error = match parse_genomes(&text) {
+            Ok(parsed) => {
+                genome.extend(parsed);
+                return;
+            }
+            Err(error) => error,
+        };
+        let mut diagnostic = error.diagnostic(&text);
+        // Com o resto em branco, um bloco cortado pelo próximo `bug` só falha no fim
+        // do texto; o erro vai para o ponto do corte
+        if diagnostic.span.start >= end
+            && let Some(next) = scan::tokenize(&source[end..]).first()
+        {
+            diagnostic.span = scan::span_at(source, end + next.start, end + next.end);
+        }
+
+        // Dentro de um bug, descarta só o membro que contém o erro e tenta de novo.
+        // Um `end` faltando só aparece no fim do bug: vale o ethics ainda aberto.
+        let position = diagnostic.span.start;
+        let position = scan::open_blocks(&text, position)
+            .iter()
+            .find(|block| block.kind == scan::BlockKind::Ethics)
+            .map_or(position, |block| block.span.start);
+        let member = members.iter().enumerate().find_map(|(n, member_start)| {
+            let member_end = members.get(n + 1).copied().or(bug_end).unwrap_or(end);
+            (*member_start <= position && position < member_end).then_some((*member_start, member_end))
+        });
+        let is_bug = source[start..end].starts_with("bug");
+        match member {
+            Some((member_start, member_end)) if is_bug && keep.len() <= members.len() => {
+                let member_end = start + source[start..member_end].trim_end().len();
+                let span = scan::span_at(source, member_start, member_end);
+                genome.push(error_genome(source, span, &diagnostic));
+                diagnostics.push(diagnostic);
+                keep = split_out(&keep, member_start, member_end);
+            }
+            _ => {
+                let span = scan::span_at(source, start, end);
+                genome.push(error_genome(source, span, &diagnostic));
+                diagnostics.push(diagnostic);
+                return;
+            }
+        }
+    }
+}
+
+/// Remove `start..end` dos intervalos mantidos.
+fn split_out(keep: &[(usize, usize)], start: usize, end: usize) -> Vec<(usize, usize)> {
+    let mut result = Vec::new();
+    for (keep_start, keep_end) in keep {
+        if end <= *keep_start || *keep_end <= start {
+            result.push((*keep_start, *keep_end));
+            continue;
+        }
+        if *keep_start < start {
+            result.push((*keep_start, start));
+        }
+        if end < *keep_end {
+            result.push((end, *keep_end));
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ParseOptions, Tree, TreeParseError};
+    use crate::dsl::ast::span::Span;
+    use crate::dsl::ast::anatomy::Anatomy;
+    use crate::dsl::ast::genome::Genome;
+    use crate::tests::load_fragment;
+
+    #[test]
+    fn test_parse_input_anatomy() {
+        let input = load_fragment("program/anatomy.sc");
+
+        let tree = Tree::parse_input(input).expect("Should parse successfully");
+
+        assert_eq!(tree.sc.fly.strand.genome.len(), 1);
+    }
+
+    #[test]
+    fn test_parse_input_returns_error_instead_of_panicking() {
+        let input = load_fragment("bug/invalid_syntax.sc");
+
+        let error = Tree::parse_input(input).expect_err("Invalid input must not parse");
+
+        assert!(matches!(error, TreeParseError::PestError(_)));
+    }
+
+    #[test]
+    fn test_parse_comments_anywhere_whitespace_is() {
+        let input = load_fragment("program/comments.sc");
+
+        let tree = Tree::parse_input(input).expect("Comments should parse");
+
+        assert!(tree.comments.is_empty());
+        assert_eq!(tree.sc.fly.strand.genome.len(), 3);
+    }
+
+    #[test]
+    fn test_parse_readme_literals_with_comments() {
+        let input = load_fragment("literal/readme_comments.sc");
+
+        let tree = Tree::parse_input(input).expect("README example should parse");
+
+        assert_eq!(tree.sc.fly.strand.genome.len(), 5);
+    }
+
+    #[test]
+    fn test_parse_with_retains_comments() {
+        let input = load_fragment("program/comments.sc");
+
+        let tree = Tree::parse_with(input.clone(), ParseOptions { retain_comments: true })
+            .expect("Comments should parse");
+
+        assert_eq!(tree.comments.len(), 7);
+        let Genome::Anatomy(Anatomy::Bug(bug)) = &tree.sc.fly.strand.genome[0] else {
+            panic!("Primeiro genoma deveria ser um bug");
+        };
+        let doc: Vec<&str> = tree.leading_comments(bug.span, &input).iter().map(|c| c.text()).collect();
+        assert_eq!(doc, vec!["Um cachorro", "com energia"]);
+        let doc: Vec<&str> = tree.leading_comments(bug.ethics[0].span, &input).iter().map(|c| c.text()).collect();
+        assert_eq!(doc, vec!["Late alto\n     e em várias linhas"]);
+    }
+
+    #[test]
+    fn test_parse_recovering_valid_input_has_no_diagnostics() {
+        let input = load_fragment("program/anatomy.sc");
+
+        let recovery = Tree::parse_recovering(input);
+
+        assert!(recovery.is_ok());
+        assert_eq!(recovery.tree.sc.fly.strand.genome.len(), 1);
+    }
+
+    #[test]
+    fn test_parse_recovering_reports_every_broken_ethics() {
+        let input = load_fragment("program/three_broken_ethics.sc");
+
+        let recovery = Tree::parse_recovering(input);
+
+        let lines: Vec<usize> = recovery.diagnostics.iter().map(|d| d.span.line).collect();
+        assert_eq!(lines, vec![4, 12, 22]);
+
+        // Os três bugs continuam na árvore, cada ethics quebrado vira um nó de erro
+        let genome = &recovery.tree.sc.fly.strand.genome;
+        let bugs: Vec<&str> = genome
+            .iter()
+            .filter_map(|g| match g {
+                Genome::Anatomy(Anatomy::Bug(bug)) => Some(bug.specie.raw.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(bugs, vec!["Dog", "Cat", "Bird"]);
+        assert_eq!(genome.iter().filter(|g| g.is_error()).count(), 3);
+
+        // O ethics válido de Dog é mantido
+        let dog = genome
+            .iter()
+            .find_map(|g| match g {
+                Genome::Anatomy(Anatomy::Bug(bug)) if bug.specie.raw == "Dog" => Some(bug),
+                _ => None,
+            })
+            .expect("Dog deveria estar na árvore");
+        let ethics: Vec<&str> = dog.ethics.iter().map(|e| e.tag.raw.as_str()).collect();
+        assert_eq!(ethics, vec!["correr"]);
+    }
+
+    #[test]
+    fn test_parse_recovering_points_a_missing_end_at_the_next_bug() {
+        let input = "bug A\n  gene x Int\n\nbug B\n  gene y Int\nend\n\nbug C\n  gene z int\nend\n".to_string();
+
+        let recovery = Tree::parse_recovering(input.clone());
+
+        let found: Vec<(&str, usize, usize)> =
+            recovery.diagnostics.iter().map(|d| (d.message.as_str(), d.span.line, d.span.col)).collect();
+        assert_eq!(
+            found,
+            vec![
+                ("expected `end` to close bug `A` opened on line 1", 4, 1),
+                ("expected a species name, found `int`", 9, 10),
+            ]
+        );
+        assert_eq!(recovery.diagnostics[0].span.slice(&input), "bug");
+    }
+
+    #[test]
+    fn test_parse_recovering_keeps_statements_around_errors() {
+        let input = "x = 1\ny = = 2\nz = 3\n".to_string();
+
+        let recovery = Tree::parse_recovering(input);
+
+        assert_eq!(recovery.diagnostics.len(), 1);
+        let genome = &recovery.tree.sc.fly.strand.genome;
+        assert_eq!(genome.len(), 3);
+        assert!(genome[1].is_error());
+        assert!(!genome[0].is_error() && !genome[2].is_error());
+        assert_eq!(genome[2].span(), Span::new(14, 19, 3, 1));
+    }
+
+    #[test]
+    fn test_parse_recovering_reports_numbers_out_of_range() {
+        let input = "x = 1\ny = 256u8\nz = 0xFF_FFu16\n".to_string();
+
+        let recovery = Tree::parse_recovering(input);
+
+        assert_eq!(recovery.diagnostics.len(), 1);
+        assert_eq!(recovery.diagnostics[0].message, "literal `256u8` does not fit in u8");
+        assert_eq!((recovery.diagnostics[0].span.line, recovery.diagnostics[0].span.col), (2, 5));
+        let genome = &recovery.tree.sc.fly.strand.genome;
+        assert_eq!(genome.len(), 3);
+        assert!(genome[1].is_error());
+    }
+
+    #[test]
+    fn test_parse_time_grows_linearly() {
+        // Spans e recuperação já foram quadráticos: 8000 linhas levavam 18 s.
+        // Dobrar a entrada tem que dobrar o tempo, não quadruplicar; o melhor
+        // de três rodadas tira o ruído de uma máquina ocupada.
+        let time = |lines: usize| {
+            let statements = "x = 1\n".repeat(lines);
+            let broken = format!("{statements}y = = 2\n{statements}");
+            let list = format!("x = [{}]\n", vec!["1"; lines].join(", "));
+            (0..3)
+                .map(|_| {
+                    let started = std::time::Instant::now();
+                    assert_eq!(Tree::parse_input(statements.clone()).expect("Should parse").sc.fly.strand.genome.len(), lines);
+                    assert!(Tree::parse_input(list.clone()).is_ok());
+                    let recovery = Tree::parse_recovering(broken.clone());
+                    let elapsed = started.elapsed();
+                    assert_eq!(recovery.diagnostics.len(), 1);
+                    assert_eq!(recovery.diagnostics[0].span.line, lines + 1);
+                    assert_eq!(recovery.tree.sc.fly.strand.genome[lines + 1].span().line, lines + 2);
+                    elapsed
+                })
+                .min()
+                .expect("Should time three runs")
+        };
+
+        let (single, double) = (time(2_000), time(4_000));
+
+        assert!(double < single * 7 / 2, "2000 lines took {single:?}, 4000 took {double:?}");
+    }
+}
@@ -0,0 +1,830 @@
+//! Impressão canônica da AST de volta para código SC.
+//!
+//! O texto gerado é reconhecido pela gramática e, ao ser lido de novo, monta
+//! a mesma árvore (a menos de spans e do texto `raw` dos blocos, ver
+//! [`same_ast`]). Cada nó também implementa `Display` com esta forma.
+//!
+//! Forma canônica: dois espaços por nível, um membro por linha, linha em
+//! branco antes de cada `ethics` com corpo e em volta dos `bug`s do nível
+//! superior, espaço depois de `,` e `:` e em volta de `=` e dos operadores.
+//! Comentários não fazem parte da AST: só `print_with_comments` os imprime.
+
+use std::fmt;
+use crate::dsl::ast::anatomy::Anatomy;
+use crate::dsl::ast::behavior::assign::Assign;
+use crate::dsl::ast::behavior::oop::Oop;
+use crate::dsl::ast::behavior::sequence::Sequence;
+use crate::dsl::ast::behavior::transport::Transport;
+use crate::dsl::ast::behavior::{Behavior, Bind, Binds, Carrier, Catalysis, EthicsBind, Trail};
+use crate::dsl::ast::bug::Bug;
+use crate::dsl::ast::contract::Contract;
+use crate::dsl::ast::comment::{Comment, CommentKind};
+use crate::dsl::ast::condition::Condition;
+use crate::dsl::ast::emitter::{Emitter, List, Literal, Map, SelfRef, Specie, SpecieRef, Tag, Template, TemplatePart};
+use crate::dsl::ast::ethics::{Ethics, Signature};
+use crate::dsl::ast::expr::{BinaryOp, Expr, UnaryOp};
+use crate::dsl::ast::fly::Fly;
+use crate::dsl::ast::gene::Gene;
+use crate::dsl::ast::genome::Genome;
+use crate::dsl::ast::import::{Import, ImportName};
+use crate::dsl::ast::kind::{Kind, Variant};
+use crate::dsl::ast::matrix::Matrix;
+use crate::dsl::ast::nucleus::Nucleus;
+use crate::dsl::ast::sc::SC;
+use crate::dsl::ast::signal::Signal;
+use crate::dsl::ast::span::Span;
+use crate::dsl::ast::error_node::ErrorNode;
+use crate::dsl::ast::statement::{
+    ElsifBlock, ForStatement, IfStatement, MatchStatement, ReturnStatement, Statement, WhenArm, WhileStatement,
+};
+use crate::dsl::ast::visit_mut::{
+    walk_assign, walk_elsif, walk_for, walk_if, walk_match, walk_matrix, walk_nucleus, walk_return, walk_when, walk_while,
+    VisitMut,
+};
+use crate::dsl::ast::strand::Strand;
+use crate::dsl::parser::tree::Tree;
+
+const INDENT: &str = "  ";
+
+/// Programa inteiro em forma canônica, terminado por uma quebra de linha.
+pub fn print_tree(tree: &Tree) -> String {
+    let mut printer = Printer::default();
+    printer.strand(&tree.sc.fly.strand);
+    printer.finish()
+}
+
+/// Como `print_tree`, mas devolvendo os comentários de `tree` (lida com
+/// `ParseOptions::retain_comments`) ao seu lugar e mantendo uma linha em
+/// branco onde `source` tinha uma ou mais entre dois statements.
+pub fn print_with_comments(tree: &Tree, source: &str) -> String {
+    let mut printer = Printer::with_comments(tree, source);
+    printer.strand(&tree.sc.fly.strand);
+    printer.finish()
+}
+
+/// Compara duas árvores ignorando posições (`span`) e o texto original dos
+/// blocos (`raw` de `Assign`, `Matrix`, statements...). Nomes e literais,
+/// que também guardam `raw`, continuam sendo comparados.
+pub fn same_ast<T: Blank>(left: &T, right: &T) -> bool {
+    let (mut left, mut right) = (left.clone(), right.clone());
+    left.blank();
+    right.blank();
+    left == right
+}
+
+/// Raízes que [`same_ast`] compara: a árvore sem spans e sem o `raw` dos blocos.
+pub trait Blank: Clone + PartialEq {
+    fn blank(&mut self);
+}
+
+macro_rules! blank {
+    ($($node:ty => $visit:ident),* $(,)?) => {
+        $(impl Blank for $node {
+            fn blank(&mut self) {
+                Blanker.$visit(self);
+            }
+        })*
+    };
+}
+
+blank!(Tree => visit_tree, SC => visit_sc, Condition => visit_condition, Oop => visit_oop);
+
+/// Zera os spans e esvazia o `raw` dos nós que guardam um bloco inteiro.
+struct Blanker;
+
+impl VisitMut for Blanker {
+    fn visit_span(&mut self, span: &mut Span) {
+        *span = Span::default();
+    }
+
+    fn visit_nucleus(&mut self, node: &mut Nucleus) {
+        node.raw.clear();
+        walk_nucleus(self, node);
+    }
+
+    fn visit_matrix(&mut self, node: &mut Matrix) {
+        node.raw.clear();
+        walk_matrix(self, node);
+    }
+
+    fn visit_assign(&mut self, node: &mut Assign) {
+        node.raw.clear();
+        walk_assign(self, node);
+    }
+
+    fn visit_if(&mut self, node: &mut IfStatement) {
+        node.raw.clear();
+        walk_if(self, node);
+    }
+
+    fn visit_elsif(&mut self, node: &mut ElsifBlock) {
+        node.raw.clear();
+        walk_elsif(self, node);
+    }
+
+    fn visit_while(&mut self, node: &mut WhileStatement) {
+        node.raw.clear();
+        walk_while(self, node);
+    }
+
+    fn visit_for(&mut self, node: &mut ForStatement) {
+        node.raw.clear();
+        walk_for(self, node);
+    }
+
+    fn visit_match(&mut self, node: &mut MatchStatement) {
+        node.raw.clear();
+        walk_match(self, node);
+    }
+
+    fn visit_when(&mut self, node: &mut WhenArm) {
+        node.raw.clear();
+        walk_when(self, node);
+    }
+
+    fn visit_return(&mut self, node: &mut ReturnStatement) {
+        node.raw.clear();
+        walk_return(self, node);
+    }
+
+    fn visit_error_node(&mut self, node: &mut ErrorNode) {
+        node.raw.clear();
+        self.visit_span(&mut node.span);
+    }
+}
+
+/// Acumula o texto e o nível de indentação atual.
+#[derive(Default)]
+struct Printer<'s> {
+    out: String,
+    depth: usize,
+    /// Logo depois da linha que abre um bloco: nada de linha em branco aqui
+    opening: bool,
+    trivia: Option<Trivia<'s>>,
+}
+
+/// Comentários ainda não impressos e o código de onde vieram.
+struct Trivia<'s> {
+    source: &'s str,
+    comments: Vec<Comment>,
+    next: usize,
+    /// Fim do último trecho do código já impresso
+    last_end: usize,
+}
+
+impl Trivia<'_> {
+    /// Primeiro offset depois de `pos` que não é espaço nem comentário
+    fn skip_forward(&self, mut pos: usize) -> usize {
+        loop {
+            let rest = &self.source[pos.min(self.source.len())..];
+            pos += rest.len() - rest.trim_start().len();
+            match self.comments.iter().find(|comment| comment.span.start == pos) {
+                Some(comment) => pos = comment.span.end,
+                None => return pos,
+            }
+        }
+    }
+
+    /// Fim do último código antes de `pos`, pulando espaços e comentários
+    fn skip_back(&self, mut pos: usize) -> usize {
+        loop {
+            pos = self.source[..pos.min(self.source.len())].trim_end().len();
+            match self.comments.iter().find(|comment| comment.span.end == pos) {
+                Some(comment) => pos = comment.span.start,
+                None => return pos,
+            }
+        }
+    }
+
+    fn take_before(&mut self, pos: usize) -> Option<Comment> {
+        let comment = self.comments.get(self.next).filter(|comment| comment.span.start < pos)?.clone();
+        self.next += 1;
+        Some(comment)
+    }
+
+    /// Havia linha em branco entre o último trecho impresso e `pos`?
+    fn blank_before(&self, pos: usize) -> bool {
+        self.source.get(self.last_end..pos).is_some_and(|gap| gap.matches('\n').count() > 1)
+    }
+}
+
+impl<'s> Printer<'s> {
+    /// Impressor que também devolve os comentários de `tree` ao seu lugar.
+    fn with_comments(tree: &Tree, source: &'s str) -> Self {
+        let trivia = Trivia { source, comments: tree.comments.clone(), next: 0, last_end: 0 };
+        Printer { trivia: Some(trivia), ..Printer::default() }
+    }
+
+    fn line(&mut self, text: &str) {
+        for _ in 0..self.depth {
+            self.out.push_str(INDENT);
+        }
+        self.out.push_str(text);
+        self.out.push('\n');
+        self.opening = false;
+    }
+
+    fn blank(&mut self) {
+        if !self.out.is_empty() && !self.out.ends_with("\n\n") && !self.opening {
+            self.out.push('\n');
+        }
+    }
+
+    /// Linha que abre um bloco; o conteúdo vem indentado logo abaixo
+    fn open(&mut self, text: &str, head_end: usize) {
+        self.line(text);
+        self.after(head_end);
+        self.opening = true;
+    }
+
+    /// Comentários antes de `pos`, cada um na sua linha
+    fn comments_before(&mut self, pos: usize, keep_blank: bool) {
+        while let Some(trivia) = self.trivia.as_mut() {
+            let Some(comment) = trivia.take_before(pos) else {
+                return;
+            };
+            let blank = keep_blank && trivia.blank_before(comment.span.start);
+            trivia.last_end = comment.span.end;
+            if blank {
+                self.blank();
+            }
+            self.line(comment.raw.trim_end());
+        }
+    }
+
+    /// Comentários antes do nó e, se `keep_blank`, a linha em branco que havia acima dele
+    fn before(&mut self, span: Span, keep_blank: bool) {
+        let Some(trivia) = &self.trivia else {
+            return;
+        };
+        let start = trivia.skip_forward(span.start);
+        self.comments_before(start, keep_blank);
+        if keep_blank && self.trivia.as_ref().is_some_and(|trivia| trivia.blank_before(start)) {
+            self.blank();
+        }
+    }
+
+    /// Comentários na mesma linha que termina em `end` vão para o fim da linha impressa
+    fn after(&mut self, end: usize) {
+        let Some(trivia) = self.trivia.as_mut() else {
+            return;
+        };
+        let mut inline = Vec::new();
+        while let Some(comment) = trivia.comments.get(trivia.next) {
+            let from = trivia.last_end.max(end);
+            let same_line = comment.span.start < end || !trivia.source[from..comment.span.start].contains('\n');
+            if !same_line {
+                break;
+            }
+            trivia.last_end = trivia.last_end.max(comment.span.end);
+            inline.push(comment.clone());
+            trivia.next += 1;
+        }
+        trivia.last_end = trivia.last_end.max(end);
+
+        let mut own_line = false;
+        for comment in inline {
+            if own_line {
+                self.line(comment.raw.trim_end());
+                continue;
+            }
+            self.out.pop();
+            self.out.push(' ');
+            self.out.push_str(comment.raw.trim_end());
+            self.out.push('\n');
+            // Depois de um `# ...` nada mais cabe na linha
+            own_line = comment.kind == CommentKind::Line;
+        }
+    }
+
+    /// Fim do nó, sem espaços e comentários que a gramática tenha incluído no span
+    fn end_of(&self, span: Span) -> usize {
+        self.trivia.as_ref().map_or(span.end, |trivia| trivia.skip_back(span.end))
+    }
+
+    /// Início da palavra-chave de `len` bytes que termina logo antes de `pos`
+    fn keyword_before(&self, pos: usize, len: usize) -> usize {
+        self.trivia.as_ref().map_or(0, |trivia| trivia.skip_back(pos).saturating_sub(len))
+    }
+
+    /// Fecha o bloco do nó com `end`, com os comentários que vinham antes e depois dele
+    fn close(&mut self, span: Span) {
+        let end = self.end_of(span);
+        self.line("end");
+        self.after(end);
+    }
+
+    fn strand(&mut self, strand: &Strand) {
+        let mut previous_bug = false;
+        for genome in &strand.genome {
+            let is_bug = genome.is_anatomy();
+            if is_bug || previous_bug {
+                self.blank();
+            }
+            self.before(genome.span(), true);
+            self.genome(genome);
+            previous_bug = is_bug;
+        }
+        self.comments_before(usize::MAX, true);
+    }
+
+    fn genome(&mut self, genome: &Genome) {
+        match genome {
+            Genome::Import(import) => {
+                self.line(&import.to_string());
+                self.after(import.span.end);
+            }
+            Genome::Anatomy(Anatomy::Bug(bug)) => self.bug(bug),
+            Genome::Anatomy(Anatomy::Kind(kind)) => self.kind(kind),
+            Genome::Anatomy(Anatomy::Contract(contract)) => self.contract(contract),
+            Genome::Behavior(behavior) => self.behavior(behavior),
+            // Trecho que não passou no parser: volta como foi escrito
+            Genome::Error(error) => {
+                for text in error.raw.lines() {
+                    self.line(text.trim_end());
+                }
+            }
+        }
+    }
+
+    fn bug(&mut self, bug: &Bug) {
+        let mut head = match bug.params.as_slice() {
+            [] => format!("bug {}", bug.specie),
+            params => format!("bug {}<{}>", bug.specie, join(params)),
+        };
+        if !bug.parents.is_empty() {
+            head = format!("{} < {}", head, join(&bug.parents));
+        }
+        let head_end = bug.parents.last().or(bug.params.last()).unwrap_or(&bug.specie).span.end;
+        self.open(&head, head_end);
+        self.depth += 1;
+        for gene in &bug.genes {
+            self.before(gene.span, false);
+            self.line(&gene.to_string());
+            self.after(gene.specie.span.end);
+        }
+        let mut previous_body = !bug.genes.is_empty();
+        for ethics in &bug.ethics {
+            if previous_body || ethics.body.is_some() {
+                self.blank();
+            }
+            self.before(ethics.span, false);
+            self.ethics(ethics);
+            previous_body = ethics.body.is_some();
+        }
+        self.comments_before(self.keyword_before(bug.span.end, "end".len()), false);
+        self.depth -= 1;
+        self.close(bug.span);
+    }
+
+    fn kind(&mut self, kind: &Kind) {
+        self.open(&format!("kind {}", kind.specie), kind.specie.span.end);
+        self.depth += 1;
+        for variant in &kind.variants {
+            self.before(variant.span, false);
+            self.line(&variant.to_string());
+            self.after(variant.span.end);
+        }
+        self.comments_before(self.keyword_before(kind.span.end, "end".len()), false);
+        self.depth -= 1;
+        self.close(kind.span);
+    }
+
+    fn contract(&mut self, contract: &Contract) {
+        self.open(&format!("contract {}", contract.specie), contract.specie.span.end);
+        self.depth += 1;
+        for ethics in &contract.ethics {
+            self.before(ethics.span, false);
+            self.ethics(ethics);
+        }
+        self.comments_before(self.keyword_before(contract.span.end, "end".len()), false);
+        self.depth -= 1;
+        self.close(contract.span);
+    }
+
+    fn ethics(&mut self, ethics: &Ethics) {
+        let head_end = ethics
+            .feedback
+            .as_ref()
+            .map(|feedback| feedback.span.end)
+            .or(ethics.signature.as_ref().map(|signature| signature.span.end))
+            .unwrap_or(ethics.tag.span.end);
+        match &ethics.body {
+            Some(body) => {
+                self.open(&head(ethics), head_end);
+                self.block(body, self.keyword_before(ethics.span.end, "end".len()));
+                self.close(ethics.span);
+            }
+            None => {
+                self.line(&head(ethics));
+                self.after(head_end);
+            }
+        }
+    }
+
+    /// Corpo indentado, com os comentários que vêm antes de `close` (a palavra que fecha o bloco)
+    fn block(&mut self, matrix: &Matrix, close: usize) {
+        self.depth += 1;
+        self.matrix(matrix);
+        self.comments_before(close, true);
+        self.depth -= 1;
+    }
+
+    fn matrix(&mut self, matrix: &Matrix) {
+        for Signal::Behavior(behavior) in &matrix.signals {
+            self.before(behavior.span(), true);
+            self.behavior(behavior);
+        }
+    }
+
+    fn behavior(&mut self, behavior: &Behavior) {
+        match behavior {
+            Behavior::Statement(statement) => self.statement(statement),
+            Behavior::Assign(assign) => {
+                self.line(&assign.to_string());
+                self.after(self.end_of(assign.span));
+            }
+            Behavior::Oop(oop) => {
+                self.line(&oop.to_string());
+                self.after(self.end_of(oop.span));
+            }
+        }
+    }
+
+    fn statement(&mut self, statement: &Statement) {
+        match statement {
+            Statement::If(if_stmt) => {
+                self.open(&format!("if {}", if_stmt.condition), if_stmt.condition.span().end);
+                let mut next = if_stmt.elsif_blocks.iter();
+                let close = match (if_stmt.elsif_blocks.first(), &if_stmt.else_block) {
+                    (Some(elsif), _) => elsif.span.start,
+                    (None, Some(block)) => self.keyword_before(block.span.start, "else".len()),
+                    (None, None) => self.keyword_before(if_stmt.span.end, "end".len()),
+                };
+                self.block(&if_stmt.then_block, close);
+                while let Some(elsif) = next.next() {
+                    self.open(&format!("elsif {}", elsif.condition), elsif.condition.span().end);
+                    let close = match (next.as_slice().first(), &if_stmt.else_block) {
+                        (Some(following), _) => following.span.start,
+                        (None, Some(block)) => self.keyword_before(block.span.start, "else".len()),
+                        (None, None) => self.keyword_before(if_stmt.span.end, "end".len()),
+                    };
+                    self.block(&elsif.block, close);
+                }
+                if let Some(block) = &if_stmt.else_block {
+                    let keyword = self.keyword_before(block.span.start, "else".len());
+                    self.open("else", keyword + "else".len());
+                    self.block(block, self.keyword_before(if_stmt.span.end, "end".len()));
+                }
+                self.close(if_stmt.span);
+            }
+            Statement::While(while_stmt) => {
+                self.open(&format!("while {}", while_stmt.condition), while_stmt.condition.span().end);
+                self.block(&while_stmt.block, self.keyword_before(while_stmt.span.end, "end".len()));
+                self.close(while_stmt.span);
+            }
+            Statement::For(for_stmt) => {
+                self.open(&format!("for {} in {}", for_stmt.variable, for_stmt.iterable), for_stmt.iterable.span.end);
+                self.block(&for_stmt.block, self.keyword_before(for_stmt.span.end, "end".len()));
+                self.close(for_stmt.span);
+            }
+            Statement::Match(match_stmt) => {
+                self.open(&format!("match {}", match_stmt.subject), match_stmt.subject.span.end);
+                let mut next = match_stmt.arms.iter();
+                while let Some(arm) = next.next() {
+                    let last = arm.variants.last().map_or(arm.span.start, |variant| variant.span.end);
+                    self.open(&format!("when {}", join(&arm.variants)), last);
+                    let close = match (next.as_slice().first(), &match_stmt.else_block) {
+                        (Some(following), _) => following.span.start,
+                        (None, Some(block)) => self.keyword_before(block.span.start, "else".len()),
+                        (None, None) => self.keyword_before(match_stmt.span.end, "end".len()),
+                    };
+                    self.block(&arm.block, close);
+                }
+                if let Some(block) = &match_stmt.else_block {
+                    let keyword = self.keyword_before(block.span.start, "else".len());
+                    self.open("else", keyword + "else".len());
+                    self.block(block, self.keyword_before(match_stmt.span.end, "end".len()));
+                }
+                self.close(match_stmt.span);
+            }
+            Statement::Return(return_stmt) => {
+                self.line(&format!("return {}", return_stmt.value));
+                self.after(return_stmt.value.span.end);
+            }
+        }
+    }
+
+    fn finish(self) -> String {
+        self.out
+    }
+}
+
+/// Cabeça do ethics: `ethics nome(a: Int) Feedback`
+pub(crate) fn head(ethics: &Ethics) -> String {
+    let mut text = format!("ethics {}", ethics.tag);
+    if let Some(signature) = &ethics.signature {
+        text.push_str(&signature.to_string());
+    }
+    if let Some(feedback) = &ethics.feedback {
+        text.push(' ');
+        text.push_str(&feedback.to_string());
+    }
+    text
+}
+
+fn join<T: fmt::Display>(items: &[T]) -> String {
+    items.iter().map(|item| item.to_string()).collect::<Vec<_>>().join(", ")
+}
+
+/// Precisa de parênteses como filho de um operador binário com precedência `parent`?
+/// `right` indica o lado direito, onde a associação à esquerda exige parênteses no empate.
+fn needs_group(child: &Expr, parent: u8, right: bool) -> bool {
+    match child {
+        Expr::Oop(_) => false,
+        Expr::Binary { op, .. } => op.precedence() < parent || (right && op.precedence() == parent),
+        // `not a == b` é `not (a == b)`: um `not` dentro de comparação ou conta vai entre parênteses
+        Expr::Unary { op: UnaryOp::Not, .. } => parent > BinaryOp::And.precedence(),
+        Expr::Unary { op: UnaryOp::Neg, .. } => false,
+    }
+}
+
+fn grouped(expr: &Expr, group: bool) -> String {
+    if group { format!("({})", expr) } else { expr.to_string() }
+}
+
+/// Nós de uma linha só: `Display` escreve o texto direto.
+macro_rules! display_inline {
+    ($($node:ty => |$this:ident, $f:ident| $body:expr;)*) => {
+        $(impl fmt::Display for $node {
+            fn fmt(&self, $f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                let $this = self;
+                $body
+            }
+        })*
+    };
+}
+
+display_inline! {
+    Tag => |tag, f| f.write_str(&tag.raw);
+    Specie => |specie, f| f.write_str(&specie.raw);
+    SpecieRef => |specie_ref, f| match specie_ref.args.as_slice() {
+        [] => specie_ref.name.fmt(f),
+        args => write!(f, "{}<{}>", specie_ref.name, join(args)),
+    };
+    SelfRef => |self_ref, f| f.write_str(&self_ref.raw);
+    Literal => |literal, f| f.write_str(literal.get_raw());
+    Emitter => |emitter, f| match emitter {
+        Emitter::Specie(specie) => specie.fmt(f),
+        Emitter::Tag(tag) => tag.fmt(f),
+        Emitter::Literal(literal) => literal.fmt(f),
+        Emitter::SelfRef(self_ref) => self_ref.fmt(f),
+        Emitter::Template(template) => template.fmt(f),
+        Emitter::List(list) => list.fmt(f),
+        Emitter::Map(map) => map.fmt(f),
+    };
+    Template => |template, f| {
+        f.write_str("\"")?;
+        for part in &template.parts {
+            match part {
+                TemplatePart::Text { raw, .. } => f.write_str(raw)?,
+                TemplatePart::Oop(oop) => write!(f, "#{{{}}}", oop)?,
+            }
+        }
+        f.write_str("\"")
+    };
+    List => |list, f| write!(f, "[{}]", join(&list.items));
+    Map => |map, f| write!(f, "{{{}}}", join(&map.entries));
+    Oop => |oop, f| {
+        write!(f, "{}", oop.emitter)?;
+        oop.trails.iter().try_for_each(|trail| write!(f, "{}", trail))
+    };
+    Trail => |trail, f| match trail {
+        Trail::Catalysis(catalysis) => catalysis.fmt(f),
+        Trail::Carrier(carrier) => carrier.fmt(f),
+    };
+    Catalysis => |catalysis, f| {
+        write!(f, ".{}", catalysis.tag)?;
+        match &catalysis.carrier {
+            Some(carrier) => carrier.fmt(f),
+            None => Ok(()),
+        }
+    };
+    Carrier => |carrier, f| match &carrier.transport {
+        Some(transport) => write!(f, "({})", transport),
+        None => f.write_str("()"),
+    };
+    Transport => |transport, f| match transport {
+        Transport::Binds(binds) => binds.fmt(f),
+        Transport::Sequence(sequence) => sequence.fmt(f),
+    };
+    Binds => |binds, f| f.write_str(&join(&binds.binds));
+    Bind => |bind, f| write!(f, "{}: {}", bind.tag, bind.oop);
+    Sequence => |sequence, f| f.write_str(&join(&sequence.oops));
+    EthicsBind => |bind, f| write!(f, "{}: {}", bind.tag, bind.specie);
+    Signature => |signature, f| match &signature.binds {
+        Some(binds) => write!(f, "({})", join(binds)),
+        None => f.write_str("()"),
+    };
+    Gene => |gene, f| write!(f, "gene {} {}", gene.tag, gene.specie);
+    Variant => |variant, f| {
+        variant.specie.fmt(f)?;
+        match variant.genes.as_slice() {
+            [] => Ok(()),
+            genes => {
+                let genes: Vec<String> = genes.iter().map(|gene| format!("{}: {}", gene.tag, gene.specie)).collect();
+                write!(f, "({})", genes.join(", "))
+            }
+        }
+    };
+    Import => |import, f| match import.names.as_slice() {
+        [] => write!(f, "use \"{}\"", import.path),
+        names => write!(f, "use {} from \"{}\"", join(names), import.path),
+    };
+    ImportName => |name, f| match &name.alias {
+        Some(alias) => write!(f, "{} as {}", name.specie, alias),
+        None => name.specie.fmt(f),
+    };
+    Assign => |assign, f| write!(f, "{} = {}", assign.tag, assign.oop);
+    Condition => |condition, f| match condition {
+        Condition::Oop(oop) => oop.fmt(f),
+        Condition::Expr(expr) => expr.fmt(f),
+    };
+    Expr => |expr, f| match expr {
+        Expr::Oop(oop) => oop.fmt(f),
+        Expr::Binary { op, left, right, .. } => {
+            let left = grouped(left, needs_group(left, op.precedence(), false));
+            let right = grouped(right, needs_group(right, op.precedence(), true));
+            write!(f, "{} {} {}", left, op.symbol(), right)
+        }
+        Expr::Unary { op: UnaryOp::Not, operand, .. } => {
+            let group = matches!(**operand, Expr::Binary { op, .. } if op.precedence() <= BinaryOp::And.precedence());
+            write!(f, "not {}", grouped(operand, group))
+        }
+        Expr::Unary { op: UnaryOp::Neg, operand, .. } => {
+            let operand = grouped(operand, !operand.is_oop());
+            // `-1` seria lido como literal negativo
+            let space = if operand.starts_with(|c: char| c.is_ascii_digit()) { " " } else { "" };
+            write!(f, "-{}{}", space, operand)
+        }
+    };
+}
+
+/// Nós de várias linhas: `Display` passa pelo `Printer`, a partir do nível zero.
+macro_rules! display_block {
+    ($($node:ty => $method:ident;)*) => {
+        $(impl fmt::Display for $node {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                let mut printer = Printer::default();
+                printer.$method(self);
+                f.write_str(printer.finish().trim_end_matches('\n'))
+            }
+        })*
+    };
+}
+
+display_block! {
+    Genome => genome;
+    Bug => bug;
+    Kind => kind;
+    Contract => contract;
+    Ethics => ethics;
+    Matrix => matrix;
+    Behavior => behavior;
+    Statement => statement;
+    Strand => strand;
+}
+
+impl fmt::Display for Anatomy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Anatomy::Bug(bug) => bug.fmt(f),
+            Anatomy::Kind(kind) => kind.fmt(f),
+            Anatomy::Contract(contract) => contract.fmt(f),
+        }
+    }
+}
+
+impl fmt::Display for Nucleus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut printer = Printer::default();
+        printer.line("do");
+        printer.block(&self.matrix, 0);
+        printer.line("end");
+        f.write_str(printer.finish().trim_end_matches('\n'))
+    }
+}
+
+impl fmt::Display for Fly {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.strand.fmt(f)
+    }
+}
+
+impl fmt::Display for SC {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fly.fmt(f)
+    }
+}
+
+impl fmt::Display for Tree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&print_tree(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use super::{print_tree, same_ast};
+    use crate::dsl::ast::behavior::oop::Oop;
+    use crate::dsl::ast::condition::Condition;
+    use crate::dsl::parser::tree::Tree;
+    use crate::tests::load_fragment;
+
+    fn fixtures(dir: &Path, found: &mut Vec<String>) {
+        for entry in std::fs::read_dir(dir).expect("Fixture dir should be readable") {
+            let path = entry.expect("Entry should be readable").path();
+            if path.is_dir() {
+                fixtures(&path, found);
+            } else if path.extension().is_some_and(|ext| ext == "sc") {
+                found.push(std::fs::read_to_string(&path).expect("Fixture should be readable"));
+            }
+        }
+    }
+
+    fn round_trip(source: &str) -> String {
+        let tree = Tree::parse_input(source.to_string()).expect("Source should parse");
+        let printed = print_tree(&tree);
+        let reparsed = Tree::parse_input(printed.clone())
+            .unwrap_or_else(|error| panic!("Printed source should parse: {}\n{}", error, printed));
+        assert!(same_ast(&tree, &reparsed), "AST changed after printing:\n{}", printed);
+        assert_eq!(print_tree(&reparsed), printed, "Printing is not stable");
+        printed
+    }
+
+    #[test]
+    fn test_every_fixture_program_round_trips() {
+        let mut sources = Vec::new();
+        fixtures(&Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/fragments"), &mut sources);
+
+        let programs: Vec<&String> =
+            sources.iter().filter(|source| Tree::parse_input(source.to_string()).is_ok()).collect();
+
+        assert!(programs.len() > 30, "only {} fixtures parsed", programs.len());
+        for source in programs {
+            round_trip(source);
+        }
+    }
+
+    #[test]
+    fn test_canonical_layout() {
+        let source = load_fragment("printer/messy.sc");
+
+        let printed = round_trip(&source);
+
+        assert_eq!(printed, load_fragment("printer/canonical.sc"));
+    }
+
+    #[test]
+    fn test_operators_keep_their_grouping() {
+        for source in ["(a + b) * c", "a - (b - c)", "a - b - c", "not (a or b)", "(not a) == b", "-(a + b)", "- 5", "a * -b"] {
+            let condition = Condition::from_string(source.to_string()).expect("Condition should parse");
+
+            let printed = condition.to_string();
+
+            assert_eq!(printed, source);
+            let reparsed = Condition::from_string(printed).expect("Printed condition should parse");
+            assert!(same_ast(&condition, &reparsed), "{}", source);
+        }
+    }
+
+    #[test]
+    fn test_redundant_parens_are_dropped() {
+        let condition = Condition::from_string("((a * b)) + (c)".to_string()).expect("Condition should parse");
+
+        assert_eq!(condition.to_string(), "a * b + c");
+    }
+
+    #[test]
+    fn test_big_decimal_round_trips() {
+        assert_eq!(round_trip("x = 12345678901234567890123.5\n"), "x = 12345678901234567890123.5\n");
+    }
+
+    #[test]
+    fn test_same_ast_compares_big_decimals() {
+        let left = Tree::parse_input("x = 12345678901234567890123.5\n".to_string()).expect("Source should parse");
+        let right = Tree::parse_input("x = 12345678901234567890124.5\n".to_string()).expect("Source should parse");
+
+        assert!(!same_ast(&left, &right));
+    }
+
+    #[test]
+    fn test_same_ast_still_compares_names() {
+        let left = Oop::from_string("rex.latir(volume: 1)".to_string()).expect("Oop should parse");
+        let right = Oop::from_string("rex.latir(altura: 1)".to_string()).expect("Oop should parse");
+
+        assert!(!same_ast(&left, &right));
+        assert_eq!(left.to_string(), "rex.latir(volume: 1)");
+    }
+}
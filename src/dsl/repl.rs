@@ -0,0 +1,230 @@
+//! Sessão interativa (`sc repl`): cada entrada é um behavior (atribuição,
+//! chamada, `if`/`while`/`for`/`match`) ou um `bug`/`kind ... end`, executado no mesmo
+//! [`Interpreter`], de modo que variáveis e espécies continuam valendo nas
+//! entradas seguintes.
+//!
+//! Uma entrada com `bug`, `kind`, `ethics`, `if`, `while`, `for`, `match`,
+//! parênteses ou colchetes ainda abertos não é executada: [`Repl::feed`]
+//! responde [`Reply::More`] e junta as próximas linhas até os blocos fecharem.
+//!
+//! ```
+//! use sc_dsl::dsl::interp::{Builtins, Value};
+//! use sc_dsl::dsl::repl::{Reply, Repl};
+//!
+//! let mut repl = Repl::with_host(Builtins::silent());
+//! assert!(matches!(repl.feed("bug Dog"), Reply::More));
+//! assert!(matches!(repl.feed("  ethics latir String\n    \"au\"\n  end"), Reply::More));
+//! assert!(matches!(repl.feed("end"), Reply::Defined(name) if name == "Dog"));
+//! assert!(matches!(repl.feed("Dog.new.latir"), Reply::Value(Value::Str(text)) if text == "au"));
+//! ```
+
+use std::error::Error;
+use pest::error::Error as PestError;
+use crate::dsl::ast::behavior::Behavior;
+use crate::dsl::ast::anatomy::Anatomy;
+use crate::dsl::ast::error::AstBuildError;
+use crate::dsl::interp::{Builtins, Host, Interpreter, Value};
+use crate::dsl::parser::diagnostic::Diagnostic;
+use crate::dsl::parser::parser::Rule;
+use crate::dsl::parser::scan;
+
+pub const PROMPT: &str = "sc> ";
+/// Prompt enquanto a entrada está incompleta
+pub const CONTINUE: &str = "... ";
+
+/// Resposta a uma linha
+#[derive(Debug)]
+pub enum Reply {
+    /// Nada para executar (linha em branco ou só comentário)
+    Empty,
+    /// Ainda há blocos abertos; a próxima linha continua a entrada
+    More,
+    /// Um bug ou kind foi (re)definido
+    Defined(String),
+    Value(Value),
+    /// Erro de sintaxe ou de execução, com a entrada a que o span se refere
+    Error { diagnostic: Diagnostic, source: String },
+}
+
+pub struct Repl<H: Host = Builtins> {
+    pub interpreter: Interpreter<H>,
+    /// Linhas de uma entrada incompleta
+    pending: String,
+}
+
+impl Repl {
+    pub fn new() -> Self {
+        Repl::with_host(Builtins::new())
+    }
+}
+
+impl Default for Repl {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<H: Host> Repl<H> {
+    pub fn with_host(host: H) -> Self {
+        Repl { interpreter: Interpreter::with_host(host), pending: String::new() }
+    }
+
+    pub fn prompt(&self) -> &'static str {
+        if self.pending.is_empty() { PROMPT } else { CONTINUE }
+    }
+
+    /// Descarta uma entrada incompleta (Ctrl-C no meio de um bloco)
+    pub fn cancel(&mut self) {
+        self.pending.clear();
+    }
+
+    pub fn feed(&mut self, line: &str) -> Reply {
+        if !self.pending.is_empty() {
+            self.pending.push('\n');
+        }
+        self.pending.push_str(line.trim_end_matches(['\n', '\r']));
+
+        let tokens = scan::tokenize(&self.pending);
+        if tokens.is_empty() {
+            self.pending.clear();
+            return Reply::Empty;
+        }
+        let parens = tokens.iter().fold(0i32, |depth, token| match token.text {
+            "(" | "[" | "{" => depth + 1,
+            ")" | "]" | "}" => depth - 1,
+            _ => depth,
+        });
+        // Uma string aberta só pode ser o último token: ela vai até o fim da entrada
+        let open_string = tokens.last().is_some_and(scan::is_open_string);
+        if parens > 0 || open_string || !scan::open_blocks(&self.pending, self.pending.len()).is_empty() {
+            return Reply::More;
+        }
+
+        let anatomy = tokens[0].is_word("bug") || tokens[0].is_word("kind") || tokens[0].is_word("contract");
+        let source = std::mem::take(&mut self.pending);
+        let reply = if anatomy { self.define(&source) } else { self.eval(&source) };
+        reply.unwrap_or_else(|diagnostic| Reply::Error { diagnostic, source })
+    }
+
+    fn define(&mut self, source: &str) -> Result<Reply, Diagnostic> {
+        let anatomy = Anatomy::from_string(source.to_string()).map_err(|error| diagnostic(error.as_ref(), source))?;
+        rest(source, anatomy.span().end)?;
+        match &anatomy {
+            Anatomy::Bug(bug) => self.interpreter.define(bug),
+            Anatomy::Kind(kind) => self.interpreter.define_kind(kind),
+            // Contratos não mudam a execução, só a verificação de tipos
+            Anatomy::Contract(_) => {}
+        }
+        Ok(Reply::Defined(anatomy.specie().raw.clone()))
+    }
+
+    fn eval(&mut self, source: &str) -> Result<Reply, Diagnostic> {
+        let behavior = Behavior::from_string(source.to_string()).map_err(|error| diagnostic(error.as_ref(), source))?;
+        rest(source, behavior.span().end)?;
+        let value = self.interpreter.eval(&behavior).map_err(|error| error.diagnostic())?;
+        Ok(Reply::Value(value))
+    }
+}
+
+/// `from_string` aceita um prefixo da entrada; o que sobrar é erro.
+fn rest(source: &str, end: usize) -> Result<(), Diagnostic> {
+    match scan::tokenize(&source[end..]).first() {
+        Some(token) => {
+            let span = scan::span_at(source, end + token.start, end + token.end);
+            Err(Diagnostic::error(format!("unexpected `{}`", token.text), span)
+                .with_note("the REPL runs one statement, `bug`, `kind` or `contract` at a time"))
+        }
+        None => Ok(()),
+    }
+}
+
+fn diagnostic(error: &(dyn Error + 'static), source: &str) -> Diagnostic {
+    if let Some(error) = error.downcast_ref::<PestError<Rule>>() {
+        Diagnostic::from_pest(error, source)
+    } else if let Some(error) = error.downcast_ref::<AstBuildError>() {
+        Diagnostic::from_ast_build(error)
+    } else {
+        Diagnostic::error(error.to_string(), scan::span_at(source, 0, 0))
+    }
+}
+
+/// Valor como o REPL mostra: strings entre aspas, para não confundir com nomes
+pub fn show(value: &Value) -> String {
+    match value {
+        Value::Str(text) => format!("{:?}", text),
+        value => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Reply, Repl};
+    use crate::dsl::interp::{Builtins, Value};
+    use crate::tests::load_fragment;
+
+    fn repl() -> Repl<Builtins> {
+        Repl::with_host(Builtins::silent())
+    }
+
+    #[test]
+    fn test_state_survives_between_lines() {
+        let mut repl = repl();
+        assert!(matches!(repl.feed("x = 40"), Reply::Value(Value::Int(40))));
+        assert!(matches!(repl.feed("x.plus(2)"), Reply::Value(Value::Int(42))));
+        assert!(matches!(repl.feed("   # só comentário"), Reply::Empty));
+        assert!(matches!(repl.feed("print(x)"), Reply::Value(Value::Nil)));
+        assert_eq!(repl.interpreter.host.printed, vec!["40"]);
+    }
+
+    #[test]
+    fn test_incomplete_input_keeps_prompting() {
+        let mut repl = repl();
+        let mut replies = Vec::new();
+        for line in load_fragment("interp/dog.sc").lines().take(19) {
+            replies.push(repl.feed(line));
+            assert_eq!(repl.prompt(), if matches!(replies.last(), Some(Reply::More)) { "... " } else { "sc> " });
+        }
+        assert!(matches!(replies.last(), Some(Reply::Defined(name)) if name == "Dog"), "{:?}", replies.last());
+
+        assert!(matches!(repl.feed("for i in 3"), Reply::More));
+        assert!(matches!(repl.feed("  print(Dog.new(nome: \"Rex\").latir(i),"), Reply::More));
+        assert!(matches!(repl.feed("    i)"), Reply::More));
+        assert!(matches!(repl.feed("end"), Reply::Value(_)));
+        assert_eq!(repl.interpreter.host.printed, vec!["au au 0", "au au 1", "au au 2"]);
+        assert!(matches!(repl.feed("x = 1.plus("), Reply::More));
+        assert!(matches!(repl.feed("  2)"), Reply::Value(Value::Int(3))));
+
+        let mut replies = Vec::new();
+        for line in load_fragment("kind/forma.sc").lines() {
+            replies.push(repl.feed(line));
+        }
+        assert!(matches!(replies.last(), Some(Reply::Defined(name)) if name == "Forma"), "{:?}", replies.last());
+        assert!(matches!(repl.feed("match Ponto"), Reply::More));
+        assert!(matches!(repl.feed("when Ponto"), Reply::More));
+        assert!(matches!(repl.feed("  1"), Reply::More));
+        assert!(matches!(repl.feed("end"), Reply::Value(Value::Int(1))));
+    }
+
+    #[test]
+    fn test_open_string_keeps_prompting() {
+        let mut repl = repl();
+        assert!(matches!(repl.feed("x = \"um"), Reply::More));
+        assert!(matches!(repl.feed("dois\""), Reply::Value(Value::Str(text)) if text == "um\ndois"));
+        assert!(matches!(repl.feed("\"soma: #{1.plus(1)"), Reply::More));
+        assert!(matches!(repl.feed("}\""), Reply::Value(Value::Str(text)) if text == "soma: 2"));
+    }
+
+    #[test]
+    fn test_errors_leave_the_session_usable() {
+        let mut repl = repl();
+        let Reply::Error { diagnostic, source } = repl.feed("x = 1 2") else {
+            panic!("Trailing input should be rejected");
+        };
+        assert_eq!(diagnostic.message, "unexpected `2`");
+        assert_eq!(source, "x = 1 2");
+
+        assert!(matches!(repl.feed("y.plus(1)"), Reply::Error { .. }));
+        assert!(matches!(repl.feed("end"), Reply::Error { .. }));
+        assert!(matches!(repl.feed("1.plus(1)"), Reply::Value(Value::Int(2))));
+    }
+}
@@ -0,0 +1,56 @@
+use thiserror::Error;
+use crate::dsl::ast::span::Span;
+use crate::dsl::parser::diagnostic::{Diagnostic, Severity};
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ResolveError {
+    #[error("cannot find `{name}` in this scope")]
+    UndefinedName { name: String, span: Span },
+    #[error("cannot find species `{name}`")]
+    UnknownSpecie { name: String, span: Span },
+    #[error("`{name}` shadows an earlier definition")]
+    Shadowing { name: String, span: Span, previous: Span },
+    #[error("`{name}` is defined more than once")]
+    Duplicate { name: String, span: Span, previous: Span },
+    #[error("cannot inherit from `{name}`: only bugs and contracts can follow `<`")]
+    InvalidParent { name: String, span: Span },
+    #[error("`{name}` is a second parent bug; a bug inherits from only one bug")]
+    MultipleParents { name: String, span: Span },
+    #[error("`{name}` inherits from itself")]
+    InheritanceCycle { name: String, span: Span },
+}
+
+impl ResolveError {
+    pub fn span(&self) -> Span {
+        match self {
+            ResolveError::UndefinedName { span, .. }
+            | ResolveError::UnknownSpecie { span, .. }
+            | ResolveError::Shadowing { span, .. }
+            | ResolveError::Duplicate { span, .. }
+            | ResolveError::InvalidParent { span, .. }
+            | ResolveError::MultipleParents { span, .. }
+            | ResolveError::InheritanceCycle { span, .. } => *span,
+        }
+    }
+
+    /// Sombreamento é só um aviso; o resto impede a execução correta
+    pub fn severity(&self) -> Severity {
+        match self {
+            ResolveError::Shadowing { .. } => Severity::Warning,
+            _ => Severity::Error,
+        }
+    }
+
+    pub fn diagnostic(&self) -> Diagnostic {
+        let diagnostic = match self.severity() {
+            Severity::Error => Diagnostic::error(self.to_string(), self.span()),
+            Severity::Warning => Diagnostic::warning(self.to_string(), self.span()),
+        };
+        match self {
+            ResolveError::Shadowing { previous, .. } | ResolveError::Duplicate { previous, .. } if *previous != Span::default() => {
+                diagnostic.with_note(format!("previous definition on line {}", previous.line))
+            }
+            _ => diagnostic,
+        }
+    }
+}
@@ -0,0 +1,753 @@
+//! Resolução de nomes: liga cada uso de tag ou espécie à sua definição.
+//!
+//! Os escopos seguem a execução do interpretador: o nível superior guarda as
+//! variáveis globais; cada `bug` tem um escopo com genes e ethics, e cada
+//! ethics um escopo com parâmetros e variáveis locais (sem acesso às globais);
+//! `for` abre um escopo só para a sua variável. Espécies vivem em um espaço
+//! de nomes próprio, junto com os `kind`s e suas variantes (cada variante
+//! tem um escopo com os seus genes). `Catalysis.tag` só é ligada quando a espécie do receptor
+//! é conhecida (`$`, genes, parâmetros, `Dog.new`...).
+//!
+//! Em `bug Cat < Animal, Falante`, o escopo de `Cat` fica dentro do de
+//! `Animal`, então genes e ethics herdados são achados pela busca normal;
+//! contratos têm um escopo com os seus ethics e só valem como espécie.
+
+pub mod error;
+pub mod symbol;
+
+use std::collections::{HashMap, HashSet};
+use crate::dsl::ast::anatomy::Anatomy;
+use crate::dsl::ast::behavior::oop::Oop;
+use crate::dsl::ast::behavior::transport::Transport;
+use crate::dsl::ast::behavior::{Behavior, Carrier, Trail};
+use crate::dsl::ast::bug::Bug;
+use crate::dsl::ast::condition::Condition;
+use crate::dsl::ast::contract::Contract;
+use crate::dsl::ast::emitter::{Emitter, Literal, Specie, SpecieRef, Tag};
+use crate::dsl::ast::expr::Expr;
+use crate::dsl::ast::fly::Fly;
+use crate::dsl::ast::genome::Genome;
+use crate::dsl::ast::kind::Kind;
+use crate::dsl::ast::matrix::Matrix;
+use crate::dsl::ast::signal::Signal;
+use crate::dsl::ast::span::Span;
+use crate::dsl::ast::statement::Statement;
+use crate::dsl::parser::tree::Tree;
+pub use error::ResolveError;
+pub use symbol::{Reference, Scope, ScopeId, ScopeKind, Symbol, SymbolId, SymbolKind};
+
+/// Espécies e funções que existem sem declaração
+pub const BUILTIN_SPECIES: &[&str] = &["Int", "Decimal", "String", "Bool", "Nil", "List", "Map", "Void"];
+pub const BUILTIN_FUNCTIONS: &[&str] = &["print", "list"];
+
+const PRELUDE: ScopeId = 0;
+const GLOBAL: ScopeId = 1;
+
+/// Tabelas de símbolos de um programa e os usos de cada símbolo.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Resolution {
+    pub scopes: Vec<Scope>,
+    pub symbols: Vec<Symbol>,
+    pub references: Vec<Reference>,
+    pub errors: Vec<ResolveError>,
+}
+
+impl Resolution {
+    pub fn symbol(&self, id: SymbolId) -> &Symbol {
+        &self.symbols[id]
+    }
+
+    /// Definição do nome usado exatamente em `span`
+    pub fn definition_of(&self, span: Span) -> Option<&Symbol> {
+        self.references.iter().find(|reference| reference.span == span).map(|reference| self.symbol(reference.symbol))
+    }
+
+    /// Símbolo usado ou definido na posição `offset` do código
+    pub fn symbol_at(&self, offset: usize) -> Option<&Symbol> {
+        self.references
+            .iter()
+            .find(|reference| reference.span.contains(offset))
+            .map(|reference| self.symbol(reference.symbol))
+            .or_else(|| self.symbols.iter().find(|symbol| symbol.span.contains(offset)))
+    }
+
+    pub fn references_to(&self, id: SymbolId) -> Vec<Span> {
+        self.references.iter().filter(|reference| reference.symbol == id).map(|reference| reference.span).collect()
+    }
+
+    /// Procura `name` a partir de `scope`, subindo pelos escopos pais
+    pub fn lookup(&self, scope: ScopeId, name: &str) -> Option<&Symbol> {
+        let mut current = Some(scope);
+        while let Some(id) = current {
+            let scope = &self.scopes[id];
+            if let Some(symbol) = scope.symbols.iter().rev().map(|id| self.symbol(*id)).find(|s| s.name == name) {
+                return Some(symbol);
+            }
+            current = scope.parent;
+        }
+        None
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.errors.iter().any(|error| !matches!(error, ResolveError::Shadowing { .. }))
+    }
+}
+
+pub fn resolve_tree(tree: &Tree) -> Resolution {
+    resolve(&tree.sc.fly)
+}
+
+pub fn resolve(fly: &Fly) -> Resolution {
+    let mut resolver = Resolver::default();
+    resolver.run(fly);
+    resolver.resolution
+}
+
+#[derive(Default)]
+struct Resolver {
+    resolution: Resolution,
+    species: HashMap<String, SymbolId>,
+    bug_scopes: HashMap<String, ScopeId>,
+    /// Espécies depois de `<` em cada bug
+    parents: HashMap<String, Vec<Specie>>,
+    contracts: HashSet<String>,
+}
+
+/// Onde o código sendo resolvido está: escopo atual, escopo que recebe
+/// variáveis novas (global ou do ethics) e o bug dono de `$`.
+#[derive(Clone)]
+struct Context {
+    scope: ScopeId,
+    function: ScopeId,
+    this: Option<String>,
+}
+
+impl Resolver {
+    fn run(&mut self, fly: &Fly) {
+        let span = fly.span;
+        self.open_scope(ScopeKind::Prelude, None, Span::default());
+        self.open_scope(ScopeKind::Global, Some(PRELUDE), span);
+        for name in BUILTIN_SPECIES {
+            let id = self.define(PRELUDE, name, SymbolKind::Specie, Span::default(), Some(name.to_string()));
+            self.species.insert(name.to_string(), id);
+        }
+        for name in BUILTIN_FUNCTIONS {
+            self.define(PRELUDE, name, SymbolKind::Builtin, Span::default(), None);
+        }
+
+        // Todos os bugs, kinds e contratos antes de qualquer corpo, para permitir referências adiante
+        for genome in &fly.strand.genome {
+            match genome {
+                Genome::Anatomy(Anatomy::Bug(bug)) => self.declare_bug(bug),
+                Genome::Anatomy(Anatomy::Kind(kind)) => self.declare_kind(kind),
+                Genome::Anatomy(Anatomy::Contract(contract)) => self.declare_contract(contract),
+                _ => {}
+            }
+        }
+        for genome in &fly.strand.genome {
+            if let Genome::Anatomy(Anatomy::Bug(bug)) = genome {
+                self.link_parents(bug);
+            }
+        }
+
+        let global = Context { scope: GLOBAL, function: GLOBAL, this: None };
+        for genome in &fly.strand.genome {
+            match genome {
+                Genome::Anatomy(Anatomy::Bug(bug)) => self.resolve_bug(bug),
+                Genome::Anatomy(Anatomy::Contract(contract)) => self.resolve_contract(contract),
+                Genome::Anatomy(Anatomy::Kind(kind)) => {
+                    for gene in kind.variants.iter().flat_map(|variant| &variant.genes) {
+                        self.resolve_specie_ref(&gene.specie, None);
+                    }
+                }
+                Genome::Behavior(behavior) => self.resolve_behavior(behavior, &global),
+                // Os nomes importados só existem no programa ligado por `module`
+                Genome::Import(_) | Genome::Error(_) => {}
+            }
+        }
+    }
+
+    fn open_scope(&mut self, kind: ScopeKind, parent: Option<ScopeId>, span: Span) -> ScopeId {
+        let id = self.resolution.scopes.len();
+        self.resolution.scopes.push(Scope { id, kind, parent, span, symbols: Vec::new() });
+        id
+    }
+
+    fn define(&mut self, scope: ScopeId, name: &str, kind: SymbolKind, span: Span, specie: Option<String>) -> SymbolId {
+        let id = self.resolution.symbols.len();
+        self.resolution.symbols.push(Symbol { id, name: name.to_string(), kind, span, scope, specie });
+        self.resolution.scopes[scope].symbols.push(id);
+        id
+    }
+
+    /// Define no escopo, reclamando se o nome já existe nele
+    fn define_unique(&mut self, scope: ScopeId, name: &str, kind: SymbolKind, span: Span, specie: Option<String>) -> SymbolId {
+        let previous = self.resolution.scopes[scope]
+            .symbols
+            .iter()
+            .map(|id| &self.resolution.symbols[*id])
+            .find(|symbol| symbol.name == name)
+            .map(|symbol| symbol.span);
+        if let Some(previous) = previous {
+            self.resolution.errors.push(ResolveError::Duplicate { name: name.to_string(), span, previous });
+        }
+        self.define(scope, name, kind, span, specie)
+    }
+
+    /// Define no escopo, avisando quando o nome esconde outro de um escopo externo
+    fn define_shadowing(&mut self, ctx: &Context, scope: ScopeId, name: &str, kind: SymbolKind, span: Span, specie: Option<String>) -> SymbolId {
+        if let Some(previous) = self.resolution.lookup(ctx.scope, name) {
+            let previous = previous.span;
+            self.resolution.errors.push(ResolveError::Shadowing { name: name.to_string(), span, previous });
+        }
+        self.define(scope, name, kind, span, specie)
+    }
+
+    fn refer(&mut self, span: Span, symbol: SymbolId) {
+        self.resolution.references.push(Reference { span, symbol });
+    }
+
+    fn declare_bug(&mut self, bug: &Bug) {
+        let name = &bug.specie.raw;
+        let id = self.define_unique(GLOBAL, name, SymbolKind::Specie, bug.specie.span, Some(name.clone()));
+        self.species.entry(name.clone()).or_insert(id);
+
+        let scope = self.open_scope(ScopeKind::Bug, Some(PRELUDE), bug.span);
+        self.bug_scopes.entry(name.clone()).or_insert(scope);
+        self.parents.entry(name.clone()).or_insert_with(|| bug.parents.clone());
+        for param in &bug.params {
+            self.define_unique(scope, &param.raw, SymbolKind::TypeParam, param.span, None);
+        }
+        for gene in &bug.genes {
+            self.define_unique(scope, &gene.tag.raw, SymbolKind::Gene, gene.tag.span, Some(gene.specie.name.raw.clone()));
+        }
+        for ethics in &bug.ethics {
+            let feedback = ethics.feedback.as_ref().map(|specie| specie.name.raw.clone());
+            self.define_unique(scope, &ethics.tag.raw, SymbolKind::Ethics, ethics.tag.span, feedback);
+        }
+    }
+
+    fn declare_contract(&mut self, contract: &Contract) {
+        let name = &contract.specie.raw;
+        let id = self.define_unique(GLOBAL, name, SymbolKind::Specie, contract.specie.span, Some(name.clone()));
+        self.species.entry(name.clone()).or_insert(id);
+        self.contracts.insert(name.clone());
+
+        let scope = self.open_scope(ScopeKind::Bug, Some(PRELUDE), contract.span);
+        self.bug_scopes.entry(name.clone()).or_insert(scope);
+        for ethics in &contract.ethics {
+            let feedback = ethics.feedback.as_ref().map(|specie| specie.name.raw.clone());
+            self.define_unique(scope, &ethics.tag.raw, SymbolKind::Ethics, ethics.tag.span, feedback);
+        }
+    }
+
+    /// Liga cada espécie depois de `<` e põe o escopo do bug dentro do escopo do bug pai
+    fn link_parents(&mut self, bug: &Bug) {
+        let mut parent_bug = None;
+        for parent in &bug.parents {
+            if !self.resolve_specie(parent) || self.contracts.contains(&parent.raw) {
+                continue;
+            }
+            let error = if !self.parents.contains_key(&parent.raw) {
+                Some(ResolveError::InvalidParent { name: parent.raw.clone(), span: parent.span })
+            } else if parent_bug.is_some() {
+                Some(ResolveError::MultipleParents { name: parent.raw.clone(), span: parent.span })
+            } else if self.ancestors(&parent.raw).contains(&bug.specie.raw) {
+                Some(ResolveError::InheritanceCycle { name: bug.specie.raw.clone(), span: parent.span })
+            } else {
+                None
+            };
+            match error {
+                Some(error) => self.resolution.errors.push(error),
+                None => parent_bug = Some(parent.raw.clone()),
+            }
+        }
+
+        let scope = parent_bug.and_then(|parent| self.bug_scopes.get(&parent).copied());
+        if let (Some(scope), Some(&bug_scope)) = (scope, self.bug_scopes.get(&bug.specie.raw)) {
+            self.resolution.scopes[bug_scope].parent = Some(scope);
+        }
+    }
+
+    /// `name` e os bugs de quem ele herda, seguindo o primeiro pai que é um
+    /// bug, como na execução; para num ciclo
+    fn ancestors(&self, name: &str) -> Vec<String> {
+        let mut ancestors: Vec<String> = Vec::new();
+        let mut next = Some(name.to_string());
+        while let Some(current) = next.filter(|current| !ancestors.contains(current)) {
+            next = self.parents.get(&current).and_then(|parents| {
+                parents.iter().find(|parent| self.parents.contains_key(&parent.raw)).map(|parent| parent.raw.clone())
+            });
+            ancestors.push(current);
+        }
+        ancestors
+    }
+
+    fn declare_kind(&mut self, kind: &Kind) {
+        let name = &kind.specie.raw;
+        let id = self.define_unique(GLOBAL, name, SymbolKind::Specie, kind.specie.span, Some(name.clone()));
+        self.species.entry(name.clone()).or_insert(id);
+
+        for variant in &kind.variants {
+            let variant_name = &variant.specie.raw;
+            let id = self.define_unique(GLOBAL, variant_name, SymbolKind::Variant, variant.specie.span, Some(name.clone()));
+            self.species.entry(variant_name.clone()).or_insert(id);
+
+            let scope = self.open_scope(ScopeKind::Bug, Some(PRELUDE), variant.span);
+            self.bug_scopes.entry(variant_name.clone()).or_insert(scope);
+            for gene in &variant.genes {
+                self.define_unique(scope, &gene.tag.raw, SymbolKind::Gene, gene.tag.span, Some(gene.specie.name.raw.clone()));
+            }
+        }
+    }
+
+    fn resolve_specie(&mut self, specie: &Specie) -> bool {
+        match self.species.get(&specie.raw) {
+            Some(id) => {
+                self.refer(specie.span, *id);
+                true
+            }
+            None => {
+                self.resolution.errors.push(ResolveError::UnknownSpecie { name: specie.raw.clone(), span: specie.span });
+                false
+            }
+        }
+    }
+
+    /// Espécie de gene, parâmetro ou feedback e os seus argumentos; dentro de
+    /// um bug genérico (`scope`), os parâmetros de tipo também valem
+    fn resolve_specie_ref(&mut self, specie: &SpecieRef, scope: Option<ScopeId>) {
+        let param = scope.and_then(|scope| {
+            self.resolution.scopes[scope].symbols.iter().copied().find(|id| {
+                let symbol = &self.resolution.symbols[*id];
+                symbol.kind == SymbolKind::TypeParam && symbol.name == specie.name.raw
+            })
+        });
+        match param {
+            Some(id) => self.refer(specie.name.span, id),
+            None => {
+                self.resolve_specie(&specie.name);
+            }
+        }
+        for arg in &specie.args {
+            self.resolve_specie_ref(arg, scope);
+        }
+    }
+
+    fn resolve_bug(&mut self, bug: &Bug) {
+        let Some(&bug_scope) = self.bug_scopes.get(&bug.specie.raw) else {
+            return;
+        };
+        for gene in &bug.genes {
+            self.resolve_specie_ref(&gene.specie, Some(bug_scope));
+        }
+        for ethics in &bug.ethics {
+            let scope = self.open_scope(ScopeKind::Ethics, Some(bug_scope), ethics.span);
+            let ctx = Context { scope, function: scope, this: Some(bug.specie.raw.clone()) };
+            for bind in ethics.signature.iter().flat_map(|signature| signature.binds.iter().flatten()) {
+                self.resolve_specie_ref(&bind.specie, Some(bug_scope));
+                let duplicate = self.resolution.scopes[scope].symbols.iter().any(|id| self.resolution.symbols[*id].name == bind.tag.raw);
+                if duplicate {
+                    self.define_unique(scope, &bind.tag.raw, SymbolKind::Param, bind.tag.span, Some(bind.specie.name.raw.clone()));
+                } else {
+                    self.define_shadowing(&ctx, scope, &bind.tag.raw, SymbolKind::Param, bind.tag.span, Some(bind.specie.name.raw.clone()));
+                }
+            }
+            if let Some(feedback) = &ethics.feedback {
+                self.resolve_specie_ref(feedback, Some(bug_scope));
+            }
+            if let Some(body) = &ethics.body {
+                self.resolve_matrix(body, &ctx);
+            }
+        }
+    }
+
+    fn resolve_contract(&mut self, contract: &Contract) {
+        for ethics in &contract.ethics {
+            for bind in ethics.signature.iter().flat_map(|signature| signature.binds.iter().flatten()) {
+                self.resolve_specie_ref(&bind.specie, None);
+            }
+            if let Some(feedback) = &ethics.feedback {
+                self.resolve_specie_ref(feedback, None);
+            }
+        }
+    }
+
+    fn resolve_matrix(&mut self, matrix: &Matrix, ctx: &Context) {
+        for Signal::Behavior(behavior) in &matrix.signals {
+            self.resolve_behavior(behavior, ctx);
+        }
+    }
+
+    fn resolve_behavior(&mut self, behavior: &Behavior, ctx: &Context) {
+        match behavior {
+            Behavior::Assign(assign) => {
+                let specie = self.resolve_oop(&assign.oop, ctx);
+                self.resolve_target(&assign.tag, specie, ctx);
+            }
+            Behavior::Oop(oop) => {
+                self.resolve_oop(oop, ctx);
+            }
+            Behavior::Statement(statement) => self.resolve_statement(statement, ctx),
+        }
+    }
+
+    /// Lado esquerdo de uma atribuição: reaproveita variável ou gene existente, senão define um novo nome
+    fn resolve_target(&mut self, tag: &Tag, specie: Option<String>, ctx: &Context) {
+        let existing = self.resolution.lookup(ctx.scope, &tag.raw).map(|symbol| (symbol.id, symbol.kind));
+        match existing {
+            Some((id, SymbolKind::Local | SymbolKind::Global | SymbolKind::Param | SymbolKind::ForVariable | SymbolKind::Gene)) => {
+                self.refer(tag.span, id);
+            }
+            Some(_) => {
+                let kind = if ctx.this.is_some() { SymbolKind::Local } else { SymbolKind::Global };
+                self.define_shadowing(ctx, ctx.function, &tag.raw, kind, tag.span, specie);
+            }
+            None => {
+                let kind = if ctx.this.is_some() { SymbolKind::Local } else { SymbolKind::Global };
+                self.define(ctx.function, &tag.raw, kind, tag.span, specie);
+            }
+        }
+    }
+
+    fn resolve_statement(&mut self, statement: &Statement, ctx: &Context) {
+        match statement {
+            Statement::If(if_stmt) => {
+                self.resolve_condition(&if_stmt.condition, ctx);
+                self.resolve_matrix(&if_stmt.then_block, ctx);
+                for elsif in &if_stmt.elsif_blocks {
+                    self.resolve_condition(&elsif.condition, ctx);
+                    self.resolve_matrix(&elsif.block, ctx);
+                }
+                if let Some(block) = &if_stmt.else_block {
+                    self.resolve_matrix(block, ctx);
+                }
+            }
+            Statement::While(while_stmt) => {
+                self.resolve_condition(&while_stmt.condition, ctx);
+                self.resolve_matrix(&while_stmt.block, ctx);
+            }
+            Statement::For(for_stmt) => {
+                self.resolve_oop(&for_stmt.iterable, ctx);
+                let scope = self.open_scope(ScopeKind::For, Some(ctx.scope), for_stmt.span);
+                let variable = &for_stmt.variable;
+                self.define_shadowing(ctx, scope, &variable.raw, SymbolKind::ForVariable, variable.span, None);
+                let inner = Context { scope, ..ctx.clone() };
+                self.resolve_matrix(&for_stmt.block, &inner);
+            }
+            Statement::Match(match_stmt) => {
+                self.resolve_oop(&match_stmt.subject, ctx);
+                for arm in &match_stmt.arms {
+                    for variant in &arm.variants {
+                        self.resolve_specie(variant);
+                    }
+                    self.resolve_matrix(&arm.block, ctx);
+                }
+                if let Some(block) = &match_stmt.else_block {
+                    self.resolve_matrix(block, ctx);
+                }
+            }
+            Statement::Return(return_stmt) => {
+                self.resolve_oop(&return_stmt.value, ctx);
+            }
+        }
+    }
+
+    fn resolve_condition(&mut self, condition: &Condition, ctx: &Context) {
+        match condition {
+            Condition::Oop(oop) => {
+                self.resolve_oop(oop, ctx);
+            }
+            Condition::Expr(expr) => self.resolve_expr(expr, ctx),
+        }
+    }
+
+    fn resolve_expr(&mut self, expr: &Expr, ctx: &Context) {
+        match expr {
+            Expr::Oop(oop) => {
+                self.resolve_oop(oop, ctx);
+            }
+            Expr::Binary { left, right, .. } => {
+                self.resolve_expr(left, ctx);
+                self.resolve_expr(right, ctx);
+            }
+            Expr::Unary { operand, .. } => self.resolve_expr(operand, ctx),
+        }
+    }
+
+    /// Resolve a cadeia e devolve a espécie do resultado, quando conhecida
+    fn resolve_oop(&mut self, oop: &Oop, ctx: &Context) -> Option<String> {
+        let mut trails = oop.trails.iter().peekable();
+        let leading = match trails.peek() {
+            Some(Trail::Carrier(carrier)) => {
+                trails.next();
+                Some(carrier)
+            }
+            _ => None,
+        };
+        if let Some(carrier) = leading {
+            self.resolve_carrier(carrier, ctx);
+        }
+
+        // Espécie usada como valor (`Dog` em `Dog.new`)
+        let mut class = None;
+        let mut current = match &oop.emitter {
+            Emitter::Literal(literal) => Some(literal_specie(literal).to_string()),
+            Emitter::Template(template) => {
+                for oop in template.oops() {
+                    self.resolve_oop(oop, ctx);
+                }
+                Some("String".to_string())
+            }
+            Emitter::List(list) => {
+                for item in &list.items {
+                    self.resolve_oop(item, ctx);
+                }
+                Some("List".to_string())
+            }
+            Emitter::Map(map) => {
+                for entry in &map.entries {
+                    self.resolve_oop(&entry.oop, ctx);
+                }
+                Some("Map".to_string())
+            }
+            Emitter::SelfRef(self_ref) => {
+                if ctx.this.is_none() {
+                    self.resolution.errors.push(ResolveError::UndefinedName { name: "$".to_string(), span: self_ref.span });
+                }
+                ctx.this.clone()
+            }
+            Emitter::Specie(specie) => {
+                let known = self.resolve_specie(specie);
+                match leading {
+                    Some(_) if known => Some(specie.raw.clone()),
+                    None if known => {
+                        class = Some(specie.raw.clone());
+                        None
+                    }
+                    _ => None,
+                }
+            }
+            Emitter::Tag(tag) => match self.resolution.lookup(ctx.scope, &tag.raw).map(|symbol| (symbol.id, symbol.specie.clone())) {
+                Some((id, specie)) => {
+                    self.refer(tag.span, id);
+                    specie
+                }
+                None => {
+                    self.resolution.errors.push(ResolveError::UndefinedName { name: tag.raw.clone(), span: tag.span });
+                    None
+                }
+            },
+        };
+
+        for trail in trails {
+            current = match trail {
+                Trail::Catalysis(catalysis) => {
+                    if let Some(carrier) = &catalysis.carrier {
+                        self.resolve_carrier(carrier, ctx);
+                    }
+                    let name = &catalysis.tag.raw;
+                    let member = current
+                        .as_ref()
+                        .and_then(|specie| self.bug_scopes.get(specie))
+                        .and_then(|scope| self.resolution.lookup(*scope, name))
+                        .filter(|symbol| symbol.scope != PRELUDE)
+                        .map(|symbol| (symbol.id, symbol.specie.clone()));
+                    match (member, class.take()) {
+                        (Some((id, specie)), _) => {
+                            self.refer(catalysis.tag.span, id);
+                            specie
+                        }
+                        (None, Some(class)) if name == "new" => Some(class),
+                        _ => None,
+                    }
+                }
+                Trail::Carrier(carrier) => {
+                    self.resolve_carrier(carrier, ctx);
+                    None
+                }
+            };
+        }
+        current
+    }
+
+    fn resolve_carrier(&mut self, carrier: &Carrier, ctx: &Context) {
+        match &carrier.transport {
+            None => {}
+            Some(Transport::Sequence(sequence)) => {
+                for oop in &sequence.oops {
+                    self.resolve_oop(oop, ctx);
+                }
+            }
+            Some(Transport::Binds(binds)) => {
+                for bind in &binds.binds {
+                    self.resolve_oop(&bind.oop, ctx);
+                }
+            }
+        }
+    }
+}
+
+fn literal_specie(literal: &Literal) -> &'static str {
+    match literal {
+        Literal::Int { .. } | Literal::Hex { .. } | Literal::Bit { .. } => "Int",
+        Literal::Decimal { .. } => "Decimal",
+        Literal::Str { .. } => "String",
+        Literal::Bool { .. } => "Bool",
+        Literal::Nil { .. } => "Nil",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{resolve_tree, Resolution, ResolveError, SymbolKind};
+    use crate::dsl::parser::diagnostic::Severity;
+    use crate::dsl::parser::tree::Tree;
+    use crate::tests::load_fragment;
+
+    fn resolution(path: &str) -> (String, Resolution) {
+        let source = load_fragment(path);
+        let tree = Tree::parse_input(source.clone()).expect("Fixture should parse");
+        (source, resolve_tree(&tree))
+    }
+
+    /// Símbolo usado na `nth` ocorrência de `text` no código
+    fn used_at<'r>(source: &str, resolution: &'r Resolution, text: &str, nth: usize) -> &'r super::Symbol {
+        let offset = source.match_indices(text).nth(nth).expect("Text must occur").0;
+        resolution.symbol_at(offset).expect("Name should resolve")
+    }
+
+    #[test]
+    fn test_well_formed_programs_resolve() {
+        for path in ["interp/dog.sc", "interp/loops.sc", "interp/kinds.sc", "literal/collections.sc", "literal/template.sc", "typeck/generics.sc", "typeck/inheritance.sc"] {
+            let (_, resolution) = resolution(path);
+            let messages: Vec<String> = resolution.errors.iter().map(|e| e.to_string()).collect();
+            assert!(messages.is_empty(), "{}: {:?}", path, messages);
+        }
+    }
+
+    #[test]
+    fn test_reports_undefined_unknown_and_shadowing() {
+        let (_, resolution) = resolution("resolve/scopes.sc");
+
+        let found: Vec<(String, usize, Severity)> =
+            resolution.errors.iter().map(|e| (e.to_string(), e.span().line, e.severity())).collect();
+
+        assert_eq!(
+            found,
+            vec![
+                ("cannot find species `Person`".to_string(), 3, Severity::Error),
+                ("`energia` shadows an earlier definition".to_string(), 5, Severity::Warning),
+                ("`rex` shadows an earlier definition".to_string(), 16, Severity::Warning),
+                ("cannot find `sobra` in this scope".to_string(), 19, Severity::Error),
+            ]
+        );
+        assert!(resolution.has_errors());
+        assert_eq!(resolution.errors[1].diagnostic().notes, vec!["previous definition on line 2".to_string()]);
+    }
+
+    #[test]
+    fn test_uses_point_to_their_definitions() {
+        let (source, resolution) = resolution("resolve/scopes.sc");
+
+        // `energia` dentro do corpo é o parâmetro; `$.energia` é o gene
+        assert_eq!(used_at(&source, &resolution, "energia", 2).kind, SymbolKind::Param);
+        let gene = used_at(&source, &resolution, "energia", 3);
+        assert_eq!((gene.kind, gene.span.line), (SymbolKind::Gene, 2));
+
+        let total = used_at(&source, &resolution, "total", 0);
+        assert_eq!(total.kind, SymbolKind::Local);
+        assert_eq!(resolution.references_to(total.id).len(), 2);
+
+        assert_eq!(used_at(&source, &resolution, "item", 1).kind, SymbolKind::ForVariable);
+        assert_eq!(used_at(&source, &resolution, "list", 0).kind, SymbolKind::Builtin);
+
+        // `rex` é um Dog, então `rex.latir` chega ao ethics
+        let latir = used_at(&source, &resolution, "latir", 1);
+        assert_eq!((latir.kind, latir.span.line), (SymbolKind::Ethics, 5));
+
+        // Dentro do segundo `for`, `rex` é a variável do laço e não a global
+        let inner = used_at(&source, &resolution, "rex", 4);
+        assert_eq!(inner.kind, SymbolKind::ForVariable);
+        let outer = used_at(&source, &resolution, "rex", 3);
+        assert_eq!(outer.kind, SymbolKind::Global);
+    }
+
+    #[test]
+    fn test_variants_belong_to_their_kind() {
+        let (source, resolution) = resolution("interp/kinds.sc");
+
+        let circulo = used_at(&source, &resolution, "Circulo", 1);
+        assert_eq!((circulo.kind, circulo.span.line), (SymbolKind::Variant, 2));
+        assert_eq!(circulo.specie.as_deref(), Some("Forma"));
+        assert_eq!(used_at(&source, &resolution, "Forma", 1).kind, SymbolKind::Specie);
+    }
+
+    #[test]
+    fn test_type_params_are_scoped_to_their_bug() {
+        let (source, resolution) = resolution("typeck/generics.sc");
+
+        let param = used_at(&source, &resolution, "T", 3);
+        assert_eq!((param.kind, param.span.line), (SymbolKind::TypeParam, 1));
+        assert_eq!(used_at(&source, &resolution, "Caixa", 1).kind, SymbolKind::Specie);
+
+        let tree = Tree::parse_input("bug Caixa<T>\nend\nbug Dog\n  gene valor T\nend".to_string()).expect("Input should parse");
+        let messages: Vec<String> = resolve_tree(&tree).errors.iter().map(|e| e.to_string()).collect();
+        assert_eq!(messages, vec!["cannot find species `T`"]);
+    }
+
+    #[test]
+    fn test_inherited_members_resolve_to_the_parent() {
+        let (source, resolution) = resolution("typeck/inheritance.sc");
+
+        // `nome` em `apresentar` de `Cat` é o gene declarado em `Animal`
+        let nome = used_at(&source, &resolution, "nome}, ", 0);
+        assert_eq!((nome.kind, nome.span.line), (SymbolKind::Gene, 6));
+        let falar = used_at(&source, &resolution, "falar", 2);
+        assert_eq!((falar.kind, falar.span.line), (SymbolKind::Ethics, 2));
+    }
+
+    #[test]
+    fn test_invalid_parents_are_reported() {
+        let source = "kind Cor Azul end\nbug A < B\nend\nbug B < A\nend\nbug C\nend\nbug D < C, B, Cor, Int\nend";
+        let tree = Tree::parse_input(source.to_string()).expect("Input should parse");
+
+        let messages: Vec<String> = resolve_tree(&tree).errors.iter().map(|e| e.to_string()).collect();
+
+        assert_eq!(
+            messages,
+            vec![
+                "`A` inherits from itself",
+                "`B` inherits from itself",
+                "`B` is a second parent bug; a bug inherits from only one bug",
+                "cannot inherit from `Cor`: only bugs and contracts can follow `<`",
+                "cannot inherit from `Int`: only bugs and contracts can follow `<`",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_duplicates_in_a_bug_are_reported() {
+        let (_, resolution) = resolution("program/anatomy.sc");
+
+        let duplicates: Vec<(String, usize)> = resolution
+            .errors
+            .iter()
+            .filter_map(|error| match error {
+                ResolveError::Duplicate { name, span, .. } => Some((name.clone(), span.line)),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(duplicates, vec![("f".to_string(), 9), ("a".to_string(), 8)]);
+    }
+
+    #[test]
+    fn test_self_outside_ethics_is_undefined() {
+        let tree = Tree::parse_input("x = $".to_string()).expect("Input should parse");
+
+        let errors = resolve_tree(&tree).errors;
+
+        assert_eq!(errors, vec![ResolveError::UndefinedName { name: "$".to_string(), span: errors[0].span() }]);
+    }
+}
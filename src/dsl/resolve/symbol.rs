@@ -0,0 +1,70 @@
+use crate::dsl::ast::span::Span;
+
+pub type SymbolId = usize;
+pub type ScopeId = usize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SymbolKind {
+    /// Espécie: um `bug`, `kind` ou `contract` declarado ou uma espécie embutida (`Int`, `String`...)
+    Specie,
+    /// Variante de um `kind`; `specie` é o kind
+    Variant,
+    Gene,
+    Ethics,
+    /// Parâmetro da `Signature` de um ethics
+    Param,
+    /// Parâmetro de tipo de um bug genérico (`T` em `bug Caixa<T>`)
+    TypeParam,
+    /// Variável atribuída fora de qualquer bug
+    Global,
+    /// Variável atribuída dentro de um ethics
+    Local,
+    /// Variável de um `for`
+    ForVariable,
+    /// Função do host, como `print`
+    Builtin,
+}
+
+/// Algo com nome que pode ser usado no código.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Symbol {
+    pub id: SymbolId,
+    pub name: String,
+    pub kind: SymbolKind,
+    /// Onde o símbolo é definido; `Span::default()` para os embutidos
+    pub span: Span,
+    pub scope: ScopeId,
+    /// Espécie do valor, quando conhecida: tipo do gene ou parâmetro, feedback do ethics
+    pub specie: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ScopeKind {
+    /// Embutidos: espécies e funções do host
+    Prelude,
+    /// Nível superior do programa
+    Global,
+    /// Genes e ethics de um bug, genes de uma variante ou ethics de um
+    /// contrato. O pai de um bug que herda é o escopo do bug pai.
+    Bug,
+    /// Parâmetros e variáveis locais de um ethics
+    Ethics,
+    /// Variável de um `for`
+    For,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Scope {
+    pub id: ScopeId,
+    pub kind: ScopeKind,
+    pub parent: Option<ScopeId>,
+    pub span: Span,
+    pub symbols: Vec<SymbolId>,
+}
+
+/// Um uso de nome no código, ligado ao símbolo que ele referencia.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Reference {
+    pub span: Span,
+    pub symbol: SymbolId,
+}
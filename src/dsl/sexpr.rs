@@ -0,0 +1,286 @@
+//! A AST como S-expressions, para inspecionar a estrutura sem spans
+//! (`sc ast --format sexpr`).
+//!
+//! `rex.latir(volume: 2)` vira `(. rex latir (args (: volume 2)))`, `print(x)`
+//! vira `(call print (args x))` e operadores ficam em prefixo: `(+ a b)`.
+
+use std::fmt;
+use crate::dsl::ast::anatomy::Anatomy;
+use crate::dsl::ast::behavior::oop::Oop;
+use crate::dsl::ast::behavior::transport::Transport;
+use crate::dsl::ast::behavior::{Behavior, Bind, Carrier, Trail};
+use crate::dsl::ast::bug::Bug;
+use crate::dsl::ast::contract::Contract;
+use crate::dsl::ast::condition::Condition;
+use crate::dsl::ast::emitter::{Emitter, SpecieRef, TemplatePart};
+use crate::dsl::ast::ethics::Ethics;
+use crate::dsl::ast::expr::Expr;
+use crate::dsl::ast::genome::Genome;
+use crate::dsl::ast::import::Import;
+use crate::dsl::ast::kind::Kind;
+use crate::dsl::ast::matrix::Matrix;
+use crate::dsl::ast::signal::Signal;
+use crate::dsl::ast::statement::Statement;
+use crate::dsl::parser::tree::Tree;
+
+/// Largura a partir da qual uma lista é quebrada em várias linhas
+const WIDTH: usize = 72;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Sexpr {
+    Atom(String),
+    List(Vec<Sexpr>),
+}
+
+impl Sexpr {
+    fn atom(text: impl Into<String>) -> Self {
+        Sexpr::Atom(text.into())
+    }
+
+    /// Lista que começa por um átomo: `(head items...)`
+    fn form(head: &str, items: impl IntoIterator<Item = Sexpr>) -> Self {
+        Sexpr::List(std::iter::once(Sexpr::atom(head)).chain(items).collect())
+    }
+
+    fn flat(&self) -> String {
+        match self {
+            Sexpr::Atom(text) => text.clone(),
+            Sexpr::List(items) => format!("({})", items.iter().map(Sexpr::flat).collect::<Vec<_>>().join(" ")),
+        }
+    }
+
+    fn write(&self, out: &mut String, indent: usize) {
+        let flat = self.flat();
+        let Sexpr::List(items) = self else {
+            out.push_str(&flat);
+            return;
+        };
+        if indent + flat.len() <= WIDTH || items.len() < 2 {
+            out.push_str(&flat);
+            return;
+        }
+        // Átomos do começo ficam na linha da abertura: `(ethics latir`
+        let head = items.iter().take_while(|item| matches!(item, Sexpr::Atom(_))).count().max(1);
+        out.push('(');
+        out.push_str(&items[..head].iter().map(Sexpr::flat).collect::<Vec<_>>().join(" "));
+        for item in &items[head..] {
+            out.push('\n');
+            out.push_str(&" ".repeat(indent + 2));
+            item.write(out, indent + 2);
+        }
+        out.push(')');
+    }
+}
+
+impl fmt::Display for Sexpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut out = String::new();
+        self.write(&mut out, 0);
+        f.write_str(&out)
+    }
+}
+
+pub fn tree(tree: &Tree) -> Sexpr {
+    Sexpr::form("program", tree.sc.fly.strand.genome.iter().map(genome))
+}
+
+pub fn genome(genome: &Genome) -> Sexpr {
+    match genome {
+        Genome::Import(node) => import(node),
+        Genome::Anatomy(Anatomy::Bug(node)) => bug(node),
+        Genome::Anatomy(Anatomy::Kind(node)) => kind(node),
+        Genome::Anatomy(Anatomy::Contract(node)) => contract(node),
+        Genome::Behavior(node) => behavior(node),
+        Genome::Error(error) => Sexpr::form("error", [Sexpr::atom(format!("{:?}", error.message))]),
+    }
+}
+
+/// `use Dog as Cao from "b.sc"` vira `(use "b.sc" (Dog Cao))`
+pub fn import(import: &Import) -> Sexpr {
+    let names = import.names.iter().map(|name| match &name.alias {
+        Some(alias) => Sexpr::List(vec![Sexpr::atom(&name.specie.raw), Sexpr::atom(&alias.raw)]),
+        None => Sexpr::atom(&name.specie.raw),
+    });
+    Sexpr::form("use", std::iter::once(Sexpr::atom(format!("{:?}", import.path))).chain(names))
+}
+
+/// Bug genérico fica com os parâmetros: `(bug (Caixa T) (gene item T))`;
+/// os pais vêm logo depois do nome: `(bug Cat (< Animal Falante) ...)`
+pub fn bug(bug: &Bug) -> Sexpr {
+    let name = match bug.params.as_slice() {
+        [] => Sexpr::atom(&bug.specie.raw),
+        params => Sexpr::form(&bug.specie.raw, params.iter().map(|param| Sexpr::atom(&param.raw))),
+    };
+    let parents = match bug.parents.as_slice() {
+        [] => None,
+        parents => Some(Sexpr::form("<", parents.iter().map(|parent| Sexpr::atom(&parent.raw)))),
+    };
+    let genes = bug.genes.iter().map(|gene| Sexpr::form("gene", [Sexpr::atom(&gene.tag.raw), specie_ref(&gene.specie)]));
+    Sexpr::form("bug", std::iter::once(name).chain(parents).chain(genes).chain(bug.ethics.iter().map(ethics)))
+}
+
+pub fn contract(contract: &Contract) -> Sexpr {
+    Sexpr::form("contract", std::iter::once(Sexpr::atom(&contract.specie.raw)).chain(contract.ethics.iter().map(ethics)))
+}
+
+/// `Int` vira átomo; `Map<String, Dog>` vira `(Map String Dog)`
+pub fn specie_ref(specie_ref: &SpecieRef) -> Sexpr {
+    match specie_ref.args.as_slice() {
+        [] => Sexpr::atom(&specie_ref.name.raw),
+        args => Sexpr::form(&specie_ref.name.raw, args.iter().map(self::specie_ref)),
+    }
+}
+
+/// Variantes sem genes ficam como átomo: `(kind Forma (Circulo (raio Decimal)) Ponto)`
+pub fn kind(kind: &Kind) -> Sexpr {
+    let variants = kind.variants.iter().map(|variant| match variant.genes.as_slice() {
+        [] => Sexpr::atom(&variant.specie.raw),
+        genes => Sexpr::List(
+            std::iter::once(Sexpr::atom(&variant.specie.raw))
+                .chain(genes.iter().map(|gene| Sexpr::List(vec![Sexpr::atom(&gene.tag.raw), specie_ref(&gene.specie)])))
+                .collect(),
+        ),
+    });
+    Sexpr::form("kind", std::iter::once(Sexpr::atom(&kind.specie.raw)).chain(variants))
+}
+
+pub fn ethics(ethics: &Ethics) -> Sexpr {
+    let mut items = vec![Sexpr::atom(&ethics.tag.raw)];
+    if let Some(signature) = &ethics.signature {
+        let params = signature.binds.iter().flatten();
+        items.push(Sexpr::form(
+            "params",
+            params.map(|bind| Sexpr::List(vec![Sexpr::atom(&bind.tag.raw), specie_ref(&bind.specie)])),
+        ));
+    }
+    if let Some(feedback) = &ethics.feedback {
+        items.push(Sexpr::form("returns", [specie_ref(feedback)]));
+    }
+    if let Some(body) = &ethics.body {
+        items.push(Sexpr::form("body", matrix(body)));
+    }
+    Sexpr::form("ethics", items)
+}
+
+fn matrix(matrix: &Matrix) -> Vec<Sexpr> {
+    matrix.signals.iter().map(|Signal::Behavior(node)| behavior(node)).collect()
+}
+
+pub fn behavior(behavior: &Behavior) -> Sexpr {
+    match behavior {
+        Behavior::Assign(assign) => Sexpr::form("=", [Sexpr::atom(&assign.tag.raw), oop(&assign.oop)]),
+        Behavior::Oop(node) => oop(node),
+        Behavior::Statement(node) => statement(node),
+    }
+}
+
+pub fn statement(statement: &Statement) -> Sexpr {
+    match statement {
+        Statement::If(if_stmt) => {
+            let mut items = vec![condition(&if_stmt.condition), Sexpr::form("then", matrix(&if_stmt.then_block))];
+            for elsif in &if_stmt.elsif_blocks {
+                items.push(Sexpr::form("elsif", std::iter::once(condition(&elsif.condition)).chain(matrix(&elsif.block))));
+            }
+            if let Some(block) = &if_stmt.else_block {
+                items.push(Sexpr::form("else", matrix(block)));
+            }
+            Sexpr::form("if", items)
+        }
+        Statement::While(while_stmt) => {
+            Sexpr::form("while", std::iter::once(condition(&while_stmt.condition)).chain(matrix(&while_stmt.block)))
+        }
+        Statement::For(for_stmt) => Sexpr::form(
+            "for",
+            [Sexpr::atom(&for_stmt.variable.raw), oop(&for_stmt.iterable)].into_iter().chain(matrix(&for_stmt.block)),
+        ),
+        Statement::Match(match_stmt) => {
+            let mut items = vec![oop(&match_stmt.subject)];
+            for arm in &match_stmt.arms {
+                let variants = arm.variants.iter().map(|variant| Sexpr::atom(&variant.raw));
+                items.push(Sexpr::form("when", variants.chain(matrix(&arm.block))));
+            }
+            if let Some(block) = &match_stmt.else_block {
+                items.push(Sexpr::form("else", matrix(block)));
+            }
+            Sexpr::form("match", items)
+        }
+        Statement::Return(return_stmt) => Sexpr::form("return", [oop(&return_stmt.value)]),
+    }
+}
+
+pub fn condition(condition: &Condition) -> Sexpr {
+    match condition {
+        Condition::Oop(node) => oop(node),
+        Condition::Expr(node) => expr(node),
+    }
+}
+
+pub fn expr(expr: &Expr) -> Sexpr {
+    match expr {
+        Expr::Oop(node) => oop(node),
+        Expr::Binary { op, left, right, .. } => Sexpr::form(op.symbol(), [self::expr(left), self::expr(right)]),
+        Expr::Unary { op, operand, .. } => Sexpr::form(op.symbol(), [self::expr(operand)]),
+    }
+}
+
+/// A cadeia vira chamadas aninhadas, da esquerda para a direita
+pub fn oop(oop: &Oop) -> Sexpr {
+    let emitter = match &oop.emitter {
+        Emitter::Template(template) => Sexpr::form(
+            "template",
+            template.parts.iter().map(|part| match part {
+                TemplatePart::Text { raw, .. } => Sexpr::atom(format!("\"{}\"", raw)),
+                TemplatePart::Oop(node) => self::oop(node),
+            }),
+        ),
+        Emitter::List(list) => Sexpr::form("list", list.items.iter().map(self::oop)),
+        Emitter::Map(map) => Sexpr::form("map", map.entries.iter().map(bind)),
+        emitter => Sexpr::atom(emitter.to_string()),
+    };
+    oop.trails.iter().fold(emitter, |receiver, trail| match trail {
+        Trail::Catalysis(catalysis) => {
+            let mut items = vec![receiver, Sexpr::atom(&catalysis.tag.raw)];
+            items.extend(catalysis.carrier.as_ref().map(args));
+            Sexpr::form(".", items)
+        }
+        Trail::Carrier(carrier) => Sexpr::form("call", [receiver, args(carrier)]),
+    })
+}
+
+fn args(carrier: &Carrier) -> Sexpr {
+    let items: Vec<Sexpr> = match &carrier.transport {
+        None => Vec::new(),
+        Some(Transport::Sequence(sequence)) => sequence.oops.iter().map(oop).collect(),
+        Some(Transport::Binds(binds)) => binds.binds.iter().map(bind).collect(),
+    };
+    Sexpr::form("args", items)
+}
+
+fn bind(bind: &Bind) -> Sexpr {
+    Sexpr::form(":", [Sexpr::atom(&bind.tag.raw), oop(&bind.oop)])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::oop;
+    use crate::dsl::ast::behavior::oop::Oop;
+    use crate::dsl::parser::tree::Tree;
+
+    #[test]
+    fn test_chains_nest_left_to_right() {
+        let node = Oop::from_string("Dog.new(nome: \"Rex\").latir(2).cansado".to_string()).expect("Oop should parse");
+
+        assert_eq!(oop(&node).to_string(), "(. (. (. Dog new (args (: nome \"Rex\"))) latir (args 2)) cansado)");
+    }
+
+    #[test]
+    fn test_program_breaks_long_lists() {
+        let source = "bug Dog\n  gene energia Int\n  ethics latir(volume: Int) String\n    if volume > 1 and $.energia\n      print(\"au\")\n    end\n  end\nend\n";
+        let tree = Tree::parse_input(source.to_string()).expect("Source should parse");
+
+        assert_eq!(
+            super::tree(&tree).to_string(),
+            "(program\n  (bug Dog\n    (gene energia Int)\n    (ethics latir\n      (params (volume Int))\n      (returns String)\n      (body\n        (if\n          (and (> volume 1) (. $ energia))\n          (then (call print (args \"au\"))))))))"
+        );
+    }
+}
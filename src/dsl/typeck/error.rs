@@ -0,0 +1,82 @@
+use thiserror::Error;
+use crate::dsl::ast::span::Span;
+use crate::dsl::parser::diagnostic::Diagnostic;
+use super::ty::Type;
+
+/// Erro encontrado pela verificação de tipos, com o trecho responsável.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum TypeError {
+    #[error("cannot find species `{name}`")]
+    UnknownSpecie { name: String, span: Span },
+    #[error("cannot find `{name}` in this scope")]
+    UndefinedName { name: String, span: Span },
+    #[error("no method `{method}` on {receiver}")]
+    UnknownMethod { receiver: Type, method: String, span: Span },
+    #[error("{found} is not callable")]
+    NotCallable { found: Type, span: Span },
+    #[error("`{ethics}` takes {expected} argument(s) but {found} were given")]
+    Arity { ethics: String, expected: usize, found: usize, span: Span },
+    #[error("`{ethics}` has no parameter `{name}`")]
+    UnknownArgument { ethics: String, name: String, span: Span },
+    #[error("{specie} has no gene `{name}`")]
+    UnknownGene { specie: String, name: String, span: Span },
+    #[error("mismatched types: expected {expected}, found {found}")]
+    Mismatch { expected: Type, found: Type, span: Span },
+    #[error("cannot assign {found} to gene `{gene}` of type {expected}")]
+    GeneAssign { gene: String, expected: Type, found: Type, span: Span },
+    #[error("`{ethics}` returns {expected}, found {found}")]
+    Return { ethics: String, expected: Type, found: Type, span: Span },
+    #[error("cannot iterate over {found}")]
+    NotIterable { found: Type, span: Span },
+    #[error("cannot match on {found}")]
+    NotMatchable { found: Type, span: Span },
+    #[error("`{name}` is not a variant of {kind}")]
+    NotAVariant { name: String, kind: String, span: Span },
+    #[error("match on {kind} does not cover {missing}")]
+    NonExhaustive { kind: String, missing: String, span: Span },
+    #[error("`{specie}` takes {expected} type argument(s) but {found} were given")]
+    TypeArity { specie: String, expected: usize, found: usize, span: Span },
+    #[error("{specie} does not implement `{ethics}` from contract {contract}")]
+    MissingEthics { specie: String, contract: String, ethics: String, span: Span },
+    #[error("`{ethics}` in {specie} does not match contract {contract}: expected `{expected}`, found `{found}`")]
+    IncompatibleEthics { specie: String, contract: String, ethics: String, expected: String, found: String, span: Span },
+    #[error("cannot create {name}: it is a contract")]
+    ContractInstance { name: String, span: Span },
+    #[error("literal `{raw}` does not fit in Int")]
+    IntOverflow { raw: String, span: Span },
+}
+
+impl TypeError {
+    pub fn span(&self) -> Span {
+        match self {
+            TypeError::UnknownSpecie { span, .. }
+            | TypeError::UndefinedName { span, .. }
+            | TypeError::UnknownMethod { span, .. }
+            | TypeError::NotCallable { span, .. }
+            | TypeError::Arity { span, .. }
+            | TypeError::UnknownArgument { span, .. }
+            | TypeError::UnknownGene { span, .. }
+            | TypeError::Mismatch { span, .. }
+            | TypeError::GeneAssign { span, .. }
+            | TypeError::Return { span, .. }
+            | TypeError::NotIterable { span, .. }
+            | TypeError::NotMatchable { span, .. }
+            | TypeError::NotAVariant { span, .. }
+            | TypeError::NonExhaustive { span, .. }
+            | TypeError::TypeArity { span, .. }
+            | TypeError::MissingEthics { span, .. }
+            | TypeError::IncompatibleEthics { span, .. }
+            | TypeError::ContractInstance { span, .. }
+            | TypeError::IntOverflow { span, .. } => *span,
+        }
+    }
+
+    pub fn diagnostic(&self) -> Diagnostic {
+        let diagnostic = Diagnostic::error(self.to_string(), self.span());
+        match self {
+            TypeError::NonExhaustive { .. } => diagnostic.with_note("add the missing `when` arms or an `else`"),
+            TypeError::IntOverflow { .. } => diagnostic.with_note(format!("Int holds values from {} to {}", i64::MIN, i64::MAX)),
+            _ => diagnostic,
+        }
+    }
+}
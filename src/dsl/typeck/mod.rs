@@ -0,0 +1,1202 @@
+//! Verificação estática dos tipos declarados em genes, assinaturas e feedbacks.
+//!
+//! Primeiro monta a tabela de espécies a partir de todos os `bug`s, `kind`s
+//! e `contract`s (cada variante com a sua tabela de genes, cada bug com os
+//! genes e ethics herdados); depois confere se cada bug cumpre os contratos
+//! que cita,
+//! infere o tipo de cada cadeia `Oop` e confere atribuições a genes,
+//! argumentos de chamadas contra a `Signature` e valores devolvidos contra o
+//! `feedback`, e se cada `match` cobre todas as variantes. Os métodos das espécies embutidas seguem o host `Builtins` do
+//! interpretador. Quando um tipo não pode ser inferido ele vira `Type::Unknown`,
+//! que aceita tudo, para não gerar erros em cascata.
+
+pub mod error;
+pub mod ty;
+
+use std::collections::HashMap;
+use std::fmt;
+use crate::dsl::ast::anatomy::Anatomy;
+use crate::dsl::ast::behavior::oop::Oop;
+use crate::dsl::ast::behavior::transport::Transport;
+use crate::dsl::ast::behavior::{Behavior, Carrier, Trail};
+use crate::dsl::ast::bug::Bug;
+use crate::dsl::ast::condition::Condition;
+use crate::dsl::ast::contract::Contract;
+use crate::dsl::ast::emitter::{Emitter, Literal, Specie, SpecieRef};
+use crate::dsl::ast::ethics::Ethics;
+use crate::dsl::ast::expr::{BinaryOp, Expr};
+use crate::dsl::ast::fly::Fly;
+use crate::dsl::ast::genome::Genome;
+use crate::dsl::ast::kind::Kind;
+use crate::dsl::ast::matrix::Matrix;
+use crate::dsl::ast::signal::Signal;
+use crate::dsl::ast::span::Span;
+use crate::dsl::ast::statement::Statement;
+use crate::dsl::parser::tree::Tree;
+pub use error::TypeError;
+pub use ty::Type;
+
+/// Genes e ethics de uma espécie, com os tipos já resolvidos. Num bug
+/// genérico, os tipos podem citar os `params` como `Type::Param`. Um bug que
+/// herda já traz os genes e ethics dos ancestrais; `supers` são os
+/// ancestrais e os contratos que ele cumpre, onde ele pode ser usado.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SpecieTable {
+    pub params: Vec<String>,
+    pub genes: Vec<(String, Type)>,
+    pub ethics: HashMap<String, EthicsType>,
+    pub supers: Vec<String>,
+    /// Um `contract`: só ethics, e não pode ser criado
+    pub contract: bool,
+}
+
+/// Parâmetros e feedback de um ethics; sem feedback declarado o retorno é `Unknown`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EthicsType {
+    pub params: Vec<(String, Type)>,
+    pub feedback: Option<Type>,
+}
+
+/// Como a assinatura é escrita no código: `(volume: Int) String`
+impl fmt::Display for EthicsType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let params: Vec<String> = self.params.iter().map(|(name, ty)| format!("{}: {}", name, ty)).collect();
+        write!(f, "({})", params.join(", "))?;
+        match &self.feedback {
+            Some(feedback) => write!(f, " {}", feedback),
+            None => Ok(()),
+        }
+    }
+}
+
+impl SpecieTable {
+    pub fn gene(&self, name: &str) -> Option<&Type> {
+        self.genes.iter().find(|(gene, _)| gene == name).map(|(_, ty)| ty)
+    }
+
+    /// Tipo de `$` dentro do bug: os parâmetros continuam parâmetros
+    fn this(&self, name: &str) -> Type {
+        Type::Bug(name.to_string(), self.params.iter().cloned().map(Type::Param).collect())
+    }
+
+    /// Genes e ethics vistos de fora, com os parâmetros trocados por `args`
+    fn instance(&self, args: &[Type]) -> SpecieTable {
+        let substitute = |ty: &Type| ty.substitute(&self.params, args);
+        SpecieTable {
+            params: Vec::new(),
+            supers: self.supers.clone(),
+            contract: self.contract,
+            genes: self.genes.iter().map(|(name, ty)| (name.clone(), substitute(ty))).collect(),
+            ethics: self
+                .ethics
+                .iter()
+                .map(|(name, ethics)| {
+                    let params = ethics.params.iter().map(|(param, ty)| (param.clone(), substitute(ty))).collect();
+                    (name.clone(), EthicsType { params, feedback: ethics.feedback.as_ref().map(substitute) })
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Verifica a árvore inteira e devolve todos os erros, na ordem em que foram encontrados.
+pub fn check_tree(tree: &Tree) -> Vec<TypeError> {
+    check(&tree.sc.fly)
+}
+
+pub fn check(fly: &Fly) -> Vec<TypeError> {
+    let mut checker = TypeChecker::new(fly);
+    checker.check_fly(fly);
+    checker.errors
+}
+
+pub struct TypeChecker {
+    species: HashMap<String, SpecieTable>,
+    /// Variantes de cada kind, na ordem declarada
+    kinds: HashMap<String, Vec<String>>,
+    /// Kind e genes de cada variante, pelo nome da variante
+    variants: HashMap<String, (String, SpecieTable)>,
+    errors: Vec<TypeError>,
+}
+
+/// Nomes visíveis e o ethics sendo verificado, se houver.
+#[derive(Default)]
+struct Scope {
+    locals: HashMap<String, Type>,
+    this: Option<String>,
+    ethics: Option<(String, Option<Type>)>,
+}
+
+/// Tipos dos argumentos de uma chamada, com o trecho de cada um.
+enum ArgTypes {
+    Positional(Vec<(Type, Span)>),
+    Named(Vec<(String, Type, Span)>),
+}
+
+impl ArgTypes {
+    fn len(&self) -> usize {
+        match self {
+            ArgTypes::Positional(args) => args.len(),
+            ArgTypes::Named(args) => args.len(),
+        }
+    }
+
+    fn positional(&self) -> Vec<(Type, Span)> {
+        match self {
+            ArgTypes::Positional(args) => args.clone(),
+            ArgTypes::Named(args) => args.iter().map(|(_, ty, span)| (ty.clone(), *span)).collect(),
+        }
+    }
+
+    /// Cada argumento ao lado do tipo declarado do gene que ele preenche
+    fn genes<'a>(&'a self, genes: &'a [(String, Type)]) -> Vec<(&'a Type, &'a Type)> {
+        match self {
+            ArgTypes::Positional(args) => genes.iter().zip(args).map(|((_, gene), (found, _))| (gene, found)).collect(),
+            ArgTypes::Named(args) => args
+                .iter()
+                .filter_map(|(name, found, _)| {
+                    genes.iter().find(|(gene, _)| gene == name).map(|(_, gene)| (gene, found))
+                })
+                .collect(),
+        }
+    }
+}
+
+/// O que um método embutido espera em cada argumento
+#[derive(Clone, Copy)]
+enum Param {
+    Any,
+    Numeric,
+    Exactly(&'static Type),
+}
+
+/// O que um método embutido devolve
+enum Ret {
+    Fixed(Type),
+    Receiver,
+    /// `Int` quando receptor e argumento são `Int`, senão `Decimal`
+    Arithmetic,
+    /// O tipo dos itens da lista ou dos valores do mapa
+    Element,
+    /// Lista com os valores do mapa
+    Values,
+    /// O receptor com mais um item; se o item não combina, os itens viram `Unknown`
+    Grown,
+}
+
+impl TypeChecker {
+    /// Monta a tabela de espécies de todos os bugs, kinds e contratos do programa
+    pub fn new(fly: &Fly) -> Self {
+        let bugs: Vec<&Bug> = fly
+            .strand
+            .genome
+            .iter()
+            .filter_map(|genome| match genome {
+                Genome::Anatomy(Anatomy::Bug(bug)) => Some(bug),
+                _ => None,
+            })
+            .collect();
+        let kinds: Vec<&Kind> = fly
+            .strand
+            .genome
+            .iter()
+            .filter_map(|genome| match genome {
+                Genome::Anatomy(Anatomy::Kind(kind)) => Some(kind),
+                _ => None,
+            })
+            .collect();
+
+        let contracts: Vec<&Contract> = fly
+            .strand
+            .genome
+            .iter()
+            .filter_map(|genome| match genome {
+                Genome::Anatomy(Anatomy::Contract(contract)) => Some(contract),
+                _ => None,
+            })
+            .collect();
+
+        let mut checker =
+            TypeChecker { species: HashMap::new(), kinds: HashMap::new(), variants: HashMap::new(), errors: Vec::new() };
+        for bug in &bugs {
+            let params = bug.params.iter().map(|param| param.raw.clone()).collect();
+            checker.species.insert(bug.specie.raw.clone(), SpecieTable { params, ..SpecieTable::default() });
+        }
+        for contract in &contracts {
+            checker.species.insert(contract.specie.raw.clone(), SpecieTable { contract: true, ..SpecieTable::default() });
+        }
+        for kind in &kinds {
+            let names = kind.variants.iter().map(|variant| variant.specie.raw.clone()).collect();
+            checker.kinds.insert(kind.specie.raw.clone(), names);
+            for variant in &kind.variants {
+                checker.variants.insert(variant.specie.raw.clone(), (kind.specie.raw.clone(), SpecieTable::default()));
+            }
+        }
+        for kind in &kinds {
+            for variant in &kind.variants {
+                let genes = variant.genes.iter().map(|gene| (gene.tag.raw.clone(), checker.resolve(&gene.specie, &[]))).collect();
+                let table = SpecieTable { genes, ..SpecieTable::default() };
+                checker.variants.insert(variant.specie.raw.clone(), (kind.specie.raw.clone(), table));
+            }
+        }
+        for contract in &contracts {
+            let ethics = contract.ethics.iter().map(|item| (item.tag.raw.clone(), checker.ethics_type(item, &[]))).collect();
+            checker.species.insert(contract.specie.raw.clone(), SpecieTable { ethics, contract: true, ..SpecieTable::default() });
+        }
+        for bug in &bugs {
+            let type_params = checker.species[&bug.specie.raw].params.clone();
+            let genes =
+                bug.genes.iter().map(|gene| (gene.tag.raw.clone(), checker.resolve(&gene.specie, &type_params))).collect();
+            let mut ethics = HashMap::new();
+            for item in &bug.ethics {
+                ethics.insert(item.tag.raw.clone(), checker.ethics_type(item, &type_params));
+            }
+            let table = SpecieTable { params: type_params, genes, ethics, ..SpecieTable::default() };
+            checker.species.insert(bug.specie.raw.clone(), table);
+        }
+
+        // Herança: genes e ethics dos ancestrais, com os do próprio bug por cima
+        let parents: HashMap<String, Vec<String>> = bugs
+            .iter()
+            .map(|bug| (bug.specie.raw.clone(), bug.parents.iter().map(|parent| parent.raw.clone()).collect()))
+            .collect();
+        let own = checker.species.clone();
+        for bug in &bugs {
+            let name = &bug.specie.raw;
+            let mut table = own[name].clone();
+            table.genes.clear();
+            table.ethics.clear();
+            for ancestor in lineage(&parents, name).iter().rev() {
+                // Os parâmetros de tipo de um pai genérico ficam `Unknown`
+                let source = if ancestor == name { own[ancestor].clone() } else { own[ancestor].instance(&[]) };
+                for (gene, ty) in source.genes {
+                    match table.genes.iter_mut().find(|(known, _)| *known == gene) {
+                        Some(slot) => slot.1 = ty,
+                        None => table.genes.push((gene, ty)),
+                    }
+                }
+                table.ethics.extend(source.ethics);
+                if ancestor != name {
+                    table.supers.push(ancestor.clone());
+                }
+                let contracts = parents[ancestor].iter().filter(|parent| own.get(*parent).is_some_and(|parent| parent.contract));
+                table.supers.extend(contracts.cloned());
+            }
+            checker.species.insert(name.clone(), table);
+        }
+        checker
+    }
+
+    /// Parâmetros e feedback declarados em um ethics
+    fn ethics_type(&mut self, ethics: &Ethics, type_params: &[String]) -> EthicsType {
+        let params = ethics
+            .signature
+            .iter()
+            .flat_map(|signature| signature.binds.iter().flatten())
+            .map(|bind| (bind.tag.raw.clone(), self.resolve(&bind.specie, type_params)))
+            .collect();
+        let feedback = ethics.feedback.as_ref().map(|specie| self.resolve(specie, type_params));
+        EthicsType { params, feedback }
+    }
+
+    /// `found` pode ocupar um lugar declarado como `expected`, contando
+    /// ancestrais e contratos
+    fn accepts(&self, expected: &Type, found: &Type) -> bool {
+        expected.accepts_with(found, &|expected, found| {
+            self.species.get(found).is_some_and(|table| table.supers.iter().any(|name| name == expected))
+        })
+    }
+
+    /// Cada contrato citado em `bug Dog < Falante` precisa de todos os seus
+    /// ethics no bug (próprios ou herdados), com assinatura compatível
+    fn check_contracts(&mut self, bug: &Bug, table: &SpecieTable) {
+        for parent in &bug.parents {
+            let Some(contract) = self.species.get(&parent.raw).filter(|table| table.contract).cloned() else {
+                continue;
+            };
+            let mut required: Vec<(&String, &EthicsType)> = contract.ethics.iter().collect();
+            required.sort_by_key(|(name, _)| *name);
+            for (name, expected) in required {
+                let Some(found) = table.ethics.get(name) else {
+                    self.errors.push(TypeError::MissingEthics {
+                        specie: bug.specie.raw.clone(),
+                        contract: parent.raw.clone(),
+                        ethics: name.clone(),
+                        span: parent.span,
+                    });
+                    continue;
+                };
+                if !self.implements(expected, found) {
+                    let own = bug.ethics.iter().find(|ethics| ethics.tag.raw == *name);
+                    self.errors.push(TypeError::IncompatibleEthics {
+                        specie: bug.specie.raw.clone(),
+                        contract: parent.raw.clone(),
+                        ethics: name.clone(),
+                        expected: expected.to_string(),
+                        found: found.to_string(),
+                        span: own.map_or(parent.span, |ethics| ethics.tag.span),
+                    });
+                }
+            }
+        }
+    }
+
+    /// Mesmos parâmetros, pelo nome e na ordem, aceitando o que o contrato
+    /// aceita; o feedback precisa caber no do contrato
+    fn implements(&self, expected: &EthicsType, found: &EthicsType) -> bool {
+        let params = expected.params.len() == found.params.len()
+            && expected.params.iter().zip(&found.params).all(|((expected_name, expected), (found_name, found))| {
+                expected_name == found_name && self.accepts(found, expected)
+            });
+        let feedback = match (&expected.feedback, &found.feedback) {
+            (None, _) => true,
+            (Some(expected), Some(found)) => self.accepts(expected, found),
+            (Some(_), None) => false,
+        };
+        params && feedback
+    }
+
+    pub fn specie(&self, name: &str) -> Option<&SpecieTable> {
+        self.species.get(name)
+    }
+
+    /// Tipo declarado por uma espécie (`Int`, `Dog`, `List<Int>`...); `params`
+    /// são os parâmetros de tipo do bug em que ela aparece
+    fn resolve(&mut self, specie_ref: &SpecieRef, params: &[String]) -> Type {
+        let specie = &specie_ref.name;
+        let args: Vec<Type> = specie_ref.args.iter().map(|arg| self.resolve(arg, params)).collect();
+        let arity = match specie.raw.as_str() {
+            name if params.iter().any(|param| param == name) => 0,
+            "List" => 1,
+            "Map" => 2,
+            name => self.species.get(name).map_or(0, |table| table.params.len()),
+        };
+        // Sem argumentos vale sempre: `List` é uma lista de qualquer coisa
+        if !args.is_empty() && args.len() != arity {
+            self.errors.push(TypeError::TypeArity {
+                specie: specie.raw.clone(),
+                expected: arity,
+                found: args.len(),
+                span: specie_ref.span,
+            });
+        }
+
+        if params.contains(&specie.raw) {
+            return Type::Param(specie.raw.clone());
+        }
+        match (specie.raw.as_str(), args.as_slice()) {
+            ("List", [item]) => return Type::List(Box::new(item.clone())),
+            ("Map", [key, value]) => {
+                self.expect(&Type::String, key.clone(), specie_ref.args[0].span);
+                return Type::Map(Box::new(value.clone()));
+            }
+            _ => {}
+        }
+        if let Some(ty) = Type::builtin(&specie.raw) {
+            return ty;
+        }
+        if self.species.contains_key(&specie.raw) {
+            let args = if args.len() == arity { args } else { Vec::new() };
+            return Type::Bug(specie.raw.clone(), args);
+        }
+        if self.kinds.contains_key(&specie.raw) {
+            return Type::Kind(specie.raw.clone());
+        }
+        // Uma variante como tipo vale pelo seu kind
+        if let Some((kind, _)) = self.variants.get(&specie.raw) {
+            return Type::Kind(kind.clone());
+        }
+        self.errors.push(TypeError::UnknownSpecie { name: specie.raw.clone(), span: specie.span });
+        Type::Unknown
+    }
+
+    fn check_fly(&mut self, fly: &Fly) {
+        let mut globals = Scope::default();
+        for genome in &fly.strand.genome {
+            match genome {
+                Genome::Anatomy(Anatomy::Bug(bug)) => self.check_bug(bug),
+                Genome::Behavior(behavior) => {
+                    self.check_behavior(behavior, &mut globals);
+                }
+                // Os genes das variantes já foram conferidos em `new`; os nomes
+                // importados só existem no programa ligado por `module`
+                Genome::Anatomy(Anatomy::Kind(_) | Anatomy::Contract(_)) | Genome::Import(_) | Genome::Error(_) => {}
+            }
+        }
+    }
+
+    fn check_bug(&mut self, bug: &Bug) {
+        let Some(table) = self.species.get(&bug.specie.raw).cloned() else {
+            return;
+        };
+        self.check_contracts(bug, &table);
+        for ethics in &bug.ethics {
+            let Some(body) = &ethics.body else {
+                continue;
+            };
+            let signature = &table.ethics[&ethics.tag.raw];
+            let mut scope = Scope {
+                locals: signature.params.iter().cloned().collect(),
+                this: Some(bug.specie.raw.clone()),
+                ethics: Some((ethics.tag.raw.clone(), signature.feedback.clone())),
+            };
+            let last = self.check_matrix(body, &mut scope);
+
+            // O valor do último sinal também é devolvido
+            if let (Some(expected), Some(Signal::Behavior(behavior))) = (&signature.feedback, body.signals.last())
+                && !self.accepts(expected, &last)
+            {
+                self.errors.push(TypeError::Return {
+                    ethics: ethics.tag.raw.clone(),
+                    expected: expected.clone(),
+                    found: last,
+                    span: behavior.span(),
+                });
+            }
+        }
+    }
+
+    fn check_matrix(&mut self, matrix: &Matrix, scope: &mut Scope) -> Type {
+        let mut last = Type::Nil;
+        for Signal::Behavior(behavior) in &matrix.signals {
+            last = self.check_behavior(behavior, scope);
+        }
+        last
+    }
+
+    /// Tipo do valor produzido pelo comportamento; blocos de controle dão `Unknown`
+    fn check_behavior(&mut self, behavior: &Behavior, scope: &mut Scope) -> Type {
+        match behavior {
+            Behavior::Assign(assign) => {
+                let found = self.infer_oop(&assign.oop, scope);
+                let name = &assign.tag.raw;
+                let gene = match &scope.this {
+                    Some(this) if !scope.locals.contains_key(name) => {
+                        self.species.get(this).and_then(|table| table.gene(name)).cloned()
+                    }
+                    _ => None,
+                };
+                match gene {
+                    Some(expected) if !self.accepts(&expected, &found) => {
+                        self.errors.push(TypeError::GeneAssign {
+                            gene: name.clone(),
+                            expected,
+                            found: found.clone(),
+                            span: assign.oop.span,
+                        });
+                    }
+                    Some(_) => {}
+                    None => {
+                        scope.locals.insert(name.clone(), found.clone());
+                    }
+                }
+                found
+            }
+            Behavior::Oop(oop) => self.infer_oop(oop, scope),
+            Behavior::Statement(statement) => {
+                self.check_statement(statement, scope);
+                Type::Unknown
+            }
+        }
+    }
+
+    fn check_statement(&mut self, statement: &Statement, scope: &mut Scope) {
+        match statement {
+            Statement::If(if_stmt) => {
+                self.infer_condition(&if_stmt.condition, scope);
+                self.check_matrix(&if_stmt.then_block, scope);
+                for elsif in &if_stmt.elsif_blocks {
+                    self.infer_condition(&elsif.condition, scope);
+                    self.check_matrix(&elsif.block, scope);
+                }
+                if let Some(block) = &if_stmt.else_block {
+                    self.check_matrix(block, scope);
+                }
+            }
+            Statement::While(while_stmt) => {
+                self.infer_condition(&while_stmt.condition, scope);
+                self.check_matrix(&while_stmt.block, scope);
+            }
+            Statement::For(for_stmt) => {
+                let iterable = self.infer_oop(&for_stmt.iterable, scope);
+                let item = match iterable {
+                    Type::Int => Type::Int,
+                    // Um mapa é percorrido pelas chaves
+                    Type::String | Type::Map(_) => Type::String,
+                    Type::List(item) => *item,
+                    Type::Unknown => Type::Unknown,
+                    found => {
+                        self.errors.push(TypeError::NotIterable { found, span: for_stmt.iterable.span });
+                        Type::Unknown
+                    }
+                };
+                scope.locals.insert(for_stmt.variable.raw.clone(), item);
+                self.check_matrix(&for_stmt.block, scope);
+            }
+            Statement::Match(match_stmt) => {
+                let kind = match self.infer_oop(&match_stmt.subject, scope) {
+                    Type::Kind(kind) => Some(kind),
+                    Type::Unknown => None,
+                    found => {
+                        self.errors.push(TypeError::NotMatchable { found, span: match_stmt.subject.span });
+                        None
+                    }
+                };
+                let mut covered = Vec::new();
+                for arm in &match_stmt.arms {
+                    for variant in &arm.variants {
+                        let owner = self.variants.get(&variant.raw).map(|(owner, _)| owner);
+                        match (owner, &kind) {
+                            (Some(owner), Some(kind)) if owner != kind => self.errors.push(TypeError::NotAVariant {
+                                name: variant.raw.clone(),
+                                kind: kind.clone(),
+                                span: variant.span,
+                            }),
+                            (Some(_), _) => covered.push(variant.raw.clone()),
+                            (None, Some(kind)) => self.errors.push(TypeError::NotAVariant {
+                                name: variant.raw.clone(),
+                                kind: kind.clone(),
+                                span: variant.span,
+                            }),
+                            (None, None) => {}
+                        }
+                    }
+                    self.check_matrix(&arm.block, scope);
+                }
+                if let Some(block) = &match_stmt.else_block {
+                    self.check_matrix(block, scope);
+                } else if let Some(kind) = kind {
+                    let missing: Vec<String> = self.kinds[&kind]
+                        .iter()
+                        .filter(|variant| !covered.contains(variant))
+                        .map(|variant| format!("`{}`", variant))
+                        .collect();
+                    if !missing.is_empty() {
+                        self.errors.push(TypeError::NonExhaustive {
+                            kind,
+                            missing: missing.join(", "),
+                            span: match_stmt.subject.span,
+                        });
+                    }
+                }
+            }
+            Statement::Return(return_stmt) => {
+                let found = self.infer_oop(&return_stmt.value, scope);
+                if let Some((ethics, Some(expected))) = &scope.ethics
+                    && !expected.accepts(&found)
+                {
+                    self.errors.push(TypeError::Return {
+                        ethics: ethics.clone(),
+                        expected: expected.clone(),
+                        found,
+                        span: return_stmt.value.span,
+                    });
+                }
+            }
+        }
+    }
+
+    fn infer_condition(&mut self, condition: &Condition, scope: &mut Scope) -> Type {
+        match condition {
+            Condition::Oop(oop) => self.infer_oop(oop, scope),
+            Condition::Expr(expr) => self.infer_expr(expr, scope),
+        }
+    }
+
+    fn infer_expr(&mut self, expr: &Expr, scope: &mut Scope) -> Type {
+        match expr {
+            Expr::Oop(oop) => self.infer_oop(oop, scope),
+            Expr::Binary { op: BinaryOp::And | BinaryOp::Or, left, right, .. } => {
+                let left = self.infer_expr(left, scope);
+                let right = self.infer_expr(right, scope);
+                if left == Type::Bool && right == Type::Bool { Type::Bool } else { Type::Unknown }
+            }
+            Expr::Binary { op, left, right, span } => {
+                let left = self.infer_expr(left, scope);
+                let right_span = right.span();
+                let right = self.infer_expr(right, scope);
+                self.send(left, op.method(), ArgTypes::Positional(vec![(right, right_span)]), true, *span)
+            }
+            Expr::Unary { op, operand, span } => {
+                let operand = self.infer_expr(operand, scope);
+                self.send(operand, op.method(), ArgTypes::Positional(Vec::new()), false, *span)
+            }
+        }
+    }
+
+    fn infer_oop(&mut self, oop: &Oop, scope: &mut Scope) -> Type {
+        let mut trails = oop.trails.iter().peekable();
+        let leading = match trails.peek() {
+            Some(Trail::Carrier(carrier)) => {
+                trails.next();
+                Some(carrier)
+            }
+            _ => None,
+        };
+
+        let mut ty = match (&oop.emitter, leading) {
+            (Emitter::Literal(literal), None) => {
+                // Um `u64` acima de `i64::MAX` é um literal válido, mas não um `Int`
+                if literal.get_integer().is_some() && literal.get_int_value().is_none() {
+                    self.errors.push(TypeError::IntOverflow { raw: literal.get_raw().to_string(), span: literal.span() });
+                }
+                literal_type(literal)
+            }
+            (Emitter::Template(template), None) => {
+                for oop in template.oops() {
+                    self.infer_oop(oop, scope);
+                }
+                Type::String
+            }
+            (Emitter::List(list), None) => {
+                let items: Vec<Type> = list.items.iter().map(|item| self.infer_oop(item, scope)).collect();
+                Type::List(Box::new(common(items)))
+            }
+            (Emitter::Map(map), None) => {
+                let values: Vec<Type> = map.entries.iter().map(|entry| self.infer_oop(&entry.oop, scope)).collect();
+                Type::Map(Box::new(common(values)))
+            }
+            (Emitter::SelfRef(self_ref), None) => match &scope.this {
+                Some(this) => self.this_type(this),
+                None => {
+                    self.errors.push(TypeError::UndefinedName { name: "$".to_string(), span: self_ref.span });
+                    Type::Unknown
+                }
+            },
+            (Emitter::Specie(specie), Some(carrier)) => {
+                let args = self.infer_carrier(carrier, scope);
+                self.instantiate(specie, args, carrier.span)
+            }
+            (Emitter::Specie(specie), None) => match self.variants.get(&specie.raw) {
+                // `Red` já é um valor do kind; `Circulo` precisa dos genes
+                Some((kind, table)) if table.genes.is_empty() => Type::Kind(kind.clone()),
+                Some(_) => Type::Specie(specie.raw.clone()),
+                None if Type::builtin(&specie.raw).is_none()
+                    && !self.species.contains_key(&specie.raw)
+                    && !self.kinds.contains_key(&specie.raw) =>
+                {
+                    self.errors.push(TypeError::UnknownSpecie { name: specie.raw.clone(), span: specie.span });
+                    Type::Unknown
+                }
+                None => Type::Specie(specie.raw.clone()),
+            },
+            (Emitter::Tag(tag), Some(carrier)) => {
+                let args = self.infer_carrier(carrier, scope);
+                self.call_function(&tag.raw, args, scope, tag.span.merge(&carrier.span))
+            }
+            (Emitter::Tag(tag), None) => self.lookup(&tag.raw, scope, tag.span),
+            (emitter, Some(carrier)) => {
+                let found = match emitter {
+                    Emitter::Literal(literal) => literal_type(literal),
+                    Emitter::Template(_) => Type::String,
+                    Emitter::List(_) => Type::builtin("List").unwrap_or(Type::Unknown),
+                    Emitter::Map(_) => Type::builtin("Map").unwrap_or(Type::Unknown),
+                    _ => scope.this.as_ref().map_or(Type::Unknown, |this| self.this_type(this)),
+                };
+                self.errors.push(TypeError::NotCallable { found, span: carrier.span });
+                Type::Unknown
+            }
+        };
+
+        for trail in trails {
+            ty = match trail {
+                Trail::Catalysis(catalysis) => {
+                    let args = match &catalysis.carrier {
+                        Some(carrier) => self.infer_carrier(carrier, scope),
+                        None => ArgTypes::Positional(Vec::new()),
+                    };
+                    self.send(ty, &catalysis.tag.raw, args, catalysis.carrier.is_some(), catalysis.span)
+                }
+                Trail::Carrier(carrier) => {
+                    if ty != Type::Unknown {
+                        self.errors.push(TypeError::NotCallable { found: ty, span: carrier.span });
+                    }
+                    Type::Unknown
+                }
+            };
+        }
+        ty
+    }
+
+    fn infer_carrier(&mut self, carrier: &Carrier, scope: &mut Scope) -> ArgTypes {
+        match &carrier.transport {
+            None => ArgTypes::Positional(Vec::new()),
+            Some(Transport::Sequence(sequence)) => ArgTypes::Positional(
+                sequence.oops.iter().map(|oop| (self.infer_oop(oop, scope), oop.span)).collect(),
+            ),
+            Some(Transport::Binds(binds)) => ArgTypes::Named(
+                binds
+                    .binds
+                    .iter()
+                    .map(|bind| (bind.tag.raw.clone(), self.infer_oop(&bind.oop, scope), bind.span))
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Nome solto: variável local, gene ou ethics sem argumentos da instância
+    fn lookup(&mut self, name: &str, scope: &Scope, span: Span) -> Type {
+        if let Some(ty) = scope.locals.get(name) {
+            return ty.clone();
+        }
+        if let Some(table) = scope.this.as_ref().and_then(|this| self.species.get(this)).cloned() {
+            if let Some(ty) = table.gene(name) {
+                return ty.clone();
+            }
+            if let Some(ethics) = table.ethics.get(name) {
+                return self.check_call(name, ethics, ArgTypes::Positional(Vec::new()), span);
+            }
+        }
+        self.errors.push(TypeError::UndefinedName { name: name.to_string(), span });
+        Type::Unknown
+    }
+
+    /// `nome(args)`: ethics da própria instância ou função embutida
+    fn call_function(&mut self, name: &str, args: ArgTypes, scope: &Scope, span: Span) -> Type {
+        let own = scope.this.as_ref().and_then(|this| self.species.get(this)).and_then(|table| table.ethics.get(name));
+        if let Some(ethics) = own.cloned() {
+            return self.check_call(name, &ethics, args, span);
+        }
+        match name {
+            "print" => Type::Nil,
+            "list" => Type::List(Box::new(Type::Unknown)),
+            _ => {
+                self.errors.push(TypeError::UndefinedName { name: name.to_string(), span });
+                Type::Unknown
+            }
+        }
+    }
+
+    fn send(&mut self, receiver: Type, name: &str, args: ArgTypes, called: bool, span: Span) -> Type {
+        match &receiver {
+            Type::Unknown => return Type::Unknown,
+            Type::Bug(specie, type_args) => {
+                let table = self.species.get(specie).map(|table| table.instance(type_args)).unwrap_or_default();
+                if let Some(ethics) = table.ethics.get(name) {
+                    return self.check_call(name, ethics, args, span);
+                }
+                if !called && let Some(ty) = table.gene(name) {
+                    return ty.clone();
+                }
+            }
+            Type::Kind(kind) if !called => {
+                if let Some(ty) = self.variant_gene(kind, name) {
+                    return ty;
+                }
+            }
+            Type::Specie(specie) if name == "new" => {
+                let specie = Specie { raw: specie.clone(), span };
+                return self.instantiate(&specie, args, span);
+            }
+            _ => {}
+        }
+        self.builtin_method(receiver, name, args, span)
+    }
+
+    /// Tipo de `$` no bug `this`
+    fn this_type(&self, this: &str) -> Type {
+        self.species.get(this).map_or(Type::Unknown, |table| table.this(this))
+    }
+
+    /// Gene lido de um valor do kind: o tipo com que as variantes o declaram,
+    /// ou `Unknown` se elas discordam
+    fn variant_gene(&self, kind: &str, name: &str) -> Option<Type> {
+        let mut types = self.kinds.get(kind)?.iter().filter_map(|variant| self.variants[variant].1.gene(name));
+        let first = types.next()?.clone();
+        Some(if types.all(|ty| *ty == first) { first } else { Type::Unknown })
+    }
+
+    /// Confere os argumentos contra a assinatura e devolve o feedback
+    fn check_call(&mut self, name: &str, ethics: &EthicsType, args: ArgTypes, span: Span) -> Type {
+        let result = ethics.feedback.clone().unwrap_or(Type::Unknown);
+        if args.len() != ethics.params.len() {
+            self.errors.push(TypeError::Arity {
+                ethics: name.to_string(),
+                expected: ethics.params.len(),
+                found: args.len(),
+                span,
+            });
+            return result;
+        }
+        match args {
+            ArgTypes::Positional(args) => {
+                for ((_, expected), (found, arg_span)) in ethics.params.iter().zip(args) {
+                    self.expect(expected, found, arg_span);
+                }
+            }
+            ArgTypes::Named(args) => {
+                for (arg, found, arg_span) in args {
+                    match ethics.params.iter().find(|(param, _)| *param == arg) {
+                        Some((_, expected)) => self.expect(expected, found, arg_span),
+                        None => self.errors.push(TypeError::UnknownArgument {
+                            ethics: name.to_string(),
+                            name: arg,
+                            span: arg_span,
+                        }),
+                    }
+                }
+            }
+        }
+        result
+    }
+
+    /// `Dog.new(...)`/`Dog(...)`: argumentos posicionais seguem a ordem dos
+    /// genes. Uma variante (`Circulo(raio: 1.0)`) dá um valor do seu kind.
+    /// Num bug genérico os argumentos de tipo saem dos valores dos genes:
+    /// `Caixa(1)` é uma `Caixa<Int>`.
+    fn instantiate(&mut self, specie: &Specie, args: ArgTypes, span: Span) -> Type {
+        if self.species.get(&specie.raw).is_some_and(|table| table.contract) {
+            self.errors.push(TypeError::ContractInstance { name: specie.raw.clone(), span });
+            return Type::Unknown;
+        }
+        let (table, ty) = match (self.species.get(&specie.raw), self.variants.get(&specie.raw)) {
+            (Some(table), _) if table.params.is_empty() => (table.clone(), Type::Bug(specie.raw.clone(), Vec::new())),
+            (Some(table), _) => {
+                let mut bindings = HashMap::new();
+                for (gene, found) in args.genes(&table.genes) {
+                    bind_params(gene, found, &mut bindings);
+                }
+                let args: Vec<Type> =
+                    table.params.iter().map(|param| bindings.remove(param).unwrap_or(Type::Unknown)).collect();
+                (table.instance(&args), Type::Bug(specie.raw.clone(), args))
+            }
+            (None, Some((kind, table))) => (table.clone(), Type::Kind(kind.clone())),
+            (None, None) => {
+                self.errors.push(TypeError::UnknownSpecie { name: specie.raw.clone(), span: specie.span });
+                return Type::Unknown;
+            }
+        };
+        match args {
+            ArgTypes::Positional(args) => {
+                if args.len() > table.genes.len() {
+                    self.errors.push(TypeError::Arity {
+                        ethics: format!("{}.new", specie.raw),
+                        expected: table.genes.len(),
+                        found: args.len(),
+                        span,
+                    });
+                }
+                for ((_, expected), (found, arg_span)) in table.genes.iter().zip(args) {
+                    self.expect(expected, found, arg_span);
+                }
+            }
+            ArgTypes::Named(args) => {
+                for (name, found, arg_span) in args {
+                    match table.gene(&name) {
+                        Some(expected) => self.expect(expected, found, arg_span),
+                        None => self.errors.push(TypeError::UnknownGene {
+                            specie: specie.raw.clone(),
+                            name,
+                            span: arg_span,
+                        }),
+                    }
+                }
+            }
+        }
+        ty
+    }
+
+    fn expect(&mut self, expected: &Type, found: Type, span: Span) {
+        if !self.accepts(expected, &found) {
+            self.errors.push(TypeError::Mismatch { expected: expected.clone(), found, span });
+        }
+    }
+
+    /// Métodos das espécies embutidas, espelhando o host `Builtins`
+    fn builtin_method(&mut self, receiver: Type, name: &str, args: ArgTypes, span: Span) -> Type {
+        let Some((params, ret)) = builtin_signature(&receiver, name) else {
+            self.errors.push(TypeError::UnknownMethod { receiver, method: name.to_string(), span });
+            return Type::Unknown;
+        };
+        let args = args.positional();
+        if args.len() != params.len() {
+            self.errors.push(TypeError::Arity {
+                ethics: name.to_string(),
+                expected: params.len(),
+                found: args.len(),
+                span,
+            });
+            return Type::Unknown;
+        }
+
+        let mut all_int = receiver == Type::Int;
+        let added = args.last().map_or(Type::Unknown, |(found, _)| found.clone());
+        for (param, (found, arg_span)) in params.iter().zip(args) {
+            match param {
+                Param::Any => {}
+                Param::Numeric => {
+                    all_int &= found == Type::Int;
+                    if !found.is_numeric() && found != Type::Unknown {
+                        self.errors.push(TypeError::Mismatch { expected: Type::Decimal, found, span: arg_span });
+                    }
+                }
+                Param::Exactly(expected) => self.expect(expected, found, arg_span),
+            }
+        }
+
+        match ret {
+            Ret::Fixed(ty) => ty,
+            Ret::Receiver => receiver,
+            Ret::Element => match receiver {
+                Type::List(item) | Type::Map(item) => *item,
+                _ => Type::Unknown,
+            },
+            Ret::Values => match receiver {
+                Type::Map(value) => Type::List(value),
+                _ => Type::Unknown,
+            },
+            Ret::Grown => match receiver {
+                Type::List(item) if self.accepts(&item, &added) => Type::List(item),
+                Type::Map(value) if self.accepts(&value, &added) => Type::Map(value),
+                Type::List(_) => Type::List(Box::new(Type::Unknown)),
+                _ => Type::Map(Box::new(Type::Unknown)),
+            },
+            Ret::Arithmetic if all_int => Type::Int,
+            Ret::Arithmetic => Type::Decimal,
+        }
+    }
+}
+
+fn builtin_signature(receiver: &Type, name: &str) -> Option<(Vec<Param>, Ret)> {
+    let signature = match (receiver, name) {
+        (Type::Specie(_), _) => return None,
+        (_, "eq" | "ne") => (vec![Param::Any], Ret::Fixed(Type::Bool)),
+        (_, "to_s") => (vec![], Ret::Fixed(Type::String)),
+        (_, "is_nil") => (vec![], Ret::Fixed(Type::Bool)),
+        (Type::Int | Type::Decimal, "plus" | "minus" | "times" | "div" | "mod") => (vec![Param::Numeric], Ret::Arithmetic),
+        (Type::Int | Type::Decimal, "lt" | "le" | "bt" | "be") => (vec![Param::Numeric], Ret::Fixed(Type::Bool)),
+        (Type::Int | Type::Decimal, "neg" | "abs") => (vec![], Ret::Receiver),
+        (Type::String, "plus") => (vec![Param::Exactly(&Type::String)], Ret::Fixed(Type::String)),
+        (Type::String, "size") => (vec![], Ret::Fixed(Type::Int)),
+        (Type::String, "upcase" | "downcase") => (vec![], Ret::Fixed(Type::String)),
+        (Type::String, "includes") => (vec![Param::Exactly(&Type::String)], Ret::Fixed(Type::Bool)),
+        (Type::Bool | Type::Nil, "not") => (vec![], Ret::Fixed(Type::Bool)),
+        (Type::Bool, "and" | "or") => (vec![Param::Any], Ret::Fixed(Type::Bool)),
+        (Type::List(_), "size") => (vec![], Ret::Fixed(Type::Int)),
+        (Type::List(_), "first" | "last") => (vec![], Ret::Element),
+        (Type::List(_), "at") => (vec![Param::Exactly(&Type::Int)], Ret::Element),
+        (Type::List(_), "push") => (vec![Param::Any], Ret::Grown),
+        (Type::List(_), "includes") => (vec![Param::Any], Ret::Fixed(Type::Bool)),
+        (Type::Map(_), "size") => (vec![], Ret::Fixed(Type::Int)),
+        (Type::Map(_), "at") => (vec![Param::Exactly(&Type::String)], Ret::Element),
+        (Type::Map(_), "has") => (vec![Param::Exactly(&Type::String)], Ret::Fixed(Type::Bool)),
+        (Type::Map(_), "keys") => (vec![], Ret::Fixed(Type::List(Box::new(Type::String)))),
+        (Type::Map(_), "values") => (vec![], Ret::Values),
+        (Type::Map(_), "put") => (vec![Param::Exactly(&Type::String), Param::Any], Ret::Grown),
+        _ => return None,
+    };
+    Some(signature)
+}
+
+/// O bug e os ancestrais, do mais próximo ao mais distante; o pai é o
+/// primeiro de `parents` que é um bug, como na execução, e um ciclo para na
+/// primeira repetição
+fn lineage(parents: &HashMap<String, Vec<String>>, name: &str) -> Vec<String> {
+    let mut lineage: Vec<String> = Vec::new();
+    let mut next = Some(name.to_string());
+    while let Some(current) = next.filter(|current| !lineage.contains(current)) {
+        next = parents.get(&current).and_then(|list| list.iter().find(|parent| parents.contains_key(*parent)).cloned());
+        lineage.push(current);
+    }
+    lineage
+}
+
+/// Descobre os parâmetros de tipo comparando o tipo declarado com o valor;
+/// vale o primeiro valor que diz algo sobre o parâmetro
+fn bind_params(expected: &Type, found: &Type, bindings: &mut HashMap<String, Type>) {
+    match (expected, found) {
+        (_, Type::Unknown | Type::Nil) => {}
+        (Type::Param(name), found) => {
+            bindings.entry(name.clone()).or_insert_with(|| found.clone());
+        }
+        (Type::List(expected), Type::List(found)) | (Type::Map(expected), Type::Map(found)) => {
+            bind_params(expected, found, bindings)
+        }
+        (Type::Bug(expected, expected_args), Type::Bug(found, found_args)) if expected == found => {
+            for (expected, found) in expected_args.iter().zip(found_args) {
+                bind_params(expected, found, bindings);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Tipo comum aos itens de uma lista ou mapa literal; `Unknown` se não há
+/// itens ou se eles discordam
+fn common(types: Vec<Type>) -> Type {
+    let mut types = types.into_iter();
+    let Some(first) = types.next() else {
+        return Type::Unknown;
+    };
+    if types.all(|ty| ty == first) { first } else { Type::Unknown }
+}
+
+fn literal_type(literal: &Literal) -> Type {
+    match literal {
+        Literal::Int { .. } | Literal::Hex { .. } | Literal::Bit { .. } => Type::Int,
+        Literal::Decimal { .. } => Type::Decimal,
+        Literal::Str { .. } => Type::String,
+        Literal::Bool { .. } => Type::Bool,
+        Literal::Nil { .. } => Type::Nil,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_tree, Type, TypeError};
+    use crate::dsl::parser::tree::Tree;
+    use crate::tests::load_fragment;
+
+    fn errors(path: &str) -> Vec<TypeError> {
+        let tree = Tree::parse_input(load_fragment(path)).expect("Fixture should parse");
+        check_tree(&tree)
+    }
+
+    #[test]
+    fn test_well_typed_programs_have_no_errors() {
+        for path in ["interp/dog.sc", "interp/loops.sc", "interp/kinds.sc", "literal/collections.sc", "literal/template.sc", "typeck/generics.sc", "typeck/inheritance.sc"] {
+            let messages: Vec<String> = errors(path).iter().map(|e| e.to_string()).collect();
+            assert!(messages.is_empty(), "{}: {:?}", path, messages);
+        }
+    }
+
+    #[test]
+    fn test_reports_every_mismatch_with_spans() {
+        let found: Vec<(String, usize)> =
+            errors("typeck/mismatches.sc").iter().map(|e| (e.to_string(), e.span().line)).collect();
+
+        assert_eq!(
+            found,
+            vec![
+                ("cannot find species `Person`".to_string(), 4),
+                ("mismatched types: expected Decimal, found String".to_string(), 7),
+                ("`latir` returns String, found Int".to_string(), 8),
+                ("cannot assign String to gene `energia` of type Int".to_string(), 10),
+                ("`idade` returns Int, found String".to_string(), 15),
+                ("mismatched types: expected String, found Int".to_string(), 19),
+                ("Dog has no gene `peso`".to_string(), 19),
+                ("mismatched types: expected Int, found String".to_string(), 20),
+                ("no method `correr` on Dog".to_string(), 21),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unknown_names_and_arity() {
+        let tree = Tree::parse_input("x = y.plus(1)\nz = 1.plus(2, 3)\nprint($)".to_string()).expect("Should parse");
+
+        let found: Vec<String> = check_tree(&tree).iter().map(|e| e.to_string()).collect();
+
+        assert_eq!(
+            found,
+            vec![
+                "cannot find `y` in this scope",
+                "`plus` takes 1 argument(s) but 2 were given",
+                "cannot find `$` in this scope",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_arithmetic_result_types() {
+        assert!(Type::Decimal.accepts(&Type::Int));
+        assert!(!Type::Int.accepts(&Type::Decimal));
+
+        let tree = Tree::parse_input("a = 1.plus(2.5)\nb = a.times(2)\nc = b.plus(\"x\")".to_string()).expect("Should parse");
+        let found: Vec<String> = check_tree(&tree).iter().map(|e| e.to_string()).collect();
+
+        assert_eq!(found, vec!["mismatched types: expected Decimal, found String"]);
+    }
+
+    #[test]
+    fn test_literal_types() {
+        let tree = Tree::parse_input("a = [1, true]\nb = {n: nil}\nfor k in b\n  k.plus(1)\nend\nc = a.plus(b)\nd = false.to_s.plus(1)".to_string())
+            .expect("Should parse");
+
+        let found: Vec<String> = check_tree(&tree).iter().map(|e| e.to_string()).collect();
+
+        assert_eq!(found, vec![
+                "mismatched types: expected String, found Int",
+                "no method `plus` on List",
+                "mismatched types: expected String, found Int",
+            ]);
+    }
+
+    #[test]
+    fn test_u64_literals_must_fit_in_int() {
+        let tree = Tree::parse_input("a = 9223372036854775807u64\nb = 18446744073709551615u64".to_string()).expect("Should parse");
+
+        let errors = check_tree(&tree);
+
+        assert_eq!(errors, vec![TypeError::IntOverflow { raw: "18446744073709551615u64".to_string(), span: errors[0].span() }]);
+        assert_eq!(errors[0].span().line, 2);
+        assert_eq!(errors[0].diagnostic().notes, vec!["Int holds values from -9223372036854775808 to 9223372036854775807".to_string()]);
+    }
+
+    #[test]
+    fn test_match_on_kinds() {
+        let kind = "kind Cor\n  Vermelho\n  Verde\n  Azul\nend\n";
+        let cases = [
+            ("match Verde\nwhen Vermelho\n  1\nend", "match on Cor does not cover `Verde`, `Azul`"),
+            ("match Verde\nwhen Vermelho, Roxo\n  1\nelse\n  2\nend", "`Roxo` is not a variant of Cor"),
+            ("match 3\nwhen Vermelho\n  1\nend", "cannot match on Int"),
+        ];
+
+        for (source, message) in cases {
+            let tree = Tree::parse_input(format!("{}{}", kind, source)).expect("Should parse");
+            let found: Vec<String> = check_tree(&tree).iter().map(|e| e.to_string()).collect();
+            assert_eq!(found, vec![message], "source: {}", source);
+        }
+    }
+
+    #[test]
+    fn test_generic_species() {
+        let caixa = "bug Caixa<T>\n  gene valor T\n  gene historico List<T>\n  ethics abrir T\n    valor\n  end\nend\n";
+        let cases = [
+            ("c = Caixa.new(valor: 1, historico: [\"a\"])", "mismatched types: expected List<Int>, found List<String>"),
+            ("c = Caixa(\"a\", [])\nc.abrir.plus(1)", "mismatched types: expected String, found Int"),
+            ("c = Caixa(1, [])\nc.abrir.upcase", "no method `upcase` on Int"),
+            ("bug Dog\n  gene c Caixa<Int, String>\nend", "`Caixa` takes 1 type argument(s) but 2 were given"),
+            ("bug Dog\n  gene idades Map<Int, String>\nend", "mismatched types: expected String, found Int"),
+            ("bug Dog\n  gene c Caixa<Int>\n  ethics f\n    c = Caixa(\"a\", [])\n  end\nend", "cannot assign Caixa<String> to gene `c` of type Caixa<Int>"),
+        ];
+
+        for (source, message) in cases {
+            let tree = Tree::parse_input(format!("{}{}", caixa, source)).expect("Should parse");
+            let found: Vec<String> = check_tree(&tree).iter().map(|e| e.to_string()).collect();
+            assert_eq!(found, vec![message], "source: {}", source);
+        }
+    }
+
+    #[test]
+    fn test_inheritance_and_contracts() {
+        let falante = "contract Falante\n  ethics falar(volume: Int) String\nend\nbug Animal\n  gene nome String\nend\n";
+        let cases = [
+            ("bug Dog < Falante\nend", vec!["Dog does not implement `falar` from contract Falante"]),
+            (
+                "bug Dog < Falante\n  ethics falar(volume: String) String\n    volume\n  end\nend",
+                vec!["`falar` in Dog does not match contract Falante: expected `(volume: Int) String`, found `(volume: String) String`"],
+            ),
+            (
+                "bug Dog < Falante\n  ethics falar(volume: Int)\nend",
+                vec!["`falar` in Dog does not match contract Falante: expected `(volume: Int) String`, found `(volume: Int)`"],
+            ),
+            (
+                "bug Base\n  ethics falar(volume: Int) String\n    \"au\"\n  end\nend\nbug Dog < Base, Falante\nend\nx = Dog.new.falar(1).plus(1)",
+                vec!["mismatched types: expected String, found Int"],
+            ),
+            (
+                "bug Cat < Animal\nend\nbug Dono\n  gene gato Cat\nend\nDono(Animal(\"Rex\"))",
+                vec!["mismatched types: expected Cat, found Animal"],
+            ),
+            ("bug Cat < Animal\nend\nbug Dono\n  gene bicho Animal\nend\nDono(Cat(nome: 1))", vec!["mismatched types: expected String, found Int"]),
+            ("x = Falante.new", vec!["cannot create Falante: it is a contract"]),
+        ];
+
+        for (source, messages) in cases {
+            let tree = Tree::parse_input(format!("{}{}", falante, source)).expect("Should parse");
+            let found: Vec<String> = check_tree(&tree).iter().map(|e| e.to_string()).collect();
+            assert_eq!(found, messages, "source: {}", source);
+        }
+    }
+}
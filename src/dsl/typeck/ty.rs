@@ -0,0 +1,117 @@
+use std::fmt;
+
+/// Tipo estático de uma expressão SC.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Type {
+    Int,
+    Decimal,
+    String,
+    Bool,
+    Nil,
+    /// Lista com o tipo dos itens; `List` sem argumento é `List(Unknown)`
+    List(Box<Type>),
+    /// Mapa com o tipo dos valores; as chaves são sempre `String`
+    Map(Box<Type>),
+    /// Feedback de ethics que não devolve nada útil; aceita qualquer valor
+    Void,
+    /// Instância de um bug declarado, com os argumentos de tipo se ele for
+    /// genérico (vazio quando não se sabe)
+    Bug(String, Vec<Type>),
+    /// Valor de um `kind`, qualquer que seja a variante
+    Kind(String),
+    /// A espécie em si, como em `Dog.new`
+    Specie(String),
+    /// Parâmetro de tipo dentro do bug genérico que o declara (`T` em `bug Caixa<T>`)
+    Param(String),
+    /// Não dá para saber sem executar; combina com qualquer tipo
+    Unknown,
+}
+
+impl Type {
+    /// Tipo das espécies embutidas; bugs declarados ficam por conta da tabela de espécies
+    pub fn builtin(name: &str) -> Option<Type> {
+        match name {
+            "Int" => Some(Type::Int),
+            "Decimal" => Some(Type::Decimal),
+            "String" => Some(Type::String),
+            "Bool" => Some(Type::Bool),
+            "Nil" => Some(Type::Nil),
+            "List" => Some(Type::List(Box::new(Type::Unknown))),
+            "Map" => Some(Type::Map(Box::new(Type::Unknown))),
+            "Void" => Some(Type::Void),
+            _ => None,
+        }
+    }
+
+    pub fn is_numeric(&self) -> bool {
+        matches!(self, Type::Int | Type::Decimal)
+    }
+
+    /// Um valor `found` pode ocupar um lugar declarado como `self`?
+    ///
+    /// `Int` serve onde se espera `Decimal`, e `nil` serve para qualquer bug ou kind.
+    /// Listas, mapas e bugs genéricos comparam os argumentos do mesmo jeito.
+    pub fn accepts(&self, found: &Type) -> bool {
+        self.accepts_with(found, &|expected, found| expected == found)
+    }
+
+    /// Como [`accepts`](Type::accepts), mas um bug também serve onde se espera
+    /// outra espécie quando `is_a(esperada, encontrada)`: um ancestral ou um
+    /// contrato que ele cumpre
+    pub fn accepts_with(&self, found: &Type, is_a: &dyn Fn(&str, &str) -> bool) -> bool {
+        match (self, found) {
+            (Type::Unknown | Type::Void, _) | (_, Type::Unknown) => true,
+            (Type::Decimal, Type::Int) => true,
+            (Type::Bug(..) | Type::Kind(_), Type::Nil) => true,
+            (Type::List(expected), Type::List(found)) | (Type::Map(expected), Type::Map(found)) => {
+                expected.accepts_with(found, is_a)
+            }
+            (Type::Bug(expected, expected_args), Type::Bug(found, found_args)) if expected == found => {
+                expected_args.iter().zip(found_args).all(|(expected, found)| expected.accepts_with(found, is_a))
+            }
+            (Type::Bug(expected, _), Type::Bug(found, _)) => is_a(expected, found),
+            (expected, found) => expected == found,
+        }
+    }
+
+    /// Troca cada `Param` de `params` pelo argumento na mesma posição
+    pub fn substitute(&self, params: &[String], args: &[Type]) -> Type {
+        match self {
+            Type::Param(name) => match params.iter().position(|param| param == name) {
+                Some(index) => args.get(index).cloned().unwrap_or(Type::Unknown),
+                None => self.clone(),
+            },
+            Type::List(item) => Type::List(Box::new(item.substitute(params, args))),
+            Type::Map(value) => Type::Map(Box::new(value.substitute(params, args))),
+            Type::Bug(name, bug_args) => {
+                Type::Bug(name.clone(), bug_args.iter().map(|arg| arg.substitute(params, args)).collect())
+            }
+            _ => self.clone(),
+        }
+    }
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Type::Int => write!(f, "Int"),
+            Type::Decimal => write!(f, "Decimal"),
+            Type::String => write!(f, "String"),
+            Type::Bool => write!(f, "Bool"),
+            Type::Nil => write!(f, "Nil"),
+            Type::List(item) if **item == Type::Unknown => write!(f, "List"),
+            Type::List(item) => write!(f, "List<{}>", item),
+            Type::Map(value) if **value == Type::Unknown => write!(f, "Map"),
+            Type::Map(value) => write!(f, "Map<String, {}>", value),
+            Type::Void => write!(f, "Void"),
+            Type::Bug(name, args) if args.is_empty() => write!(f, "{}", name),
+            Type::Bug(name, args) => {
+                let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+                write!(f, "{}<{}>", name, args.join(", "))
+            }
+            Type::Kind(name) | Type::Param(name) => write!(f, "{}", name),
+            Type::Specie(name) => write!(f, "species {}", name),
+            Type::Unknown => write!(f, "_"),
+        }
+    }
+}
@@ -0,0 +1,165 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+use crate::dsl::ast::span::Span;
+use crate::dsl::interp::Value;
+
+/// Argumentos de uma chamada, já empilhados: `n` valores ou os valores dos
+/// nomes em `Chunk::keywords[k]`, na ordem do código.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Argc {
+    Positional(u16),
+    Named(u16),
+}
+
+/// Uma instrução. Os operandos apontam para as tabelas do `Chunk` que a contém:
+/// `constants`, `names`, `locals` ou `keywords`; saltos são índices em `code`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    /// Empilha `constants[i]`
+    Const(u32),
+    Nil,
+    Pop,
+    /// Variável local; se ainda não tem valor, procura o nome como `LoadName`
+    Load(u32),
+    /// Atribuição com a regra do interpretador (local, senão gene, senão nova
+    /// local); o valor fica na pilha
+    Store(u32),
+    /// Nome que não é local deste trecho: gene ou ethics sem argumentos de `$`,
+    /// ou variável global no programa principal
+    LoadName(u32),
+    /// `$`
+    LoadSelf,
+    /// A espécie `names[i]` como valor, para `Dog.new`
+    Specie(u32),
+    /// `Dog(args)`
+    New(u32, Argc),
+    /// `"a #{b}"`: junta os `n` valores do topo num texto
+    Concat(u16),
+    /// `[a, b]`: junta os `n` valores do topo numa lista
+    List(u16),
+    /// `{k: v}`: junta os valores do topo num mapa com as chaves de `keywords[k]`
+    Map(u16),
+    /// `nome(args)`: ethics de `$` ou função do host
+    Call(u32, Argc),
+    /// `valor.nome(args)`; operadores binários também viram `Send`
+    Send(u32, Argc),
+    /// `valor.nome` sem parênteses: ethics, gene ou método sem argumentos
+    Get(u32),
+    /// Parênteses depois de um valor que não é função: `x.y(1)(2)`
+    NotCallable,
+    /// Literal inteiro fora do alcance de `Int`; só falha quando executado
+    Overflow,
+    Jump(u32),
+    /// Desempilha a condição e salta se ela for falsa
+    JumpIfFalse(u32),
+    /// `and`: salta mantendo o valor se ele for falso, senão o descarta
+    And(u32),
+    /// `or`: salta mantendo o valor se ele for verdadeiro, senão o descarta
+    Or(u32),
+    /// Desempilha o valor de um `for` e começa a percorrê-lo
+    Iter,
+    /// Salta para `target` se o valor do topo (o sujeito de um `match`, que
+    /// fica na pilha) é a variante `names[i]`
+    When(u32, u32),
+    /// Próximo item do `for` mais interno na local `slot`, ou fim do laço
+    /// (salta para `exit`)
+    Next { slot: u32, exit: u32 },
+    Return,
+}
+
+/// Código de um ethics ou do programa principal, com suas tabelas.
+#[derive(Debug, Clone, Default)]
+pub struct Chunk {
+    /// `main` ou `Especie.ethics`, só para o disassembler
+    pub name: String,
+    pub code: Vec<Op>,
+    /// Trecho do código de origem de cada instrução, para os erros
+    pub spans: Vec<Span>,
+    /// Valores dos literais, sem repetição
+    pub constants: Vec<Value>,
+    /// Nomes de métodos, funções, espécies e genes, sem repetição
+    pub names: Vec<String>,
+    /// Variáveis locais; os parâmetros vêm primeiro
+    pub locals: Vec<String>,
+    /// Nomes (índices em `names`) e spans dos argumentos `nome: valor`
+    pub keywords: Vec<Vec<(u32, Span)>>,
+}
+
+/// Um ethics compilado. Sem corpo, a chamada devolve `nil`.
+#[derive(Debug, Clone)]
+pub struct Function {
+    pub name: String,
+    pub params: Vec<String>,
+    pub body: Option<Rc<Chunk>>,
+}
+
+/// Um bug compilado: genes com suas espécies e ethics pelo nome. As
+/// variantes de um `kind` também viram classes, sem ethics.
+#[derive(Debug, Clone)]
+pub struct Class {
+    pub name: String,
+    /// O kind, se a classe é uma de suas variantes
+    pub kind: Option<String>,
+    /// Espécies depois de `<`; o pai é a primeira que for um bug
+    pub parents: Vec<String>,
+    pub genes: Vec<(String, String)>,
+    pub ethics: HashMap<String, Rc<Function>>,
+    /// Ordem de declaração dos ethics, para o disassembler
+    pub order: Vec<String>,
+}
+
+/// Resultado de [`compile`](fn@super::compile): o programa principal e os bugs
+/// que ele declara.
+#[derive(Debug, Clone)]
+pub struct Program {
+    pub main: Rc<Chunk>,
+    pub classes: Vec<Rc<Class>>,
+}
+
+impl Chunk {
+    pub fn new(name: impl Into<String>) -> Self {
+        Chunk { name: name.into(), ..Chunk::default() }
+    }
+
+    /// Acrescenta uma instrução e devolve seu índice
+    pub fn emit(&mut self, op: Op, span: Span) -> usize {
+        self.code.push(op);
+        self.spans.push(span);
+        self.code.len() - 1
+    }
+
+    /// Índice de um valor em `constants`, acrescentando-o se for novo
+    pub fn constant(&mut self, value: Value) -> u32 {
+        // `1` e `1.0` são iguais para `==`, mas não são a mesma constante
+        let found = self.constants.iter().position(|known| known.specie() == value.specie() && *known == value);
+        index(found.unwrap_or_else(|| {
+            self.constants.push(value);
+            self.constants.len() - 1
+        }))
+    }
+
+    /// Índice de um nome em `names`, acrescentando-o se for novo
+    pub fn name(&mut self, name: &str) -> u32 {
+        let found = self.names.iter().position(|known| known == name);
+        index(found.unwrap_or_else(|| {
+            self.names.push(name.to_string());
+            self.names.len() - 1
+        }))
+    }
+
+    pub fn local(&self, name: &str) -> Option<u32> {
+        self.locals.iter().position(|known| known == name).map(index)
+    }
+
+    /// Número de argumentos de uma chamada
+    pub fn argc(&self, argc: Argc) -> usize {
+        match argc {
+            Argc::Positional(count) => count as usize,
+            Argc::Named(keywords) => self.keywords[keywords as usize].len(),
+        }
+    }
+}
+
+fn index(position: usize) -> u32 {
+    u32::try_from(position).expect("chunk tables hold fewer than 2^32 entries")
+}
@@ -0,0 +1,462 @@
+//! Tradução da AST para [`Chunk`]s.
+//!
+//! Todo `Behavior` deixa exatamente um valor na pilha (o mesmo que o
+//! interpretador devolveria para ele); um bloco descarta todos menos o último.
+//! Listas, mapas, templates e chamadas com mais itens do que cabem em uma
+//! instrução viram [`RuntimeError::TooManyItems`].
+
+use std::collections::HashMap;
+use std::rc::Rc;
+use crate::dsl::ast::anatomy::Anatomy;
+use crate::dsl::ast::behavior::assign::Assign;
+use crate::dsl::ast::behavior::oop::Oop;
+use crate::dsl::ast::behavior::transport::Transport;
+use crate::dsl::ast::behavior::{Behavior, Bind, Carrier, Trail};
+use crate::dsl::ast::bug::Bug;
+use crate::dsl::ast::condition::Condition;
+use crate::dsl::ast::emitter::literal::unescape;
+use crate::dsl::ast::emitter::{Emitter, Literal, TemplatePart};
+use crate::dsl::ast::ethics::Ethics;
+use crate::dsl::ast::expr::{BinaryOp, Expr};
+use crate::dsl::ast::fly::Fly;
+use crate::dsl::ast::genome::Genome;
+use crate::dsl::ast::kind::Kind;
+use crate::dsl::ast::matrix::Matrix;
+use crate::dsl::ast::signal::Signal;
+use crate::dsl::ast::span::Span;
+use crate::dsl::ast::statement::{ForStatement, Statement};
+use crate::dsl::ast::visit::{self, Visit};
+use crate::dsl::interp::{leading_carrier, literal_value, RuntimeError, Value};
+use super::chunk::{Argc, Chunk, Class, Function, Op, Program};
+
+pub fn compile(fly: &Fly) -> Result<Program, RuntimeError> {
+    let genome = &fly.strand.genome;
+    let behaviors: Vec<&Behavior> = genome
+        .iter()
+        .filter_map(|item| match item {
+            Genome::Behavior(behavior) => Some(behavior),
+            _ => None,
+        })
+        .collect();
+
+    let mut locals = Locals::default();
+    for behavior in &behaviors {
+        locals.visit_behavior(behavior);
+    }
+    let mut main = Compiler::new(Chunk::new("main"), locals.0);
+    main.sequence(&behaviors, fly.span);
+    main.chunk.emit(Op::Return, fly.span);
+    let main = main.finish()?;
+
+    let mut classes = Vec::new();
+    for item in genome {
+        match item {
+            Genome::Anatomy(Anatomy::Bug(bug)) => classes.push(Rc::new(class(bug)?)),
+            Genome::Anatomy(Anatomy::Kind(kind)) => classes.extend(variants(kind)),
+            _ => {}
+        }
+    }
+    Ok(Program { main: Rc::new(main), classes })
+}
+
+fn class(bug: &Bug) -> Result<Class, RuntimeError> {
+    let mut ethics = HashMap::new();
+    let mut order = Vec::new();
+    for item in &bug.ethics {
+        // Como no interpretador, vale o primeiro ethics com o nome
+        if !ethics.contains_key(&item.tag.raw) {
+            order.push(item.tag.raw.clone());
+            ethics.insert(item.tag.raw.clone(), Rc::new(function(&bug.specie.raw, item)?));
+        }
+    }
+    Ok(Class {
+        name: bug.specie.raw.clone(),
+        kind: None,
+        parents: bug.parents.iter().map(|parent| parent.raw.clone()).collect(),
+        genes: bug.genes.iter().map(|gene| (gene.tag.raw.clone(), gene.specie.name.raw.clone())).collect(),
+        ethics,
+        order,
+    })
+}
+
+fn variants(kind: &Kind) -> Vec<Rc<Class>> {
+    kind.variants
+        .iter()
+        .map(|variant| {
+            Rc::new(Class {
+                name: variant.specie.raw.clone(),
+                kind: Some(kind.specie.raw.clone()),
+                parents: Vec::new(),
+                genes: variant.genes.iter().map(|gene| (gene.tag.raw.clone(), gene.specie.name.raw.clone())).collect(),
+                ethics: HashMap::new(),
+                order: Vec::new(),
+            })
+        })
+        .collect()
+}
+
+fn function(specie: &str, ethics: &Ethics) -> Result<Function, RuntimeError> {
+    let params: Vec<String> = ethics
+        .signature
+        .iter()
+        .flat_map(|signature| signature.binds.iter().flatten())
+        .map(|bind| bind.tag.raw.clone())
+        .collect();
+    let body = match &ethics.body {
+        Some(body) => {
+            let mut locals = Locals(params.clone());
+            locals.visit_matrix(body);
+            let mut compiler = Compiler::new(Chunk::new(format!("{}.{}", specie, ethics.tag.raw)), locals.0);
+            compiler.matrix(body);
+            compiler.chunk.emit(Op::Return, body.span);
+            Some(Rc::new(compiler.finish()?))
+        }
+        None => None,
+    };
+    Ok(Function { name: ethics.tag.raw.clone(), params, body })
+}
+
+/// Nomes que recebem valor no trecho: atribuições e variáveis de `for`
+#[derive(Default)]
+struct Locals(Vec<String>);
+
+impl Locals {
+    fn add(&mut self, name: &str) {
+        if !self.0.iter().any(|known| known == name) {
+            self.0.push(name.to_string());
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for Locals {
+    fn visit_assign(&mut self, node: &'ast Assign) {
+        self.add(&node.tag.raw);
+        visit::walk_assign(self, node);
+    }
+
+    fn visit_for(&mut self, node: &'ast ForStatement) {
+        self.add(&node.variable.raw);
+        visit::walk_for(self, node);
+    }
+}
+
+struct Compiler {
+    chunk: Chunk,
+    /// Primeiro erro encontrado; a compilação segue e o chunk é descartado no fim
+    error: Option<RuntimeError>,
+}
+
+impl Compiler {
+    fn new(mut chunk: Chunk, locals: Vec<String>) -> Self {
+        chunk.locals = locals;
+        Compiler { chunk, error: None }
+    }
+
+    fn finish(self) -> Result<Chunk, RuntimeError> {
+        match self.error {
+            Some(error) => Err(error),
+            None => Ok(self.chunk),
+        }
+    }
+
+    /// Quantidade de itens de uma instrução, que precisa caber em 16 bits
+    fn count(&mut self, found: usize, span: Span) -> u16 {
+        u16::try_from(found).unwrap_or_else(|_| {
+            let limit = u16::MAX as usize;
+            self.error.get_or_insert(RuntimeError::TooManyItems { found, limit, span });
+            0
+        })
+    }
+
+    fn emit(&mut self, op: Op, span: Span) -> usize {
+        self.chunk.emit(op, span)
+    }
+
+    fn here(&self) -> u32 {
+        self.chunk.code.len() as u32
+    }
+
+    /// Aponta o salto em `at` para a próxima instrução
+    fn patch(&mut self, at: usize) {
+        let target = self.here();
+        match &mut self.chunk.code[at] {
+            Op::Jump(to)
+            | Op::JumpIfFalse(to)
+            | Op::And(to)
+            | Op::Or(to)
+            | Op::When(_, to)
+            | Op::Next { exit: to, .. } => *to = target,
+            op => unreachable!("{:?} is not a jump", op),
+        }
+    }
+
+    fn matrix(&mut self, matrix: &Matrix) {
+        let behaviors: Vec<&Behavior> = matrix.signals.iter().map(|Signal::Behavior(behavior)| behavior).collect();
+        self.sequence(&behaviors, matrix.span);
+    }
+
+    /// Vale o último comportamento, ou `nil` se não houver nenhum
+    fn sequence(&mut self, behaviors: &[&Behavior], span: Span) {
+        let Some((last, rest)) = behaviors.split_last() else {
+            self.emit(Op::Nil, span);
+            return;
+        };
+        for behavior in rest {
+            self.behavior(behavior);
+            self.emit(Op::Pop, behavior.span());
+        }
+        self.behavior(last);
+    }
+
+    fn behavior(&mut self, behavior: &Behavior) {
+        match behavior {
+            Behavior::Statement(statement) => self.statement(statement),
+            Behavior::Assign(assign) => {
+                self.oop(&assign.oop);
+                let slot = self.chunk.local(&assign.tag.raw).expect("assigned names are locals");
+                self.emit(Op::Store(slot), assign.span);
+            }
+            Behavior::Oop(oop) => self.oop(oop),
+        }
+    }
+
+    fn statement(&mut self, statement: &Statement) {
+        match statement {
+            Statement::If(if_stmt) => {
+                let mut exits = Vec::new();
+                let branches = std::iter::once((&if_stmt.condition, &if_stmt.then_block))
+                    .chain(if_stmt.elsif_blocks.iter().map(|elsif| (&elsif.condition, &elsif.block)));
+                for (condition, block) in branches {
+                    self.condition(condition);
+                    let skip = self.emit(Op::JumpIfFalse(0), condition.span());
+                    self.matrix(block);
+                    exits.push(self.emit(Op::Jump(0), block.span));
+                    self.patch(skip);
+                }
+                match &if_stmt.else_block {
+                    Some(block) => self.matrix(block),
+                    None => {
+                        self.emit(Op::Nil, if_stmt.span);
+                    }
+                }
+                for exit in exits {
+                    self.patch(exit);
+                }
+            }
+            Statement::While(while_stmt) => {
+                let start = self.here();
+                self.condition(&while_stmt.condition);
+                let exit = self.emit(Op::JumpIfFalse(0), while_stmt.condition.span());
+                self.matrix(&while_stmt.block);
+                self.emit(Op::Pop, while_stmt.block.span);
+                self.emit(Op::Jump(start), while_stmt.span);
+                self.patch(exit);
+                self.emit(Op::Nil, while_stmt.span);
+            }
+            Statement::For(for_stmt) => {
+                self.oop(&for_stmt.iterable);
+                self.emit(Op::Iter, for_stmt.iterable.span);
+                let start = self.here();
+                let slot = self.chunk.local(&for_stmt.variable.raw).expect("loop variables are locals");
+                let next = self.emit(Op::Next { slot, exit: 0 }, for_stmt.variable.span);
+                self.matrix(&for_stmt.block);
+                self.emit(Op::Pop, for_stmt.block.span);
+                self.emit(Op::Jump(start), for_stmt.span);
+                self.patch(next);
+                self.emit(Op::Nil, for_stmt.span);
+            }
+            // O sujeito fica na pilha durante os `WHEN` e sai no começo do braço escolhido
+            Statement::Match(match_stmt) => {
+                let span = match_stmt.subject.span;
+                self.oop(&match_stmt.subject);
+                let mut arms = Vec::new();
+                for arm in &match_stmt.arms {
+                    let whens: Vec<usize> = arm
+                        .variants
+                        .iter()
+                        .map(|variant| {
+                            let name = self.chunk.name(&variant.raw);
+                            self.emit(Op::When(name, 0), span)
+                        })
+                        .collect();
+                    arms.push(whens);
+                }
+                self.emit(Op::Pop, span);
+                match &match_stmt.else_block {
+                    Some(block) => self.matrix(block),
+                    None => {
+                        self.emit(Op::Nil, match_stmt.span);
+                    }
+                }
+                let mut exits = vec![self.emit(Op::Jump(0), match_stmt.span)];
+                for (arm, whens) in match_stmt.arms.iter().zip(arms) {
+                    for when in whens {
+                        self.patch(when);
+                    }
+                    self.emit(Op::Pop, arm.span);
+                    self.matrix(&arm.block);
+                    exits.push(self.emit(Op::Jump(0), arm.block.span));
+                }
+                for exit in exits {
+                    self.patch(exit);
+                }
+            }
+            Statement::Return(return_stmt) => {
+                self.oop(&return_stmt.value);
+                self.emit(Op::Return, return_stmt.span);
+            }
+        }
+    }
+
+    fn condition(&mut self, condition: &Condition) {
+        match condition {
+            Condition::Oop(oop) => self.oop(oop),
+            Condition::Expr(expr) => self.expr(expr),
+        }
+    }
+
+    /// Operadores viram `Send` do método correspondente, exceto `and`/`or`,
+    /// que só avaliam o lado direito quando precisam.
+    fn expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Oop(oop) => self.oop(oop),
+            Expr::Binary { op: op @ (BinaryOp::And | BinaryOp::Or), left, right, span } => {
+                self.expr(left);
+                let jump = match op {
+                    BinaryOp::And => Op::And(0),
+                    _ => Op::Or(0),
+                };
+                let end = self.emit(jump, *span);
+                self.expr(right);
+                self.patch(end);
+            }
+            Expr::Binary { op, left, right, span } => {
+                self.expr(left);
+                self.expr(right);
+                let method = self.chunk.name(op.method());
+                self.emit(Op::Send(method, Argc::Positional(1)), *span);
+            }
+            Expr::Unary { op, operand, span } => {
+                self.expr(operand);
+                let method = self.chunk.name(op.method());
+                self.emit(Op::Get(method), *span);
+            }
+        }
+    }
+
+    fn oop(&mut self, oop: &Oop) {
+        let mut trails = oop.trails.iter().peekable();
+        match &oop.emitter {
+            Emitter::Literal(Literal::Nil { span, .. }) => {
+                self.emit(Op::Nil, *span);
+            }
+            Emitter::Literal(literal) => {
+                match literal_value(literal) {
+                    Ok(value) => {
+                        let index = self.chunk.constant(value);
+                        self.emit(Op::Const(index), literal.span())
+                    }
+                    Err(_) => self.emit(Op::Overflow, literal.span()),
+                };
+            }
+            Emitter::Template(template) => {
+                for part in &template.parts {
+                    match part {
+                        TemplatePart::Text { raw, span } => {
+                            let index = self.chunk.constant(Value::Str(unescape(raw)));
+                            self.emit(Op::Const(index), *span);
+                        }
+                        TemplatePart::Oop(oop) => self.oop(oop),
+                    }
+                }
+                let parts = self.count(template.parts.len(), template.span);
+                self.emit(Op::Concat(parts), template.span);
+            }
+            Emitter::List(list) => {
+                for item in &list.items {
+                    self.oop(item);
+                }
+                let items = self.count(list.items.len(), list.span);
+                self.emit(Op::List(items), list.span);
+            }
+            Emitter::Map(map) => {
+                let keywords = self.keywords(&map.entries, map.span);
+                self.emit(Op::Map(keywords), map.span);
+            }
+            Emitter::SelfRef(self_ref) => {
+                self.emit(Op::LoadSelf, self_ref.span);
+            }
+            // `Dog(energia: 1)` é o mesmo que `Dog.new(energia: 1)`
+            Emitter::Specie(specie) => {
+                let name = self.chunk.name(&specie.raw);
+                match leading_carrier(&mut trails) {
+                    Some(carrier) => {
+                        let argc = self.arguments(carrier);
+                        self.emit(Op::New(name, argc), carrier.span);
+                    }
+                    None => {
+                        self.emit(Op::Specie(name), specie.span);
+                    }
+                }
+            }
+            Emitter::Tag(tag) => match (leading_carrier(&mut trails), self.chunk.local(&tag.raw)) {
+                (Some(carrier), _) => {
+                    let argc = self.arguments(carrier);
+                    let name = self.chunk.name(&tag.raw);
+                    self.emit(Op::Call(name, argc), tag.span.merge(&carrier.span));
+                }
+                (None, Some(slot)) => {
+                    self.emit(Op::Load(slot), tag.span);
+                }
+                (None, None) => {
+                    let name = self.chunk.name(&tag.raw);
+                    self.emit(Op::LoadName(name), tag.span);
+                }
+            },
+        }
+        for trail in trails {
+            match trail {
+                Trail::Catalysis(catalysis) => {
+                    let method = self.chunk.name(&catalysis.tag.raw);
+                    match &catalysis.carrier {
+                        Some(carrier) => {
+                            let argc = self.arguments(carrier);
+                            self.emit(Op::Send(method, argc), catalysis.span);
+                        }
+                        None => {
+                            self.emit(Op::Get(method), catalysis.span);
+                        }
+                    }
+                }
+                Trail::Carrier(carrier) => {
+                    self.emit(Op::NotCallable, carrier.span);
+                }
+            }
+        }
+    }
+
+    /// Empilha os argumentos, da esquerda para a direita
+    fn arguments(&mut self, carrier: &Carrier) -> Argc {
+        match &carrier.transport {
+            None => Argc::Positional(0),
+            Some(Transport::Sequence(sequence)) => {
+                for oop in &sequence.oops {
+                    self.oop(oop);
+                }
+                Argc::Positional(self.count(sequence.oops.len(), sequence.span))
+            }
+            Some(Transport::Binds(binds)) => Argc::Named(self.keywords(&binds.binds, binds.span)),
+        }
+    }
+
+    /// Empilha os valores de `nome: valor` e guarda os nomes em `keywords`
+    fn keywords(&mut self, binds: &[Bind], span: Span) -> u16 {
+        let mut keywords = Vec::new();
+        for bind in binds {
+            self.oop(&bind.oop);
+            keywords.push((self.chunk.name(&bind.tag.raw), bind.span));
+        }
+        self.chunk.keywords.push(keywords);
+        self.count(self.chunk.keywords.len() - 1, span)
+    }
+}
@@ -0,0 +1,166 @@
+//! Listagem legível do bytecode, para depuração (`sc disasm`). O começo de
+//! `soma = 0`, `for n in 3`, `soma = soma.plus(n)`:
+//!
+//! ```text
+//! == main ==
+//! 0000  1:8     CONST         0         0
+//! 0001  1:1     STORE         0         soma
+//! 0002  1:1     POP
+//! 0003  2:10    CONST         1         3
+//! 0004  2:10    ITER
+//! 0005  2:5     NEXT          1 -> 0012 n
+//! 0006  3:10    LOAD          0         soma
+//! 0007  3:20    LOAD          1         n
+//! 0008  3:14    SEND          0 1       plus(1)
+//! ...
+//! ```
+//!
+//! Cada linha traz o índice da instrução, a posição no código de origem, os
+//! operandos e, por último, o que eles querem dizer.
+
+use std::fmt::Write;
+use crate::dsl::interp::Value;
+use super::chunk::{Argc, Chunk, Op, Program};
+
+/// O programa principal seguido de cada ethics, na ordem do código
+pub fn disassemble(program: &Program) -> String {
+    let mut out = chunk(&program.main);
+    for class in &program.classes {
+        for name in &class.order {
+            let function = &class.ethics[name];
+            let header = format!("{}.{}({})", class.name, name, function.params.join(", "));
+            match &function.body {
+                Some(body) => {
+                    out.push('\n');
+                    out.push_str(&listing(&header, body));
+                }
+                None => {
+                    let _ = writeln!(out, "\n== {} ==\n(no body)", header);
+                }
+            }
+        }
+    }
+    out
+}
+
+pub fn chunk(chunk: &Chunk) -> String {
+    listing(&chunk.name, chunk)
+}
+
+fn listing(header: &str, chunk: &Chunk) -> String {
+    let mut out = format!("== {} ==\n", header);
+    for (index, (op, span)) in chunk.code.iter().zip(&chunk.spans).enumerate() {
+        let (name, operands, meaning) = describe(chunk, *op);
+        let position = format!("{}:{}", span.line, span.col);
+        let line = format!("{:04}  {:<7} {:<13} {:<9} {}", index, position, name, operands, meaning);
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+/// Nome da instrução, operandos e comentário
+fn describe(chunk: &Chunk, op: Op) -> (&'static str, String, String) {
+    let name = |index: u32| chunk.names[index as usize].clone();
+    let local = |slot: u32| chunk.locals[slot as usize].clone();
+    let call = |index: u32, argc: Argc| match argc {
+        Argc::Positional(count) => (format!("{} {}", index, count), format!("{}({})", name(index), count)),
+        Argc::Named(keywords) => {
+            let binds: Vec<String> =
+                chunk.keywords[keywords as usize].iter().map(|(bind, _)| format!("{}:", name(*bind))).collect();
+            (format!("{} k{}", index, keywords), format!("{}({})", name(index), binds.join(" ")))
+        }
+    };
+    match op {
+        Op::Const(index) => {
+            let value = &chunk.constants[index as usize];
+            let shown = match value {
+                Value::Str(text) => format!("{:?}", text),
+                value => value.to_string(),
+            };
+            ("CONST", index.to_string(), shown)
+        }
+        Op::Nil => ("NIL", String::new(), String::new()),
+        Op::Pop => ("POP", String::new(), String::new()),
+        Op::Load(slot) => ("LOAD", slot.to_string(), local(slot)),
+        Op::Store(slot) => ("STORE", slot.to_string(), local(slot)),
+        Op::LoadName(index) => ("LOAD_NAME", index.to_string(), name(index)),
+        Op::LoadSelf => ("LOAD_SELF", String::new(), "$".to_string()),
+        Op::Specie(index) => ("SPECIE", index.to_string(), name(index)),
+        Op::New(index, argc) => {
+            let (operands, meaning) = call(index, argc);
+            ("NEW", operands, meaning)
+        }
+        Op::Concat(count) => ("CONCAT", count.to_string(), String::new()),
+        Op::List(count) => ("LIST", count.to_string(), String::new()),
+        Op::Map(keywords) => {
+            let keys: Vec<String> = chunk.keywords[keywords as usize].iter().map(|(key, _)| format!("{}:", name(*key))).collect();
+            ("MAP", format!("k{}", keywords), format!("{{{}}}", keys.join(" ")))
+        }
+        Op::Call(index, argc) => {
+            let (operands, meaning) = call(index, argc);
+            ("CALL", operands, meaning)
+        }
+        Op::Send(index, argc) => {
+            let (operands, meaning) = call(index, argc);
+            ("SEND", operands, meaning)
+        }
+        Op::Get(index) => ("GET", index.to_string(), name(index)),
+        Op::NotCallable => ("NOT_CALLABLE", String::new(), String::new()),
+        Op::Overflow => ("OVERFLOW", String::new(), String::new()),
+        Op::Jump(target) => ("JUMP", format!("-> {:04}", target), String::new()),
+        Op::JumpIfFalse(target) => ("JUMP_IF_FALSE", format!("-> {:04}", target), String::new()),
+        Op::And(target) => ("AND", format!("-> {:04}", target), String::new()),
+        Op::Or(target) => ("OR", format!("-> {:04}", target), String::new()),
+        Op::When(index, target) => ("WHEN", format!("{} -> {:04}", index, target), name(index)),
+        Op::Iter => ("ITER", String::new(), String::new()),
+        Op::Next { slot, exit } => ("NEXT", format!("{} -> {:04}", slot, exit), local(slot)),
+        Op::Return => ("RETURN", String::new(), String::new()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::disassemble;
+    use crate::dsl::parser::tree::Tree;
+    use crate::dsl::vm::compile_tree;
+
+    #[test]
+    fn test_lists_main_and_every_ethics() {
+        let source = "bug Dog\n  gene energia Int\n  ethics latir(volume: Int)\n    if volume > 1 and energia\n      print(\"au\")\n    end\n  end\n  ethics dormir\nend\nrex = Dog(energia: 1)\nrex.latir(2)\n";
+        let tree = Tree::parse_input(source.to_string()).expect("Source should parse");
+
+        let listing = disassemble(&compile_tree(&tree).expect("Should compile"));
+
+        assert_eq!(
+            listing,
+            "\
+== main ==
+0000  10:20   CONST         0         1
+0001  10:10   NEW           0 k0      Dog(energia:)
+0002  10:1    STORE         0         rex
+0003  10:1    POP
+0004  11:1    LOAD          0         rex
+0005  11:11   CONST         1         2
+0006  11:4    SEND          2 1       latir(1)
+0007  1:1     RETURN
+
+== Dog.latir(volume) ==
+0000  4:8     LOAD          0         volume
+0001  4:17    CONST         0         1
+0002  4:8     SEND          0 1       bt(1)
+0003  4:8     AND           -> 0005
+0004  4:23    LOAD_NAME     1         energia
+0005  4:8     JUMP_IF_FALSE -> 0009
+0006  5:13    CONST         1         \"au\"
+0007  5:7     CALL          2 1       print(1)
+0008  5:7     JUMP          -> 0010
+0009  4:5     NIL
+0010  4:5     RETURN
+
+== Dog.dormir() ==
+(no body)
+"
+        );
+    }
+}
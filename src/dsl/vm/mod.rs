@@ -0,0 +1,619 @@
+//! Compilador para bytecode e máquina de pilha que o executa.
+//!
+//! Mesma semântica do [`Interpreter`](crate::dsl::interp::Interpreter), com o
+//! mesmo [`Host`] e os mesmos [`RuntimeError`]s, mas sem percorrer a árvore a
+//! cada passo: cada `ethics` vira um [`Chunk`] com constantes, nomes e
+//! variáveis locais em tabelas, e as locais ficam em posições fixas em vez de
+//! um `HashMap`. Chamadas de ethics não usam a pilha do Rust.
+//!
+//! ```
+//! use sc_dsl::dsl::interp::{Builtins, Value};
+//! use sc_dsl::dsl::parser::tree::Tree;
+//! use sc_dsl::dsl::vm::{self, Vm};
+//!
+//! let tree = Tree::parse_input("soma = 0\nfor i in 4\n  soma = soma.plus(i)\nend\nsoma".to_string()).unwrap();
+//! let program = vm::compile_tree(&tree).unwrap();
+//! assert!(vm::disassemble(&program).contains("SEND"));
+//! assert_eq!(Vm::with_host(Builtins::silent()).run(&program), Ok(Value::Int(6)));
+//! ```
+
+pub mod chunk;
+pub mod compile;
+pub mod disasm;
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::vec;
+use crate::dsl::ast::span::Span;
+use crate::dsl::interp::species::{bind_genes, default_genes, lineage, Args};
+use crate::dsl::interp::{Builtins, Host, Instance, RuntimeError, Value, Variant};
+use crate::dsl::parser::tree::Tree;
+pub use chunk::{Argc, Chunk, Class, Function, Op, Program};
+pub use compile::compile;
+pub use disasm::disassemble;
+
+/// Limite de chamadas aninhadas; os frames ficam no heap, não na pilha nativa
+pub const MAX_FRAMES: usize = 100_000;
+
+pub fn compile_tree(tree: &Tree) -> Result<Program, RuntimeError> {
+    compile(&tree.sc.fly)
+}
+
+pub struct Vm<H: Host = Builtins> {
+    pub host: H,
+    species: HashMap<String, Rc<Class>>,
+    globals: HashMap<String, Value>,
+    stack: Vec<Value>,
+    /// Locais de todas as chamadas em andamento; `None` é "ainda sem valor"
+    slots: Vec<Option<Value>>,
+    /// Itens restantes de cada `for` em andamento
+    iters: Vec<vec::IntoIter<Value>>,
+    frames: Vec<Frame>,
+}
+
+/// Uma chamada em andamento e onde começam suas partes das pilhas.
+struct Frame {
+    chunk: Rc<Chunk>,
+    /// Próxima instrução; só é atualizado quando outra chamada começa
+    ip: usize,
+    slots: usize,
+    stack: usize,
+    iters: usize,
+    this: Option<Rc<RefCell<Instance>>>,
+}
+
+impl Vm<Builtins> {
+    pub fn new() -> Self {
+        Vm::with_host(Builtins::new())
+    }
+}
+
+impl Default for Vm<Builtins> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<H: Host> Vm<H> {
+    pub fn with_host(host: H) -> Self {
+        Vm {
+            host,
+            species: HashMap::new(),
+            globals: HashMap::new(),
+            stack: Vec::new(),
+            slots: Vec::new(),
+            iters: Vec::new(),
+            frames: Vec::new(),
+        }
+    }
+
+    /// Variável de nível superior, como ficou depois das execuções anteriores
+    pub fn global(&self, name: &str) -> Option<&Value> {
+        self.globals.get(name)
+    }
+
+    pub fn run_tree(&mut self, tree: &Tree) -> Result<Value, RuntimeError> {
+        self.run(&compile_tree(tree)?)
+    }
+
+    /// Executa um programa compilado. Como no interpretador, os bugs são
+    /// registrados antes de tudo e as variáveis e espécies ficam guardadas
+    /// para as próximas execuções.
+    pub fn run(&mut self, program: &Program) -> Result<Value, RuntimeError> {
+        for class in &program.classes {
+            self.species.insert(class.name.clone(), class.clone());
+        }
+        let main = program.main.clone();
+        self.slots = main.locals.iter().map(|name| self.globals.get(name).cloned()).collect();
+        self.frames.push(Frame { chunk: main.clone(), ip: 0, slots: 0, stack: 0, iters: 0, this: None });
+
+        let result = self.execute();
+
+        // As locais do programa principal são as globais, mesmo depois de um erro
+        for (name, value) in main.locals.iter().zip(self.slots.drain(..)) {
+            if let Some(value) = value {
+                self.globals.insert(name.clone(), value);
+            }
+        }
+        self.stack.clear();
+        self.iters.clear();
+        self.frames.clear();
+        result
+    }
+
+    fn frame(&self) -> &Frame {
+        self.frames.last().expect("a chunk is running")
+    }
+
+    fn frame_mut(&mut self) -> &mut Frame {
+        self.frames.last_mut().expect("a chunk is running")
+    }
+
+    /// Chunk, próxima instrução e início das locais da chamada atual
+    fn current(&self) -> (Rc<Chunk>, usize, usize) {
+        let frame = self.frame();
+        (frame.chunk.clone(), frame.ip, frame.slots)
+    }
+
+    fn pop(&mut self) -> Value {
+        self.stack.pop().expect("stack is balanced")
+    }
+
+    fn top(&self) -> &Value {
+        self.stack.last().expect("stack is balanced")
+    }
+
+    fn execute(&mut self) -> Result<Value, RuntimeError> {
+        let (mut chunk, mut ip, mut base) = self.current();
+        loop {
+            let op = chunk.code[ip];
+            let span = chunk.spans[ip];
+            ip += 1;
+            match op {
+                Op::Const(index) => self.stack.push(chunk.constants[index as usize].clone()),
+                Op::Nil => self.stack.push(Value::Nil),
+                Op::Pop => {
+                    self.pop();
+                }
+                Op::Load(slot) => match &self.slots[base + slot as usize] {
+                    Some(value) => self.stack.push(value.clone()),
+                    None => {
+                        self.frame_mut().ip = ip;
+                        self.lookup(&chunk, &chunk.locals[slot as usize], span)?;
+                        (chunk, ip, base) = self.current();
+                    }
+                },
+                Op::Store(slot) => {
+                    let value = self.top().clone();
+                    self.assign(base + slot as usize, &chunk.locals[slot as usize], value);
+                }
+                Op::LoadName(name) => {
+                    self.frame_mut().ip = ip;
+                    self.lookup(&chunk, &chunk.names[name as usize], span)?;
+                    (chunk, ip, base) = self.current();
+                }
+                Op::LoadSelf => match &self.frame().this {
+                    Some(this) => self.stack.push(Value::Object(this.clone())),
+                    None => return Err(RuntimeError::UndefinedName { name: "$".to_string(), span }),
+                },
+                Op::Specie(name) => {
+                    let name = &chunk.names[name as usize];
+                    // Variante sem genes pelo nome: `Red` já é o valor
+                    if self.species.get(name).is_some_and(|class| class.kind.is_some() && class.genes.is_empty()) {
+                        let bottom = self.stack.len();
+                        self.instantiate(&chunk, name, Argc::Positional(0), bottom, span)?;
+                    } else {
+                        self.stack.push(Value::Specie(name.clone()));
+                    }
+                }
+                Op::New(name, argc) => {
+                    let bottom = self.stack.len() - chunk.argc(argc);
+                    self.instantiate(&chunk, &chunk.names[name as usize], argc, bottom, span)?;
+                }
+                Op::Concat(count) => {
+                    let parts = self.stack.split_off(self.stack.len() - count as usize);
+                    self.stack.push(Value::Str(parts.iter().map(|part| self.host.show(part)).collect()));
+                }
+                Op::List(count) => {
+                    let items = self.stack.split_off(self.stack.len() - count as usize);
+                    self.stack.push(Value::List(items));
+                }
+                Op::Map(keywords) => {
+                    let keys = &chunk.keywords[keywords as usize];
+                    let values = self.stack.split_off(self.stack.len() - keys.len());
+                    let entries = keys.iter().map(|(key, _)| chunk.names[*key as usize].clone()).zip(values).collect();
+                    self.stack.push(Value::Map(entries));
+                }
+                Op::Call(name, argc) => {
+                    self.frame_mut().ip = ip;
+                    self.call_function(&chunk, &chunk.names[name as usize], argc, span)?;
+                    (chunk, ip, base) = self.current();
+                }
+                Op::Send(name, argc) => {
+                    self.frame_mut().ip = ip;
+                    self.send(&chunk, &chunk.names[name as usize], argc, true, span)?;
+                    (chunk, ip, base) = self.current();
+                }
+                Op::Get(name) => {
+                    self.frame_mut().ip = ip;
+                    self.send(&chunk, &chunk.names[name as usize], Argc::Positional(0), false, span)?;
+                    (chunk, ip, base) = self.current();
+                }
+                Op::NotCallable => return Err(RuntimeError::NotCallable { found: self.top().specie(), span }),
+                Op::Overflow => return Err(RuntimeError::Overflow { span }),
+                Op::Jump(target) => ip = target as usize,
+                Op::JumpIfFalse(target) => {
+                    if !self.pop().is_truthy() {
+                        ip = target as usize;
+                    }
+                }
+                Op::And(target) => {
+                    if self.top().is_truthy() {
+                        self.pop();
+                    } else {
+                        ip = target as usize;
+                    }
+                }
+                Op::Or(target) => {
+                    if self.top().is_truthy() {
+                        ip = target as usize;
+                    } else {
+                        self.pop();
+                    }
+                }
+                Op::When(name, target) => match self.top() {
+                    Value::Variant(variant) => {
+                        if variant.name == chunk.names[name as usize] {
+                            ip = target as usize;
+                        }
+                    }
+                    subject => {
+                        return Err(RuntimeError::TypeMismatch {
+                            expected: "a kind".to_string(),
+                            found: subject.specie(),
+                            span,
+                        });
+                    }
+                },
+                Op::Iter => {
+                    let iterable = self.pop();
+                    let items = self
+                        .host
+                        .iterate(&iterable)
+                        .ok_or_else(|| RuntimeError::NotIterable { found: iterable.specie(), span })?;
+                    self.iters.push(items.into_iter());
+                }
+                Op::Next { slot, exit } => match self.iters.last_mut().and_then(Iterator::next) {
+                    // A variável do laço é sempre local, mesmo com um gene de mesmo nome
+                    Some(item) => self.slots[base + slot as usize] = Some(item),
+                    None => {
+                        self.iters.pop();
+                        ip = exit as usize;
+                    }
+                },
+                Op::Return => {
+                    let value = self.pop();
+                    let frame = self.frames.pop().expect("a chunk is running");
+                    // As locais do programa principal ainda vão virar globais
+                    if self.frames.is_empty() {
+                        return Ok(value);
+                    }
+                    self.stack.truncate(frame.stack);
+                    self.slots.truncate(frame.slots);
+                    self.iters.truncate(frame.iters);
+                    self.stack.push(value);
+                    (chunk, ip, base) = self.current();
+                }
+            }
+        }
+    }
+
+    fn ethics_of(&self, specie: &str, name: &str) -> Option<Rc<Function>> {
+        self.lineage(specie).iter().find_map(|class| class.ethics.get(name).cloned())
+    }
+
+    /// A classe e seus ancestrais, como `Interpreter::lineage`
+    fn lineage(&self, specie: &str) -> Vec<Rc<Class>> {
+        lineage(self.species.get(specie), |class| {
+            class.parents.iter().find_map(|parent| self.species.get(parent).filter(|parent| parent.kind.is_none()))
+        })
+    }
+
+    /// Atribuição: variável local já existente, senão gene da instância, senão nova variável local
+    fn assign(&mut self, slot: usize, name: &str, value: Value) {
+        if self.slots[slot].is_none()
+            && let Some(this) = &self.frame().this
+            && let Some(gene) = this.borrow_mut().genes.get_mut(name)
+        {
+            *gene = value;
+            return;
+        }
+        self.slots[slot] = Some(value);
+    }
+
+    /// Nome solto sem valor local: gene ou ethics sem argumentos da instância,
+    /// ou global no programa principal
+    fn lookup(&mut self, chunk: &Chunk, name: &str, span: Span) -> Result<(), RuntimeError> {
+        match self.frame().this.clone() {
+            Some(this) => {
+                let gene = this.borrow().genes.get(name).cloned();
+                if let Some(value) = gene {
+                    self.stack.push(value);
+                    return Ok(());
+                }
+                let function = self.ethics_of(&this.borrow().specie, name);
+                if let Some(function) = function {
+                    let bottom = self.stack.len();
+                    return self.invoke(this, &function, chunk, Argc::Positional(0), bottom, span);
+                }
+            }
+            None if self.frames.len() == 1 => {
+                if let Some(value) = self.globals.get(name) {
+                    self.stack.push(value.clone());
+                    return Ok(());
+                }
+            }
+            None => {}
+        }
+        Err(RuntimeError::UndefinedName { name: name.to_string(), span })
+    }
+
+    /// `nome(args)`: ethics da própria instância ou função do host
+    fn call_function(&mut self, chunk: &Chunk, name: &str, argc: Argc, span: Span) -> Result<(), RuntimeError> {
+        let start = self.stack.len() - chunk.argc(argc);
+        if let Some(this) = self.frame().this.clone() {
+            let function = self.ethics_of(&this.borrow().specie, name);
+            if let Some(function) = function {
+                return self.invoke(this, &function, chunk, argc, start, span);
+            }
+        }
+        match self.host.call_function(name, &self.stack[start..], span)? {
+            Some(value) => {
+                self.stack.truncate(start);
+                self.stack.push(value);
+                Ok(())
+            }
+            None => Err(RuntimeError::UndefinedName { name: name.to_string(), span }),
+        }
+    }
+
+    /// `valor.nome(args)`, com o receptor logo abaixo dos argumentos: ethics ou
+    /// gene de um bug, `new` de uma espécie, ou método do host
+    fn send(&mut self, chunk: &Chunk, name: &str, argc: Argc, called: bool, span: Span) -> Result<(), RuntimeError> {
+        let start = self.stack.len() - chunk.argc(argc);
+        let receiver = start - 1;
+        match &self.stack[receiver] {
+            Value::Object(this) => {
+                let this = this.clone();
+                let function = self.ethics_of(&this.borrow().specie, name);
+                if let Some(function) = function {
+                    return self.invoke(this, &function, chunk, argc, receiver, span);
+                }
+                let gene = if called { None } else { this.borrow().genes.get(name).cloned() };
+                if let Some(value) = gene {
+                    self.stack[receiver] = value;
+                    return Ok(());
+                }
+            }
+            Value::Specie(specie) if name == "new" => {
+                let specie = specie.clone();
+                return self.instantiate(chunk, &specie, argc, receiver, span);
+            }
+            Value::Variant(variant) if !called => {
+                if let Some(value) = variant.gene(name).cloned() {
+                    self.stack[receiver] = value;
+                    return Ok(());
+                }
+            }
+            _ => {}
+        }
+        let (below, args) = self.stack.split_at(start);
+        match self.host.call_method(&below[receiver], name, args, span)? {
+            Some(value) => {
+                self.stack.truncate(receiver);
+                self.stack.push(value);
+                Ok(())
+            }
+            None => Err(RuntimeError::UnknownMethod {
+                receiver: self.stack[receiver].specie(),
+                method: name.to_string(),
+                span,
+            }),
+        }
+    }
+
+    /// Cria um bug (ou uma variante de kind) com os genes nos valores padrão
+    /// do host, sobrescritos pelos argumentos; a pilha volta a `bottom` e
+    /// recebe a instância.
+    fn instantiate(&mut self, chunk: &Chunk, specie: &str, argc: Argc, bottom: usize, span: Span) -> Result<(), RuntimeError> {
+        let class = self
+            .species
+            .get(specie)
+            .cloned()
+            .ok_or_else(|| RuntimeError::UnknownSpecie { name: specie.to_string(), span })?;
+        // Genes herdados primeiro, como no interpretador
+        let lineage = self.lineage(specie);
+        let genes = lineage.iter().rev().flat_map(|class| &class.genes);
+        let genes = default_genes(&self.host, genes.map(|(name, specie)| (name.as_str(), specie.as_str())));
+        let start = self.stack.len() - chunk.argc(argc);
+        let values = self.stack.drain(start..);
+        let args = match argc {
+            Argc::Positional(_) => Args::Positional(values.collect()),
+            Argc::Named(keywords) => Args::Named(
+                chunk.keywords[keywords as usize]
+                    .iter()
+                    .zip(values)
+                    .map(|(&(name, bind_span), value)| (chunk.names[name as usize].clone(), value, bind_span))
+                    .collect(),
+            ),
+        };
+        let genes = bind_genes(specie, genes, args, span)?;
+        self.stack.truncate(bottom);
+        let value = match &class.kind {
+            Some(kind) => Value::Variant(Rc::new(Variant { kind: kind.clone(), name: specie.to_string(), genes })),
+            None => Value::object(Instance { specie: specie.to_string(), genes: genes.into_iter().collect() }),
+        };
+        self.stack.push(value);
+        Ok(())
+    }
+
+    /// Começa a executar um ethics com os argumentos do topo da pilha ligados
+    /// aos parâmetros; quando ele terminar, a pilha volta a `bottom` e recebe o
+    /// resultado. Sem corpo, o resultado (`nil`) já fica na pilha.
+    fn invoke(
+        &mut self,
+        this: Rc<RefCell<Instance>>,
+        function: &Function,
+        chunk: &Chunk,
+        argc: Argc,
+        bottom: usize,
+        span: Span,
+    ) -> Result<(), RuntimeError> {
+        let count = chunk.argc(argc);
+        if count != function.params.len() {
+            return Err(RuntimeError::Arity {
+                ethics: function.name.clone(),
+                expected: function.params.len(),
+                found: count,
+                span,
+            });
+        }
+        // Posição de cada argumento nomeado entre os parâmetros
+        let positions: Option<Vec<usize>> = match argc {
+            Argc::Positional(_) => None,
+            Argc::Named(keywords) => {
+                let mut positions = Vec::with_capacity(count);
+                for &(name, bind_span) in &chunk.keywords[keywords as usize] {
+                    let name = &chunk.names[name as usize];
+                    let position = function.params.iter().position(|param| param == name).ok_or_else(|| {
+                        RuntimeError::UnknownArgument { ethics: function.name.clone(), name: name.clone(), span: bind_span }
+                    })?;
+                    positions.push(position);
+                }
+                Some(positions)
+            }
+        };
+        let Some(body) = &function.body else {
+            self.stack.truncate(bottom);
+            self.stack.push(Value::Nil);
+            return Ok(());
+        };
+        if self.frames.len() > MAX_FRAMES {
+            return Err(RuntimeError::StackOverflow { limit: MAX_FRAMES, span });
+        }
+
+        let slots = self.slots.len();
+        self.slots.resize(slots + body.locals.len(), None);
+        let start = self.stack.len() - count;
+        for (index, value) in self.stack.drain(start..).enumerate() {
+            let position = positions.as_ref().map_or(index, |positions| positions[index]);
+            self.slots[slots + position] = Some(value);
+        }
+        self.stack.truncate(bottom);
+        self.frames.push(Frame {
+            chunk: body.clone(),
+            ip: 0,
+            slots,
+            stack: bottom,
+            iters: self.iters.len(),
+            this: Some(this),
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compile_tree, Op, Vm, MAX_FRAMES};
+    use crate::dsl::interp::{Builtins, Interpreter, RuntimeError, Value};
+    use crate::dsl::parser::tree::Tree;
+    use crate::tests::load_fragment;
+
+    /// Roda o mesmo programa nos dois executores
+    fn both(source: String) -> [(Result<String, RuntimeError>, Vec<String>); 2] {
+        let tree = Tree::parse_input(source).expect("Source should parse");
+        let mut interpreter = Interpreter::with_host(Builtins::silent());
+        let walked = interpreter.run_tree(&tree).map(|value| value.to_string());
+        let mut vm = Vm::with_host(Builtins::silent());
+        let compiled = vm.run_tree(&tree).map(|value| value.to_string());
+        [(walked, interpreter.host.printed), (compiled, vm.host.printed)]
+    }
+
+    #[test]
+    fn test_fixtures_run_like_the_interpreter() {
+        for path in ["interp/dog.sc", "interp/loops.sc", "interp/errors.sc", "interp/kinds.sc", "literal/collections.sc", "literal/template.sc", "literal/numbers.sc", "typeck/generics.sc", "typeck/inheritance.sc"] {
+            let [walked, compiled] = both(load_fragment(path));
+
+            assert_eq!(compiled, walked, "{}", path);
+        }
+    }
+
+    #[test]
+    fn test_programs_run_like_the_interpreter() {
+        let cases = [
+            "x = 1\nif x > 1 or x == 1 and not (x < 0)\n  \"sim\"\nelse\n  \"não\"\nend",
+            "bug Conta\n  gene saldo Int\n  ethics depositar(valor: Int)\n    saldo = saldo.plus(valor)\n    $\n  end\n  ethics total\n    return saldo\n    0\n  end\nend\nc = Conta(10)\nc.depositar(5).depositar(valor: 1)\nc.total",
+            "bug Par\n  gene a Int\n  gene b Int\n  ethics soma\n    a.plus(b)\n  end\n  ethics dobro\n    soma.times(2)\n  end\nend\nPar.new(b: 2, a: 1).dobro",
+            "bug Nada\n  ethics vazio(x: Int)\nend\nNada.new.vazio(1).is_nil",
+            "n = 0\nwhile n < 10\n  n = n.plus(1)\nend\nfor c in \"abc\"\n  print(c, n)\nend",
+            "bug Busca\n  ethics achar(itens: List)\n    for i in itens\n      if i > 2\n        return i\n      end\n    end\n    nil\n  end\nend\nBusca.new.achar(list(1, 5, 3))",
+            "y.plus(1)",
+            "Fish.new",
+            "1.div(0)",
+            "18446744073709551615u64",
+            "3.voar",
+            "x = 3\nx(1)",
+            "bug Cat\n  gene vidas Int\nend\nCat(1, 2)",
+            "bug Cat\n  gene vidas Int\nend\nCat(asas: 2)",
+            "bug A < B\n  gene a Int\nend\nbug B < A\n  gene b Int\n  ethics f\n    b\n  end\nend\nA(1, 2).f",
+            "bug A\n  gene a Int\n  ethics f\n    a\n  end\nend\nbug B < A\n  gene a String\nend\nB.new.f.size",
+            "kind Cor\n  Azul\n  Rgb(r: Int)\nend\nc = Rgb(r: 1)\nmatch c\nwhen Azul\n  0\nend\nif c == Rgb(1) and Azul == Azul\n  print(c, c.r)\nend",
+            "kind Cor\n  Azul\nend\nmatch 3\nwhen Azul\n  0\nend",
+            "m = {b: [nil, true], a: {}}\nm.put(\"c\", m.size).values",
+            "{a: 1}.at(1)",
+        ];
+
+        for source in cases {
+            let [walked, compiled] = both(source.to_string());
+
+            assert_eq!(compiled, walked, "source: {}", source);
+        }
+    }
+
+    #[test]
+    fn test_errors_point_at_the_same_span() {
+        let tree = Tree::parse_input(load_fragment("interp/errors.sc")).expect("Fixture should parse");
+
+        let error = Vm::with_host(Builtins::silent()).run_tree(&tree).expect_err("Program must fail");
+
+        assert_eq!(error.to_string(), "`miar` has no parameter `forte`");
+        assert_eq!((error.span().line, error.span().col), (10, 11));
+    }
+
+    #[test]
+    fn test_recursion_limit() {
+        let source = "bug Loop\n  ethics sempre\n    sempre\n  end\nend\nLoop.new.sempre";
+        let tree = Tree::parse_input(source.to_string()).expect("Should parse");
+
+        let error = Vm::with_host(Builtins::silent()).run_tree(&tree).expect_err("Must overflow");
+
+        assert!(matches!(error, RuntimeError::StackOverflow { limit: MAX_FRAMES, .. }));
+    }
+
+    #[test]
+    fn test_too_many_items_is_a_compile_error() {
+        let items = vec!["0"; 65_536].join(", ");
+        let tree = Tree::parse_input(format!("x = 1\ny = [{}]", items)).expect("Should parse");
+
+        let error = compile_tree(&tree).expect_err("65536 items do not fit in an instruction");
+
+        assert_eq!(error.to_string(), "too many items (65536); a list, map, template or call takes at most 65535");
+        assert_eq!((error.span().line, error.span().col), (2, 5));
+    }
+
+    #[test]
+    fn test_globals_survive_between_runs() {
+        let mut vm = Vm::with_host(Builtins::silent());
+        let first = Tree::parse_input("x = 40".to_string()).expect("Should parse");
+        let second = Tree::parse_input("x.plus(2)".to_string()).expect("Should parse");
+
+        vm.run_tree(&first).expect("First run");
+        let value = vm.run_tree(&second).expect("Second run");
+
+        assert_eq!(value, Value::Int(42));
+        assert_eq!(vm.global("x"), Some(&Value::Int(40)));
+    }
+
+    #[test]
+    fn test_literals_and_names_are_pooled() {
+        let tree = Tree::parse_input("a = 1.plus(1).plus(1.0)\nb = a.plus(1)".to_string()).expect("Should parse");
+
+        let program = compile_tree(&tree).expect("Should compile");
+
+        assert_eq!(program.main.constants, vec![Value::Int(1), Value::Decimal(1.0)]);
+        assert_eq!(program.main.names, vec!["plus"]);
+        assert_eq!(program.main.locals, vec!["a", "b"]);
+        assert!(std::mem::size_of::<Op>() <= 12);
+    }
+}
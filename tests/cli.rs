@@ -0,0 +1,113 @@
+//! Testes do binário `sc`: códigos de saída, arquivos, entrada padrão e opções.
+
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Output, Stdio};
+
+fn fixture(path: &str) -> String {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/fragments")
+        .join(path)
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Roda `sc` com `args`, mandando `stdin` para a entrada padrão
+fn sc(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_sc"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("sc should start");
+    child.stdin.take().expect("stdin should be piped").write_all(stdin.as_bytes()).expect("stdin should be writable");
+    child.wait_with_output().expect("sc should finish")
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+#[test]
+fn test_exit_codes() {
+    let ok = sc(&["run", &fixture("interp/dog.sc")], "");
+    assert_eq!(ok.status.code(), Some(0), "{}", stderr(&ok));
+    assert_eq!(stdout(&ok), "Rex AU AU\nau au\n0\n");
+
+    let broken = sc(&["parse", "-"], "x = (\n");
+    assert_eq!(broken.status.code(), Some(1));
+    assert!(stderr(&broken).contains("error: expected an expression, found `(`"), "{}", stderr(&broken));
+    assert!(stderr(&broken).contains("<stdin>:1:5"), "{}", stderr(&broken));
+
+    let usage = sc(&["voar"], "");
+    assert_eq!(usage.status.code(), Some(2));
+    assert!(stderr(&usage).starts_with("error: unknown command `voar`\nusage: sc"), "{}", stderr(&usage));
+    assert_eq!(sc(&[], "").status.code(), Some(2));
+    assert_eq!(sc(&["repl", "a.sc"], "").status.code(), Some(2));
+}
+
+#[test]
+fn test_every_file_is_checked() {
+    let missing = fixture("sumido.sc");
+    let output = sc(&["parse", &missing, &fixture("interp/dog.sc"), &fixture("bug/invalid_lowercase_specie.sc")], "");
+
+    assert_eq!(output.status.code(), Some(1));
+    let errors = stderr(&output);
+    assert!(errors.contains(&format!("error: cannot read {}", missing)), "{}", errors);
+    assert!(errors.contains("invalid_lowercase_specie.sc:"), "{}", errors);
+    assert!(!errors.contains("dog.sc"), "{}", errors);
+
+    let output = sc(&["run", &fixture("interp/dog.sc"), &fixture("interp/dog.sc")], "");
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "Rex AU AU\nau au\n0\n".repeat(2));
+}
+
+#[test]
+fn test_standard_input() {
+    let output = sc(&["run"], "print(1.plus(1))\n");
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "2\n");
+
+    // `-` pode aparecer entre arquivos
+    let output = sc(&["run", "-", &fixture("interp/dog.sc")], "print(\"antes\")\n");
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "antes\nRex AU AU\nau au\n0\n");
+
+    let output = sc(&["fmt"], "x=1\n");
+    assert_eq!(stdout(&output), "x = 1\n");
+}
+
+#[test]
+fn test_format_option() {
+    let joined = sc(&["ast", "--format=sexpr"], "x = 1");
+    let separate = sc(&["ast", "--format", "sexpr"], "x = 1");
+
+    assert_eq!(joined.status.code(), Some(0));
+    assert_eq!(stdout(&joined), "(program (= x 1))\n");
+    assert_eq!(stdout(&separate), stdout(&joined));
+
+    let output = sc(&["ast", "--format"], "x = 1");
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).starts_with("error: `--format` needs a value"), "{}", stderr(&output));
+    let output = sc(&["ast", "--format=xml"], "x = 1");
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).starts_with("error: unknown format `xml`"), "{}", stderr(&output));
+}
+
+#[test]
+fn test_fmt_check() {
+    let output = sc(&["fmt", "--check", &fixture("printer/canonical.sc")], "");
+    assert_eq!(output.status.code(), Some(0), "{}", stderr(&output));
+
+    let messy = fixture("printer/messy.sc");
+    let output = sc(&["fmt", "--check", &messy], "");
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).starts_with("error: file is not formatted\n"), "{}", stderr(&output));
+    assert!(stderr(&output).contains(&format!("run `sc fmt {}` to fix it", messy)), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "");
+}
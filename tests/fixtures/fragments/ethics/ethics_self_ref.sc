@@ -0,0 +1,6 @@
+ethics descansar(horas: Int) Int
+  $.energia.plus(horas)
+  $.latir(volume: horas)
+  dono = $
+  $
+end
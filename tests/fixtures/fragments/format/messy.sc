@@ -0,0 +1,25 @@
+# Cachorro de exemplo
+bug Dog
+      # nome de batismo
+    gene nome String
+ gene energia Int   # de 0 a 10
+ethics latir(volume:Int,vezes:Int) String
+  #[ late
+     alto ]#
+if volume>1 # alto
+        print("au")
+   # só quando quieto
+else
+  print("...")
+   end
+  "au"   # sempre
+  # antes do fim
+end
+end
+
+
+
+rex=Dog.new(nome:"Rex",energia:1)
+x = Math.max(1, #[ em linha ]# 2)
+rex.latir(volume:2,vezes:1) # fim
+# rodapé
@@ -0,0 +1,26 @@
+bug Dog
+  gene nome String
+  gene energia Int
+
+  ethics latir(volume: Int) String
+    if volume > 5
+      return "AU AU"
+    end
+    "au au"
+  end
+
+  ethics correr(distancia: Int)
+    while distancia > 0 and energia > 0
+      energia = energia.minus(1)
+      distancia = distancia.minus(1)
+    end
+    $.energia
+  end
+end
+
+rex = Dog.new(nome: "Rex", energia: 3)
+print(rex.nome, rex.latir(volume: 10))
+print(rex.latir(1))
+restante = rex.correr(distancia: 10)
+print(restante)
+rex
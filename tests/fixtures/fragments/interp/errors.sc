@@ -0,0 +1,10 @@
+bug Cat
+  gene vidas Int
+
+  ethics miar(alto: Bool)
+    "miau"
+  end
+end
+
+gato = Cat(7)
+gato.miar(forte: 1)
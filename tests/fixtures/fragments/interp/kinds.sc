@@ -0,0 +1,30 @@
+kind Forma
+  Circulo(raio: Decimal)
+  Retangulo(largura: Decimal, altura: Decimal)
+  Ponto
+end
+
+bug Desenho
+  gene forma Forma
+
+  ethics area Decimal
+    match forma
+    when Circulo
+      forma.raio.times(forma.raio).times(3.0)
+    when Retangulo
+      forma.largura.times(forma.altura)
+    else
+      0.0
+    end
+  end
+end
+
+for f in list(Circulo(raio: 1.0), Retangulo(2.0, 3.0), Ponto)
+  print(f, Desenho(f).area)
+end
+match Ponto
+when Circulo, Retangulo
+  "cheio"
+when Ponto
+  "vazio"
+end
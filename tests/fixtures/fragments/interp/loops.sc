@@ -0,0 +1,14 @@
+soma = 0
+for n in list(1, 2, 3, 4)
+  if n % 2 == 0
+    soma = soma.plus(n)
+  elsif n == 3
+    soma = soma.plus(100)
+  else
+    soma = soma.minus(1)
+  end
+end
+for i in 3
+  print(i)
+end
+soma
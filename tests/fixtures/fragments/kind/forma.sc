@@ -0,0 +1,5 @@
+kind Forma
+  Circulo(raio: Decimal)
+  Retangulo(largura: Decimal, altura: Decimal)
+  Ponto
+end
@@ -0,0 +1,11 @@
+vazio = []
+nomes = ["Rex", "Bidu"]
+dono = {nome: "Ana", idade: 30, pets: nomes, ativo: true}
+nada = {}
+if dono.at("ativo") and not false
+  print(dono.at("nome"), nomes.size, nil.is_nil)
+end
+for chave in dono
+  print(chave)
+end
+[1, [2, 3], {x: nil}]
@@ -0,0 +1,6 @@
+grande = 1_000_000
+mascara = 0xFF_FFu16
+bits = 0b1010_0101u8
+pequeno = -128i8
+preco = 1_234.50
+print(grande, mascara, bits, pequeno, preco)
@@ -0,0 +1,5 @@
+42          # inteiro
+0xFF        # hexadecimal
+0b1010      # binário
+3.14        # decimal
+"hello"     # string
@@ -0,0 +1,12 @@
+bug Dog
+  gene nome String
+  gene energia Int
+  ethics status String
+    "#{nome}: energia #{energia.plus(1)}"
+  end
+end
+rex = Dog(nome: "Rex", energia: 2)
+print(rex.status)
+print("aspas \"#{rex.nome.upcase}\"\tfim\\ \#{nada} \u{e9}")
+print("lista #{[1, "dois"]} e #{nil}")
+"#{"a".plus("#{1}")}b"
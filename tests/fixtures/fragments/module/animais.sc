@@ -0,0 +1,11 @@
+bug Dog
+  ethics latir String
+    "au"
+  end
+end
+
+bug Cat
+  ethics miar String
+    "miau"
+  end
+end
@@ -0,0 +1,4 @@
+use "cycle_b.sc"
+
+bug A
+end
@@ -0,0 +1,4 @@
+use "cycle_a.sc"
+
+bug B
+end
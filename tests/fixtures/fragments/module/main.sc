@@ -0,0 +1,6 @@
+use "animais.sc"
+use Dog as Lobo from "selvagens.sc"
+
+rex = Dog.new
+lobo = Lobo(presa: Cat.new)
+print(rex.latir, lobo.latir, lobo.presa.miar)
@@ -0,0 +1,5 @@
+bug Medida
+  ethics grande Decimal
+    12345678901234567890123.5
+  end
+end
@@ -0,0 +1,9 @@
+use Cat from "animais.sc"
+
+bug Dog
+  gene presa Cat
+
+  ethics latir String
+    "auuu"
+  end
+end
@@ -0,0 +1,23 @@
+x = Dog.new(nome: "Rex", energia: 1)
+
+bug Dog
+  gene nome String
+  gene energia Int
+
+  ethics a
+
+  ethics latir(volume: Int, vezes: Int) String
+    if volume > 1 and not $.cansado
+      print("au")
+    elsif volume == 0
+      return ""
+    else
+      for i in list(1, 2)
+        print(i)
+      end
+    end
+    "au"
+  end
+end
+
+x.latir(volume: 2, vezes: 1)
@@ -0,0 +1,16 @@
+# Um cachorro
+# com energia
+bug Dog
+  gene energia Int # inteiro
+
+  #[ Late alto
+     e em várias linhas ]#
+  ethics latir(volume: Int)
+    print(volume) # fala
+  end
+end
+
+# solto no meio do programa
+
+x = Math.max(1, #[ em linha ]# 2)
+print(x)
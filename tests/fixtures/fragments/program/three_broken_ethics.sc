@@ -0,0 +1,27 @@
+bug Dog
+  gene energia Int
+
+  ethics latir(volume: int)
+    print(volume)
+  end
+
+  ethics correr(distancia: Int) Int
+    distancia.plus(1)
+  end
+
+  ethics comer(comida Food)
+    energia = comida
+  end
+end
+
+bug Cat
+  gene vidas Int
+
+  ethics miar
+    print("miau"
+  end
+end
+
+bug Bird
+  gene asas Int
+end
@@ -0,0 +1,19 @@
+bug Dog
+  gene energia Int
+  gene dono Person
+
+  ethics latir(energia: Int) String
+    total = energia
+    for item in list(1, 2)
+      total = total.plus(item)
+    end
+    $.energia
+  end
+end
+
+rex = Dog.new(energia: 1)
+rex.latir(energia: 2)
+for rex in list(rex)
+  print(rex)
+end
+print(sobra)
@@ -0,0 +1,6 @@
+while counter < 10 and not done
+  counter = counter.plus(1)
+  if counter % 2 == 0
+    print(counter)
+  end
+end
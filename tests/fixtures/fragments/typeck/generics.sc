@@ -0,0 +1,25 @@
+bug Caixa<T>
+  gene valor T
+  gene historico List<T>
+
+  ethics trocar(novo: T) Caixa<T>
+    historico = historico.push(valor)
+    valor = novo
+    $
+  end
+
+  ethics abrir T
+    valor
+  end
+end
+
+bug Par<A, B>
+  gene primeiro A
+  gene segundo B
+end
+
+idades = {rex: 3, bolt: 5}
+caixa = Caixa.new(valor: 1, historico: [0])
+total = caixa.trocar(2).abrir.plus(idades.at("rex"))
+par = Par("rex", caixa)
+print(total, par.primeiro.upcase, par.segundo.abrir, idades.values.first.plus(1))
@@ -0,0 +1,46 @@
+contract Falante
+  ethics falar(volume: Int) String
+end
+
+bug Animal
+  gene nome String
+  gene energia Int
+
+  ethics correr(distancia: Int) Int
+    energia = energia.minus(distancia)
+    energia
+  end
+
+  ethics apresentar String
+    "sou #{nome}"
+  end
+end
+
+bug Cat < Animal, Falante
+  gene vidas Int
+
+  ethics falar(volume: Int) String
+    if volume > 5
+      return "MIAU"
+    end
+    "miau"
+  end
+
+  ethics apresentar String
+    "gato #{nome}, #{vidas} vidas"
+  end
+end
+
+bug Dono
+  gene bicho Animal
+  gene voz Falante
+
+  ethics chamar String
+    voz.falar(1).plus(bicho.apresentar)
+  end
+end
+
+mia = Cat.new(nome: "Mia", energia: 10, vidas: 7)
+dono = Dono(mia, mia)
+print(mia.correr(3), mia.falar(9), dono.chamar)
+Cat("Tom", 5).apresentar
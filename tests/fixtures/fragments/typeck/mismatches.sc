@@ -0,0 +1,21 @@
+bug Dog
+  gene nome String
+  gene energia Int
+  gene dono Person
+
+  ethics latir(volume: Int) String
+    if volume > "alto"
+      return volume
+    end
+    energia = "cheio"
+    "au"
+  end
+
+  ethics idade Int
+    nome
+  end
+end
+
+rex = Dog.new(nome: 1, peso: 3)
+rex.latir(volume: "alto")
+rex.correr